@@ -40,6 +40,7 @@ DROP TABLE IF EXISTS `cedar_policy_set`;
 CREATE TABLE `cedar_policy_set` (
   `policy_id` int NOT NULL AUTO_INCREMENT,
   `policy_uuid` char(36) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL,
+  `tenant_id` int NOT NULL DEFAULT '1' COMMENT '所属租户',
   `annotation` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT 'policy的注解',
   `policy_text` text CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT '单条Policy;所有可用Policy 组和后给Cedar使用',
   `effect` varchar(10) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT '这条策略是许可策略 or 拒绝策略',
@@ -51,9 +52,11 @@ CREATE TABLE `cedar_policy_set` (
   `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
   `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
   PRIMARY KEY (`policy_id`,`policy_uuid`) USING BTREE,
-  UNIQUE KEY `uniq_policy_hash` (`policy_hash`),
+  UNIQUE KEY `uniq_policy_hash` (`tenant_id`,`policy_hash`),
   UNIQUE KEY `policy_uuid` (`policy_uuid`) USING BTREE,
   KEY `created_by` (`created_by`),
+  KEY `idx_cedar_policy_set_tenant_id` (`tenant_id`),
+  CONSTRAINT `cedar_policy_set_tenant_fk` FOREIGN KEY (`tenant_id`) REFERENCES `tenants` (`tenant_id`) ON DELETE CASCADE,
   CONSTRAINT `cedar_policy_set_ibfk_1` FOREIGN KEY (`created_by`) REFERENCES `users` (`user_id`)
 ) ENGINE=InnoDB AUTO_INCREMENT=7 DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci COMMENT='用来记录Cedar Policy Set的，一条记录一条策略';
 
@@ -76,19 +79,22 @@ DROP TABLE IF EXISTS `cedar_schema`;
 CREATE TABLE `cedar_schema` (
   `schema_id` int NOT NULL AUTO_INCREMENT,
   `schema_uuid` char(36) COLLATE utf8mb4_general_ci NOT NULL,
+  `tenant_id` int NOT NULL DEFAULT '1' COMMENT '所属租户',
   `schema` text COLLATE utf8mb4_general_ci NOT NULL,
   `description` varchar(255) COLLATE utf8mb4_general_ci NOT NULL,
   `is_active` tinyint(1) NOT NULL DEFAULT '0',
   `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
   `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
-  PRIMARY KEY (`schema_id`)
+  PRIMARY KEY (`schema_id`),
+  UNIQUE KEY `uk_cedar_schema_tenant` (`tenant_id`),
+  CONSTRAINT `cedar_schema_tenant_fk` FOREIGN KEY (`tenant_id`) REFERENCES `tenants` (`tenant_id`) ON DELETE CASCADE
 ) ENGINE=InnoDB AUTO_INCREMENT=2 DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci COMMENT='存储 Cedar Schema ';
 
 -- ----------------------------
 -- Records of cedar_schema
 -- ----------------------------
 BEGIN;
-INSERT INTO `cedar_schema` (`schema_id`, `schema_uuid`, `schema`, `description`, `is_active`, `created_at`, `updated_at`) VALUES (1, 'e1c94cb0-3e26-4319-a35b-244a3b0c186d', '// 没有多租户的需求不设置命名空间\n\n// 定义别名组合\n\n\n// -------------------------------------------------\n// 1. 定义核心实体类型\n// -------------------------------------------------\n\n// 应用程序实体（全局权限检查）\nentity Application;\n\n// 用户 (User) 实体。\n// 这是我们系统中的主体（Principal）。\n// 一个用户可以是多个角色的成员。\"用户-角色\"(临时附加) \"用户-用户组-角色\"(常规情况)。\n\nentity User in [Role, Department, Group] = {\n    name: String\n};\n\n\n// 角色 (Role) 实体。\nentity Role = {\n    name: String\n};\n\n// 用户组\nentity Group = {\n    name: String\n};\n// 部门\nentity Department = {\n    name: String\n};\n\n// Cedar Policy\nentity Policy = {\n    name: String\n};\n\n// 资源 (Resource) 实体。\n// 这是被保护的对象，例如一篇文章、一个文件或一个API端点。\n// 为了增加灵活性，资源可以被分组到“资源组”中。\n\n\n\n// -------------------------------------------------\n// 2. 定义操作 (Actions)\n// -------------------------------------------------\n\n\n// 定义CRUD操作。\n// appliesTo 部分将这些操作与我们的核心实体关联起来。\n// 主体 (principal) 通常是用户。\n// 资源 (resource) 就是被保护的Resource。\n// 用户\n\naction \"ViewUser\" appliesTo {\n    principal: User,\n    resource: User\n};\n\naction \"CreateUser\" appliesTo {\n    principal: User,\n    resource: User\n};\n\naction \"UpdateUser\" appliesTo {\n    principal: User,\n    resource: User\n};\n\naction \"DeleteUser\" appliesTo {\n    principal: User,\n    resource: User\n};\n\n// 用户组\n\naction \"ViewGroup\" appliesTo {\n    principal: User,\n    resource: Group\n};\n\naction \"ViewGroupUsers\" appliesTo {\n    principal: User,\n    resource: Group\n};\n\naction \"CreateGroup\" appliesTo {\n    principal: User,\n    resource: Group\n};\n\naction \"UpdateGroup\" appliesTo {\n    principal: User,\n    resource: Group\n};\n\naction \"DeleteGroup\" appliesTo {\n    principal: User,\n    resource: Group\n};\n\n\n// 角色\n\naction \"ViewRole\" appliesTo {\n    principal: User,\n    resource: Role\n};\n\naction \"CreateRole\" appliesTo {\n    principal: User,\n    resource: Role\n};\n\naction \"UpdateRole\" appliesTo {\n    principal: User,\n    resource: Role\n};\n\naction \"DeleteRole\" appliesTo {\n    principal: User,\n    resource: Role\n};\n\naction \"AssignRole\" appliesTo {\n    principal: User,\n    resource: Role\n};\n\naction \"RevokeRole\" appliesTo {\n    principal: User,\n    resource: Role\n};\n\n// 部门\n\naction \"ViewDepartment\" appliesTo {\n    principal: User,\n    resource: Department\n};\n\naction \"ViewDepartmentUsers\" appliesTo {\n    principal: User,\n    resource: Department\n};\n\naction \"CreateDepartment\" appliesTo {\n    principal: User,\n    resource: Department\n};\n\naction \"UpdateDepartment\" appliesTo {\n    principal: User,\n    resource: Department\n};\n\naction \"DeleteDepartment\" appliesTo {\n    principal: User,\n    resource: Department\n};\n\n// 策略\n\naction \"ViewPolicy\" appliesTo {\n    principal: User,\n    resource: Policy\n};\n\naction \"CreatePolicy\" appliesTo {\n    principal: User,\n    resource: Policy\n};\n\naction \"UpdatePolicy\" appliesTo {\n    principal: User,\n    resource: Policy\n};\n\naction \"DeletePolicy\" appliesTo {\n    principal: User,\n    resource: Policy\n};', 'V1', 0, '2025-08-16 13:51:28', '2025-09-23 16:28:59');
+INSERT INTO `cedar_schema` (`schema_id`, `schema_uuid`, `schema`, `description`, `is_active`, `created_at`, `updated_at`) VALUES (1, 'e1c94cb0-3e26-4319-a35b-244a3b0c186d', '// 默认租户不设置命名空间, 其他租户的 Schema 包在各自的 namespace 中\n\n// 定义别名组合\n\n\n// -------------------------------------------------\n// 1. 定义核心实体类型\n// -------------------------------------------------\n\n// 应用程序实体（全局权限检查）\nentity Application;\n\n// 用户 (User) 实体。\n// 这是我们系统中的主体（Principal）。\n// 一个用户可以是多个角色的成员。\"用户-角色\"(临时附加) \"用户-用户组-角色\"(常规情况)。\n\nentity User in [Role, Department, Group] = {\n    name: String\n};\n\n\n// 角色 (Role) 实体。\n// 角色可以继承其他角色, 子角色拥有父角色的全部权限。\nentity Role in [Role] = {\n    name: String\n};\n\n// 用户组\nentity Group = {\n    name: String\n};\n// 部门\nentity Department = {\n    name: String\n};\n\n// Cedar Policy\nentity Policy = {\n    name: String\n};\n\n// 访问权限复核活动\nentity AccessReview = {\n    name: String\n};\n\n// 租户, code 同时用作子域名\nentity Tenant = {\n    name: String,\n    code: String\n};\n\n// 受管系统 (下游系统), code 为程序识别用的唯一编码\nentity System = {\n    name: String,\n    code: String\n};\n\n// 服务账号 (RobotAccount), 非人类主体, 可加入用户组并分配角色\nentity RobotAccount in [Role, Group] = {\n    name: String\n};\n\n// OAuth2 客户端应用\nentity OAuthClient = {\n    name: String\n};\n\n// Webhook 订阅\nentity Webhook = {\n    name: String\n};\n\n// 资源 (Resource) 实体。\n// 这是被保护的对象，例如一篇文章、一个文件或一个API端点。\n// 为了增加灵活性，资源可以被分组到“资源组”中。\n\n\n\n// -------------------------------------------------\n// 2. 定义操作 (Actions)\n// -------------------------------------------------\n\n\n// 定义CRUD操作。\n// appliesTo 部分将这些操作与我们的核心实体关联起来。\n// 主体 (principal) 通常是用户。\n// 资源 (resource) 就是被保护的Resource。\n// 用户\n\naction \"ViewUser\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: User\n};\n\naction \"CreateUser\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: User\n};\n\naction \"UpdateUser\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: User\n};\n\naction \"DeleteUser\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: User\n};\n\n// 用户组\n\naction \"ViewGroup\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Group\n};\n\naction \"ViewGroupUsers\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Group\n};\n\naction \"CreateGroup\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Group\n};\n\naction \"UpdateGroup\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Group\n};\n\naction \"DeleteGroup\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Group\n};\n\n\n// 角色\n\naction \"ViewRole\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Role\n};\n\naction \"CreateRole\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Role\n};\n\naction \"UpdateRole\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Role\n};\n\naction \"DeleteRole\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Role\n};\n\naction \"AssignRole\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Role\n};\n\naction \"RevokeRole\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Role\n};\n\n// 部门\n\naction \"ViewDepartment\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Department\n};\n\naction \"ViewDepartmentUsers\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Department\n};\n\naction \"CreateDepartment\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Department\n};\n\naction \"UpdateDepartment\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Department\n};\n\naction \"DeleteDepartment\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Department\n};\n\n// 策略\n\naction \"ViewPolicy\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Policy\n};\n\naction \"CreatePolicy\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Policy\n};\n\naction \"UpdatePolicy\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Policy\n};\n\naction \"DeletePolicy\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Policy\n};\n\n// 消息\n\naction \"SendMessage\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: [User, Group, Department]\n};\n\n// Webhook\n\naction \"ViewWebhook\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Webhook\n};\n\naction \"CreateWebhook\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Webhook\n};\n\naction \"UpdateWebhook\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Webhook\n};\n\naction \"DeleteWebhook\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Webhook\n};\n\n// OAuth2 客户端应用\n\naction \"ViewOAuthClient\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: OAuthClient\n};\n\naction \"CreateOAuthClient\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: OAuthClient\n};\n\naction \"UpdateOAuthClient\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: OAuthClient\n};\n\naction \"DeleteOAuthClient\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: OAuthClient\n};\n\n// 允许用户登录该应用\naction \"OAuthLogin\" appliesTo {\n    principal: User,\n    resource: OAuthClient\n};\n\n// 服务账号\naction \"ViewRobotAccount\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: RobotAccount\n};\n\naction \"CreateRobotAccount\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: RobotAccount\n};\n\naction \"UpdateRobotAccount\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: RobotAccount\n};\n\naction \"DeleteRobotAccount\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: RobotAccount\n};\n\n// 受管系统\naction \"ViewSystem\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: System\n};\n\naction \"CreateSystem\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: System\n};\n\naction \"UpdateSystem\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: System\n};\n\naction \"DeleteSystem\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: System\n};\n\naction \"ViewSystemConfig\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: System\n};\n\naction \"UpdateSystemConfig\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: System\n};\n\n// 租户 (只有默认租户的主体可以管理)\naction \"ViewTenant\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Tenant\n};\n\naction \"CreateTenant\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Tenant\n};\n\naction \"UpdateTenant\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Tenant\n};\n\naction \"DeleteTenant\" appliesTo {\n    principal: [User, RobotAccount],\n    resource: Tenant\n};\n\n// 审批角色申请, 资源为申请的角色\naction \"ApproveRoleRequest\" appliesTo {\n    principal: User,\n    resource: Role\n};\n\n// 访问权限复核\naction \"ViewAccessReview\" appliesTo {\n    principal: User,\n    resource: AccessReview\n};\n\naction \"CreateAccessReview\" appliesTo {\n    principal: User,\n    resource: AccessReview\n};\n\naction \"UpdateAccessReview\" appliesTo {\n    principal: User,\n    resource: AccessReview\n};', 'V1', 0, '2025-08-16 13:51:28', '2025-09-23 16:28:59');
 COMMIT;
 
 -- ----------------------------
@@ -98,6 +104,7 @@ DROP TABLE IF EXISTS `departments`;
 CREATE TABLE `departments` (
   `dept_id` int NOT NULL AUTO_INCREMENT,
   `dept_uuid` char(36) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  `tenant_id` int NOT NULL DEFAULT '1' COMMENT '所属租户',
   `created_at` datetime(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
   `updated_at` datetime(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6),
   `name` varchar(50) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL COMMENT '部门名称',
@@ -106,7 +113,8 @@ CREATE TABLE `departments` (
   `order` int NOT NULL DEFAULT '0' COMMENT '排序',
   `parent_id` int NOT NULL DEFAULT '0' COMMENT '父部门ID',
   PRIMARY KEY (`dept_id`) USING BTREE,
-  UNIQUE KEY `name_and_parent_id` (`name`,`parent_id`) USING BTREE
+  UNIQUE KEY `name_and_parent_id` (`tenant_id`,`name`,`parent_id`) USING BTREE,
+  CONSTRAINT `departments_tenant_fk` FOREIGN KEY (`tenant_id`) REFERENCES `tenants` (`tenant_id`) ON DELETE CASCADE
 ) ENGINE=InnoDB AUTO_INCREMENT=37 DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
 
 -- ----------------------------
@@ -129,6 +137,81 @@ INSERT INTO `departments` (`dept_id`, `dept_uuid`, `created_at`, `updated_at`, `
 INSERT INTO `departments` (`dept_id`, `dept_uuid`, `created_at`, `updated_at`, `name`, `desc`, `is_deleted`, `order`, `parent_id`) VALUES (15, 'eacb9d3a-1ac7-4a76-a38f-1257c2aec597', '2024-08-28 20:56:44.034183', '2025-09-18 09:56:54.229852', 'Marketing', '', 0, 0, 2);
 COMMIT;
 
+-- ----------------------------
+-- Table structure for email_outbox
+-- ----------------------------
+DROP TABLE IF EXISTS `email_outbox`;
+CREATE TABLE `email_outbox` (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `email_uuid` char(36) NOT NULL COMMENT '邮件UUID',
+  `recipient` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL COMMENT '收件人',
+  `subject` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL COMMENT '邮件主题',
+  `body` mediumtext CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL COMMENT '加密的HTML正文(含一次性令牌), 发送成功或进入死信后清空',
+  `template` varchar(50) NOT NULL COMMENT '邮件模板名称',
+  `language` varchar(10) NOT NULL DEFAULT 'EN' COMMENT '邮件语言',
+  `status` varchar(20) NOT NULL DEFAULT 'pending' COMMENT '投递状态。pending: 待发送；sending: 发送中；sent: 已发送；dead: 重试耗尽(死信)',
+  `attempts` int NOT NULL DEFAULT '0' COMMENT '已尝试次数',
+  `last_error` text CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci COMMENT '最近一次发送失败的原因',
+  `next_attempt_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '下次尝试发送的时间',
+  `claimed_at` timestamp NULL DEFAULT NULL COMMENT '开始发送的时间, 超过租约仍未完成时重新排队',
+  `sent_at` timestamp NULL DEFAULT NULL COMMENT '发送成功的时间',
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `uk_email_uuid` (`email_uuid`),
+  KEY `idx_status_next_attempt` (`status`,`next_attempt_at`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- ----------------------------
+-- Records of email_outbox
+-- ----------------------------
+BEGIN;
+COMMIT;
+
+-- ----------------------------
+-- Table structure for role_parents
+-- ----------------------------
+DROP TABLE IF EXISTS `role_parents`;
+CREATE TABLE `role_parents` (
+  `role_id` int NOT NULL,
+  `parent_role_id` int NOT NULL,
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`role_id`,`parent_role_id`),
+  KEY `parent_role_id` (`parent_role_id`),
+  CONSTRAINT `role_parents_ibfk_1` FOREIGN KEY (`role_id`) REFERENCES `roles` (`role_id`) ON DELETE CASCADE,
+  CONSTRAINT `role_parents_ibfk_2` FOREIGN KEY (`parent_role_id`) REFERENCES `roles` (`role_id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci COMMENT='角色继承关系, 子角色继承父角色的权限';
+
+-- ----------------------------
+-- Table structure for role_requests
+-- ----------------------------
+DROP TABLE IF EXISTS `role_requests`;
+CREATE TABLE `role_requests` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `request_uuid` char(36) NOT NULL COMMENT '申请UUID',
+  `tenant_id` int NOT NULL DEFAULT '1' COMMENT '所属租户',
+  `requester_id` int NOT NULL COMMENT '申请人',
+  `role_id` int NOT NULL COMMENT '申请的角色',
+  `reason` varchar(500) NOT NULL COMMENT '申请理由',
+  `valid_from` timestamp NULL DEFAULT NULL COMMENT '申请的生效时间, 为空表示批准后立即生效',
+  `valid_until` timestamp NULL DEFAULT NULL COMMENT '申请的失效时间, 为空表示永久',
+  `status` varchar(20) NOT NULL DEFAULT 'pending' COMMENT 'pending: 待审批；approved: 已批准；rejected: 已拒绝；cancelled: 已取消；expired: 未审批即过期',
+  `approver_id` int DEFAULT NULL COMMENT '审批人',
+  `decision_comment` varchar(500) DEFAULT NULL COMMENT '审批意见',
+  `decided_at` timestamp NULL DEFAULT NULL,
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `uk_role_request_uuid` (`request_uuid`),
+  KEY `idx_role_requests_status` (`tenant_id`,`status`),
+  KEY `fk_role_request_requester` (`requester_id`),
+  KEY `fk_role_request_role` (`role_id`),
+  CONSTRAINT `fk_role_request_requester` FOREIGN KEY (`requester_id`) REFERENCES `users` (`user_id`) ON DELETE CASCADE,
+  CONSTRAINT `fk_role_request_role` FOREIGN KEY (`role_id`) REFERENCES `roles` (`role_id`) ON DELETE CASCADE,
+  CONSTRAINT `fk_role_request_approver` FOREIGN KEY (`approver_id`) REFERENCES `users` (`user_id`) ON DELETE SET NULL,
+  CONSTRAINT `role_requests_tenant_fk` FOREIGN KEY (`tenant_id`) REFERENCES `tenants` (`tenant_id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci COMMENT='角色申请, 审批通过后按申请的有效期分配角色';
+
 -- ----------------------------
 -- Table structure for group_roles
 -- ----------------------------
@@ -136,8 +219,12 @@ DROP TABLE IF EXISTS `group_roles`;
 CREATE TABLE `group_roles` (
   `group_id` int NOT NULL,
   `role_id` int NOT NULL,
+  `valid_from` timestamp NULL DEFAULT NULL COMMENT '生效时间, 为空表示立即生效',
+  `valid_until` timestamp NULL DEFAULT NULL COMMENT '失效时间, 为空表示永久有效',
+  `activated_at` timestamp NULL DEFAULT CURRENT_TIMESTAMP COMMENT '实际生效的时间, 为空表示尚未到生效时间',
   PRIMARY KEY (`group_id`,`role_id`),
   KEY `role_id` (`role_id`),
+  KEY `idx_group_roles_valid_until` (`valid_until`),
   CONSTRAINT `group_roles_ibfk_1` FOREIGN KEY (`group_id`) REFERENCES `user_groups` (`user_group_id`) ON DELETE CASCADE,
   CONSTRAINT `group_roles_ibfk_2` FOREIGN KEY (`role_id`) REFERENCES `roles` (`role_id`) ON DELETE CASCADE
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci COMMENT='用户组与角色的关联表 (M:N)';
@@ -150,6 +237,34 @@ INSERT INTO `group_roles` (`group_id`, `role_id`) VALUES (1, 2);
 INSERT INTO `group_roles` (`group_id`, `role_id`) VALUES (2, 3);
 COMMIT;
 
+-- ----------------------------
+-- Table structure for notifications
+-- ----------------------------
+DROP TABLE IF EXISTS `notifications`;
+CREATE TABLE `notifications` (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `notification_uuid` char(36) NOT NULL COMMENT '通知UUID',
+  `user_id` int NOT NULL COMMENT '接收人',
+  `category` varchar(50) NOT NULL COMMENT '通知分类, 如 system、security、invitation',
+  `level` varchar(20) NOT NULL DEFAULT 'info' COMMENT '通知等级。info、success、warning、error',
+  `title` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL COMMENT '标题',
+  `content` text CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci COMMENT '内容',
+  `link` varchar(512) DEFAULT NULL COMMENT '点击通知后跳转的前端地址',
+  `is_read` tinyint(1) NOT NULL DEFAULT '0' COMMENT '是否已读',
+  `read_at` timestamp NULL DEFAULT NULL COMMENT '已读时间',
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `uk_notification_uuid` (`notification_uuid`),
+  KEY `idx_user_read_created` (`user_id`,`is_read`,`created_at`),
+  CONSTRAINT `fk_notification_user_id` FOREIGN KEY (`user_id`) REFERENCES `users` (`user_id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- ----------------------------
+-- Records of notifications
+-- ----------------------------
+BEGIN;
+COMMIT;
+
 -- ----------------------------
 -- Table structure for roles
 -- ----------------------------
@@ -157,11 +272,13 @@ DROP TABLE IF EXISTS `roles`;
 CREATE TABLE `roles` (
   `role_id` int NOT NULL AUTO_INCREMENT,
   `role_uuid` char(36) COLLATE utf8mb4_general_ci NOT NULL,
+  `tenant_id` int NOT NULL DEFAULT '1' COMMENT '所属租户',
   `role_name` varchar(50) COLLATE utf8mb4_general_ci NOT NULL,
   `description` varchar(255) COLLATE utf8mb4_general_ci DEFAULT NULL,
   `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
   PRIMARY KEY (`role_id`),
-  UNIQUE KEY `role_name` (`role_name`)
+  UNIQUE KEY `role_name` (`tenant_id`,`role_name`),
+  CONSTRAINT `roles_tenant_fk` FOREIGN KEY (`tenant_id`) REFERENCES `tenants` (`tenant_id`) ON DELETE CASCADE
 ) ENGINE=InnoDB AUTO_INCREMENT=8 DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
 
 -- ----------------------------
@@ -178,13 +295,22 @@ COMMIT;
 -- ----------------------------
 DROP TABLE IF EXISTS `systems`;
 CREATE TABLE `systems` (
-  `system_id` int unsigned NOT NULL AUTO_INCREMENT COMMENT '系统唯一ID/系统唯一编码, 用于程序识别',
+  `system_id` int unsigned NOT NULL AUTO_INCREMENT COMMENT '系统唯一ID',
+  `system_uuid` char(36) COLLATE utf8mb4_general_ci NOT NULL,
+  `tenant_id` int NOT NULL DEFAULT '1' COMMENT '所属租户',
+  `code` varchar(64) COLLATE utf8mb4_general_ci NOT NULL COMMENT '系统编码, 租户内唯一, 用于程序识别',
   `name` varchar(100) COLLATE utf8mb4_general_ci NOT NULL COMMENT '系统名称',
   `description` text COLLATE utf8mb4_general_ci COMMENT '系统描述',
+  `base_url` varchar(255) COLLATE utf8mb4_general_ci DEFAULT NULL COMMENT '系统访问地址',
+  `config_schema` json DEFAULT NULL COMMENT '配置的 JSON Schema, 为空时不校验',
+  `is_active` tinyint(1) NOT NULL DEFAULT '1',
   `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
   `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
-  PRIMARY KEY (`system_id`)
-) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci COMMENT='系统信息表,用于实现多租户';
+  PRIMARY KEY (`system_id`),
+  UNIQUE KEY `uk_system_uuid` (`system_uuid`),
+  UNIQUE KEY `uk_system_code` (`tenant_id`,`code`),
+  CONSTRAINT `systems_tenant_fk` FOREIGN KEY (`tenant_id`) REFERENCES `tenants` (`tenant_id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci COMMENT='受管系统登记表';
 
 -- ----------------------------
 -- Records of systems
@@ -199,6 +325,7 @@ DROP TABLE IF EXISTS `template_links`;
 CREATE TABLE `template_links` (
   `link_id` int NOT NULL AUTO_INCREMENT,
   `link_uuid` char(36) COLLATE utf8mb4_general_ci NOT NULL,
+  `tenant_id` int NOT NULL DEFAULT '1' COMMENT '所属租户',
   `template_uuid` char(36) COLLATE utf8mb4_general_ci NOT NULL,
   `principal_uid` varchar(50) COLLATE utf8mb4_general_ci NOT NULL,
   `resource_uid` varchar(50) COLLATE utf8mb4_general_ci NOT NULL,
@@ -206,6 +333,8 @@ CREATE TABLE `template_links` (
   `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
   PRIMARY KEY (`link_id`) USING BTREE,
   KEY `template_uuid` (`template_uuid`),
+  KEY `idx_template_links_tenant_id` (`tenant_id`),
+  CONSTRAINT `template_links_tenant_fk` FOREIGN KEY (`tenant_id`) REFERENCES `tenants` (`tenant_id`) ON DELETE CASCADE,
   CONSTRAINT `template_links_ibfk_1` FOREIGN KEY (`template_uuid`) REFERENCES `cedar_policy_set` (`policy_uuid`) ON DELETE CASCADE ON UPDATE CASCADE
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
 
@@ -246,11 +375,14 @@ DROP TABLE IF EXISTS `user_groups`;
 CREATE TABLE `user_groups` (
   `user_group_id` int NOT NULL AUTO_INCREMENT,
   `user_group_uuid` char(36) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT '用户组唯一ID/用户组唯一标识符',
+  `tenant_id` int NOT NULL DEFAULT '1' COMMENT '所属租户',
   `name` varchar(100) COLLATE utf8mb4_general_ci NOT NULL COMMENT '用户组名称, 如: 项目A核心开发组',
   `description` text COLLATE utf8mb4_general_ci COMMENT '用户组描述',
   `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
   `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
-  PRIMARY KEY (`user_group_id`)
+  PRIMARY KEY (`user_group_id`),
+  KEY `idx_user_groups_tenant_id` (`tenant_id`),
+  CONSTRAINT `user_groups_tenant_fk` FOREIGN KEY (`tenant_id`) REFERENCES `tenants` (`tenant_id`) ON DELETE CASCADE
 ) ENGINE=InnoDB AUTO_INCREMENT=4 DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci COMMENT='用户组定义表';
 
 -- ----------------------------
@@ -268,21 +400,30 @@ COMMIT;
 DROP TABLE IF EXISTS `user_invitations`;
 CREATE TABLE `user_invitations` (
   `id` int NOT NULL AUTO_INCREMENT,
+  `tenant_id` int NOT NULL DEFAULT '1' COMMENT '所属租户',
   `inviter_user_id` int NOT NULL COMMENT '邀请人的用户ID',
   `invitee_email` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL COMMENT '被邀请人的邮箱地址',
   `invitee_user_id` int DEFAULT NULL COMMENT '被邀请人接受邀请并注册后的用户ID',
-  `invitation_code` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL COMMENT '唯一的邀请码',
+  `invitation_uuid` char(36) NOT NULL COMMENT '邀请UUID',
+  `invitation_code` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL COMMENT '唯一的邀请码(SHA-256哈希)',
+  `dept_id` int NOT NULL COMMENT '预分配的部门ID',
+  `group_uuids` json DEFAULT NULL COMMENT '预分配的用户组UUID列表',
+  `role_uuids` json DEFAULT NULL COMMENT '预分配的角色UUID列表',
+  `language` varchar(10) NOT NULL DEFAULT 'EN' COMMENT '邀请邮件语言',
   `status` varchar(20) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL DEFAULT 'pending' COMMENT '邀请的状态。pending: 待接受；accepted: 已接受；expired: 已过期；revoked: 已撤销',
   `expires_at` timestamp NOT NULL COMMENT '邀请的过期时间',
   `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
   `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
   PRIMARY KEY (`id`),
   UNIQUE KEY `uk_invitation_code` (`invitation_code`),
+  UNIQUE KEY `uk_invitation_uuid` (`invitation_uuid`),
+  KEY `fk_invitation_dept_id` (`dept_id`),
   KEY `idx_inviter_user_id` (`inviter_user_id`),
   KEY `idx_invitee_email` (`invitee_email`),
   KEY `fk_invitee_user_id` (`invitee_user_id`),
   CONSTRAINT `fk_invitee_user_id` FOREIGN KEY (`invitee_user_id`) REFERENCES `users` (`user_id`),
-  CONSTRAINT `fk_inviter_user_id` FOREIGN KEY (`inviter_user_id`) REFERENCES `users` (`user_id`)
+  CONSTRAINT `fk_inviter_user_id` FOREIGN KEY (`inviter_user_id`) REFERENCES `users` (`user_id`),
+  CONSTRAINT `fk_invitation_dept_id` FOREIGN KEY (`dept_id`) REFERENCES `departments` (`dept_id`) ON DELETE CASCADE
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
 
 -- ----------------------------
@@ -291,6 +432,20 @@ CREATE TABLE `user_invitations` (
 BEGIN;
 COMMIT;
 
+-- ----------------------------
+-- Table structure for user_password_history
+-- ----------------------------
+DROP TABLE IF EXISTS `user_password_history`;
+CREATE TABLE `user_password_history` (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `user_id` int NOT NULL COMMENT '用户ID',
+  `password_hash` varchar(128) NOT NULL COMMENT '历史密码哈希',
+  `created_at` datetime(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+  PRIMARY KEY (`id`),
+  KEY `idx_user_password_history_user_id` (`user_id`),
+  CONSTRAINT `user_password_history_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `users` (`user_id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
 -- ----------------------------
 -- Table structure for user_roles
 -- ----------------------------
@@ -299,8 +454,12 @@ CREATE TABLE `user_roles` (
   `user_id` int NOT NULL,
   `role_id` int NOT NULL,
   `assigned_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `valid_from` timestamp NULL DEFAULT NULL COMMENT '生效时间, 为空表示立即生效',
+  `valid_until` timestamp NULL DEFAULT NULL COMMENT '失效时间, 为空表示永久有效',
+  `activated_at` timestamp NULL DEFAULT CURRENT_TIMESTAMP COMMENT '实际生效的时间, 为空表示尚未到生效时间',
   PRIMARY KEY (`user_id`,`role_id`),
   KEY `role_id` (`role_id`),
+  KEY `idx_user_roles_valid_until` (`valid_until`),
   CONSTRAINT `user_roles_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `users` (`user_id`) ON DELETE CASCADE,
   CONSTRAINT `user_roles_ibfk_2` FOREIGN KEY (`role_id`) REFERENCES `roles` (`role_id`) ON DELETE CASCADE
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci;
@@ -319,6 +478,7 @@ DROP TABLE IF EXISTS `users`;
 CREATE TABLE `users` (
   `user_id` int NOT NULL AUTO_INCREMENT,
   `user_uuid` char(36) NOT NULL COMMENT '用户UUID',
+  `tenant_id` int NOT NULL DEFAULT '1' COMMENT '所属租户',
   `created_at` datetime(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
   `updated_at` datetime(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6),
   `username` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL COMMENT '用户名称',
@@ -332,9 +492,11 @@ CREATE TABLE `users` (
   `last_login` datetime(6) DEFAULT NULL COMMENT '最后登录时间',
   `reset_token` varchar(128) DEFAULT NULL COMMENT '重置密码的token',
   `reset_triggered` datetime(6) DEFAULT NULL COMMENT '重置触发时间',
+  `password_changed_at` datetime(6) DEFAULT NULL COMMENT '密码最后修改时间',
   PRIMARY KEY (`user_id`) USING BTREE,
-  UNIQUE KEY `username` (`username`),
-  UNIQUE KEY `email` (`email`),
+  UNIQUE KEY `username` (`tenant_id`,`username`),
+  UNIQUE KEY `email` (`tenant_id`,`email`),
+  CONSTRAINT `users_tenant_fk` FOREIGN KEY (`tenant_id`) REFERENCES `tenants` (`tenant_id`) ON DELETE CASCADE,
   KEY `users_ibfk_1` (`dept_id`),
   CONSTRAINT `users_ibfk_1` FOREIGN KEY (`dept_id`) REFERENCES `departments` (`dept_id`) ON DELETE CASCADE ON UPDATE RESTRICT
 ) ENGINE=InnoDB AUTO_INCREMENT=49 DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
@@ -351,4 +513,358 @@ INSERT INTO `users` (`user_id`, `user_uuid`, `created_at`, `updated_at`, `userna
 INSERT INTO `users` (`user_id`, `user_uuid`, `created_at`, `updated_at`, `username`, `alias`, `email`, `phone`, `password`, `dept_id`, `is_active`, `avatar`, `last_login`, `reset_token`, `reset_triggered`) VALUES (48, 'e0e442f7-2783-401a-b44c-84a26862097d', '2025-09-26 12:21:33.522042', '2025-09-26 12:21:33.522042', 'test_user_uuid', NULL, 'test_user_uuid@xxx.com', NULL, '$argon2id$v=19$m=19456,t=2,p=1$mMKzGiCs8YQDg1HhLIAtiw$j7Ubnqh6I+ZF3tyejtHO89IftrK+wbxLGod677zziwA', 15, 1, NULL, NULL, NULL, NULL);
 COMMIT;
 
+-- ----------------------------
+-- Table structure for webhook_deliveries
+-- ----------------------------
+DROP TABLE IF EXISTS `webhook_deliveries`;
+CREATE TABLE `webhook_deliveries` (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `delivery_uuid` char(36) NOT NULL COMMENT '投递UUID',
+  `webhook_id` int NOT NULL COMMENT '所属订阅',
+  `event_id` char(36) NOT NULL COMMENT '领域事件ID, 同一事件重新投递时不变',
+  `event_type` varchar(50) NOT NULL COMMENT '事件类型',
+  `payload` mediumtext CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL COMMENT '发送的JSON内容',
+  `status` varchar(20) NOT NULL DEFAULT 'pending' COMMENT '投递状态。pending: 待发送；sending: 发送中；succeeded: 成功；dead: 重试耗尽',
+  `attempts` int NOT NULL DEFAULT '0' COMMENT '已尝试次数',
+  `response_status` int DEFAULT NULL COMMENT '最近一次响应的HTTP状态码',
+  `last_error` text CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci COMMENT '最近一次失败的原因, 不含响应内容',
+  `next_attempt_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP COMMENT '下次尝试发送的时间',
+  `claimed_at` timestamp NULL DEFAULT NULL COMMENT '开始发送的时间, 超过租约仍未完成时重新排队',
+  `delivered_at` timestamp NULL DEFAULT NULL COMMENT '投递成功的时间',
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `uk_delivery_uuid` (`delivery_uuid`),
+  KEY `idx_status_next_attempt` (`status`,`next_attempt_at`),
+  KEY `idx_webhook_created` (`webhook_id`,`created_at`),
+  CONSTRAINT `fk_delivery_webhook_id` FOREIGN KEY (`webhook_id`) REFERENCES `webhooks` (`webhook_id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- ----------------------------
+-- Records of webhook_deliveries
+-- ----------------------------
+BEGIN;
+COMMIT;
+
+-- ----------------------------
+-- Table structure for webhooks
+-- ----------------------------
+DROP TABLE IF EXISTS `webhooks`;
+CREATE TABLE `webhooks` (
+  `webhook_id` int NOT NULL AUTO_INCREMENT,
+  `webhook_uuid` char(36) NOT NULL COMMENT '订阅UUID',
+  `tenant_id` int NOT NULL DEFAULT '1' COMMENT '所属租户',
+  `name` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL COMMENT '名称',
+  `url` varchar(512) NOT NULL COMMENT '接收事件的地址',
+  `secret` varchar(512) NOT NULL COMMENT '加密后的签名密钥',
+  `events` json NOT NULL COMMENT '订阅的事件类型, 空数组表示全部事件',
+  `is_active` tinyint(1) NOT NULL DEFAULT '1' COMMENT '是否启用',
+  `created_by` int NOT NULL COMMENT '创建人',
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  PRIMARY KEY (`webhook_id`),
+  UNIQUE KEY `uk_webhook_uuid` (`webhook_uuid`),
+  KEY `idx_webhooks_tenant` (`tenant_id`,`is_active`),
+  CONSTRAINT `webhooks_tenant_fk` FOREIGN KEY (`tenant_id`) REFERENCES `tenants` (`tenant_id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- ----------------------------
+-- Records of webhooks
+-- ----------------------------
+BEGIN;
+COMMIT;
+
+-- ----------------------------
+-- Table structure for directory_links
+-- ----------------------------
+DROP TABLE IF EXISTS `directory_links`;
+CREATE TABLE `directory_links` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `source` varchar(32) NOT NULL COMMENT '外部目录, 如 ldap',
+  `entry_type` varchar(32) NOT NULL COMMENT 'user、group、department',
+  `external_id` varchar(255) NOT NULL COMMENT '外部目录中的唯一标识',
+  `local_id` int NOT NULL COMMENT '本地用户、用户组或部门ID',
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `uk_directory_external` (`source`,`entry_type`,`external_id`),
+  UNIQUE KEY `uk_directory_local` (`source`,`entry_type`,`local_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- ----------------------------
+-- Records of directory_links
+-- ----------------------------
+BEGIN;
+COMMIT;
+
+-- ----------------------------
+-- Table structure for oauth_clients
+-- ----------------------------
+DROP TABLE IF EXISTS `oauth_clients`;
+CREATE TABLE `oauth_clients` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `client_id` varchar(64) NOT NULL COMMENT '客户端标识, 协议端点不区分租户, 全局唯一',
+  `tenant_id` int NOT NULL DEFAULT '1' COMMENT '所属租户',
+  `name` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL COMMENT '应用名称, 租户内唯一',
+  `description` varchar(255) DEFAULT NULL COMMENT '描述',
+  `secret_hash` char(64) DEFAULT NULL COMMENT '客户端密钥的 SHA-256, 公开客户端为空',
+  `redirect_uris` json NOT NULL COMMENT '允许的回调地址',
+  `allowed_scopes` json NOT NULL COMMENT '允许申请的 scope',
+  `skip_consent` tinyint(1) NOT NULL DEFAULT '0' COMMENT '内部可信应用, 不需要用户确认授权',
+  `is_active` tinyint(1) NOT NULL DEFAULT '1' COMMENT '是否启用',
+  `created_by` int NOT NULL COMMENT '创建人',
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `uk_oauth_client_id` (`client_id`),
+  UNIQUE KEY `uk_oauth_client_name` (`tenant_id`,`name`),
+  CONSTRAINT `oauth_clients_tenant_fk` FOREIGN KEY (`tenant_id`) REFERENCES `tenants` (`tenant_id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- ----------------------------
+-- Records of oauth_clients
+-- ----------------------------
+BEGIN;
+COMMIT;
+
+-- ----------------------------
+-- Table structure for oauth_consents
+-- ----------------------------
+DROP TABLE IF EXISTS `oauth_consents`;
+CREATE TABLE `oauth_consents` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `user_id` int NOT NULL,
+  `client_id` int NOT NULL,
+  `scopes` json NOT NULL COMMENT '用户已同意的 scope',
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `uk_oauth_consent_user_client` (`user_id`, `client_id`),
+  KEY `idx_oauth_consent_client_id` (`client_id`),
+  CONSTRAINT `fk_oauth_consent_user_id` FOREIGN KEY (`user_id`) REFERENCES `users` (`user_id`) ON DELETE CASCADE,
+  CONSTRAINT `fk_oauth_consent_client_id` FOREIGN KEY (`client_id`) REFERENCES `oauth_clients` (`id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- ----------------------------
+-- Records of oauth_consents
+-- ----------------------------
+BEGIN;
+COMMIT;
+
+-- ----------------------------
+-- Table structure for personal_access_tokens
+-- ----------------------------
+DROP TABLE IF EXISTS `personal_access_tokens`;
+CREATE TABLE `personal_access_tokens` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `token_uuid` varchar(36) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  `user_id` int NOT NULL COMMENT '令牌所属用户',
+  `name` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL COMMENT '令牌名称',
+  `token_prefix` varchar(16) NOT NULL COMMENT '令牌前几位, 用于识别',
+  `token_hash` char(64) NOT NULL COMMENT '令牌的 SHA-256',
+  `scopes` json NOT NULL COMMENT '允许访问的范围',
+  `expires_at` timestamp NULL DEFAULT NULL COMMENT '过期时间, 为空表示永不过期',
+  `last_used_at` timestamp NULL DEFAULT NULL COMMENT '最后使用时间',
+  `last_used_ip` varchar(45) DEFAULT NULL COMMENT '最后使用的 IP',
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `uk_personal_token_uuid` (`token_uuid`),
+  UNIQUE KEY `uk_personal_token_hash` (`token_hash`),
+  KEY `idx_personal_token_user_id` (`user_id`),
+  CONSTRAINT `fk_personal_token_user_id` FOREIGN KEY (`user_id`) REFERENCES `users` (`user_id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- ----------------------------
+-- Records of personal_access_tokens
+-- ----------------------------
+BEGIN;
+COMMIT;
+
+-- ----------------------------
+-- Table structure for robot_accounts
+-- ----------------------------
+DROP TABLE IF EXISTS `robot_accounts`;
+CREATE TABLE `robot_accounts` (
+  `robot_id` int NOT NULL AUTO_INCREMENT,
+  `robot_uuid` varchar(36) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  `tenant_id` int NOT NULL DEFAULT '1' COMMENT '所属租户',
+  `name` varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL COMMENT '服务账号名称',
+  `description` varchar(255) DEFAULT NULL COMMENT '描述',
+  `secret_prefix` varchar(16) NOT NULL COMMENT '密钥前几位, 用于识别',
+  `secret_hash` char(64) NOT NULL COMMENT '密钥的 SHA-256',
+  `is_active` tinyint(1) NOT NULL DEFAULT '1' COMMENT '是否启用',
+  `expires_at` timestamp NULL DEFAULT NULL COMMENT '密钥过期时间, 为空表示永不过期',
+  `last_used_at` timestamp NULL DEFAULT NULL COMMENT '最后使用时间',
+  `last_used_ip` varchar(45) DEFAULT NULL COMMENT '最后使用的 IP',
+  `created_by` int NOT NULL COMMENT '创建人',
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  PRIMARY KEY (`robot_id`),
+  UNIQUE KEY `uk_robot_uuid` (`robot_uuid`),
+  UNIQUE KEY `uk_robot_name` (`tenant_id`,`name`),
+  UNIQUE KEY `uk_robot_secret_hash` (`secret_hash`),
+  CONSTRAINT `robot_accounts_tenant_fk` FOREIGN KEY (`tenant_id`) REFERENCES `tenants` (`tenant_id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- ----------------------------
+-- Records of robot_accounts
+-- ----------------------------
+BEGIN;
+COMMIT;
+
+-- ----------------------------
+-- Table structure for robot_account_roles
+-- ----------------------------
+DROP TABLE IF EXISTS `robot_account_roles`;
+CREATE TABLE `robot_account_roles` (
+  `robot_id` int NOT NULL,
+  `role_id` int NOT NULL,
+  PRIMARY KEY (`robot_id`, `role_id`),
+  KEY `idx_robot_role_role_id` (`role_id`),
+  CONSTRAINT `fk_robot_role_robot_id` FOREIGN KEY (`robot_id`) REFERENCES `robot_accounts` (`robot_id`) ON DELETE CASCADE,
+  CONSTRAINT `fk_robot_role_role_id` FOREIGN KEY (`role_id`) REFERENCES `roles` (`role_id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- ----------------------------
+-- Records of robot_account_roles
+-- ----------------------------
+BEGIN;
+COMMIT;
+
+-- ----------------------------
+-- Table structure for robot_account_groups
+-- ----------------------------
+DROP TABLE IF EXISTS `robot_account_groups`;
+CREATE TABLE `robot_account_groups` (
+  `robot_id` int NOT NULL,
+  `group_id` int NOT NULL,
+  PRIMARY KEY (`robot_id`, `group_id`),
+  KEY `idx_robot_group_group_id` (`group_id`),
+  CONSTRAINT `fk_robot_group_robot_id` FOREIGN KEY (`robot_id`) REFERENCES `robot_accounts` (`robot_id`) ON DELETE CASCADE,
+  CONSTRAINT `fk_robot_group_group_id` FOREIGN KEY (`group_id`) REFERENCES `user_groups` (`user_group_id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci;
+
+-- ----------------------------
+-- Records of robot_account_groups
+-- ----------------------------
+BEGIN;
+COMMIT;
+
+-- ----------------------------
+-- Table structure for cluster_config
+-- ----------------------------
+DROP TABLE IF EXISTS `cluster_config`;
+CREATE TABLE `cluster_config` (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `created_at` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `updated_at` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  `system_id` int unsigned NOT NULL,
+  `name` varchar(100) COLLATE utf8mb4_general_ci NOT NULL COMMENT '配置名称, 同一系统内唯一',
+  `config` json NOT NULL COMMENT '配置内容, 按所属系统的 config_schema 校验',
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `uk_cluster_config_system_name` (`system_id`, `name`),
+  CONSTRAINT `fk_cluster_config_system_id` FOREIGN KEY (`system_id`) REFERENCES `systems` (`system_id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci COMMENT='受管系统的配置';
+
+-- ----------------------------
+-- Records of cluster_config
+-- ----------------------------
+BEGIN;
+COMMIT;
+
+-- ----------------------------
+-- Table structure for tenants
+-- ----------------------------
+DROP TABLE IF EXISTS `tenants`;
+CREATE TABLE `tenants` (
+  `tenant_id` int NOT NULL AUTO_INCREMENT,
+  `tenant_uuid` char(36) COLLATE utf8mb4_general_ci NOT NULL,
+  `code` varchar(63) COLLATE utf8mb4_general_ci NOT NULL COMMENT '租户编码, 同时用作子域名',
+  `namespace` varchar(64) COLLATE utf8mb4_general_ci DEFAULT NULL COMMENT 'Cedar 命名空间, 默认租户为空',
+  `name` varchar(100) COLLATE utf8mb4_general_ci NOT NULL COMMENT '租户名称',
+  `description` varchar(255) COLLATE utf8mb4_general_ci DEFAULT NULL,
+  `is_active` tinyint(1) NOT NULL DEFAULT '1' COMMENT '是否启用',
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  PRIMARY KEY (`tenant_id`),
+  UNIQUE KEY `uk_tenant_uuid` (`tenant_uuid`),
+  UNIQUE KEY `uk_tenant_code` (`code`),
+  UNIQUE KEY `uk_tenant_namespace` (`namespace`)
+) ENGINE=InnoDB AUTO_INCREMENT=2 DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci COMMENT='租户表, 每个租户有独立的 Cedar 命名空间、Schema 和策略集';
+
+-- ----------------------------
+-- Records of tenants
+-- ----------------------------
+BEGIN;
+INSERT INTO `tenants` (`tenant_id`, `tenant_uuid`, `code`, `namespace`, `name`, `description`, `is_active`, `created_at`, `updated_at`) VALUES (1, 'c8f3b1de-5a0e-4f59-9c0b-2d7e6a4b9f10', 'default', NULL, 'AxumVueAdmin', '默认租户', 1, '2025-09-26 12:00:00', '2025-09-26 12:00:00');
+COMMIT;
+
+-- ----------------------------
+-- Table structure for access_reviews
+-- ----------------------------
+DROP TABLE IF EXISTS `access_reviews`;
+CREATE TABLE `access_reviews` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `review_uuid` char(36) NOT NULL COMMENT '复核活动UUID',
+  `tenant_id` int NOT NULL DEFAULT '1' COMMENT '所属租户',
+  `name` varchar(100) NOT NULL COMMENT '复核活动名称',
+  `description` varchar(500) DEFAULT NULL,
+  `dept_id` int DEFAULT NULL COMMENT '复核范围: 部门及其子部门, 为空表示整个租户',
+  `scope_name` varchar(100) NOT NULL COMMENT '创建时的复核范围名称',
+  `status` varchar(20) NOT NULL DEFAULT 'active' COMMENT 'active: 进行中；completed: 已完成；cancelled: 已取消',
+  `language` varchar(10) NOT NULL DEFAULT 'EN' COMMENT '提醒邮件的语言',
+  `due_at` timestamp NOT NULL COMMENT '截止时间',
+  `created_by` int DEFAULT NULL COMMENT '创建人',
+  `last_reminded_at` timestamp NULL DEFAULT NULL COMMENT '上次提醒复核人的时间',
+  `completed_at` timestamp NULL DEFAULT NULL,
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `uk_access_review_uuid` (`review_uuid`),
+  KEY `idx_access_reviews_status` (`tenant_id`,`status`),
+  KEY `fk_access_review_creator` (`created_by`),
+  CONSTRAINT `fk_access_review_creator` FOREIGN KEY (`created_by`) REFERENCES `users` (`user_id`) ON DELETE SET NULL,
+  CONSTRAINT `access_reviews_tenant_fk` FOREIGN KEY (`tenant_id`) REFERENCES `tenants` (`tenant_id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci COMMENT='访问权限复核活动';
+
+-- ----------------------------
+-- Table structure for access_review_reviewers
+-- ----------------------------
+DROP TABLE IF EXISTS `access_review_reviewers`;
+CREATE TABLE `access_review_reviewers` (
+  `review_id` int NOT NULL,
+  `user_id` int NOT NULL,
+  PRIMARY KEY (`review_id`,`user_id`),
+  KEY `fk_access_review_reviewer_user` (`user_id`),
+  CONSTRAINT `fk_access_review_reviewer_review` FOREIGN KEY (`review_id`) REFERENCES `access_reviews` (`id`) ON DELETE CASCADE,
+  CONSTRAINT `fk_access_review_reviewer_user` FOREIGN KEY (`user_id`) REFERENCES `users` (`user_id`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci COMMENT='复核活动的复核人';
+
+-- ----------------------------
+-- Table structure for access_review_items
+-- ----------------------------
+DROP TABLE IF EXISTS `access_review_items`;
+CREATE TABLE `access_review_items` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `item_uuid` char(36) NOT NULL COMMENT '复核项UUID',
+  `review_id` int NOT NULL,
+  `item_type` varchar(20) NOT NULL COMMENT 'user_role: 用户角色；group_role: 用户组角色；group_member: 用户组成员',
+  `subject_uuid` char(36) NOT NULL COMMENT '用户或用户组UUID',
+  `subject_name` varchar(100) NOT NULL COMMENT '快照时的用户名或用户组名',
+  `target_uuid` char(36) NOT NULL COMMENT '角色或用户组UUID',
+  `target_name` varchar(100) NOT NULL COMMENT '快照时的角色名或用户组名',
+  `decision` varchar(20) NOT NULL DEFAULT 'pending' COMMENT 'pending: 待复核；deciding: 复核中；approved: 保留；revoked: 已撤销',
+  `reviewer_id` int DEFAULT NULL COMMENT '复核人',
+  `reviewer_name` varchar(100) DEFAULT NULL COMMENT '复核时的复核人用户名, 复核人删除后仍保留',
+  `comment` varchar(500) DEFAULT NULL COMMENT '复核意见',
+  `decided_at` timestamp NULL DEFAULT NULL,
+  `created_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `uk_access_review_item_uuid` (`item_uuid`),
+  KEY `idx_access_review_items_decision` (`review_id`,`decision`),
+  KEY `fk_access_review_item_reviewer` (`reviewer_id`),
+  CONSTRAINT `fk_access_review_item_review` FOREIGN KEY (`review_id`) REFERENCES `access_reviews` (`id`) ON DELETE CASCADE,
+  CONSTRAINT `fk_access_review_item_reviewer` FOREIGN KEY (`reviewer_id`) REFERENCES `users` (`user_id`) ON DELETE SET NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_general_ci COMMENT='复核项, 创建复核活动时对角色分配和用户组成员关系的快照';
+
 SET FOREIGN_KEY_CHECKS = 1;
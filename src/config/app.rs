@@ -1,11 +1,29 @@
 // 应用配置
 
 use serde::{Deserialize, Serialize};
+use url::Url;
 use validator::Validate;
 
 // --- 用于Redis的常量 ---
-pub const REDIS_PUB_SUB_CHANNEL: &str = "policy_updates";
+pub const DOMAIN_EVENTS_CHANNEL: &str = "domain_events";
 pub const BLACK_LIST_JTI: &str = "blacklist:jti";
+pub const EMAIL_CHANGE_PREFIX: &str = "email_change";
+pub const TOKENS_VALID_AFTER: &str = "tokens_valid_after";
+pub const PASSWORD_RESET_THROTTLE: &str = "password_reset:throttle";
+pub const SSE_PRESENCE_PREFIX: &str = "sse:presence";
+pub const SSE_NODE_CHANNEL_PREFIX: &str = "sse:node";
+pub const SSE_EVENT_SEQ_PREFIX: &str = "sse:seq";
+pub const SSE_EVENT_LOG_PREFIX: &str = "sse:events";
+pub const SSE_OFFLINE_SINCE_PREFIX: &str = "sse:offline_since";
+pub const SCHEDULED_MESSAGES_QUEUE: &str = "messages:scheduled";
+pub const SCHEDULED_MESSAGES_JOBS: &str = "messages:scheduled:jobs";
+pub const LDAP_SYNC_LOCK: &str = "ldap:sync:lock";
+pub const LDAP_SYNC_CONFLICTS: &str = "ldap:sync:conflicts";
+pub const OIDC_STATE: &str = "oidc:state";
+pub const OIDC_METADATA: &str = "oidc:metadata";
+pub const OAUTH_AUTHORIZE_REQUEST: &str = "oauth:request";
+pub const OAUTH_CODE: &str = "oauth:code";
+pub const OAUTH_REFRESH_TOKEN: &str = "oauth:refresh";
 
 // --------------------
 
@@ -15,6 +33,41 @@ pub struct ServerConfig {
     pub port: u16,
     pub workers: Option<usize>,
     pub timeout_seconds: Option<u64>,
+    /// 前端对外访问地址, 邮件中的链接以此为基础生成
+    #[serde(default = "default_public_url")]
+    #[validate(url)]
+    pub public_url: String,
+}
+
+fn default_public_url() -> String {
+    "http://127.0.0.1:5173".to_string()
+}
+
+impl ServerConfig {
+    /// 基于 public_url 生成前端页面链接, 保留 public_url 中的子路径, 链接始终指向配置的站点
+    pub fn public_link(&self, path: &str) -> Result<Url, url::ParseError> {
+        let mut base = Url::parse(&self.public_url)?;
+        if !base.path().ends_with('/') {
+            let base_path = format!("{}/", base.path());
+            base.set_path(&base_path);
+        }
+        // 去掉开头的斜杠, 避免 //host 形式的路径跳转到其他站点
+        base.join(path.trim_start_matches('/'))
+    }
+
+    /// 站内相对路径, 或指向 public_url 站点的 http(s) 链接
+    pub fn is_public_link(&self, link: &str) -> bool {
+        if link.starts_with('/') {
+            return !link.starts_with("//") && !link.contains('\\');
+        }
+        let (Ok(link), Ok(public)) = (Url::parse(link), Url::parse(&self.public_url)) else {
+            return false;
+        };
+        matches!(link.scheme(), "http" | "https")
+            && link.host_str().is_some()
+            && link.host_str() == public.host_str()
+            && link.port_or_known_default() == public.port_or_known_default()
+    }
 }
 
 
@@ -25,6 +78,58 @@ impl Default for ServerConfig {
             port: 9999,
             workers: Some(4),
             timeout_seconds: Some(30),
+            public_url: default_public_url(),
         }
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(public_url: &str) -> ServerConfig {
+        ServerConfig { public_url: public_url.to_string(), ..ServerConfig::default() }
+    }
+
+    #[test]
+    fn builds_links_on_public_url() {
+        let link = server("https://admin.example.com").public_link("/confirm-email/abc").unwrap();
+        assert_eq!(link.as_str(), "https://admin.example.com/confirm-email/abc");
+    }
+
+    #[test]
+    fn keeps_public_url_sub_path() {
+        for public_url in ["https://example.com/admin", "https://example.com/admin/"] {
+            let link = server(public_url).public_link("/confirm-email/abc").unwrap();
+            assert_eq!(link.as_str(), "https://example.com/admin/confirm-email/abc");
+        }
+    }
+
+    #[test]
+    fn accepts_only_links_on_public_host() {
+        let config = server("https://admin.example.com");
+        for link in ["/messages/1", "/users?page=2", "https://admin.example.com/messages/1"] {
+            assert!(config.is_public_link(link), "{}", link);
+        }
+        for link in [
+            "javascript:alert(1)",
+            "data:text/html,hi",
+            "https://evil.example.net/login",
+            "http://admin.example.com.evil.net/",
+            "https://admin.example.com:8443/messages",
+            "//evil.example.net/login",
+            "/\\evil.example.net/login",
+            "messages/1",
+            "",
+        ] {
+            assert!(!config.is_public_link(link), "{}", link);
+        }
+    }
+
+    #[test]
+    fn stays_on_public_host() {
+        let link = server("https://admin.example.com").public_link("//evil.example.net/confirm-email/abc").unwrap();
+        assert_eq!(link.host_str(), Some("admin.example.com"));
+    }
+}
@@ -4,3 +4,7 @@ pub static JWT_SECRET: &str = "3488a63e1765035d386f05409663f55c83bfae3b3c61a9327
 pub static ACCESS_TOKEN_EXPIRATION: i64 = 900; // 秒 15分钟
 
 pub static REFRESH_TOKEN_EXPIRATION: i64 = 604800; // 秒 七天
+
+pub static EMAIL_CHANGE_EXPIRATION: u64 = 86400; // 秒 一天
+
+pub static INVITATION_EXPIRATION: i64 = 259200; // 秒 三天
@@ -0,0 +1,76 @@
+// 邮件发送配置
+
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// 邮件投递方式
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MailTransport {
+    /// 通过 [smtp] 配置的服务器发送
+    Smtp,
+    /// 写入本地目录的 .eml 文件, 用于开发和测试
+    File,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(default)]
+pub struct EmailConfig {
+    /// 发件人地址
+    #[validate(email)]
+    pub from_address: String,
+    /// 发件人名称
+    pub from_name: String,
+    pub transport: MailTransport,
+    /// transport = "file" 时邮件的写入目录
+    pub file_dir: String,
+    #[validate]
+    pub outbox: OutboxConfig,
+}
+
+/// 发件箱后台任务配置
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(default)]
+pub struct OutboxConfig {
+    /// 轮询发件箱的间隔(秒)
+    #[validate(range(min = 1))]
+    pub poll_interval_seconds: u64,
+    /// 每次取出的邮件数量
+    #[validate(range(min = 1))]
+    pub batch_size: u64,
+    /// 最多尝试发送次数, 超过后进入死信
+    #[validate(range(min = 1))]
+    pub max_attempts: i32,
+    /// 重试基础间隔(秒), 每多失败一次翻倍
+    pub retry_base_seconds: i64,
+    /// 重试最长间隔(秒)
+    pub retry_max_seconds: i64,
+    /// 发送租约(秒), 发送中的邮件超过该时间仍未完成(如实例崩溃)才会重新排队
+    #[validate(range(min = 1))]
+    pub lease_seconds: i64,
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        EmailConfig {
+            from_address: "no-reply@axum-vue-admin.com".to_string(),
+            from_name: "AxumVueAdmin".to_string(),
+            transport: MailTransport::Smtp,
+            file_dir: "mail".to_string(),
+            outbox: OutboxConfig::default(),
+        }
+    }
+}
+
+impl Default for OutboxConfig {
+    fn default() -> Self {
+        OutboxConfig {
+            poll_interval_seconds: 5,
+            batch_size: 20,
+            max_attempts: 5,
+            retry_base_seconds: 30,
+            retry_max_seconds: 3600,
+            lease_seconds: 300,
+        }
+    }
+}
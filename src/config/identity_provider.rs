@@ -0,0 +1,46 @@
+// 作为 OAuth2 / OpenID Connect 身份提供方, 供内部应用登录
+
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(default)]
+pub struct IdentityProviderConfig {
+    pub enabled: bool,
+    /// 签发者, 需与对外访问的 /api/oauth2 地址一致
+    #[validate(url)]
+    pub issuer: String,
+    /// ES256 签名私钥(PKCS#8 PEM), 不存在时自动生成, 多节点部署时需共享同一个文件
+    pub signing_key_path: String,
+    /// 前端授权确认页面
+    pub consent_path: String,
+    /// 授权请求的有效期(秒)
+    #[validate(range(min = 60))]
+    pub request_ttl_seconds: u64,
+    /// 授权码的有效期(秒)
+    #[validate(range(min = 10, max = 600))]
+    pub code_ttl_seconds: u64,
+    #[validate(range(min = 60))]
+    pub access_token_ttl_seconds: u64,
+    #[validate(range(min = 60))]
+    pub id_token_ttl_seconds: u64,
+    /// 申请 offline_access 时签发的 Refresh Token 有效期(秒)
+    #[validate(range(min = 60))]
+    pub refresh_token_ttl_seconds: u64,
+}
+
+impl Default for IdentityProviderConfig {
+    fn default() -> Self {
+        IdentityProviderConfig {
+            enabled: false,
+            issuer: "http://127.0.0.1:9999/api/oauth2".to_string(),
+            signing_key_path: "keys/oauth_signing_key.pem".to_string(),
+            consent_path: "/oauth/consent".to_string(),
+            request_ttl_seconds: 600,
+            code_ttl_seconds: 60,
+            access_token_ttl_seconds: 3600,
+            id_token_ttl_seconds: 3600,
+            refresh_token_ttl_seconds: 30 * 86400,
+        }
+    }
+}
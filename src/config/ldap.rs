@@ -0,0 +1,86 @@
+// LDAP / Active Directory 认证与目录同步配置
+
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(default)]
+pub struct LdapConfig {
+    pub enabled: bool,
+    /// 如 ldap://127.0.0.1:389 或 ldaps://ad.example.com:636
+    pub url: String,
+    pub starttls: bool,
+    /// 用于查询目录的服务账号
+    pub bind_dn: String,
+    pub bind_password: String,
+    pub base_dn: String,
+    /// 用户所在的子树, 为空时使用 base_dn
+    pub user_base_dn: String,
+    pub user_filter: String,
+    /// 登录名属性, Active Directory 为 sAMAccountName
+    pub username_attr: String,
+    pub email_attr: String,
+    pub display_name_attr: String,
+    pub phone_attr: String,
+    /// 用户组所在的子树, 为空时使用 base_dn
+    pub group_base_dn: String,
+    pub group_filter: String,
+    pub group_name_attr: String,
+    pub group_member_attr: String,
+    pub ou_filter: String,
+    pub ou_name_attr: String,
+    /// 唯一标识属性, 条目改名或移动后仍能对应到本地记录, Active Directory 为 objectGUID
+    pub id_attr: String,
+    /// base_dn 对应的本地部门, 为空时使用根部门
+    pub root_dept_uuid: String,
+    /// 同步间隔(秒), 为 0 时不同步
+    pub sync_interval_seconds: u64,
+    /// 目录中已删除的用户在本地禁用
+    pub disable_missing_users: bool,
+    /// 一次同步最多禁用的已关联用户比例(%), 超过时不禁用并报告冲突, 防止目录故障或过滤条件错误导致大批用户被禁用
+    #[validate(range(max = 100))]
+    pub max_disable_percent: u32,
+    #[validate(range(min = 1))]
+    pub timeout_seconds: u64,
+}
+
+impl LdapConfig {
+    pub fn user_base(&self) -> &str {
+        if self.user_base_dn.is_empty() { &self.base_dn } else { &self.user_base_dn }
+    }
+
+    pub fn group_base(&self) -> &str {
+        if self.group_base_dn.is_empty() { &self.base_dn } else { &self.group_base_dn }
+    }
+}
+
+impl Default for LdapConfig {
+    fn default() -> Self {
+        LdapConfig {
+            enabled: false,
+            url: "ldap://127.0.0.1:389".to_string(),
+            starttls: false,
+            bind_dn: "cn=admin,dc=example,dc=org".to_string(),
+            bind_password: String::new(),
+            base_dn: "dc=example,dc=org".to_string(),
+            user_base_dn: String::new(),
+            user_filter: "(objectClass=inetOrgPerson)".to_string(),
+            username_attr: "uid".to_string(),
+            email_attr: "mail".to_string(),
+            display_name_attr: "cn".to_string(),
+            phone_attr: "telephoneNumber".to_string(),
+            group_base_dn: String::new(),
+            group_filter: "(objectClass=groupOfNames)".to_string(),
+            group_name_attr: "cn".to_string(),
+            group_member_attr: "member".to_string(),
+            ou_filter: "(objectClass=organizationalUnit)".to_string(),
+            ou_name_attr: "ou".to_string(),
+            id_attr: "entryUUID".to_string(),
+            root_dept_uuid: String::new(),
+            sync_interval_seconds: 3600,
+            disable_missing_users: true,
+            max_disable_percent: 20,
+            timeout_seconds: 10,
+        }
+    }
+}
@@ -12,6 +12,17 @@ pub mod redis;
 pub mod database;
 pub mod logging;
 pub mod smtp;
+pub mod email;
+pub mod security;
+pub mod rate_limit;
+pub mod storage;
+pub mod webhook;
+pub mod scim;
+pub mod ldap;
+pub mod oidc;
+pub mod identity_provider;
+pub mod personal_token;
+pub mod tenant;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
@@ -21,6 +32,28 @@ pub struct AppConfig {
     pub redis: redis::RedisConfig,
     pub log: logging::LogConfig,
     pub smtp: smtp::SmtpConfig,
+    #[serde(default)]
+    pub email: email::EmailConfig,
+    #[serde(default)]
+    pub security: security::SecurityConfig,
+    #[serde(default)]
+    pub rate_limit: rate_limit::RateLimitConfig,
+    #[serde(default)]
+    pub storage: storage::StorageConfig,
+    #[serde(default)]
+    pub webhook: webhook::WebhookConfig,
+    #[serde(default)]
+    pub scim: scim::ScimConfig,
+    #[serde(default)]
+    pub ldap: ldap::LdapConfig,
+    #[serde(default)]
+    pub oidc: oidc::OidcConfig,
+    #[serde(default)]
+    pub identity_provider: identity_provider::IdentityProviderConfig,
+    #[serde(default)]
+    pub personal_token: personal_token::PersonalTokenConfig,
+    #[serde(default)]
+    pub tenant: tenant::TenantConfig,
 }
 
 
@@ -46,6 +79,17 @@ impl AppConfig {
         self.database.validate()?;
         self.redis.validate()?;
         self.log.validate()?;
+        self.email.validate()?;
+        self.security.validate()?;
+        self.rate_limit.validate()?;
+        self.storage.validate()?;
+        self.webhook.validate()?;
+        self.scim.validate()?;
+        self.ldap.validate()?;
+        self.oidc.validate()?;
+        self.identity_provider.validate()?;
+        self.personal_token.validate()?;
+        self.tenant.validate()?;
         Ok(())
     }
 
@@ -64,6 +108,17 @@ impl Default for AppConfig {
             redis:  redis::RedisConfig::default(),
             log: logging::LogConfig::default(),
             smtp: smtp::SmtpConfig::default(),
+            email: email::EmailConfig::default(),
+            security: security::SecurityConfig::default(),
+            rate_limit: rate_limit::RateLimitConfig::default(),
+            storage: storage::StorageConfig::default(),
+            webhook: webhook::WebhookConfig::default(),
+            scim: scim::ScimConfig::default(),
+            ldap: ldap::LdapConfig::default(),
+            oidc: oidc::OidcConfig::default(),
+            identity_provider: identity_provider::IdentityProviderConfig::default(),
+            personal_token: personal_token::PersonalTokenConfig::default(),
+            tenant: tenant::TenantConfig::default(),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,118 @@
+// OpenID Connect 单点登录配置
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use validator::Validate;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(default)]
+pub struct OidcConfig {
+    /// 登录请求(state、PKCE)的有效期(秒)
+    #[validate(range(min = 60))]
+    pub state_ttl_seconds: u64,
+    /// 发现文档和 JWKS 的缓存时间(秒)
+    pub metadata_cache_seconds: u64,
+    /// 请求身份提供方的超时(秒)
+    #[validate(range(min = 1))]
+    pub timeout_seconds: u64,
+    #[validate]
+    pub providers: Vec<OidcProviderConfig>,
+}
+
+impl Default for OidcConfig {
+    fn default() -> Self {
+        OidcConfig {
+            state_ttl_seconds: 600,
+            metadata_cache_seconds: 3600,
+            timeout_seconds: 10,
+            providers: Vec::new(),
+        }
+    }
+}
+
+impl OidcConfig {
+    pub fn provider(&self, name: &str) -> Option<&OidcProviderConfig> {
+        self.providers.iter().find(|provider| provider.name == name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(default)]
+pub struct OidcProviderConfig {
+    /// 路由中使用的标识, 如 keycloak
+    #[validate(length(min = 1, max = 64))]
+    pub name: String,
+    /// 登录页显示的名称
+    pub display_name: String,
+    /// 签发者, 发现文档位于 {issuer}/.well-known/openid-configuration
+    #[validate(url)]
+    pub issuer: String,
+    #[validate(length(min = 1))]
+    pub client_id: String,
+    pub client_secret: String,
+    /// 前端的回调地址, 需要在身份提供方注册
+    #[validate(url)]
+    pub redirect_uri: String,
+    pub scopes: Vec<String>,
+    /// 首次登录时自动创建用户
+    pub auto_create_users: bool,
+    /// 按已验证的邮箱关联已有的本地用户
+    pub link_by_email: bool,
+    /// 新用户默认所在部门, 为空时放在根部门
+    pub default_dept_uuid: String,
+    pub claims: OidcClaimMapping,
+    /// 部门声明的值 -> 部门UUID, 未配置的值按部门名称匹配
+    pub department_mapping: HashMap<String, String>,
+    /// 用户组声明的值 -> 用户组名称, 只同步配置中的用户组
+    pub group_mapping: HashMap<String, String>,
+    /// 角色声明的值 -> 角色名称, 只同步配置中的角色
+    pub role_mapping: HashMap<String, String>,
+}
+
+impl Default for OidcProviderConfig {
+    fn default() -> Self {
+        OidcProviderConfig {
+            name: String::new(),
+            display_name: String::new(),
+            issuer: String::new(),
+            client_id: String::new(),
+            client_secret: String::new(),
+            redirect_uri: String::new(),
+            scopes: vec!["openid".to_string(), "profile".to_string(), "email".to_string()],
+            auto_create_users: true,
+            link_by_email: false,
+            default_dept_uuid: String::new(),
+            claims: OidcClaimMapping::default(),
+            department_mapping: HashMap::new(),
+            group_mapping: HashMap::new(),
+            role_mapping: HashMap::new(),
+        }
+    }
+}
+
+/// 声明名称, 支持 realm_access.roles 这样的嵌套路径, 为空时不使用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OidcClaimMapping {
+    pub username: String,
+    pub email: String,
+    pub display_name: String,
+    pub phone: String,
+    pub department: String,
+    pub groups: String,
+    pub roles: String,
+}
+
+impl Default for OidcClaimMapping {
+    fn default() -> Self {
+        OidcClaimMapping {
+            username: "preferred_username".to_string(),
+            email: "email".to_string(),
+            display_name: "name".to_string(),
+            phone: "phone_number".to_string(),
+            department: String::new(),
+            groups: "groups".to_string(),
+            roles: "roles".to_string(),
+        }
+    }
+}
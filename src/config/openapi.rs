@@ -10,6 +10,17 @@ pub const ROLE_TAG: &str = "Role";
 pub const DEPARTMENT_TAG: &str = "Department";
 pub const ME_TAG: &str = "Me";
 pub const GROUP_TAG: &str = "Group";
+pub const FILE_TAG: &str = "File";
+pub const INVITATION_TAG: &str = "Invitation";
+pub const NOTIFICATION_TAG: &str = "Notification";
+pub const MESSAGE_TAG: &str = "Message";
+pub const WEBHOOK_TAG: &str = "Webhook";
+pub const SCIM_TAG: &str = "SCIM";
+pub const OAUTH_TAG: &str = "OAuth2";
+pub const SYSTEM_TAG: &str = "System";
+pub const TENANT_TAG: &str = "Tenant";
+pub const ROLE_REQUEST_TAG: &str = "RoleRequest";
+pub const ACCESS_REVIEW_TAG: &str = "AccessReview";
 
 pub const CEDAR_POLICY_TAG: &str = "Cedar Policy";
 
@@ -31,6 +42,18 @@ pub const ROBOT_CLUSTER: &str = "RobotCluster";
         (name = DEPARTMENT_TAG, description = "Department API endpoints"),
         (name = ME_TAG, description = "User Profile API endpoints"),
         (name = CEDAR_POLICY_TAG, description = "Cedar Policy API endpoints"),
+        (name = FILE_TAG, description = "File API endpoints"),
+        (name = INVITATION_TAG, description = "User Invitation API endpoints"),
+        (name = NOTIFICATION_TAG, description = "Notification API endpoints"),
+        (name = MESSAGE_TAG, description = "Message API endpoints"),
+        (name = WEBHOOK_TAG, description = "Webhook API endpoints"),
+        (name = SCIM_TAG, description = "SCIM 2.0 provisioning endpoints"),
+        (name = OAUTH_TAG, description = "OAuth2 / OpenID Connect identity provider endpoints"),
+        (name = ROBOT_ACCOUNT, description = "Robot service account API endpoints"),
+        (name = SYSTEM_TAG, description = "Managed system registry API endpoints"),
+        (name = TENANT_TAG, description = "Tenant management API endpoints"),
+        (name = ROLE_REQUEST_TAG, description = "Role request and approval API endpoints"),
+        (name = ACCESS_REVIEW_TAG, description = "Access review campaign API endpoints"),
     ),
     modifiers(&SecurityAddon),
     security(
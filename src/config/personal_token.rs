@@ -0,0 +1,28 @@
+// 个人访问令牌, 供脚本和 CI 调用接口
+
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(default)]
+pub struct PersonalTokenConfig {
+    pub enabled: bool,
+    /// 每个用户最多持有的令牌数量
+    #[validate(range(min = 1))]
+    pub max_tokens_per_user: u64,
+    /// 最长有效期(天), 为 0 时允许创建永不过期的令牌
+    pub max_expires_in_days: u32,
+    /// 最后使用时间的更新间隔(秒), 避免每次请求都写数据库
+    pub last_used_update_interval_seconds: i64,
+}
+
+impl Default for PersonalTokenConfig {
+    fn default() -> Self {
+        PersonalTokenConfig {
+            enabled: true,
+            max_tokens_per_user: 20,
+            max_expires_in_days: 365,
+            last_used_update_interval_seconds: 60,
+        }
+    }
+}
@@ -0,0 +1,150 @@
+// 限流配置
+
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+// --- 用于Redis的常量 ---
+pub const RATE_LIMIT_PREFIX: &str = "rate_limit";
+
+// --------------------
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    /// 未匹配到路由组时使用的默认限额
+    #[validate]
+    pub default: RateLimitRule,
+    /// 按路由前缀配置的限额, 匹配最长前缀
+    #[validate]
+    pub routes: Vec<RouteRateLimit>,
+}
+
+/// 令牌桶参数
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct RateLimitRule {
+    /// 桶容量(允许的突发请求数)
+    #[validate(range(min = 1))]
+    pub capacity: u32,
+    /// 每秒补充的令牌数
+    #[validate(range(min = 0.001))]
+    pub refill_per_second: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct RouteRateLimit {
+    /// 完整请求路径前缀, 如 /api/v1/auth
+    pub prefix: String,
+    #[validate(range(min = 1))]
+    pub capacity: u32,
+    #[validate(range(min = 0.001))]
+    pub refill_per_second: f64,
+}
+
+impl RateLimitConfig {
+    /// 返回匹配的路由组名称和限额
+    pub fn rule_for(&self, path: &str) -> (&str, RateLimitRule) {
+        self.routes
+            .iter()
+            .filter(|r| matches_prefix(path, &r.prefix))
+            .max_by_key(|r| r.prefix.len())
+            .map(|r| {
+                (
+                    r.prefix.as_str(),
+                    RateLimitRule {
+                        capacity: r.capacity,
+                        refill_per_second: r.refill_per_second,
+                    },
+                )
+            })
+            .unwrap_or(("default", self.default.clone()))
+    }
+}
+
+// 按路径段匹配前缀, /api/v1/auth 匹配 /api/v1/auth 和 /api/v1/auth/login, 不匹配 /api/v1/authx
+pub(crate) fn matches_prefix(path: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    match path.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        RateLimitConfig {
+            enabled: true,
+            default: RateLimitRule {
+                capacity: 120,
+                refill_per_second: 2.0,
+            },
+            routes: vec![
+                RouteRateLimit {
+                    prefix: "/api/v1/auth".to_string(),
+                    capacity: 10,
+                    refill_per_second: 0.2,
+                },
+                RouteRateLimit {
+                    prefix: "/api/v1/password-resets".to_string(),
+                    capacity: 5,
+                    refill_per_second: 0.05,
+                },
+            ],
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RateLimitConfig {
+        let mut config = RateLimitConfig::default();
+        config.routes.push(RouteRateLimit {
+            prefix: "/api/v1/auth/login".to_string(),
+            capacity: 3,
+            refill_per_second: 0.1,
+        });
+        config.routes.push(RouteRateLimit {
+            prefix: "/api/v1/files/".to_string(),
+            capacity: 30,
+            refill_per_second: 1.0,
+        });
+        config
+    }
+
+    #[test]
+    fn picks_longest_matching_prefix() {
+        let config = config();
+        let (group, rule) = config.rule_for("/api/v1/auth/login");
+        assert_eq!(group, "/api/v1/auth/login");
+        assert_eq!(rule.capacity, 3);
+
+        let (group, rule) = config.rule_for("/api/v1/auth/refresh");
+        assert_eq!(group, "/api/v1/auth");
+        assert_eq!(rule.capacity, 10);
+    }
+
+    #[test]
+    fn falls_back_to_default() {
+        let config = config();
+        let (group, rule) = config.rule_for("/api/v1/users");
+        assert_eq!(group, "default");
+        assert_eq!(rule.capacity, config.default.capacity);
+    }
+
+    #[test]
+    fn matches_on_segment_boundaries() {
+        let config = config();
+        assert_eq!(config.rule_for("/api/v1/auth").0, "/api/v1/auth");
+        assert_eq!(config.rule_for("/api/v1/auth/").0, "/api/v1/auth");
+        assert_eq!(config.rule_for("/api/v1/authx").0, "default");
+        assert_eq!(config.rule_for("/api/v1/auth-providers/oidc").0, "default");
+        assert_eq!(config.rule_for("/api/v1/auth/loginx").0, "/api/v1/auth");
+        // 配置的前缀带结尾斜杠时同样按路径段匹配
+        assert_eq!(config.rule_for("/api/v1/files").0, "/api/v1/files/");
+        assert_eq!(config.rule_for("/api/v1/files/abc").0, "/api/v1/files/");
+        assert_eq!(config.rule_for("/api/v1/filesystem").0, "default");
+    }
+}
@@ -0,0 +1,29 @@
+// SCIM 2.0 用户同步配置
+
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(default)]
+pub struct ScimConfig {
+    /// 是否开启 SCIM 接口
+    pub enabled: bool,
+    /// 身份提供方调用时携带的 Bearer 令牌
+    pub bearer_token: String,
+    /// 新用户默认所在部门, 为空时放在根部门
+    pub default_dept_uuid: String,
+    /// 列表接口单页最大数量
+    #[validate(range(min = 1))]
+    pub max_results: u64,
+}
+
+impl Default for ScimConfig {
+    fn default() -> Self {
+        ScimConfig {
+            enabled: false,
+            bearer_token: String::new(),
+            default_dept_uuid: String::new(),
+            max_results: 200,
+        }
+    }
+}
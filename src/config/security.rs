@@ -0,0 +1,135 @@
+// 安全相关配置
+
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+use crate::utils::net::parse_ip_range;
+
+// --- 用于Redis的常量 ---
+pub const LOGIN_FAILURES_USER: &str = "login:failures:user";
+pub const LOGIN_FAILURES_IP: &str = "login:failures:ip";
+pub const LOGIN_LOCK_USER: &str = "login:lock:user";
+pub const LOGIN_LOCK_IP: &str = "login:lock:ip";
+
+// 静态数据加密密钥的环境变量, 优先于配置文件
+pub const DATA_ENCRYPTION_KEY_ENV: &str = "DATA_ENCRYPTION_KEY";
+
+// --------------------
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, Default)]
+#[serde(default)]
+pub struct SecurityConfig {
+    #[validate]
+    pub login: LoginProtectionConfig,
+    #[validate]
+    pub password: PasswordPolicyConfig,
+    #[validate]
+    pub password_reset: PasswordResetConfig,
+    /// 静态数据(如发件箱中的邮件正文)的加密密钥, 64 位十六进制(32 字节)
+    /// 也可以通过环境变量 DATA_ENCRYPTION_KEY 提供, 未配置时服务拒绝启动
+    pub data_encryption_key: String,
+    /// 受信任的反向代理地址或网段(CIDR), 只有来自这些地址的请求才按 X-Forwarded-For 确定客户端 IP
+    /// 为空时始终使用连接的对端地址
+    #[validate(custom = "validate_trusted_proxies")]
+    pub trusted_proxies: Vec<String>,
+}
+
+fn validate_trusted_proxies(trusted_proxies: &Vec<String>) -> Result<(), ValidationError> {
+    if trusted_proxies.iter().all(|range| parse_ip_range(range).is_some()) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("invalid_trusted_proxy"))
+    }
+}
+
+/// 登录防爆破配置
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(default)]
+pub struct LoginProtectionConfig {
+    /// 同一用户名连续失败多少次后锁定
+    #[validate(range(min = 1))]
+    pub max_username_failures: u32,
+    /// 同一IP连续失败多少次后锁定
+    #[validate(range(min = 1))]
+    pub max_ip_failures: u32,
+    /// 同一用户名失败多少次之后开始退避, IP 维度只按 max_ip_failures 锁定
+    pub backoff_after: u32,
+    /// 退避基础时长(秒), 每多失败一次翻倍
+    pub backoff_base_seconds: u64,
+    /// 退避最长时长(秒)
+    pub backoff_max_seconds: u64,
+    /// 达到失败上限后的锁定时长(秒)
+    pub lockout_seconds: u64,
+    /// 失败计数的统计窗口(秒)
+    pub failure_window_seconds: u64,
+}
+
+impl Default for LoginProtectionConfig {
+    fn default() -> Self {
+        LoginProtectionConfig {
+            max_username_failures: 10,
+            max_ip_failures: 50,
+            backoff_after: 3,
+            backoff_base_seconds: 2,
+            backoff_max_seconds: 300,
+            lockout_seconds: 900,
+            failure_window_seconds: 3600,
+        }
+    }
+}
+
+/// 密码策略配置
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(default)]
+pub struct PasswordPolicyConfig {
+    #[validate(range(min = 1))]
+    pub min_length: usize,
+    pub max_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    /// 最低强度评分(0-4)
+    #[validate(range(max = 4))]
+    pub min_strength_score: u8,
+    /// 禁止重复使用最近 N 次的密码, 0 表示不限制
+    pub history_count: u32,
+    /// 密码最长有效天数, 过期后下次登录必须修改, 0 表示不限制
+    pub max_age_days: u32,
+}
+
+impl Default for PasswordPolicyConfig {
+    fn default() -> Self {
+        PasswordPolicyConfig {
+            min_length: 8,
+            max_length: 128,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_symbol: false,
+            min_strength_score: 2,
+            history_count: 5,
+            max_age_days: 0,
+        }
+    }
+}
+
+/// 密码重置配置
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(default)]
+pub struct PasswordResetConfig {
+    /// 重置链接有效期(分钟)
+    #[validate(range(min = 1))]
+    pub token_expiration_minutes: i64,
+    /// 同一邮箱两次申请重置的最小间隔(秒)
+    pub throttle_seconds: u64,
+}
+
+impl Default for PasswordResetConfig {
+    fn default() -> Self {
+        PasswordResetConfig {
+            token_expiration_minutes: 30,
+            throttle_seconds: 60,
+        }
+    }
+}
@@ -2,11 +2,10 @@ use crate::errors::app_error::AppError;
 use crate::services::cache::CacheService;
 use crate::services::cedar_auth::CedarAuthService;
 use crate::services::email::EmailService;
+use crate::services::storage::StorageService;
 use crate::services::policy_link_manager::PolicyLinkManager;
-use crate::utils::function::{
-    load_active_policies_and_templates, load_active_schema, load_all_template_links,
-};
-use cedar_policy::{Entities, PolicySet, Schema};
+use crate::utils::crypto::init_data_key;
+use crate::utils::function::load_tenants_authz;
 use sea_orm::{ConnectOptions, Database, DatabaseConnection};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -14,8 +13,10 @@ use std::time::Duration;
 use tokio::sync::{mpsc, Mutex};
 use tracing::info;
 use crate::config::AppConfig;
+use crate::utils::sse::SseMessage;
 
-pub type SSESenders = Arc<Mutex<HashMap<String, mpsc::Sender<String>>>>;
+// 用户UUID -> 连接ID -> sender, 同一用户可以同时有多个连接
+pub type SSESenders = Arc<Mutex<HashMap<String, HashMap<String, mpsc::Sender<SseMessage>>>>>;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -24,14 +25,18 @@ pub struct AppState {
     pub auth_service: Arc<CedarAuthService>,
     pub cache_service: Arc<CacheService>,
     pub email_service: Arc<EmailService>,
+    pub storage_service: Arc<StorageService>,
     pub sse_senders: SSESenders, // 这个SSE对象可以在全局Handler中对用户发送消息
     pub policy_link_manager: Arc<PolicyLinkManager>,
+    pub node_id: String, // 当前节点的唯一标识, 用于多节点之间转发SSE消息
+    pub config: Arc<AppConfig>,
 }
 
 impl AppState {
     pub async fn new(
         config: &AppConfig
     ) -> Result<Self, AppError> {
+        init_data_key(&config.security.data_encryption_key)?;
 
         let mut opt = ConnectOptions::new(&config.database.url);
         opt.max_connections(100)
@@ -48,28 +53,23 @@ impl AppState {
         let redis = redis::Client::open(config.redis.url.clone()).expect("Failed to connect to redis client");
 
 
-        let schema = load_active_schema(&db).await?;
-
-        let cache_service = Arc::new(CacheService::new(redis.clone(), schema.clone()));
+        let cache_service = Arc::new(CacheService::new(redis.clone()));
         let auth_service = Arc::new(CedarAuthService::new(
             cache_service.clone(),
-            schema.clone(),
+            HashMap::new(),
         ));
 
-        let initial_policies = load_active_policies_and_templates(&db).await?;
-        auth_service.update_policies_and_templates_in_cache(&initial_policies).await?;
-        info!("已成功加载并缓存策略和模板。");
-
-        let initial_link_records = load_all_template_links(&db).await?;
-        auth_service.update_template_link_records_in_cache(&initial_link_records).await?;
-        info!("成功加载并缓存 {} 个模板链接。", initial_link_records.len());
+        let tenants = load_tenants_authz(&db, &auth_service).await?;
+        info!("已成功加载并缓存 {} 个租户的 Schema、策略、模板和模板链接。", tenants.len());
+        auth_service.update_tenants(tenants).await;
 
         let policy_link_manager = Arc::new(PolicyLinkManager::new(
             db.clone(),
             auth_service.clone(),
         ));
 
-        let email_service = Arc::new(EmailService::new(&config.smtp));
+        let email_service = Arc::new(EmailService::new(&config.smtp, &config.email, db.clone())?);
+        let storage_service = Arc::new(StorageService::new(&config.storage)?);
         
         let app_state = Self {
             db,
@@ -77,8 +77,11 @@ impl AppState {
             auth_service,
             cache_service,
             email_service,
+            storage_service,
             sse_senders: Arc::new(Mutex::new(HashMap::new())),
-            policy_link_manager
+            policy_link_manager,
+            node_id: uuid::Uuid::new_v4().to_string(),
+            config: Arc::new(config.clone()),
         };
         Ok(app_state)
     }
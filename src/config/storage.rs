@@ -0,0 +1,91 @@
+// 文件存储配置
+
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    Local,
+    S3,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(default)]
+pub struct StorageConfig {
+    pub backend: StorageBackend,
+    /// 单个上传文件的最大字节数
+    #[validate(range(min = 1))]
+    pub max_upload_bytes: usize,
+    /// 允许上传的文件类型
+    pub allowed_content_types: Vec<String>,
+    /// 头像边长(像素)
+    pub avatar_size: u32,
+    /// 缩略图边长(像素)
+    pub thumbnail_size: u32,
+    /// 签名下载链接有效期(秒)
+    pub signed_url_expiration: u64,
+    pub local: LocalStorageConfig,
+    pub s3: S3StorageConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LocalStorageConfig {
+    /// 本地存储根目录
+    pub root: String,
+}
+
+/// S3 兼容存储(AWS S3、MinIO 等)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct S3StorageConfig {
+    /// 自定义Endpoint, MinIO 如 http://127.0.0.1:9000
+    pub endpoint: Option<String>,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub allow_http: bool,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig {
+            backend: StorageBackend::Local,
+            max_upload_bytes: 5 * 1024 * 1024,
+            allowed_content_types: vec![
+                "image/png".to_string(),
+                "image/jpeg".to_string(),
+                "image/gif".to_string(),
+                "image/webp".to_string(),
+            ],
+            avatar_size: 256,
+            thumbnail_size: 64,
+            signed_url_expiration: 3600,
+            local: LocalStorageConfig::default(),
+            s3: S3StorageConfig::default(),
+        }
+    }
+}
+
+impl Default for LocalStorageConfig {
+    fn default() -> Self {
+        LocalStorageConfig {
+            root: "uploads".to_string(),
+        }
+    }
+}
+
+impl Default for S3StorageConfig {
+    fn default() -> Self {
+        S3StorageConfig {
+            endpoint: Some("http://127.0.0.1:9000".to_string()),
+            bucket: "axum-vue-admin".to_string(),
+            region: "us-east-1".to_string(),
+            access_key_id: "minioadmin".to_string(),
+            secret_access_key: "minioadmin".to_string(),
+            allow_http: true,
+        }
+    }
+}
@@ -0,0 +1,23 @@
+// 多租户: 按请求头或子域名识别租户
+
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(default)]
+pub struct TenantConfig {
+    /// 指定租户编码的请求头, 优先于子域名
+    #[validate(length(min = 1))]
+    pub header_name: String,
+    /// 租户子域名的上级域名, 例如 admin.example.com 时 acme.admin.example.com 解析为租户 acme; 为空时不按子域名识别
+    pub base_domain: Option<String>,
+}
+
+impl Default for TenantConfig {
+    fn default() -> Self {
+        TenantConfig {
+            header_name: "X-Tenant".to_string(),
+            base_domain: None,
+        }
+    }
+}
@@ -0,0 +1,45 @@
+// Webhook 投递配置
+
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[serde(default)]
+pub struct WebhookConfig {
+    /// 轮询待投递记录的间隔(秒)
+    #[validate(range(min = 1))]
+    pub poll_interval_seconds: u64,
+    /// 每次取出的投递数量
+    #[validate(range(min = 1))]
+    pub batch_size: u64,
+    /// 最多尝试投递次数, 超过后不再重试
+    #[validate(range(min = 1))]
+    pub max_attempts: i32,
+    /// 重试基础间隔(秒), 每多失败一次翻倍
+    pub retry_base_seconds: i64,
+    /// 重试最长间隔(秒)
+    pub retry_max_seconds: i64,
+    /// 单次请求超时(秒)
+    #[validate(range(min = 1))]
+    pub timeout_seconds: u64,
+    /// 发送租约(秒), 发送中的记录超过该时间仍未完成(如实例崩溃)才会重新排队
+    #[validate(range(min = 1))]
+    pub lease_seconds: i64,
+    /// 是否允许投递到回环、私有和链路本地地址, 仅用于本地开发
+    pub allow_private_targets: bool,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        WebhookConfig {
+            poll_interval_seconds: 5,
+            batch_size: 20,
+            max_attempts: 8,
+            retry_base_seconds: 30,
+            retry_max_seconds: 6 * 3600,
+            timeout_seconds: 10,
+            lease_seconds: 300,
+            allow_private_targets: false,
+        }
+    }
+}
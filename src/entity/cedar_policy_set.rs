@@ -14,11 +14,11 @@ pub struct Model {
     #[sea_orm(custom_type = "i8")]
     pub is_active: bool,
     pub description: String,
-    #[sea_orm(unique)]
     pub policy_hash: String,
     pub policy_type: String,
     #[sea_orm(unique)]
     pub policy_uuid: String,
+    pub tenant_id: i32,
     pub created_by: i32,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
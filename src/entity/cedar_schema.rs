@@ -8,6 +8,7 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub schema_id: i32,
     pub schema_uuid: String,
+    pub tenant_id: i32,
     pub schema: String,
     pub description: String,
     #[sea_orm(custom_type="i8")]
@@ -9,11 +9,27 @@ pub struct Model {
     pub id: i64,
     pub created_at: DateTime,
     pub updated_at: DateTime,
+    pub system_id: u32,
     pub name: String,
     pub config: Json,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::systems::Entity",
+        from = "Column::SystemId",
+        to = "super::systems::Column::SystemId",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Systems,
+}
+
+impl Related<super::systems::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Systems.def()
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -9,6 +9,7 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub dept_id: i32,
     pub dept_uuid: String,
+    pub tenant_id: i32,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
     pub name: String,
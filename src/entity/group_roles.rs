@@ -9,6 +9,9 @@ pub struct Model {
     pub group_id: i32,
     #[sea_orm(primary_key, auto_increment = false)]
     pub role_id: i32,
+    pub valid_from: Option<DateTimeUtc>,
+    pub valid_until: Option<DateTimeUtc>,
+    pub activated_at: Option<DateTimeUtc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
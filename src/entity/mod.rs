@@ -2,16 +2,35 @@
 
 pub mod prelude;
 
+pub mod access_review_items;
+pub mod access_review_reviewers;
+pub mod access_reviews;
 pub mod auditlog;
 pub mod cluster_config;
 pub mod departments;
+pub mod directory_links;
+pub mod email_outbox;
 pub mod group_roles;
+pub mod notifications;
+pub mod oauth_clients;
+pub mod oauth_consents;
+pub mod personal_access_tokens;
+pub mod robot_account_groups;
+pub mod robot_account_roles;
+pub mod robot_accounts;
+pub mod role_parents;
+pub mod role_requests;
 pub mod roles;
 pub mod systems;
+pub mod tenants;
 pub mod user_group_members;
 pub mod user_groups;
+pub mod user_invitations;
+pub mod user_password_history;
 pub mod user_roles;
 pub mod users;
+pub mod webhook_deliveries;
+pub mod webhooks;
 pub mod cedar_policy_set;
 pub mod cedar_schema;
 pub mod template_links;
\ No newline at end of file
@@ -1,14 +1,34 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.0
 
+pub use super::access_review_items::Entity as AccessReviewItems;
+pub use super::access_review_reviewers::Entity as AccessReviewReviewers;
+pub use super::access_reviews::Entity as AccessReviews;
 pub use super::auditlog::Entity as Auditlog;
 pub use super::cedar_policy_set::Entity as CedarPolicySet;
 pub use super::template_links::Entity as TemplateLinks;
 pub use super::cedar_schema::Entity as CedarSchema;
+pub use super::cluster_config::Entity as ClusterConfig;
 pub use super::departments::Entity as Departments;
+pub use super::directory_links::Entity as DirectoryLinks;
+pub use super::email_outbox::Entity as EmailOutbox;
 pub use super::group_roles::Entity as GroupRoles;
+pub use super::notifications::Entity as Notifications;
+pub use super::oauth_clients::Entity as OauthClients;
+pub use super::oauth_consents::Entity as OauthConsents;
+pub use super::personal_access_tokens::Entity as PersonalAccessTokens;
+pub use super::robot_account_groups::Entity as RobotAccountGroups;
+pub use super::robot_account_roles::Entity as RobotAccountRoles;
+pub use super::robot_accounts::Entity as RobotAccounts;
+pub use super::role_parents::Entity as RoleParents;
+pub use super::role_requests::Entity as RoleRequests;
 pub use super::roles::Entity as Roles;
 pub use super::systems::Entity as Systems;
+pub use super::tenants::Entity as Tenants;
 pub use super::user_group_members::Entity as UserGroupMembers;
 pub use super::user_groups::Entity as UserGroups;
+pub use super::user_invitations::Entity as UserInvitations;
+pub use super::user_password_history::Entity as UserPasswordHistory;
 pub use super::user_roles::Entity as UserRoles;
-pub use super::users::Entity as Users;
\ No newline at end of file
+pub use super::users::Entity as Users;
+pub use super::webhook_deliveries::Entity as WebhookDeliveries;
+pub use super::webhooks::Entity as Webhooks;
\ No newline at end of file
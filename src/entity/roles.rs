@@ -8,16 +8,19 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub role_id: i32,
     pub role_uuid: String,
-    #[sea_orm(unique)]
+    pub tenant_id: i32,
     pub role_name: String,
     pub description: Option<String>,
     pub created_at: DateTimeUtc,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+#[allow(clippy::enum_variant_names)]
 pub enum Relation {
     #[sea_orm(has_many = "super::group_roles::Entity")]
     GroupRoles,
+    #[sea_orm(has_many = "super::robot_account_roles::Entity")]
+    RobotAccountRoles,
     #[sea_orm(has_many = "super::user_roles::Entity")]
     UserRoles,
 }
@@ -28,6 +31,12 @@ impl Related<super::group_roles::Entity> for Entity {
     }
 }
 
+impl Related<super::robot_account_roles::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RobotAccountRoles.def()
+    }
+}
+
 impl Related<super::user_roles::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::UserRoles.def()
@@ -7,14 +7,30 @@ use sea_orm::entity::prelude::*;
 pub struct Model {
     #[sea_orm(primary_key)]
     pub system_id: u32,
+    #[sea_orm(unique)]
+    pub system_uuid: String,
+    pub tenant_id: i32,
+    pub code: String,
     pub name: String,
     #[sea_orm(column_type = "Text", nullable)]
     pub description: Option<String>,
+    pub base_url: Option<String>,
+    pub config_schema: Option<Json>,
+    pub is_active: bool,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(has_many = "super::cluster_config::Entity")]
+    ClusterConfig,
+}
+
+impl Related<super::cluster_config::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ClusterConfig.def()
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -8,6 +8,7 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub link_id: i32,
     pub link_uuid: String,
+    pub tenant_id: i32,
     pub template_uuid: String,
     pub principal_uid: String,
     pub resource_uid: String,
@@ -8,6 +8,7 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub user_group_id: i32,
     pub user_group_uuid: String,
+    pub tenant_id: i32,
     pub name: String,
     #[sea_orm(column_type = "Text", nullable)]
     pub description: Option<String>,
@@ -19,6 +20,8 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(has_many = "super::group_roles::Entity")]
     GroupRoles,
+    #[sea_orm(has_many = "super::robot_account_groups::Entity")]
+    RobotAccountGroups,
     #[sea_orm(has_many = "super::user_group_members::Entity")]
     UserGroupMembers,
 }
@@ -29,6 +32,12 @@ impl Related<super::group_roles::Entity> for Entity {
     }
 }
 
+impl Related<super::robot_account_groups::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RobotAccountGroups.def()
+    }
+}
+
 impl Related<super::user_group_members::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::UserGroupMembers.def()
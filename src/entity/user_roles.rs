@@ -10,6 +10,9 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub role_id: i32,
     pub assigned_at: DateTimeUtc,
+    pub valid_from: Option<DateTimeUtc>,
+    pub valid_until: Option<DateTimeUtc>,
+    pub activated_at: Option<DateTimeUtc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -8,12 +8,11 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub user_id: i32,
     pub user_uuid: String,
+    pub tenant_id: i32,
     pub created_at: DateTime,
     pub updated_at: DateTime,
-    #[sea_orm(unique)]
     pub username: String,
     pub alias: Option<String>,
-    #[sea_orm(unique)]
     pub email: String,
     pub phone: Option<String>,
     pub password: String,
@@ -24,6 +23,7 @@ pub struct Model {
     pub last_login: Option<DateTime>,
     pub reset_token: Option<String>,
     pub reset_triggered: Option<DateTime>,
+    pub password_changed_at: Option<DateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -38,8 +38,16 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Departments,
+    #[sea_orm(has_many = "super::notifications::Entity")]
+    Notifications,
+    #[sea_orm(has_many = "super::oauth_consents::Entity")]
+    OauthConsents,
+    #[sea_orm(has_many = "super::personal_access_tokens::Entity")]
+    PersonalAccessTokens,
     #[sea_orm(has_many = "super::user_group_members::Entity")]
     UserGroupMembers,
+    #[sea_orm(has_many = "super::user_password_history::Entity")]
+    UserPasswordHistory,
     #[sea_orm(has_many = "super::user_roles::Entity")]
     UserRoles,
 }
@@ -56,6 +64,23 @@ impl Related<super::departments::Entity> for Entity {
     }
 }
 
+impl Related<super::notifications::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Notifications.def()
+    }
+}
+
+impl Related<super::oauth_consents::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OauthConsents.def()
+    }
+}
+
+impl Related<super::personal_access_tokens::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PersonalAccessTokens.def()
+    }
+}
 
 impl Related<super::user_group_members::Entity> for Entity {
     fn to() -> RelationDef {
@@ -63,6 +88,12 @@ impl Related<super::user_group_members::Entity> for Entity {
     }
 }
 
+impl Related<super::user_password_history::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserPasswordHistory.def()
+    }
+}
+
 impl Related<super::user_roles::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::UserRoles.def()
@@ -3,7 +3,7 @@ use anyhow::anyhow;
 use crate::schemas::response::ApiResponse;
 use axum::{
     Json,
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
 };
 
@@ -14,6 +14,7 @@ pub enum ErrorType {
     Forbidden,     // 403 - 无权限
     NotFound,      // 404 - 资源不存在
     Conflict,      // 409 - 资源冲突
+    TooManyRequests, // 429 - 请求过于频繁
     InternalServerError, // 500 - 服务器内部错误
 }
 
@@ -22,11 +23,18 @@ pub enum ErrorType {
 pub struct AppError {
     pub error_type: ErrorType,
     pub source: anyhow::Error,
+    // 429 响应的 Retry-After(秒)
+    pub retry_after: Option<u64>,
 }
 
 impl AppError {
     pub fn new(error_type: ErrorType, source: anyhow::Error) -> Self {
-        Self { error_type, source }
+        Self { error_type, source, retry_after: None }
+    }
+
+    pub fn with_retry_after(mut self, seconds: u64) -> Self {
+        self.retry_after = Some(seconds);
+        self
     }
 
     pub fn bad_request<E>(err: E) -> Self
@@ -64,6 +72,13 @@ impl AppError {
         Self::new(ErrorType::Conflict, err.into())
     }
 
+    pub fn too_many_requests<E>(err: E) -> Self
+    where
+        E: Into<anyhow::Error>,
+    {
+        Self::new(ErrorType::TooManyRequests, err.into())
+    }
+
     pub fn internal_server_error<E>(err: E) -> Self
     where
         E: Into<anyhow::Error>,
@@ -78,6 +93,7 @@ impl AppError {
             ErrorType::Forbidden => StatusCode::FORBIDDEN,
             ErrorType::NotFound => StatusCode::NOT_FOUND,
             ErrorType::Conflict => StatusCode::CONFLICT,
+            ErrorType::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
             ErrorType::InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
@@ -240,6 +256,46 @@ impl From<lettre::transport::smtp::Error> for AppError {
     }
 }
 
+impl From<object_store::Error> for AppError {
+    fn from(err: object_store::Error) -> Self {
+        tracing::error!("Object store error: {:?}", err);
+        match err {
+            object_store::Error::NotFound { .. } => Self::not_found(anyhow::Error::from(err)),
+            _ => Self::internal_server_error(anyhow::Error::from(err)),
+        }
+    }
+}
+
+// LDAP 服务不可用等错误默认为内部服务器错误
+impl From<ldap3::LdapError> for AppError {
+    fn from(err: ldap3::LdapError) -> Self {
+        tracing::error!("LDAP error: {:?}", err);
+        Self::internal_server_error(anyhow::Error::from(err))
+    }
+}
+
+// 请求身份提供方等外部服务失败
+impl From<reqwest::Error> for AppError {
+    fn from(err: reqwest::Error) -> Self {
+        tracing::error!("HTTP client error: {:?}", err);
+        Self::internal_server_error(anyhow::Error::from(err))
+    }
+}
+
+impl From<image::ImageError> for AppError {
+    fn from(err: image::ImageError) -> Self {
+        tracing::warn!("Image error: {:?}", err);
+        Self::bad_request(anyhow::Error::from(err))
+    }
+}
+
+impl From<axum::extract::multipart::MultipartError> for AppError {
+    fn from(err: axum::extract::multipart::MultipartError) -> Self {
+        tracing::warn!("Multipart error: {:?}", err);
+        Self::bad_request(anyhow::Error::from(err))
+    }
+}
+
 impl From<ParseIntError> for AppError {
     fn from(err: ParseIntError) -> Self {
         tracing::error!("Parse int error: {:?}", err);
@@ -277,7 +333,11 @@ impl IntoResponse for AppError {
             _ => {
                 tracing::warn!("Client error ({}): {}", status.as_u16(), self.source);
                 let response = ApiResponse::<()>::error(status.as_u16(), self.source.to_string());
-                (status, Json(response)).into_response()
+                let mut response = (status, Json(response)).into_response();
+                if let Some(seconds) = self.retry_after {
+                    response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+                }
+                response
             }
         }
     }
@@ -332,4 +392,14 @@ macro_rules! conflict {
     ($fmt:expr, $($arg:tt)*) => {
         AppError::conflict(anyhow::anyhow!($fmt, $($arg)*))
     };
+}
+
+#[macro_export]
+macro_rules! too_many_requests {
+    ($msg:expr) => {
+        AppError::too_many_requests(anyhow::anyhow!($msg))
+    };
+    ($fmt:expr, $($arg:tt)*) => {
+        AppError::too_many_requests(anyhow::anyhow!($fmt, $($arg)*))
+    };
 }
\ No newline at end of file
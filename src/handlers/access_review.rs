@@ -0,0 +1,291 @@
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use validator::Validate;
+
+use crate::config::openapi::ACCESS_REVIEW_TAG;
+use crate::errors::app_error::AppError;
+use crate::schemas::access_review::{
+    AccessReviewItemQueryParams, AccessReviewItemResponse, AccessReviewQueryParams,
+    AccessReviewReport, AccessReviewReportParams, AccessReviewResponse, CreateAccessReviewDto,
+    ReviewDecisionDto,
+};
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::paginated::PaginatedApiResponse;
+use crate::schemas::response::ApiResponse;
+use crate::services::access_review::{report_to_csv, AccessReviewService};
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(AccessReviewQueryParams),
+    responses((status = 200, body = Vec<AccessReviewResponse>),),
+    tag = ACCESS_REVIEW_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn list_reviews(
+    State(service): State<AccessReviewService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Query(params): Query<AccessReviewQueryParams>,
+) -> Result<impl IntoResponse, AppError> {
+    params.validate()?;
+    let (reviews, total) = service.list_reviews(
+        current_user,
+        context,
+        params.clone()
+    ).await?;
+    Ok(PaginatedApiResponse::success(
+        reviews,
+        total,
+        params.page,
+        params.page_size,
+        StatusCode::OK,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    request_body=CreateAccessReviewDto,
+    responses(( status=201, body=AccessReviewResponse, description = "创建成功"),
+                (status=400, description = "截止时间无效或范围内没有需要复核的权限"),
+                (status=404, description = "部门或复核人不存在"),),
+    tag = ACCESS_REVIEW_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn create_review(
+    State(service): State<AccessReviewService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Json(dto): Json<CreateAccessReviewDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    let review = service.create_review(
+        current_user,
+        context,
+        dto).await?;
+    Ok(ApiResponse::success(review, StatusCode::CREATED))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{review_uuid}",
+    params(
+        ("review_uuid" = String, Path, description = "复核活动UUID")
+    ),
+    responses(( status=200, body=AccessReviewResponse, description = "获取成功"),
+    ( status=404, description = "不存在"),),
+    tag = ACCESS_REVIEW_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn get_review(
+    Path(review_uuid): Path<String>,
+    State(service): State<AccessReviewService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    let review = service.get_review(
+        current_user,
+        context,
+        review_uuid).await?;
+    Ok(ApiResponse::success(review, StatusCode::OK))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{review_uuid}/items",
+    params(
+        ("review_uuid" = String, Path, description = "复核活动UUID"),
+        AccessReviewItemQueryParams
+    ),
+    responses(( status=200, body=Vec<AccessReviewItemResponse>, description = "获取成功"),
+                (status=404, description="复核活动不存在"),),
+    tag = ACCESS_REVIEW_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn list_items(
+    Path(review_uuid): Path<String>,
+    State(service): State<AccessReviewService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Query(params): Query<AccessReviewItemQueryParams>,
+) -> Result<impl IntoResponse, AppError> {
+    params.validate()?;
+    let (items, total) = service.list_items(
+        current_user,
+        context,
+        review_uuid,
+        params.clone()
+    ).await?;
+    Ok(PaginatedApiResponse::success(
+        items,
+        total,
+        params.page,
+        params.page_size,
+        StatusCode::OK,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{review_uuid}/items/{item_uuid}/decision",
+    request_body=ReviewDecisionDto,
+    params(
+        ("review_uuid" = String, Path, description = "复核活动UUID"),
+        ("item_uuid" = String, Path, description = "复核项UUID")
+    ),
+    responses(( status=200, body=AccessReviewItemResponse, description = "复核成功"),
+                (status=400, description="复核结论无效"),
+                (status=403, description="不是复核人、复核自己的权限或无撤销权限"),
+                (status=409, description="复核活动已结束或复核项已复核"),),
+    tag = ACCESS_REVIEW_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn decide_item(
+    Path((review_uuid, item_uuid)): Path<(String, String)>,
+    State(service): State<AccessReviewService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Json(dto): Json<ReviewDecisionDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    let item = service.decide_item(
+        current_user,
+        context,
+        review_uuid,
+        item_uuid,
+        dto).await?;
+    Ok(ApiResponse::success(item, StatusCode::OK))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{review_uuid}/complete",
+    params(
+        ("review_uuid" = String, Path, description = "复核活动UUID")
+    ),
+    responses(( status=200, body=AccessReviewResponse, description = "已完成"),
+                (status=409, description="复核活动已结束"),),
+    tag = ACCESS_REVIEW_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn complete_review(
+    Path(review_uuid): Path<String>,
+    State(service): State<AccessReviewService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    let review = service.complete_review(
+        current_user,
+        context,
+        review_uuid).await?;
+    Ok(ApiResponse::success(review, StatusCode::OK))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{review_uuid}/cancel",
+    params(
+        ("review_uuid" = String, Path, description = "复核活动UUID")
+    ),
+    responses(( status=200, body=AccessReviewResponse, description = "已取消"),
+                (status=409, description="复核活动已结束"),),
+    tag = ACCESS_REVIEW_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn cancel_review(
+    Path(review_uuid): Path<String>,
+    State(service): State<AccessReviewService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    let review = service.cancel_review(
+        current_user,
+        context,
+        review_uuid).await?;
+    Ok(ApiResponse::success(review, StatusCode::OK))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{review_uuid}/remind",
+    params(
+        ("review_uuid" = String, Path, description = "复核活动UUID")
+    ),
+    responses(( status=204, description = "已提醒复核人"),
+                (status=409, description="复核活动已结束"),),
+    tag = ACCESS_REVIEW_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn remind_reviewers(
+    Path(review_uuid): Path<String>,
+    State(service): State<AccessReviewService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    service.remind_reviewers(
+        current_user,
+        context,
+        review_uuid).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/{review_uuid}/report",
+    params(
+        ("review_uuid" = String, Path, description = "复核活动UUID"),
+        AccessReviewReportParams
+    ),
+    responses(( status=200, body=AccessReviewReport, description = "复核证据报告, format=csv 时返回 CSV 文件"),
+                (status=404, description="复核活动不存在"),),
+    tag = ACCESS_REVIEW_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn get_report(
+    Path(review_uuid): Path<String>,
+    State(service): State<AccessReviewService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Query(params): Query<AccessReviewReportParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let report = service.get_report(
+        current_user,
+        context,
+        review_uuid.clone()).await?;
+    if params.format.as_deref().is_some_and(|format| format.eq_ignore_ascii_case("csv")) {
+        let disposition = format!("attachment; filename=\"access-review-{}.csv\"", review_uuid);
+        return Ok((
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (header::CONTENT_DISPOSITION, disposition),
+            ],
+            report_to_csv(&report),
+        )
+            .into_response());
+    }
+    Ok(ApiResponse::success(report, StatusCode::OK).into_response())
+}
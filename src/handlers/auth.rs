@@ -1,6 +1,6 @@
 // 认证相关路由（登录、SSO等）
 
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Extension, Json};
 use axum_extra::extract::CookieJar;
 use axum_extra::headers::Authorization;
 use axum_extra::headers::authorization::Bearer;
@@ -13,23 +13,30 @@ use crate::{
     errors::app_error::AppError,
     services::auth::AuthService,
 };
+use crate::middlewares::client_ip::ClientIp;
 use crate::schemas::auth::{AuthResponse, Credentials};
+use crate::schemas::tenant::CurrentTenant;
 
 #[utoipa::path(
     post,
     path = "/login",
     request_body=Credentials,
     responses(( status=200, body=AuthResponse, description = "登陆成功"),
-                (status=401, description = "认证失败"),),
+                (status=401, description = "认证失败"),
+                (status=429, description = "登录失败次数过多, 暂时锁定"),),
     tag = AUTH_TAG
 )]
 pub async fn login(
     State(service): State<AuthService>,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
+    Extension(current_tenant): Extension<CurrentTenant>,
     jar: CookieJar,
     Json(dto): Json<Credentials>,
 ) -> Result<(CookieJar, ApiResponse<AuthResponse>), AppError> {
     dto.validate()?;
-    let (cookie_jar, auth_response) = service.authenticate(jar, dto).await?;
+    let (cookie_jar, auth_response) = service
+        .authenticate(jar, current_tenant.tenant_id, dto, client_ip.to_string())
+        .await?;
     Ok((cookie_jar, ApiResponse::success(auth_response, StatusCode::OK)))
 }
 
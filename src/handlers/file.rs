@@ -0,0 +1,69 @@
+use axum::{
+    extract::{Extension, Multipart, Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
+use bytes::Bytes;
+
+use crate::bad_request;
+use crate::config::openapi::FILE_TAG;
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::file::{DownloadQuery, FileResponse, UploadForm};
+use crate::schemas::response::ApiResponse;
+use crate::services::file::FileService;
+
+// 读取 multipart 表单中的 file 字段
+pub async fn read_file_field(
+    multipart: &mut Multipart,
+) -> Result<(Option<String>, Bytes), AppError> {
+    while let Some(field) = multipart.next_field().await? {
+        if field.name() == Some("file") {
+            let content_type = field.content_type().map(|ct| ct.to_string());
+            let data = field.bytes().await?;
+            return Ok((content_type, data));
+        }
+    }
+    Err(bad_request!("Missing file field"))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    request_body(content = UploadForm, content_type = "multipart/form-data"),
+    responses(( status=201, body=FileResponse, description = "上传成功"),
+                (status=400, description = "文件类型或大小不符合要求"),),
+    tag = FILE_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn upload_file(
+    State(service): State<FileService>,
+    Extension(current_user): Extension<CurrentUser>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, AppError> {
+    let (content_type, data) = read_file_field(&mut multipart).await?;
+    let file = service.upload(current_user, content_type, data).await?;
+    Ok(ApiResponse::success(file, StatusCode::CREATED))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{*key}",
+    params(
+        ("key" = String, Path, description = "文件路径"),
+        DownloadQuery
+    ),
+    responses(( status=200, description = "文件内容"),
+                (status=403, description = "签名无效或已过期"),),
+    tag = FILE_TAG
+)]
+pub async fn download_file(
+    State(service): State<FileService>,
+    Path(key): Path<String>,
+    Query(query): Query<DownloadQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let (content_type, data) = service.download(key, query).await?;
+    Ok(([(header::CONTENT_TYPE, content_type)], data))
+}
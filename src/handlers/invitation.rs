@@ -0,0 +1,164 @@
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use validator::Validate;
+
+use crate::config::openapi::INVITATION_TAG;
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::invitation::{
+    AcceptInvitationDto, CreateInvitationDto, InvitationPreview, InvitationQueryParams,
+    InvitationResponse,
+};
+use crate::schemas::paginated::PaginatedApiResponse;
+use crate::schemas::response::ApiResponse;
+use crate::services::invitation::InvitationService;
+
+#[utoipa::path(get, path = "",
+    params(InvitationQueryParams),
+    responses((status = 200, body = Vec<InvitationResponse>),),
+    tag = INVITATION_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn list_invitations(
+    State(service): State<InvitationService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Query(params): Query<InvitationQueryParams>,
+) -> Result<impl IntoResponse, AppError> {
+    params.validate()?;
+    let (invitations, total) = service.list_invitations(
+        current_user,
+        context,
+        params.clone()
+    ).await?;
+    Ok(PaginatedApiResponse::success(
+        invitations,
+        total,
+        params.page,
+        params.page_size,
+        StatusCode::OK,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    request_body=CreateInvitationDto,
+    responses(( status=201, body=InvitationResponse, description = "邀请已发送"),
+                (status=409, description = "邮箱已存在或已有待接受的邀请"),),
+    tag = INVITATION_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn create_invitation(
+    State(service): State<InvitationService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Json(dto): Json<CreateInvitationDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    let invitation = service.create_invitation(
+        current_user,
+        context,
+        dto).await?;
+    Ok(ApiResponse::success(invitation, StatusCode::CREATED))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{invitation_uuid}/resend",
+    params(
+        ("invitation_uuid" = String, Path, description = "邀请唯一UUID")
+    ),
+    responses(( status=200, body=InvitationResponse, description = "邀请已重新发送"),
+                (status=404, description = "邀请不存在"),),
+    tag = INVITATION_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn resend_invitation(
+    Path(invitation_uuid): Path<String>,
+    State(service): State<InvitationService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    let invitation = service.resend_invitation(
+        current_user,
+        context,
+        invitation_uuid).await?;
+    Ok(ApiResponse::success(invitation, StatusCode::OK))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{invitation_uuid}",
+    params(
+        ("invitation_uuid" = String, Path, description = "邀请唯一UUID")
+    ),
+    responses(( status=204, description = "邀请已撤销"),),
+    tag = INVITATION_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn revoke_invitation(
+    Path(invitation_uuid): Path<String>,
+    State(service): State<InvitationService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    service.revoke_invitation(
+        current_user,
+        context,
+        invitation_uuid).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/accept/{code}",
+    params(
+        ("code" = String, Path, description = "邀请码")
+    ),
+    responses(( status=200, body=InvitationPreview, description = "邀请信息"),
+                (status=404, description = "邀请不存在或已失效"),),
+    tag = INVITATION_TAG
+)]
+pub async fn preview_invitation(
+    State(service): State<InvitationService>,
+    Path(code): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let preview = service.preview_invitation(code).await?;
+    Ok(ApiResponse::success(preview, StatusCode::OK))
+}
+
+#[utoipa::path(
+    post,
+    path = "/accept/{code}",
+    params(
+        ("code" = String, Path, description = "邀请码")
+    ),
+    request_body=AcceptInvitationDto,
+    responses(( status=201, description = "接受邀请成功, 账户已创建"),
+                (status=404, description = "邀请不存在或已失效"),
+                (status=409, description = "用户名或邮箱已存在"),),
+    tag = INVITATION_TAG
+)]
+pub async fn accept_invitation(
+    State(service): State<InvitationService>,
+    Path(code): Path<String>,
+    Json(dto): Json<AcceptInvitationDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    service.accept_invitation(code, dto).await?;
+    Ok(StatusCode::CREATED)
+}
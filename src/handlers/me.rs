@@ -6,10 +6,16 @@ use crate::schemas::{
 };
 use crate::services::me::MeService;
 use axum::{
-    extract::{Extension, State},
+    extract::{Extension, Multipart, Path, State},
     http::StatusCode,
     response::IntoResponse,
+    Json,
 };
+use validator::Validate;
+use crate::schemas::password::ChangePasswordDto;
+use crate::schemas::me::{ChangeEmailDto, Info, UpdateProfileDto};
+use crate::handlers::file::read_file_field;
+use crate::schemas::file::{AvatarResponse, UploadForm};
 use crate::schemas::cedar_policy::CedarContext;
 
 #[utoipa::path(get, 
@@ -27,4 +33,109 @@ pub async fn profile(
 ) -> Result<impl IntoResponse, AppError> {
     let profile = service.profile(current_user, context).await?;
     Ok(ApiResponse::success(profile, StatusCode::OK))
+}
+
+
+#[utoipa::path(post,
+    path = "/password",
+    request_body = ChangePasswordDto,
+    responses((status = 204, description = "密码修改成功"),
+              (status = 400, description = "当前密码错误或新密码不符合密码策略"),),
+    tag = ME_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn change_password(
+    State(service): State<MeService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(dto): Json<ChangePasswordDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    service.change_password(current_user, dto).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+
+#[utoipa::path(patch,
+    path = "",
+    request_body = UpdateProfileDto,
+    responses((status = 200, body = Info, description = "资料修改成功"),),
+    tag = ME_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn update_profile(
+    State(service): State<MeService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(dto): Json<UpdateProfileDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    let info = service.update_profile(current_user, dto).await?;
+    Ok(ApiResponse::success(info, StatusCode::OK))
+}
+
+
+#[utoipa::path(post,
+    path = "/email",
+    request_body = ChangeEmailDto,
+    responses((status = 202, description = "确认邮件已发送到新邮箱"),
+              (status = 409, description = "邮箱已存在"),),
+    tag = ME_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn request_email_change(
+    State(service): State<MeService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(dto): Json<ChangeEmailDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    service.request_email_change(current_user, dto).await?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+
+#[utoipa::path(post,
+    path = "/email/{token}",
+    params(
+        ("token" = String, Path, description = "邮件中的确认Token")
+    ),
+    responses((status = 200, body = Info, description = "邮箱修改成功"),
+              (status = 404, description = "Token不存在或已过期"),),
+    tag = ME_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn confirm_email_change(
+    State(service): State<MeService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(token): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let info = service.confirm_email_change(current_user, token).await?;
+    Ok(ApiResponse::success(info, StatusCode::OK))
+}
+
+
+#[utoipa::path(post,
+    path = "/avatar",
+    request_body(content = UploadForm, content_type = "multipart/form-data"),
+    responses((status = 200, body = AvatarResponse, description = "头像上传成功"),
+              (status = 400, description = "文件类型或大小不符合要求"),),
+    tag = ME_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn upload_avatar(
+    State(service): State<MeService>,
+    Extension(current_user): Extension<CurrentUser>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, AppError> {
+    let (content_type, data) = read_file_field(&mut multipart).await?;
+    let avatar = service.upload_avatar(current_user, content_type, data).await?;
+    Ok(ApiResponse::success(avatar, StatusCode::OK))
 }
\ No newline at end of file
@@ -0,0 +1,67 @@
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use validator::Validate;
+
+use crate::config::openapi::MESSAGE_TAG;
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::message::{SendMessageDto, SendMessageResponse};
+use crate::schemas::response::ApiResponse;
+use crate::services::message::MessageService;
+
+#[utoipa::path(
+    post,
+    path = "",
+    request_body=SendMessageDto,
+    responses(( status=202, body=SendMessageResponse, description = "消息已进入发送队列"),
+                (status=400, description = "没有接收人"),
+                (status=403, description = "无权向该对象发送消息"),),
+    tag = MESSAGE_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn send_message(
+    State(service): State<MessageService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Json(dto): Json<SendMessageDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    let response = service.send_message(
+        current_user,
+        context,
+        dto).await?;
+    Ok(ApiResponse::success(response, StatusCode::ACCEPTED))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{message_uuid}",
+    params(
+        ("message_uuid" = String, Path, description = "定时消息UUID")
+    ),
+    responses(( status=204, description = "定时消息已取消"),
+                (status=404, description = "定时消息不存在或已发送"),),
+    tag = MESSAGE_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn cancel_scheduled_message(
+    Path(message_uuid): Path<String>,
+    State(service): State<MessageService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    service.cancel_scheduled_message(
+        current_user,
+        context,
+        message_uuid).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -11,3 +11,18 @@ pub mod group;
 pub mod sse;
 pub mod cedar_policy;
 pub mod cedar_schema;
+pub mod file;
+pub mod invitation;
+pub mod notification;
+pub mod message;
+pub mod webhook;
+pub mod scim;
+pub mod oidc;
+pub mod oauth;
+pub mod oauth_client;
+pub mod personal_token;
+pub mod robot_account;
+pub mod system;
+pub mod tenant;
+pub mod role_request;
+pub mod access_review;
\ No newline at end of file
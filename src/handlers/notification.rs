@@ -0,0 +1,97 @@
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use validator::Validate;
+
+use crate::config::openapi::NOTIFICATION_TAG;
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::notification::{
+    MarkAllReadResponse, NotificationQueryParams, NotificationResponse, UnreadCountResponse,
+};
+use crate::schemas::paginated::PaginatedApiResponse;
+use crate::schemas::response::ApiResponse;
+use crate::services::notification::NotificationService;
+
+#[utoipa::path(get, path = "",
+    params(NotificationQueryParams),
+    responses((status = 200, body = Vec<NotificationResponse>),),
+    tag = NOTIFICATION_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn list_notifications(
+    State(service): State<NotificationService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(params): Query<NotificationQueryParams>,
+) -> Result<impl IntoResponse, AppError> {
+    params.validate()?;
+    let (notifications, total) = service.list_notifications(
+        current_user,
+        params.clone()
+    ).await?;
+    Ok(PaginatedApiResponse::success(
+        notifications,
+        total,
+        params.page,
+        params.page_size,
+        StatusCode::OK,
+    ))
+}
+
+#[utoipa::path(get, path = "/unread-count",
+    responses((status = 200, body = UnreadCountResponse),),
+    tag = NOTIFICATION_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn unread_count(
+    State(service): State<NotificationService>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    let unread = service.unread_count(current_user).await?;
+    Ok(ApiResponse::success(UnreadCountResponse { unread }, StatusCode::OK))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{notification_uuid}/read",
+    params(
+        ("notification_uuid" = String, Path, description = "通知唯一UUID")
+    ),
+    responses(( status=204, description = "已标记为已读"),
+                (status=404, description = "通知不存在"),),
+    tag = NOTIFICATION_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn mark_read(
+    Path(notification_uuid): Path<String>,
+    State(service): State<NotificationService>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    service.mark_read(current_user, notification_uuid).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/read-all",
+    responses(( status=200, body=MarkAllReadResponse, description = "全部标记为已读"),),
+    tag = NOTIFICATION_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn mark_all_read(
+    State(service): State<NotificationService>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    let updated = service.mark_all_read(current_user).await?;
+    Ok(ApiResponse::success(MarkAllReadResponse { updated }, StatusCode::OK))
+}
@@ -0,0 +1,197 @@
+// OAuth2 / OpenID Connect 身份提供方: 协议端点和用户授权确认
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Redirect},
+    Form, Json,
+};
+use axum_extra::headers::authorization::{Basic, Bearer};
+use axum_extra::headers::Authorization;
+use axum_extra::TypedHeader;
+use serde_json::Value;
+
+use crate::config::openapi::OAUTH_TAG;
+use crate::errors::app_error::AppError;
+use crate::middlewares::client_ip::ClientIp;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::oauth::{
+    AuthorizationDecision, AuthorizationRedirect, AuthorizationRequestInfo, AuthorizeParams,
+    OAuthConsentResponse, OAuthError, OAuthErrorBody, TokenRequest, TokenResponse,
+};
+use crate::schemas::response::ApiResponse;
+use crate::services::oauth::OAuthService;
+
+#[utoipa::path(
+    get,
+    path = "/.well-known/openid-configuration",
+    responses((status = 200, description = "OpenID Connect 发现文档"),
+              (status = 404, body = OAuthErrorBody, description = "未启用身份提供方"),),
+    tag = OAUTH_TAG
+)]
+pub async fn discovery(
+    State(service): State<OAuthService>,
+) -> Result<Json<Value>, OAuthError> {
+    Ok(Json(service.discovery()?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/jwks",
+    responses((status = 200, description = "签名公钥 (JWK Set)"),),
+    tag = OAUTH_TAG
+)]
+pub async fn jwks(
+    State(service): State<OAuthService>,
+) -> Result<Json<Value>, OAuthError> {
+    Ok(Json(service.jwks().await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/authorize",
+    params(AuthorizeParams),
+    responses((status = 303, description = "跳转到授权确认页面, 或携带错误跳转回应用"),
+              (status = 400, body = OAuthErrorBody, description = "client_id 或 redirect_uri 无效"),),
+    tag = OAUTH_TAG
+)]
+pub async fn authorize(
+    State(service): State<OAuthService>,
+    Query(params): Query<AuthorizeParams>,
+) -> Result<Redirect, OAuthError> {
+    let location = service.authorize(params).await?;
+    Ok(Redirect::to(&location))
+}
+
+#[utoipa::path(
+    post,
+    path = "/token",
+    request_body(content = TokenRequest, content_type = "application/x-www-form-urlencoded"),
+    responses((status = 200, body = TokenResponse, description = "签发成功"),
+              (status = 400, body = OAuthErrorBody, description = "授权码或 Refresh Token 无效"),
+              (status = 401, body = OAuthErrorBody, description = "客户端认证失败"),),
+    tag = OAUTH_TAG
+)]
+pub async fn token(
+    State(service): State<OAuthService>,
+    Extension(ClientIp(client_ip)): Extension<ClientIp>,
+    basic_auth: Option<TypedHeader<Authorization<Basic>>>,
+    Form(request): Form<TokenRequest>,
+) -> Result<impl IntoResponse, OAuthError> {
+    let context = CedarContext {
+        source_ip: client_ip.to_string(),
+    };
+    let client_credentials = basic_auth.map(|TypedHeader(auth)| (auth.username().to_string(), auth.password().to_string()));
+    let response = service.token(client_credentials, context, request).await?;
+    Ok(([(header::CACHE_CONTROL, "no-store")], Json(response)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/userinfo",
+    responses((status = 200, description = "按授权 scope 返回的用户信息"),
+              (status = 401, body = OAuthErrorBody, description = "Access Token 无效"),),
+    tag = OAUTH_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn userinfo(
+    State(service): State<OAuthService>,
+    bearer: Option<TypedHeader<Authorization<Bearer>>>,
+) -> Result<Json<Value>, OAuthError> {
+    let TypedHeader(auth) = bearer.ok_or(OAuthError::invalid_token("Access token is required"))?;
+    let info = service.userinfo(auth.token()).await?;
+    Ok(Json(Value::Object(info)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/authorizations/{request_id}",
+    params(
+        ("request_id" = String, Path, description = "授权请求ID")
+    ),
+    responses(( status=200, body=AuthorizationRequestInfo, description = "授权确认页面内容"),
+              ( status=404, description = "授权请求不存在或已过期"),),
+    tag = OAUTH_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn get_authorization_request(
+    Path(request_id): Path<String>,
+    State(service): State<OAuthService>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    let info = service.get_authorization_request(current_user, request_id).await?;
+    Ok(ApiResponse::success(info, StatusCode::OK))
+}
+
+#[utoipa::path(
+    post,
+    path = "/authorizations/{request_id}",
+    request_body=AuthorizationDecision,
+    params(
+        ("request_id" = String, Path, description = "授权请求ID")
+    ),
+    responses(( status=200, body=AuthorizationRedirect, description = "跳转回应用的地址"),
+              ( status=404, description = "授权请求不存在或已过期"),),
+    tag = OAUTH_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn decide_authorization(
+    Path(request_id): Path<String>,
+    State(service): State<OAuthService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Json(decision): Json<AuthorizationDecision>,
+) -> Result<impl IntoResponse, AppError> {
+    let redirect = service.decide_authorization(
+        current_user,
+        context,
+        request_id,
+        decision).await?;
+    Ok(ApiResponse::success(redirect, StatusCode::OK))
+}
+
+#[utoipa::path(
+    get,
+    path = "/consents",
+    responses(( status=200, body=Vec<OAuthConsentResponse>, description = "已授权的应用"),),
+    tag = OAUTH_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn list_consents(
+    State(service): State<OAuthService>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    let consents = service.list_consents(current_user).await?;
+    Ok(ApiResponse::success(consents, StatusCode::OK))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/consents/{client_id}",
+    params(
+        ("client_id" = String, Path, description = "客户端ID")
+    ),
+    responses(( status=204, description = "撤销成功"),
+              ( status=404, description = "未授权该应用"),),
+    tag = OAUTH_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn revoke_consent(
+    Path(client_id): Path<String>,
+    State(service): State<OAuthService>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    service.revoke_consent(current_user, client_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,183 @@
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use validator::Validate;
+
+use crate::config::openapi::OAUTH_TAG;
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::oauth::{
+    CreateOAuthClientDto, OAuthClientQueryParams, OAuthClientResponse, UpdateOAuthClientDto,
+};
+use crate::schemas::paginated::PaginatedApiResponse;
+use crate::schemas::response::ApiResponse;
+use crate::services::oauth_client::OAuthClientService;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(OAuthClientQueryParams),
+    responses((status = 200, body = Vec<OAuthClientResponse>),),
+    tag = OAUTH_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn list_clients(
+    State(service): State<OAuthClientService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Query(params): Query<OAuthClientQueryParams>,
+) -> Result<impl IntoResponse, AppError> {
+    params.validate()?;
+    let (clients, total) = service.list_clients(
+        current_user,
+        context,
+        params.clone()
+    ).await?;
+    Ok(PaginatedApiResponse::success(
+        clients,
+        total,
+        params.page,
+        params.page_size,
+        StatusCode::OK,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    request_body=CreateOAuthClientDto,
+    responses(( status=201, body=OAuthClientResponse, description = "创建成功, 客户端密钥只返回这一次"),
+                (status=400, description = "回调地址或 scope 无效"),),
+    tag = OAUTH_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn create_client(
+    State(service): State<OAuthClientService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Json(dto): Json<CreateOAuthClientDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    let client = service.create_client(
+        current_user,
+        context,
+        dto).await?;
+    Ok(ApiResponse::success(client, StatusCode::CREATED))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{client_id}",
+    params(
+        ("client_id" = String, Path, description = "客户端ID")
+    ),
+    responses(( status=200, body=OAuthClientResponse, description = "获取成功"),
+    ( status=404, description = "不存在"),),
+    tag = OAUTH_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn get_client(
+    Path(client_id): Path<String>,
+    State(service): State<OAuthClientService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    let client = service.get_client(
+        current_user,
+        context,
+        client_id).await?;
+    Ok(ApiResponse::success(client, StatusCode::OK))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{client_id}",
+    request_body=UpdateOAuthClientDto,
+    params(
+        ("client_id" = String, Path, description = "客户端ID")
+    ),
+    responses(( status=200, body=OAuthClientResponse, description = "更新成功"),
+                (status=404, description="客户端不存在"),),
+    tag = OAUTH_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn update_client(
+    Path(client_id): Path<String>,
+    State(service): State<OAuthClientService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Json(dto): Json<UpdateOAuthClientDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    let client = service.update_client(
+        current_user,
+        context,
+        client_id,
+        dto).await?;
+    Ok(ApiResponse::success(client, StatusCode::OK))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{client_id}",
+    params(
+        ("client_id" = String, Path, description = "客户端ID")
+    ),
+    responses(( status=204, description = "删除成功"),
+                (status=404, description="客户端不存在"),),
+    tag = OAUTH_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn delete_client(
+    Path(client_id): Path<String>,
+    State(service): State<OAuthClientService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    service.delete_client(
+        current_user,
+        context,
+        client_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/{client_id}/secret",
+    params(
+        ("client_id" = String, Path, description = "客户端ID")
+    ),
+    responses(( status=200, body=OAuthClientResponse, description = "重置成功, 新密钥只返回这一次"),
+                (status=400, description="公开客户端没有密钥"),
+                (status=404, description="客户端不存在"),),
+    tag = OAUTH_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn rotate_secret(
+    Path(client_id): Path<String>,
+    State(service): State<OAuthClientService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    let client = service.rotate_secret(
+        current_user,
+        context,
+        client_id).await?;
+    Ok(ApiResponse::success(client, StatusCode::OK))
+}
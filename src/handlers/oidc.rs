@@ -0,0 +1,70 @@
+// OpenID Connect 单点登录
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use axum_extra::extract::CookieJar;
+use validator::Validate;
+
+use crate::config::openapi::AUTH_TAG;
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::AuthResponse;
+use crate::schemas::oidc::{OidcAuthorizeResponse, OidcCallback, OidcProviderInfo};
+use crate::schemas::response::ApiResponse;
+use crate::services::oidc::OidcService;
+
+#[utoipa::path(
+    get,
+    path = "/providers",
+    responses((status = 200, body = Vec<OidcProviderInfo>, description = "可用的身份提供方"),),
+    tag = AUTH_TAG
+)]
+pub async fn list_providers(
+    State(service): State<OidcService>,
+) -> Result<ApiResponse<Vec<OidcProviderInfo>>, AppError> {
+    Ok(ApiResponse::success(service.list_providers(), StatusCode::OK))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{provider}/authorize",
+    params(
+        ("provider" = String, Path, description = "身份提供方标识")
+    ),
+    responses((status = 200, body = OidcAuthorizeResponse, description = "授权地址"),
+              (status = 404, description = "身份提供方不存在"),),
+    tag = AUTH_TAG
+)]
+pub async fn authorize(
+    Path(provider): Path<String>,
+    State(service): State<OidcService>,
+    jar: CookieJar,
+) -> Result<(CookieJar, ApiResponse<OidcAuthorizeResponse>), AppError> {
+    let (cookie_jar, response) = service.authorize(jar, &provider).await?;
+    Ok((cookie_jar, ApiResponse::success(response, StatusCode::OK)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{provider}/callback",
+    request_body = OidcCallback,
+    params(
+        ("provider" = String, Path, description = "身份提供方标识")
+    ),
+    responses((status = 200, body = AuthResponse, description = "登陆成功"),
+              (status = 401, description = "认证失败或 state 与发起登录的浏览器不匹配"),
+              (status = 409, description = "用户名或邮箱已被本地用户使用"),),
+    tag = AUTH_TAG
+)]
+pub async fn callback(
+    Path(provider): Path<String>,
+    State(service): State<OidcService>,
+    jar: CookieJar,
+    Json(dto): Json<OidcCallback>,
+) -> Result<(CookieJar, ApiResponse<AuthResponse>), AppError> {
+    dto.validate()?;
+    let (cookie_jar, auth_response) = service.callback(jar, &provider, dto).await?;
+    Ok((cookie_jar, ApiResponse::success(auth_response, StatusCode::OK)))
+}
@@ -1,15 +1,13 @@
 use crate::config::openapi::PASSWORD_TAG;
 use crate::errors::app_error::AppError;
 use crate::schemas::password::{ForgotPasswordDto, ResetPasswordDto};
+use crate::schemas::tenant::CurrentTenant;
 use crate::services::password::PasswordService;
-use axum::{Json, extract::{State, Path},
+use axum::{Extension, Json, extract::{State, Path},
            http::StatusCode
 };
 use axum::response::IntoResponse;
-use axum_extra::TypedHeader;
-use headers::Referer;
 use validator::Validate;
-use crate::bad_request;
 
 #[utoipa::path(
     post,
@@ -22,18 +20,12 @@ use crate::bad_request;
 )]
 pub async fn forgot_password(
     State(service): State<PasswordService>,
-    referer: Option<TypedHeader<Referer>>,
+    Extension(current_tenant): Extension<CurrentTenant>,
     Json(dto): Json<ForgotPasswordDto>,
 ) -> Result<impl IntoResponse, AppError> {
     dto.validate()?;
 
-    let referer_url = match referer {
-        Some(referer_url) => referer_url.to_string(),
-        None => {
-            return Err(bad_request!("Bad Request"))
-        }
-    };
-    service.forgot_password(referer_url, dto).await?;
+    service.forgot_password(current_tenant.tenant_id, dto).await?;
     Ok(StatusCode::ACCEPTED)
 }
 
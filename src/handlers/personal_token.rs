@@ -0,0 +1,74 @@
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use validator::Validate;
+
+use crate::config::openapi::ME_TAG;
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::personal_token::{CreatePersonalTokenDto, PersonalTokenResponse};
+use crate::schemas::response::ApiResponse;
+use crate::services::personal_token::PersonalTokenService;
+
+#[utoipa::path(
+    get,
+    path = "",
+    responses((status = 200, body = Vec<PersonalTokenResponse>, description = "当前用户的个人访问令牌"),),
+    tag = ME_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn list_tokens(
+    State(service): State<PersonalTokenService>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    let tokens = service.list_tokens(current_user).await?;
+    Ok(ApiResponse::success(tokens, StatusCode::OK))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    request_body=CreatePersonalTokenDto,
+    responses(( status=201, body=PersonalTokenResponse, description = "创建成功, 完整令牌只返回这一次"),
+                (status=400, description = "scope 或有效期无效, 或令牌数量已达上限"),),
+    tag = ME_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn create_token(
+    State(service): State<PersonalTokenService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(dto): Json<CreatePersonalTokenDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    let token = service.create_token(current_user, dto).await?;
+    Ok(ApiResponse::success(token, StatusCode::CREATED))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{token_uuid}",
+    params(
+        ("token_uuid" = String, Path, description = "令牌UUID")
+    ),
+    responses(( status=204, description = "撤销成功"),
+                (status=404, description="令牌不存在"),),
+    tag = ME_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn revoke_token(
+    Path(token_uuid): Path<String>,
+    State(service): State<PersonalTokenService>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    service.revoke_token(current_user, token_uuid).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,189 @@
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use validator::Validate;
+
+use crate::config::openapi::ROBOT_ACCOUNT;
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::paginated::PaginatedApiResponse;
+use crate::schemas::response::ApiResponse;
+use crate::schemas::robot_account::{
+    CreateRobotAccountDto, RobotAccountQueryParams, RobotAccountResponse, RotateRobotSecretDto,
+    UpdateRobotAccountDto,
+};
+use crate::services::robot_account::RobotAccountService;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(RobotAccountQueryParams),
+    responses((status = 200, body = Vec<RobotAccountResponse>),),
+    tag = ROBOT_ACCOUNT,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn list_robots(
+    State(service): State<RobotAccountService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Query(params): Query<RobotAccountQueryParams>,
+) -> Result<impl IntoResponse, AppError> {
+    params.validate()?;
+    let (robots, total) = service.list_robots(
+        current_user,
+        context,
+        params.clone()
+    ).await?;
+    Ok(PaginatedApiResponse::success(
+        robots,
+        total,
+        params.page,
+        params.page_size,
+        StatusCode::OK,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    request_body=CreateRobotAccountDto,
+    responses(( status=201, body=RobotAccountResponse, description = "创建成功, 密钥只返回这一次"),
+                (status=404, description = "角色或用户组不存在"),
+                (status=409, description = "名称已存在"),),
+    tag = ROBOT_ACCOUNT,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn create_robot(
+    State(service): State<RobotAccountService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Json(dto): Json<CreateRobotAccountDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    let robot = service.create_robot(
+        current_user,
+        context,
+        dto).await?;
+    Ok(ApiResponse::success(robot, StatusCode::CREATED))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{robot_uuid}",
+    params(
+        ("robot_uuid" = String, Path, description = "服务账号UUID")
+    ),
+    responses(( status=200, body=RobotAccountResponse, description = "获取成功"),
+    ( status=404, description = "不存在"),),
+    tag = ROBOT_ACCOUNT,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn get_robot(
+    Path(robot_uuid): Path<String>,
+    State(service): State<RobotAccountService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    let robot = service.get_robot(
+        current_user,
+        context,
+        robot_uuid).await?;
+    Ok(ApiResponse::success(robot, StatusCode::OK))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{robot_uuid}",
+    request_body=UpdateRobotAccountDto,
+    params(
+        ("robot_uuid" = String, Path, description = "服务账号UUID")
+    ),
+    responses(( status=200, body=RobotAccountResponse, description = "更新成功"),
+                (status=404, description="服务账号不存在"),
+                (status=409, description="名称已存在"),),
+    tag = ROBOT_ACCOUNT,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn update_robot(
+    Path(robot_uuid): Path<String>,
+    State(service): State<RobotAccountService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Json(dto): Json<UpdateRobotAccountDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    let robot = service.update_robot(
+        current_user,
+        context,
+        robot_uuid,
+        dto).await?;
+    Ok(ApiResponse::success(robot, StatusCode::OK))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{robot_uuid}",
+    params(
+        ("robot_uuid" = String, Path, description = "服务账号UUID")
+    ),
+    responses(( status=204, description = "删除成功"),
+                (status=404, description="服务账号不存在"),),
+    tag = ROBOT_ACCOUNT,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn delete_robot(
+    Path(robot_uuid): Path<String>,
+    State(service): State<RobotAccountService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    service.delete_robot(
+        current_user,
+        context,
+        robot_uuid).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/{robot_uuid}/secret",
+    request_body=RotateRobotSecretDto,
+    params(
+        ("robot_uuid" = String, Path, description = "服务账号UUID")
+    ),
+    responses(( status=200, body=RobotAccountResponse, description = "重置成功, 新密钥只返回这一次"),
+                (status=404, description="服务账号不存在"),),
+    tag = ROBOT_ACCOUNT,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn rotate_secret(
+    Path(robot_uuid): Path<String>,
+    State(service): State<RobotAccountService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Json(dto): Json<RotateRobotSecretDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    let robot = service.rotate_secret(
+        current_user,
+        context,
+        robot_uuid,
+        dto).await?;
+    Ok(ApiResponse::success(robot, StatusCode::OK))
+}
@@ -79,6 +79,7 @@ pub async fn get_role(
     path = "",
     request_body=CreateRoleDto,
     responses(( status=201, body=RoleResponse, description = "角色创建成功"),
+                (status=404, description = "父角色不存在"),
                 (status=409, description = "角色已存在"),),
     tag = ROLE_TAG,
     security(
@@ -104,7 +105,8 @@ pub async fn create_role(
     path = "/{role_uuid}",
     request_body=UpdateRoleDto,
     responses(  (status=200, body=RoleResponse, description="更新成功"),
-                (status=404, description="角色不存在"),),
+                (status=400, description="继承自身或形成继承环"),
+                (status=404, description="角色或父角色不存在"),),
     tag = ROLE_TAG,
     security(
       ("bearerAuth" = [])
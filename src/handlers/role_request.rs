@@ -0,0 +1,189 @@
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use validator::Validate;
+
+use crate::config::openapi::ROLE_REQUEST_TAG;
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::paginated::PaginatedApiResponse;
+use crate::schemas::response::ApiResponse;
+use crate::schemas::role_request::{
+    ApproveRoleRequestDto, CreateRoleRequestDto, RejectRoleRequestDto, RoleRequestQueryParams,
+    RoleRequestResponse,
+};
+use crate::services::role_request::RoleRequestService;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(RoleRequestQueryParams),
+    responses((status = 200, body = Vec<RoleRequestResponse>),),
+    tag = ROLE_REQUEST_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn list_requests(
+    State(service): State<RoleRequestService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Query(params): Query<RoleRequestQueryParams>,
+) -> Result<impl IntoResponse, AppError> {
+    params.validate()?;
+    let (requests, total) = service.list_requests(
+        current_user,
+        context,
+        params.clone()
+    ).await?;
+    Ok(PaginatedApiResponse::success(
+        requests,
+        total,
+        params.page,
+        params.page_size,
+        StatusCode::OK,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    request_body=CreateRoleRequestDto,
+    responses(( status=201, body=RoleRequestResponse, description = "提交成功"),
+                (status=400, description = "有效期无效"),
+                (status=404, description = "角色不存在"),
+                (status=409, description = "已拥有该角色或已有待审批的申请"),),
+    tag = ROLE_REQUEST_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn create_request(
+    State(service): State<RoleRequestService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(dto): Json<CreateRoleRequestDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    let request = service.create_request(
+        current_user,
+        dto).await?;
+    Ok(ApiResponse::success(request, StatusCode::CREATED))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{request_uuid}",
+    params(
+        ("request_uuid" = String, Path, description = "申请UUID")
+    ),
+    responses(( status=200, body=RoleRequestResponse, description = "获取成功"),
+    ( status=404, description = "不存在"),),
+    tag = ROLE_REQUEST_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn get_request(
+    Path(request_uuid): Path<String>,
+    State(service): State<RoleRequestService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    let request = service.get_request(
+        current_user,
+        context,
+        request_uuid).await?;
+    Ok(ApiResponse::success(request, StatusCode::OK))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{request_uuid}/approve",
+    request_body=ApproveRoleRequestDto,
+    params(
+        ("request_uuid" = String, Path, description = "申请UUID")
+    ),
+    responses(( status=200, body=RoleRequestResponse, description = "审批通过"),
+                (status=400, description="失效时间晚于申请的失效时间"),
+                (status=403, description="无审批权限或审批自己的申请"),
+                (status=409, description="申请已处理或申请人已永久拥有该角色"),),
+    tag = ROLE_REQUEST_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn approve_request(
+    Path(request_uuid): Path<String>,
+    State(service): State<RoleRequestService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Json(dto): Json<ApproveRoleRequestDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    let request = service.approve_request(
+        current_user,
+        context,
+        request_uuid,
+        dto).await?;
+    Ok(ApiResponse::success(request, StatusCode::OK))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{request_uuid}/reject",
+    request_body=RejectRoleRequestDto,
+    params(
+        ("request_uuid" = String, Path, description = "申请UUID")
+    ),
+    responses(( status=200, body=RoleRequestResponse, description = "已驳回"),
+                (status=403, description="无审批权限或审批自己的申请"),
+                (status=409, description="申请已处理"),),
+    tag = ROLE_REQUEST_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn reject_request(
+    Path(request_uuid): Path<String>,
+    State(service): State<RoleRequestService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Json(dto): Json<RejectRoleRequestDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    let request = service.reject_request(
+        current_user,
+        context,
+        request_uuid,
+        dto).await?;
+    Ok(ApiResponse::success(request, StatusCode::OK))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{request_uuid}/cancel",
+    params(
+        ("request_uuid" = String, Path, description = "申请UUID")
+    ),
+    responses(( status=204, description = "已撤回"),
+                (status=403, description="不是申请人"),
+                (status=409, description="申请已处理"),),
+    tag = ROLE_REQUEST_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn cancel_request(
+    Path(request_uuid): Path<String>,
+    State(service): State<RoleRequestService>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, AppError> {
+    service.cancel_request(
+        current_user,
+        request_uuid).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
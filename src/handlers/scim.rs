@@ -0,0 +1,280 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+
+use crate::config::openapi::SCIM_TAG;
+use crate::schemas::scim::{
+    ScimError, ScimErrorBody, ScimGroup, ScimListParams, ScimListResponse, ScimPatchRequest,
+    ScimResponse, ScimUser,
+};
+use crate::services::scim::ScimService;
+
+#[utoipa::path(
+    get,
+    path = "/ServiceProviderConfig",
+    responses((status = 200, description = "服务能力说明"),),
+    tag = SCIM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn service_provider_config(
+    State(service): State<ScimService>,
+) -> Result<impl IntoResponse, ScimError> {
+    Ok(ScimResponse(StatusCode::OK, service.service_provider_config()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/Users",
+    params(ScimListParams),
+    responses((status = 200, body = ScimListResponse<ScimUser>),
+              (status = 400, body = ScimErrorBody, description = "过滤表达式无效"),),
+    tag = SCIM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn list_users(
+    State(service): State<ScimService>,
+    Query(params): Query<ScimListParams>,
+) -> Result<impl IntoResponse, ScimError> {
+    let users = service.list_users(params).await?;
+    Ok(ScimResponse(StatusCode::OK, users))
+}
+
+#[utoipa::path(
+    post,
+    path = "/Users",
+    request_body = ScimUser,
+    responses((status = 201, body = ScimUser, description = "创建成功"),
+              (status = 409, body = ScimErrorBody, description = "用户名或邮箱已存在"),),
+    tag = SCIM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn create_user(
+    State(service): State<ScimService>,
+    Json(resource): Json<ScimUser>,
+) -> Result<impl IntoResponse, ScimError> {
+    let user = service.create_user(resource).await?;
+    Ok(ScimResponse(StatusCode::CREATED, user))
+}
+
+#[utoipa::path(
+    get,
+    path = "/Users/{user_uuid}",
+    params(
+        ("user_uuid" = String, Path, description = "用户唯一UUID")
+    ),
+    responses((status = 200, body = ScimUser),
+              (status = 404, body = ScimErrorBody, description = "用户不存在"),),
+    tag = SCIM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn get_user(
+    Path(user_uuid): Path<String>,
+    State(service): State<ScimService>,
+) -> Result<impl IntoResponse, ScimError> {
+    let user = service.get_user(&user_uuid).await?;
+    Ok(ScimResponse(StatusCode::OK, user))
+}
+
+#[utoipa::path(
+    put,
+    path = "/Users/{user_uuid}",
+    request_body = ScimUser,
+    params(
+        ("user_uuid" = String, Path, description = "用户唯一UUID")
+    ),
+    responses((status = 200, body = ScimUser, description = "更新成功"),
+              (status = 404, body = ScimErrorBody, description = "用户不存在"),),
+    tag = SCIM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn replace_user(
+    Path(user_uuid): Path<String>,
+    State(service): State<ScimService>,
+    Json(resource): Json<ScimUser>,
+) -> Result<impl IntoResponse, ScimError> {
+    let user = service.replace_user(&user_uuid, resource).await?;
+    Ok(ScimResponse(StatusCode::OK, user))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/Users/{user_uuid}",
+    request_body = ScimPatchRequest,
+    params(
+        ("user_uuid" = String, Path, description = "用户唯一UUID")
+    ),
+    responses((status = 200, body = ScimUser, description = "更新成功"),
+              (status = 404, body = ScimErrorBody, description = "用户不存在"),),
+    tag = SCIM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn patch_user(
+    Path(user_uuid): Path<String>,
+    State(service): State<ScimService>,
+    Json(patch): Json<ScimPatchRequest>,
+) -> Result<impl IntoResponse, ScimError> {
+    let user = service.patch_user(&user_uuid, patch).await?;
+    Ok(ScimResponse(StatusCode::OK, user))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/Users/{user_uuid}",
+    params(
+        ("user_uuid" = String, Path, description = "用户唯一UUID")
+    ),
+    responses((status = 204, description = "用户已停用"),
+              (status = 404, body = ScimErrorBody, description = "用户不存在"),),
+    tag = SCIM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn delete_user(
+    Path(user_uuid): Path<String>,
+    State(service): State<ScimService>,
+) -> Result<impl IntoResponse, ScimError> {
+    service.delete_user(&user_uuid).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/Groups",
+    params(ScimListParams),
+    responses((status = 200, body = ScimListResponse<ScimGroup>),
+              (status = 400, body = ScimErrorBody, description = "过滤表达式无效"),),
+    tag = SCIM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn list_groups(
+    State(service): State<ScimService>,
+    Query(params): Query<ScimListParams>,
+) -> Result<impl IntoResponse, ScimError> {
+    let groups = service.list_groups(params).await?;
+    Ok(ScimResponse(StatusCode::OK, groups))
+}
+
+#[utoipa::path(
+    post,
+    path = "/Groups",
+    request_body = ScimGroup,
+    responses((status = 201, body = ScimGroup, description = "创建成功"),
+              (status = 409, body = ScimErrorBody, description = "用户组已存在"),),
+    tag = SCIM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn create_group(
+    State(service): State<ScimService>,
+    Json(resource): Json<ScimGroup>,
+) -> Result<impl IntoResponse, ScimError> {
+    let group = service.create_group(resource).await?;
+    Ok(ScimResponse(StatusCode::CREATED, group))
+}
+
+#[utoipa::path(
+    get,
+    path = "/Groups/{group_uuid}",
+    params(
+        ("group_uuid" = String, Path, description = "用户组唯一UUID")
+    ),
+    responses((status = 200, body = ScimGroup),
+              (status = 404, body = ScimErrorBody, description = "用户组不存在"),),
+    tag = SCIM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn get_group(
+    Path(group_uuid): Path<String>,
+    State(service): State<ScimService>,
+) -> Result<impl IntoResponse, ScimError> {
+    let group = service.get_group(&group_uuid).await?;
+    Ok(ScimResponse(StatusCode::OK, group))
+}
+
+#[utoipa::path(
+    put,
+    path = "/Groups/{group_uuid}",
+    request_body = ScimGroup,
+    params(
+        ("group_uuid" = String, Path, description = "用户组唯一UUID")
+    ),
+    responses((status = 200, body = ScimGroup, description = "更新成功"),
+              (status = 404, body = ScimErrorBody, description = "用户组不存在"),),
+    tag = SCIM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn replace_group(
+    Path(group_uuid): Path<String>,
+    State(service): State<ScimService>,
+    Json(resource): Json<ScimGroup>,
+) -> Result<impl IntoResponse, ScimError> {
+    let group = service.replace_group(&group_uuid, resource).await?;
+    Ok(ScimResponse(StatusCode::OK, group))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/Groups/{group_uuid}",
+    request_body = ScimPatchRequest,
+    params(
+        ("group_uuid" = String, Path, description = "用户组唯一UUID")
+    ),
+    responses((status = 200, body = ScimGroup, description = "更新成功"),
+              (status = 404, body = ScimErrorBody, description = "用户组不存在"),),
+    tag = SCIM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn patch_group(
+    Path(group_uuid): Path<String>,
+    State(service): State<ScimService>,
+    Json(patch): Json<ScimPatchRequest>,
+) -> Result<impl IntoResponse, ScimError> {
+    let group = service.patch_group(&group_uuid, patch).await?;
+    Ok(ScimResponse(StatusCode::OK, group))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/Groups/{group_uuid}",
+    params(
+        ("group_uuid" = String, Path, description = "用户组唯一UUID")
+    ),
+    responses((status = 204, description = "删除成功"),
+              (status = 404, body = ScimErrorBody, description = "用户组不存在"),),
+    tag = SCIM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn delete_group(
+    Path(group_uuid): Path<String>,
+    State(service): State<ScimService>,
+) -> Result<impl IntoResponse, ScimError> {
+    service.delete_group(&group_uuid).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
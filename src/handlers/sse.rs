@@ -3,12 +3,19 @@ use crate::services::sse::SSEService;
 use crate::errors::app_error::AppError;
 use crate::schemas::auth::CurrentUser;
 use axum::extract::State;
+use axum::http::HeaderMap;
 use axum::{response::sse::{Event, Sse}, Extension};
 use futures_util::Stream;
 
 pub async fn global_message_push(
     State(service): State<SSEService>,
     Extension(current_user): Extension<CurrentUser>,
+    headers: HeaderMap,
 ) -> Sse<impl Stream<Item = Result<Event, AppError>>> {
-    service.global_message(current_user).await
+    // 浏览器重连时会带上最后收到的事件ID
+    let last_event_id = headers
+        .get("Last-Event-ID")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    service.global_message(current_user, last_event_id).await
 }
\ No newline at end of file
@@ -0,0 +1,273 @@
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use validator::Validate;
+
+use crate::config::openapi::SYSTEM_TAG;
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::paginated::PaginatedApiResponse;
+use crate::schemas::response::ApiResponse;
+use crate::schemas::system::{
+    CreateSystemDto, SystemConfigResponse, SystemQueryParams, SystemResponse, UpdateSystemDto,
+    UpsertSystemConfigDto,
+};
+use crate::services::system::SystemService;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(SystemQueryParams),
+    responses((status = 200, body = Vec<SystemResponse>),),
+    tag = SYSTEM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn list_systems(
+    State(service): State<SystemService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Query(params): Query<SystemQueryParams>,
+) -> Result<impl IntoResponse, AppError> {
+    params.validate()?;
+    let (systems, total) = service.list_systems(
+        current_user,
+        context,
+        params.clone()
+    ).await?;
+    Ok(PaginatedApiResponse::success(
+        systems,
+        total,
+        params.page,
+        params.page_size,
+        StatusCode::OK,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    request_body=CreateSystemDto,
+    responses(( status=201, body=SystemResponse, description = "创建成功"),
+                (status=400, description = "系统编码或配置 Schema 无效"),
+                (status=409, description = "系统编码已存在"),),
+    tag = SYSTEM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn create_system(
+    State(service): State<SystemService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Json(dto): Json<CreateSystemDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    let system = service.create_system(
+        current_user,
+        context,
+        dto).await?;
+    Ok(ApiResponse::success(system, StatusCode::CREATED))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{system_uuid}",
+    params(
+        ("system_uuid" = String, Path, description = "系统UUID")
+    ),
+    responses(( status=200, body=SystemResponse, description = "获取成功"),
+    ( status=404, description = "不存在"),),
+    tag = SYSTEM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn get_system(
+    Path(system_uuid): Path<String>,
+    State(service): State<SystemService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    let system = service.get_system(
+        current_user,
+        context,
+        system_uuid).await?;
+    Ok(ApiResponse::success(system, StatusCode::OK))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{system_uuid}",
+    request_body=UpdateSystemDto,
+    params(
+        ("system_uuid" = String, Path, description = "系统UUID")
+    ),
+    responses(( status=200, body=SystemResponse, description = "更新成功"),
+                (status=400, description="配置 Schema 无效或与已有配置不兼容"),
+                (status=404, description="系统不存在"),),
+    tag = SYSTEM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn update_system(
+    Path(system_uuid): Path<String>,
+    State(service): State<SystemService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Json(dto): Json<UpdateSystemDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    let system = service.update_system(
+        current_user,
+        context,
+        system_uuid,
+        dto).await?;
+    Ok(ApiResponse::success(system, StatusCode::OK))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{system_uuid}",
+    params(
+        ("system_uuid" = String, Path, description = "系统UUID")
+    ),
+    responses(( status=204, description = "删除成功"),
+                (status=404, description="系统不存在"),),
+    tag = SYSTEM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn delete_system(
+    Path(system_uuid): Path<String>,
+    State(service): State<SystemService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    service.delete_system(
+        current_user,
+        context,
+        system_uuid).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/{system_uuid}/configs",
+    params(
+        ("system_uuid" = String, Path, description = "系统UUID")
+    ),
+    responses(( status=200, body=Vec<SystemConfigResponse>, description = "获取成功"),
+                (status=404, description="系统不存在"),),
+    tag = SYSTEM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn list_configs(
+    Path(system_uuid): Path<String>,
+    State(service): State<SystemService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    let configs = service.list_configs(
+        current_user,
+        context,
+        system_uuid).await?;
+    Ok(ApiResponse::success(configs, StatusCode::OK))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{system_uuid}/configs/{name}",
+    params(
+        ("system_uuid" = String, Path, description = "系统UUID"),
+        ("name" = String, Path, description = "配置名称")
+    ),
+    responses(( status=200, body=SystemConfigResponse, description = "获取成功"),
+                (status=404, description="系统或配置不存在"),),
+    tag = SYSTEM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn get_config(
+    Path((system_uuid, name)): Path<(String, String)>,
+    State(service): State<SystemService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    let config = service.get_config(
+        current_user,
+        context,
+        system_uuid,
+        name).await?;
+    Ok(ApiResponse::success(config, StatusCode::OK))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{system_uuid}/configs/{name}",
+    request_body=UpsertSystemConfigDto,
+    params(
+        ("system_uuid" = String, Path, description = "系统UUID"),
+        ("name" = String, Path, description = "配置名称")
+    ),
+    responses(( status=200, body=SystemConfigResponse, description = "保存成功"),
+                (status=400, description="配置不符合系统的 Schema"),
+                (status=404, description="系统不存在"),),
+    tag = SYSTEM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn upsert_config(
+    Path((system_uuid, name)): Path<(String, String)>,
+    State(service): State<SystemService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Json(dto): Json<UpsertSystemConfigDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let config = service.upsert_config(
+        current_user,
+        context,
+        system_uuid,
+        name,
+        dto).await?;
+    Ok(ApiResponse::success(config, StatusCode::OK))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{system_uuid}/configs/{name}",
+    params(
+        ("system_uuid" = String, Path, description = "系统UUID"),
+        ("name" = String, Path, description = "配置名称")
+    ),
+    responses(( status=204, description = "删除成功"),
+                (status=404, description="系统或配置不存在"),),
+    tag = SYSTEM_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn delete_config(
+    Path((system_uuid, name)): Path<(String, String)>,
+    State(service): State<SystemService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    service.delete_config(
+        current_user,
+        context,
+        system_uuid,
+        name).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,159 @@
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use validator::Validate;
+
+use crate::config::openapi::TENANT_TAG;
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::paginated::PaginatedApiResponse;
+use crate::schemas::response::ApiResponse;
+use crate::schemas::tenant::{
+    CreateTenantDto, TenantQueryParams, TenantResponse, UpdateTenantDto,
+};
+use crate::services::tenant::TenantService;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(TenantQueryParams),
+    responses((status = 200, body = Vec<TenantResponse>),),
+    tag = TENANT_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn list_tenants(
+    State(service): State<TenantService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Query(params): Query<TenantQueryParams>,
+) -> Result<impl IntoResponse, AppError> {
+    params.validate()?;
+    let (tenants, total) = service.list_tenants(
+        current_user,
+        context,
+        params.clone()
+    ).await?;
+    Ok(PaginatedApiResponse::success(
+        tenants,
+        total,
+        params.page,
+        params.page_size,
+        StatusCode::OK,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    request_body=CreateTenantDto,
+    responses(( status=201, body=TenantResponse, description = "创建成功"),
+                (status=400, description = "租户编码、命名空间或管理员密码无效"),
+                (status=409, description = "租户编码或命名空间已存在"),),
+    tag = TENANT_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn create_tenant(
+    State(service): State<TenantService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Json(dto): Json<CreateTenantDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    let tenant = service.create_tenant(
+        current_user,
+        context,
+        dto).await?;
+    Ok(ApiResponse::success(tenant, StatusCode::CREATED))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{tenant_uuid}",
+    params(
+        ("tenant_uuid" = String, Path, description = "租户UUID")
+    ),
+    responses(( status=200, body=TenantResponse, description = "获取成功"),
+    ( status=404, description = "不存在"),),
+    tag = TENANT_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn get_tenant(
+    Path(tenant_uuid): Path<String>,
+    State(service): State<TenantService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    let tenant = service.get_tenant(
+        current_user,
+        context,
+        tenant_uuid).await?;
+    Ok(ApiResponse::success(tenant, StatusCode::OK))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{tenant_uuid}",
+    request_body=UpdateTenantDto,
+    params(
+        ("tenant_uuid" = String, Path, description = "租户UUID")
+    ),
+    responses(( status=200, body=TenantResponse, description = "更新成功"),
+                (status=403, description="默认租户不能停用"),
+                (status=404, description="租户不存在"),),
+    tag = TENANT_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn update_tenant(
+    Path(tenant_uuid): Path<String>,
+    State(service): State<TenantService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Json(dto): Json<UpdateTenantDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    let tenant = service.update_tenant(
+        current_user,
+        context,
+        tenant_uuid,
+        dto).await?;
+    Ok(ApiResponse::success(tenant, StatusCode::OK))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{tenant_uuid}",
+    params(
+        ("tenant_uuid" = String, Path, description = "租户UUID")
+    ),
+    responses(( status=204, description = "删除成功"),
+                (status=403, description="默认租户不能删除"),
+                (status=404, description="租户不存在"),),
+    tag = TENANT_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn delete_tenant(
+    Path(tenant_uuid): Path<String>,
+    State(service): State<TenantService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    service.delete_tenant(
+        current_user,
+        context,
+        tenant_uuid).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -235,4 +235,30 @@ pub async fn revoke_roles(
         user_uuid,
         role_uuid).await?;
     Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{user_uuid}/lock",
+    params(
+        ("user_uuid" = String, Path, description = "用户唯一UUID")
+    ),
+    responses(( status=204, description="解除登录锁定成功"),
+                (status=404, description="用户不存在"),),
+    tag = USER_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn unlock_user(
+    Path(user_uuid): Path<String>,
+    State(service): State<UserService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    service.unlock_user(
+        current_user,
+        context,
+        user_uuid).await?;
+    Ok(StatusCode::NO_CONTENT)
 }
\ No newline at end of file
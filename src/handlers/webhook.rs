@@ -0,0 +1,222 @@
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use validator::Validate;
+
+use crate::config::openapi::WEBHOOK_TAG;
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::paginated::PaginatedApiResponse;
+use crate::schemas::response::ApiResponse;
+use crate::schemas::webhook::{
+    CreateWebhookDto, DeliveryQueryParams, UpdateWebhookDto, WebhookDeliveryResponse,
+    WebhookQueryParams, WebhookResponse,
+};
+use crate::services::webhook::WebhookService;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(WebhookQueryParams),
+    responses((status = 200, body = Vec<WebhookResponse>),),
+    tag = WEBHOOK_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn list_webhooks(
+    State(service): State<WebhookService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Query(params): Query<WebhookQueryParams>,
+) -> Result<impl IntoResponse, AppError> {
+    params.validate()?;
+    let (webhooks, total) = service.list_webhooks(
+        current_user,
+        context,
+        params.clone()
+    ).await?;
+    Ok(PaginatedApiResponse::success(
+        webhooks,
+        total,
+        params.page,
+        params.page_size,
+        StatusCode::OK,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    request_body=CreateWebhookDto,
+    responses(( status=201, body=WebhookResponse, description = "创建成功, 签名密钥只返回这一次"),
+                (status=400, description = "未知的事件类型"),),
+    tag = WEBHOOK_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn create_webhook(
+    State(service): State<WebhookService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Json(dto): Json<CreateWebhookDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    let webhook = service.create_webhook(
+        current_user,
+        context,
+        dto).await?;
+    Ok(ApiResponse::success(webhook, StatusCode::CREATED))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{webhook_uuid}",
+    params(
+        ("webhook_uuid" = String, Path, description = "Webhook唯一UUID")
+    ),
+    responses(( status=200, body=WebhookResponse, description = "获取成功"),
+    ( status=404, description = "不存在"),),
+    tag = WEBHOOK_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn get_webhook(
+    Path(webhook_uuid): Path<String>,
+    State(service): State<WebhookService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    let webhook = service.get_webhook(
+        current_user,
+        context,
+        webhook_uuid).await?;
+    Ok(ApiResponse::success(webhook, StatusCode::OK))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{webhook_uuid}",
+    request_body=UpdateWebhookDto,
+    params(
+        ("webhook_uuid" = String, Path, description = "Webhook唯一UUID")
+    ),
+    responses(( status=200, body=WebhookResponse, description = "更新成功"),
+                (status=404, description="Webhook不存在"),),
+    tag = WEBHOOK_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn update_webhook(
+    Path(webhook_uuid): Path<String>,
+    State(service): State<WebhookService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Json(dto): Json<UpdateWebhookDto>,
+) -> Result<impl IntoResponse, AppError> {
+    dto.validate()?;
+    let webhook = service.update_webhook(
+        current_user,
+        context,
+        webhook_uuid,
+        dto).await?;
+    Ok(ApiResponse::success(webhook, StatusCode::OK))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{webhook_uuid}",
+    params(
+        ("webhook_uuid" = String, Path, description = "Webhook唯一UUID")
+    ),
+    responses(( status=204, description = "删除成功"),
+                (status=404, description="Webhook不存在"),),
+    tag = WEBHOOK_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn delete_webhook(
+    Path(webhook_uuid): Path<String>,
+    State(service): State<WebhookService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    service.delete_webhook(
+        current_user,
+        context,
+        webhook_uuid).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/{webhook_uuid}/deliveries",
+    params(
+        ("webhook_uuid" = String, Path, description = "Webhook唯一UUID"),
+        DeliveryQueryParams
+    ),
+    responses((status = 200, body = Vec<WebhookDeliveryResponse>),
+              (status=404, description="Webhook不存在"),),
+    tag = WEBHOOK_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn list_deliveries(
+    Path(webhook_uuid): Path<String>,
+    State(service): State<WebhookService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+    Query(params): Query<DeliveryQueryParams>,
+) -> Result<impl IntoResponse, AppError> {
+    params.validate()?;
+    let (deliveries, total) = service.list_deliveries(
+        current_user,
+        context,
+        webhook_uuid,
+        params.clone()
+    ).await?;
+    Ok(PaginatedApiResponse::success(
+        deliveries,
+        total,
+        params.page,
+        params.page_size,
+        StatusCode::OK,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{webhook_uuid}/deliveries/{delivery_uuid}/redeliver",
+    params(
+        ("webhook_uuid" = String, Path, description = "Webhook唯一UUID"),
+        ("delivery_uuid" = String, Path, description = "投递记录UUID")
+    ),
+    responses(( status=202, body=WebhookDeliveryResponse, description = "已重新加入投递队列"),
+                (status=404, description="投递记录不存在"),),
+    tag = WEBHOOK_TAG,
+    security(
+      ("bearerAuth" = [])
+    ),
+)]
+pub async fn redeliver(
+    Path((webhook_uuid, delivery_uuid)): Path<(String, String)>,
+    State(service): State<WebhookService>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(context): Extension<CedarContext>,
+) -> Result<impl IntoResponse, AppError> {
+    let delivery = service.redeliver(
+        current_user,
+        context,
+        webhook_uuid,
+        delivery_uuid).await?;
+    Ok(ApiResponse::success(delivery, StatusCode::ACCEPTED))
+}
@@ -24,7 +24,13 @@ mod services;
 mod utils;
 mod schemas;
 
-use crate::utils::function::subscribe_to_policy_updates;
+use crate::services::event_bus::subscribe_to_domain_events;
+use crate::utils::sse::{refresh_presence, subscribe_to_sse_messages};
+use crate::services::message::run_scheduled_messages;
+use crate::services::webhook::run_webhook_worker;
+use crate::services::role_request::run_role_assignment_scheduler;
+use crate::services::access_review::run_access_review_reminders;
+use crate::services::ldap::run_ldap_sync;
 
 
 #[derive(Parser, Debug)]
@@ -74,10 +80,38 @@ async fn main(){
     ).await.expect("Failed to initialize state");
     
 
-    // 启动后台任务，监听访问策略更新
+    // 启动后台任务，接收其他节点发布的领域事件(策略更新、缓存失效等)
     let state_for_subscriber = app_state.clone();
     tokio::spawn(async move {
-        subscribe_to_policy_updates(state_for_subscriber).await;
+        subscribe_to_domain_events(state_for_subscriber).await;
+    });
+
+    // 启动后台任务，接收其他节点转发的SSE消息并维护在线状态
+    let state_for_sse = app_state.clone();
+    tokio::spawn(async move {
+        subscribe_to_sse_messages(state_for_sse).await;
+    });
+    tokio::spawn(refresh_presence(app_state.clone()));
+
+    // 启动后台任务，发送到期的定时消息
+    tokio::spawn(run_scheduled_messages(app_state.clone()));
+
+    // 启动后台任务，投递 Webhook
+    tokio::spawn(run_webhook_worker(app_state.clone()));
+
+    // 启动后台任务，定时同步 LDAP 目录
+    tokio::spawn(run_ldap_sync(app_state.clone()));
+
+    // 启动后台任务，激活到达生效时间的角色分配并撤销到期的角色分配
+    tokio::spawn(run_role_assignment_scheduler(app_state.clone()));
+
+    // 启动后台任务，提醒访问权限复核的复核人
+    tokio::spawn(run_access_review_reminders(app_state.clone()));
+
+    // 启动后台任务，投递发件箱中的邮件
+    let email_service = app_state.email_service.clone();
+    tokio::spawn(async move {
+        email_service.run_outbox_worker().await;
     });
     
     let (router, api) = OpenApiRouter::with_openapi(config::openapi::ApiDoc::openapi())
@@ -100,5 +134,10 @@ async fn main(){
     info!("正在监听 {}", addr);
     // Run server
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, router).await.unwrap();
+    axum::serve(
+        listener,
+        router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
@@ -1,18 +1,31 @@
 use crate::config::app::BLACK_LIST_JTI;
 use crate::config::state::AppState;
 use crate::errors::app_error::AppError;
+use crate::middlewares::client_ip::ClientIp;
 use crate::schemas::{auth::CurrentUser, cedar_policy::CedarContext};
+use crate::schemas::personal_token::PERSONAL_TOKEN_PREFIX;
+use crate::schemas::robot_account::ROBOT_TOKEN_PREFIX;
+use crate::schemas::tenant::CurrentTenant;
+use crate::services::auth::is_token_revoked;
+use crate::services::personal_token::authenticate_personal_token;
+use crate::services::robot_account::authenticate_robot;
 use crate::utils::jwt::decode_token;
 use axum::{
-    extract::{ConnectInfo, Request, State},
+    extract::{ConnectInfo, OriginalUri, Request, State},
     middleware::Next,
     response::Response,
 };
 use redis::AsyncCommands;
 use std::net::SocketAddr;
-use std::str::FromStr;
 use cedar_policy::{Context, Expression};
-use crate::unauthorized;
+use crate::{forbidden, unauthorized};
+
+// 密码过期时仍允许访问的接口
+const PASSWORD_EXPIRED_ALLOWED_PATHS: &[&str] = &[
+    "/api/v1/me/password",
+    "/api/v1/me/profile",
+    "/api/v1/auth/logout",
+];
 
 pub async fn auth_guard_middleware(
     State(state): State<AppState>,
@@ -25,34 +38,58 @@ pub async fn auth_guard_middleware(
         .and_then(|h| h.to_str().ok())
         .and_then(|v| v.strip_prefix("Bearer "))
     {
+        // 个人访问令牌映射为令牌所属用户, 按令牌的 scope 限制可访问的接口
+        if token.starts_with(PERSONAL_TOKEN_PREFIX) {
+            let path = original_path(&req);
+            let source_ip = source_ip(&req);
+            let current_user = authenticate_personal_token(&state, token, req.method(), &path, &source_ip).await?;
+            ensure_same_tenant(&req, &current_user)?;
+            req.extensions_mut().insert(current_user);
+            req.extensions_mut().insert(CedarContext { source_ip });
+            return Ok(next.run(req).await);
+        }
+
+        // 服务账号密钥, 以 RobotAccount 主体进行授权
+        if token.starts_with(ROBOT_TOKEN_PREFIX) {
+            let path = original_path(&req);
+            let source_ip = source_ip(&req);
+            let current_user = authenticate_robot(&state, token, &path, &source_ip).await?;
+            ensure_same_tenant(&req, &current_user)?;
+            req.extensions_mut().insert(current_user);
+            req.extensions_mut().insert(CedarContext { source_ip });
+            return Ok(next.run(req).await);
+        }
+
         let payload = decode_token(token)?;
         // 检查黑名单...
         let mut redis_conn = state.redis.get_multiplexed_async_connection().await?;
         if redis_conn
             .exists::<_, bool>(format!("{}:{}", BLACK_LIST_JTI, payload.jti))
             .await?
+            || is_token_revoked(&mut redis_conn, &payload).await?
         {
             return Err(unauthorized!("InvalidToken".to_string()));
         };
 
+        if payload.password_expired {
+            let path = original_path(&req);
+            if !PASSWORD_EXPIRED_ALLOWED_PATHS.contains(&path.as_str()) {
+                return Err(forbidden!("Password expired, please change your password".to_string()));
+            }
+        }
+
         let current_user = CurrentUser {
             uuid: payload.sub,
             dept_uuid: payload.dept_id,
             username: payload.name,
             is_super_admin: payload.is_super_admin,
+            tenant_id: payload.tenant_id,
         };
+        ensure_same_tenant(&req, &current_user)?;
         req.extensions_mut().insert(current_user);
 
-        let remote_addr = req
-            .extensions()
-            .get::<ConnectInfo<SocketAddr>>()
-            .map(|ci| ci.0)
-            .unwrap_or(SocketAddr::from_str("127.0.0.1:6000").unwrap())
-            .ip()
-            .to_string();
-        
         let cedar_context = CedarContext {
-            source_ip: remote_addr
+            source_ip: source_ip(&req)
         };
         req.extensions_mut().insert(cedar_context);
 
@@ -60,3 +97,29 @@ pub async fn auth_guard_middleware(
     };
     return Err(unauthorized!("Unauthorized".to_string()));
 }
+
+// 凭据只能在其所属租户下使用, 防止跨租户访问
+fn ensure_same_tenant(req: &Request, current_user: &CurrentUser) -> Result<(), AppError> {
+    match req.extensions().get::<CurrentTenant>() {
+        Some(tenant) if tenant.tenant_id == current_user.tenant_id => Ok(()),
+        Some(tenant) => Err(unauthorized!("Credential does not belong to tenant {}", tenant.code)),
+        None => Err(unauthorized!("Credential does not belong to this tenant")),
+    }
+}
+
+// 嵌套路由中 uri 不含前缀, 使用完整的请求路径
+fn original_path(req: &Request) -> String {
+    req.extensions()
+        .get::<OriginalUri>()
+        .map(|uri| uri.path().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string())
+}
+
+// 客户端地址由 client_ip_middleware 按受信任代理配置确定
+fn source_ip(req: &Request) -> String {
+    req.extensions()
+        .get::<ClientIp>()
+        .map(|ClientIp(ip)| ip.to_string())
+        .or_else(|| req.extensions().get::<ConnectInfo<SocketAddr>>().map(|ci| ci.0.ip().to_string()))
+        .unwrap_or_else(|| "127.0.0.1".to_string())
+}
@@ -0,0 +1,28 @@
+use crate::config::state::AppState;
+use crate::utils::net::client_ip;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+/// 请求的客户端地址, 经过受信任代理时取自 X-Forwarded-For
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub IpAddr);
+
+// 在限流、登录防爆破和鉴权之前确定客户端地址, 之后统一从请求扩展中读取
+pub async fn client_ip_middleware(
+    State(state): State<AppState>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let peer = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ci| ci.0.ip())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+    let ip = client_ip(peer, req.headers(), &state.config.security.trusted_proxies);
+    req.extensions_mut().insert(ClientIp(ip));
+    next.run(req).await
+}
@@ -1,4 +1,8 @@
 // 中间件模块入口
 
 pub mod audit_log;
-pub mod auth_guard;
\ No newline at end of file
+pub mod auth_guard;
+pub mod client_ip;
+pub mod rate_limit;
+pub mod scim_auth;
+pub mod tenant;
\ No newline at end of file
@@ -0,0 +1,151 @@
+use crate::config::rate_limit::RATE_LIMIT_PREFIX;
+use crate::config::state::AppState;
+use crate::errors::app_error::AppError;
+use crate::middlewares::client_ip::ClientIp;
+use crate::too_many_requests;
+use crate::utils::jwt::decode_token;
+use axum::{
+    extract::{ConnectInfo, OriginalUri, Request, State},
+    http::{HeaderMap, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::net::SocketAddr;
+use std::sync::LazyLock;
+
+// 令牌桶: 在Redis中原子地补充并扣减令牌, 时间取Redis服务器时间避免多实例时钟偏差
+// 返回 {是否放行, 剩余令牌, 需要等待的秒数, 桶回满的秒数}
+static TOKEN_BUCKET_SCRIPT: LazyLock<redis::Script> = LazyLock::new(|| {
+    redis::Script::new(
+        r#"
+local capacity = tonumber(ARGV[1])
+local rate = tonumber(ARGV[2])
+local t = redis.call('TIME')
+local now = tonumber(t[1]) + tonumber(t[2]) / 1000000
+
+local bucket = redis.call('HMGET', KEYS[1], 'tokens', 'ts')
+local tokens = tonumber(bucket[1])
+local ts = tonumber(bucket[2])
+if tokens == nil or ts == nil then
+    tokens = capacity
+    ts = now
+end
+
+tokens = math.min(capacity, tokens + math.max(0, now - ts) * rate)
+local allowed = 0
+if tokens >= 1 then
+    tokens = tokens - 1
+    allowed = 1
+end
+
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'ts', tostring(now))
+redis.call('EXPIRE', KEYS[1], math.ceil(capacity / rate) + 1)
+
+local retry_after = 0
+if allowed == 0 then
+    retry_after = math.ceil((1 - tokens) / rate)
+end
+return {allowed, math.floor(tokens), retry_after, math.ceil((capacity - tokens) / rate)}
+"#,
+    )
+});
+
+pub async fn rate_limit_middleware(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let config = &state.config.rate_limit;
+    if !config.enabled {
+        return Ok(next.run(req).await);
+    }
+
+    // 嵌套路由中 uri 已去掉前缀, 使用原始路径匹配路由组
+    let path = req
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|uri| uri.path().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+    let (group, rule) = config.rule_for(&path);
+
+    // 已登录用户按UUID限流, 其余按IP限流
+    let subject = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .and_then(|token| decode_token(token).ok())
+        .map(|claims| format!("user:{}", claims.sub))
+        .unwrap_or_else(|| {
+            let ip = req
+                .extensions()
+                .get::<ClientIp>()
+                .map(|ClientIp(ip)| ip.to_string())
+                .or_else(|| req.extensions().get::<ConnectInfo<SocketAddr>>().map(|ci| ci.0.ip().to_string()))
+                .unwrap_or_else(|| "127.0.0.1".to_string());
+            format!("ip:{}", ip)
+        });
+    let key = format!("{}:{}:{}", RATE_LIMIT_PREFIX, group, subject);
+
+    // Redis不可用时放行, 不因限流组件故障拒绝服务
+    let result: Result<(i64, i64, i64, i64), redis::RedisError> = async {
+        let mut redis_conn = state.redis.get_multiplexed_async_connection().await?;
+        TOKEN_BUCKET_SCRIPT
+            .key(&key)
+            .arg(rule.capacity)
+            .arg(rule.refill_per_second)
+            .invoke_async(&mut redis_conn)
+            .await
+    }
+    .await;
+    let (allowed, remaining, retry_after, reset) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            tracing::error!("Rate limit check failed: {}", e);
+            return Ok(next.run(req).await);
+        }
+    };
+
+    if allowed == 0 {
+        return Ok(limited_response(rule.capacity, remaining, retry_after, reset));
+    }
+
+    let mut response = next.run(req).await;
+    insert_rate_limit_headers(response.headers_mut(), rule.capacity, remaining, reset);
+    Ok(response)
+}
+
+// 超出限额时的 429 响应
+fn limited_response(limit: u32, remaining: i64, retry_after: i64, reset: i64) -> Response {
+    let mut response =
+        too_many_requests!("Too many requests, please retry after {} seconds", retry_after)
+            .into_response();
+    let headers = response.headers_mut();
+    insert_rate_limit_headers(headers, limit, remaining, reset);
+    headers.insert("Retry-After", HeaderValue::from(retry_after));
+    response
+}
+
+fn insert_rate_limit_headers(headers: &mut HeaderMap, limit: u32, remaining: i64, reset: i64) {
+    headers.insert("RateLimit-Limit", HeaderValue::from(limit));
+    headers.insert("RateLimit-Remaining", HeaderValue::from(remaining));
+    headers.insert("RateLimit-Reset", HeaderValue::from(reset));
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    #[test]
+    fn limited_response_carries_rate_limit_headers() {
+        let response = limited_response(10, 0, 5, 50);
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        let header = |name: &str| response.headers().get(name).unwrap().to_str().unwrap().to_string();
+        assert_eq!(header("RateLimit-Limit"), "10");
+        assert_eq!(header("RateLimit-Remaining"), "0");
+        assert_eq!(header("RateLimit-Reset"), "50");
+        assert_eq!(header("Retry-After"), "5");
+    }
+}
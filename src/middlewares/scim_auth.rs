@@ -0,0 +1,32 @@
+use crate::config::state::AppState;
+use crate::errors::app_error::AppError;
+use crate::schemas::scim::ScimError;
+use crate::utils::crypto::hash_token;
+use crate::{not_found, unauthorized};
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+// SCIM 接口使用配置中的静态 Bearer 令牌认证, 比较哈希值避免通过耗时猜测令牌
+pub async fn scim_auth_middleware(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Result<Response, ScimError> {
+    let config = &state.config.scim;
+    if !config.enabled || config.bearer_token.is_empty() {
+        return Err(not_found!("SCIM is not enabled").into());
+    }
+
+    let token = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    match token {
+        Some(token) if hash_token(token) == hash_token(&config.bearer_token) => Ok(next.run(req).await),
+        _ => Err(unauthorized!("Invalid SCIM token").into()),
+    }
+}
@@ -0,0 +1,67 @@
+use crate::config::state::AppState;
+use crate::entity::tenants;
+use crate::errors::app_error::AppError;
+use crate::schemas::tenant::{CurrentTenant, DEFAULT_TENANT_ID};
+use crate::{forbidden, not_found};
+use axum::{
+    extract::{Request, State},
+    http::header::HOST,
+    middleware::Next,
+    response::Response,
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+
+// 识别请求所属租户: 先看请求头, 再看子域名, 都没有时为默认租户
+pub async fn tenant_middleware(
+    State(state): State<AppState>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let config = &state.config.tenant;
+    let code = req
+        .headers()
+        .get(config.header_name.as_str())
+        .and_then(|h| h.to_str().ok())
+        .map(|code| code.trim().to_lowercase())
+        .filter(|code| !code.is_empty())
+        .or_else(|| {
+            let base_domain = config.base_domain.as_deref()?;
+            let host = req.headers().get(HOST)?.to_str().ok()?;
+            subdomain_of(host, base_domain)
+        });
+
+    let tenant = match code {
+        Some(code) => tenants::Entity::find()
+            .filter(tenants::Column::Code.eq(&code))
+            .filter(tenants::Column::IsActive.eq(true))
+            .one(&state.db)
+            .await?
+            .ok_or(not_found!("Tenant {} not found", code))?,
+        None => tenants::Entity::find_by_id(DEFAULT_TENANT_ID)
+            .one(&state.db)
+            .await?
+            .ok_or(not_found!("Default tenant not found"))?,
+    };
+
+    req.extensions_mut().insert(CurrentTenant {
+        tenant_id: tenant.tenant_id,
+        code: tenant.code,
+    });
+    Ok(next.run(req).await)
+}
+
+// 平台级功能(租户、单点登录)只对默认租户开放
+// 认证中间件已保证凭据属于当前租户, 这里只需检查请求所属租户
+pub async fn platform_tenant_middleware(req: Request, next: Next) -> Result<Response, AppError> {
+    match req.extensions().get::<CurrentTenant>() {
+        Some(tenant) if tenant.tenant_id == DEFAULT_TENANT_ID => Ok(next.run(req).await),
+        _ => Err(forbidden!("This feature is only available to the platform tenant")),
+    }
+}
+
+// acme.admin.example.com:8080 在 base_domain 为 admin.example.com 时返回 acme
+fn subdomain_of(host: &str, base_domain: &str) -> Option<String> {
+    let host = host.split(':').next()?.to_lowercase();
+    let subdomain = host.strip_suffix(&base_domain.to_lowercase())?.strip_suffix('.')?;
+    (!subdomain.is_empty() && !subdomain.contains('.')).then(|| subdomain.to_string())
+}
@@ -1,11 +1,28 @@
+use axum::middleware;
 use utoipa_axum::router::OpenApiRouter;
 use crate::config::state::AppState;
+use crate::middlewares::client_ip::client_ip_middleware;
+use crate::middlewares::rate_limit::rate_limit_middleware;
+use crate::middlewares::tenant::tenant_middleware;
 
 mod v1;
+mod scim;
+mod oauth2;
 
 
 pub fn api_router(app_state: AppState) -> OpenApiRouter {
     OpenApiRouter::new()
     .nest("/v1", v1::protected_router(app_state.clone()))
         .nest("/v1", v1::public_router(app_state.clone()))
+        .nest("/scim/v2", scim::scim_router(app_state.clone()))
+        .nest("/oauth2", oauth2::oauth2_router(app_state.clone()))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(), rate_limit_middleware
+        ))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(), tenant_middleware
+        ))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(), client_ip_middleware
+        ))
 }
\ No newline at end of file
@@ -0,0 +1,18 @@
+// OAuth2 / OpenID Connect 协议端点, 由客户端应用调用, 不经过后台的登录认证
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+use crate::config::state::AppState;
+use crate::handlers::oauth;
+use crate::services::oauth::OAuthService;
+
+pub fn oauth2_router(app_state: AppState) -> OpenApiRouter {
+    let service = OAuthService::new(app_state);
+
+    OpenApiRouter::new()
+        .routes(routes!(oauth::discovery))
+        .routes(routes!(oauth::jwks))
+        .routes(routes!(oauth::authorize))
+        .routes(routes!(oauth::token))
+        .routes(routes!(oauth::userinfo))
+        .with_state(service)
+}
@@ -0,0 +1,23 @@
+// SCIM 2.0 路由, 使用独立的令牌认证
+use axum::middleware;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+use crate::config::state::AppState;
+use crate::handlers::scim;
+use crate::middlewares::scim_auth::scim_auth_middleware;
+use crate::services::scim::ScimService;
+
+pub fn scim_router(app_state: AppState) -> OpenApiRouter {
+    let service = ScimService::new(app_state.clone());
+
+    OpenApiRouter::new()
+        .routes(routes!(scim::service_provider_config))
+        .routes(routes!(scim::list_users, scim::create_user))
+        .routes(routes!(scim::get_user, scim::replace_user, scim::patch_user, scim::delete_user))
+        .routes(routes!(scim::list_groups, scim::create_group))
+        .routes(routes!(scim::get_group, scim::replace_group, scim::patch_group, scim::delete_group))
+        .with_state(service)
+        .layer(middleware::from_fn_with_state(
+            app_state, scim_auth_middleware
+        ))
+}
@@ -0,0 +1,20 @@
+use crate::config::state::AppState;
+use crate::handlers::access_review;
+use crate::services::access_review::AccessReviewService;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+pub fn protected_routes(app_state: AppState) -> OpenApiRouter {
+    let service = AccessReviewService::new(app_state);
+
+    OpenApiRouter::new()
+        .routes(routes!(access_review::list_reviews, access_review::create_review))
+        .routes(routes!(access_review::get_review))
+        .routes(routes!(access_review::list_items))
+        .routes(routes!(access_review::decide_item))
+        .routes(routes!(access_review::complete_review))
+        .routes(routes!(access_review::cancel_review))
+        .routes(routes!(access_review::remind_reviewers))
+        .routes(routes!(access_review::get_report))
+        .with_state(service)
+}
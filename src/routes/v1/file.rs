@@ -0,0 +1,28 @@
+use axum::extract::DefaultBodyLimit;
+use crate::services::file::FileService;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+use crate::handlers::file;
+use crate::config::state::AppState;
+
+// multipart 表单边界等额外开销
+pub const MULTIPART_OVERHEAD_BYTES: usize = 64 * 1024;
+
+pub fn protected_routes(app_state: AppState) -> OpenApiRouter {
+    let body_limit = app_state.storage_service.max_upload_bytes() + MULTIPART_OVERHEAD_BYTES;
+    let service = FileService::new(app_state);
+
+    OpenApiRouter::new()
+        .routes(routes!(file::upload_file))
+        .layer(DefaultBodyLimit::max(body_limit))
+        .with_state(service)
+}
+
+pub fn public_routes(app_state: AppState) -> OpenApiRouter {
+    let service = FileService::new(app_state);
+
+    OpenApiRouter::new()
+        .routes(routes!(file::download_file))
+        .with_state(service)
+}
@@ -0,0 +1,27 @@
+use crate::services::invitation::InvitationService;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+use crate::handlers::invitation;
+use crate::config::state::AppState;
+
+pub fn protected_routes(app_state: AppState) -> OpenApiRouter {
+    let service = InvitationService::new(app_state);
+
+    OpenApiRouter::new()
+        .routes(routes!(invitation::list_invitations, invitation::create_invitation))
+        .routes(routes!(invitation::revoke_invitation))
+        .routes(routes!(invitation::resend_invitation))
+        .with_state(service)
+}
+
+pub fn public_routes(app_state: AppState) -> OpenApiRouter {
+    let service = InvitationService::new(app_state);
+
+    OpenApiRouter::new()
+        .routes(routes!(
+            invitation::preview_invitation,
+            invitation::accept_invitation
+        ))
+        .with_state(service)
+}
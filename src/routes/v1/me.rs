@@ -1,12 +1,26 @@
+use axum::extract::DefaultBodyLimit;
 use crate::config::state::AppState;
 use crate::handlers::me;
+use crate::routes::v1::file::MULTIPART_OVERHEAD_BYTES;
 use crate::services::me::MeService;
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
 
 pub fn protected_routes(app_state: AppState) -> OpenApiRouter {
+    let body_limit = app_state.storage_service.max_upload_bytes() + MULTIPART_OVERHEAD_BYTES;
     let service = MeService::new(app_state);
+
+    // 头像上传单独设置请求体大小限制
+    let upload_routes = OpenApiRouter::new()
+        .routes(routes!(me::upload_avatar))
+        .layer(DefaultBodyLimit::max(body_limit));
+
     OpenApiRouter::new()
         .routes(routes!(me::profile))
+        .routes(routes!(me::update_profile))
+        .routes(routes!(me::change_password))
+        .routes(routes!(me::request_email_change))
+        .routes(routes!(me::confirm_email_change))
+        .merge(upload_routes)
         .with_state(service)
 }
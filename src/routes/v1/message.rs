@@ -0,0 +1,14 @@
+use crate::config::state::AppState;
+use crate::handlers::message;
+use crate::services::message::MessageService;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+pub fn protected_routes(app_state: AppState) -> OpenApiRouter {
+    let service = MessageService::new(app_state);
+
+    OpenApiRouter::new()
+        .routes(routes!(message::send_message))
+        .routes(routes!(message::cancel_scheduled_message))
+        .with_state(service)
+}
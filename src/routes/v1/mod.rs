@@ -17,12 +17,29 @@ mod group;
 mod sse;
 mod cedar_policy;
 mod cedar_schema;
+mod file;
+mod invitation;
+mod notification;
+mod message;
+mod webhook;
+mod oidc;
+mod oauth;
+mod oauth_client;
+mod personal_token;
+mod robot_account;
+mod system;
+mod tenant;
+mod role_request;
+mod access_review;
 
 
 pub fn public_router(app_state: AppState) -> OpenApiRouter {
     let routes = OpenApiRouter::new()
         .nest("/auth", auth::public_routes(app_state.clone()))
-        .nest("/password-resets", password::public_routes(app_state.clone()));
+        .nest("/auth/oidc", oidc::public_routes(app_state.clone()))
+        .nest("/password-resets", password::public_routes(app_state.clone()))
+        .nest("/files", file::public_routes(app_state.clone()))
+        .nest("/invitations", invitation::public_routes(app_state.clone()));
     
     routes
 }
@@ -41,9 +58,22 @@ pub fn protected_router(app_state: AppState) -> OpenApiRouter {
         )
         .nest("/groups", group::protected_routes(app_state.clone()))
         .nest("/me", me::protected_routes(app_state.clone()))
+        .nest("/me/notifications", notification::protected_routes(app_state.clone()))
+        .nest("/me/tokens", personal_token::protected_routes(app_state.clone()))
+        .nest("/robot-accounts", robot_account::protected_routes(app_state.clone()))
+        .nest("/systems", system::protected_routes(app_state.clone()))
+        .nest("/tenants", tenant::protected_routes(app_state.clone()))
+        .nest("/role-requests", role_request::protected_routes(app_state.clone()))
+        .nest("/access-reviews", access_review::protected_routes(app_state.clone()))
         .nest("/cedar_policies", cedar_policy::protected_routes(app_state.clone()))
         .nest("/cedar_schema", cedar_schema::protected_routes(app_state.clone()))
         .nest("/event", sse::protected_routes(app_state.clone()))
+        .nest("/files", file::protected_routes(app_state.clone()))
+        .nest("/invitations", invitation::protected_routes(app_state.clone()))
+        .nest("/messages", message::protected_routes(app_state.clone()))
+        .nest("/webhooks", webhook::protected_routes(app_state.clone()))
+        .nest("/oauth", oauth::protected_routes(app_state.clone()))
+        .nest("/oauth-clients", oauth_client::protected_routes(app_state.clone()))
         .layer(middleware::from_fn_with_state(
             app_state.clone(), auth_guard_middleware
         ));
@@ -0,0 +1,16 @@
+use crate::config::state::AppState;
+use crate::handlers::notification;
+use crate::services::notification::NotificationService;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+pub fn protected_routes(app_state: AppState) -> OpenApiRouter {
+    let service = NotificationService::new(app_state);
+
+    OpenApiRouter::new()
+        .routes(routes!(notification::list_notifications))
+        .routes(routes!(notification::unread_count))
+        .routes(routes!(notification::mark_read))
+        .routes(routes!(notification::mark_all_read))
+        .with_state(service)
+}
@@ -0,0 +1,16 @@
+// 用户确认授权和管理已授权的应用
+use crate::config::state::AppState;
+use crate::handlers::oauth;
+use crate::services::oauth::OAuthService;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+pub fn protected_routes(app_state: AppState) -> OpenApiRouter {
+    let service = OAuthService::new(app_state);
+
+    OpenApiRouter::new()
+        .routes(routes!(oauth::get_authorization_request, oauth::decide_authorization))
+        .routes(routes!(oauth::list_consents))
+        .routes(routes!(oauth::revoke_consent))
+        .with_state(service)
+}
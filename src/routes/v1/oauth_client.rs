@@ -0,0 +1,15 @@
+use crate::config::state::AppState;
+use crate::handlers::oauth_client;
+use crate::services::oauth_client::OAuthClientService;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+pub fn protected_routes(app_state: AppState) -> OpenApiRouter {
+    let service = OAuthClientService::new(app_state);
+
+    OpenApiRouter::new()
+        .routes(routes!(oauth_client::list_clients, oauth_client::create_client))
+        .routes(routes!(oauth_client::get_client, oauth_client::update_client, oauth_client::delete_client))
+        .routes(routes!(oauth_client::rotate_secret))
+        .with_state(service)
+}
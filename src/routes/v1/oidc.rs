@@ -0,0 +1,20 @@
+// OpenID Connect 单点登录路由
+
+use axum::middleware;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+use crate::config::state::AppState;
+use crate::handlers::oidc;
+use crate::middlewares::tenant::platform_tenant_middleware;
+use crate::services::oidc::OidcService;
+
+pub fn public_routes(app_state: AppState) -> OpenApiRouter {
+    let service = OidcService::new(app_state);
+    OpenApiRouter::new()
+        .routes(routes!(oidc::list_providers))
+        .routes(routes!(oidc::authorize))
+        .routes(routes!(oidc::callback))
+        .with_state(service)
+        .layer(middleware::from_fn(platform_tenant_middleware))
+}
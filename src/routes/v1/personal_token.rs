@@ -0,0 +1,14 @@
+use crate::config::state::AppState;
+use crate::handlers::personal_token;
+use crate::services::personal_token::PersonalTokenService;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+pub fn protected_routes(app_state: AppState) -> OpenApiRouter {
+    let service = PersonalTokenService::new(app_state);
+
+    OpenApiRouter::new()
+        .routes(routes!(personal_token::list_tokens, personal_token::create_token))
+        .routes(routes!(personal_token::revoke_token))
+        .with_state(service)
+}
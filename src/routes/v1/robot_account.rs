@@ -0,0 +1,15 @@
+use crate::config::state::AppState;
+use crate::handlers::robot_account;
+use crate::services::robot_account::RobotAccountService;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+pub fn protected_routes(app_state: AppState) -> OpenApiRouter {
+    let service = RobotAccountService::new(app_state);
+
+    OpenApiRouter::new()
+        .routes(routes!(robot_account::list_robots, robot_account::create_robot))
+        .routes(routes!(robot_account::get_robot, robot_account::update_robot, robot_account::delete_robot))
+        .routes(routes!(robot_account::rotate_secret))
+        .with_state(service)
+}
@@ -0,0 +1,17 @@
+use crate::config::state::AppState;
+use crate::handlers::role_request;
+use crate::services::role_request::RoleRequestService;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+pub fn protected_routes(app_state: AppState) -> OpenApiRouter {
+    let service = RoleRequestService::new(app_state);
+
+    OpenApiRouter::new()
+        .routes(routes!(role_request::list_requests, role_request::create_request))
+        .routes(routes!(role_request::get_request))
+        .routes(routes!(role_request::approve_request))
+        .routes(routes!(role_request::reject_request))
+        .routes(routes!(role_request::cancel_request))
+        .with_state(service)
+}
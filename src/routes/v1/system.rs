@@ -0,0 +1,16 @@
+use crate::config::state::AppState;
+use crate::handlers::system;
+use crate::services::system::SystemService;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+pub fn protected_routes(app_state: AppState) -> OpenApiRouter {
+    let service = SystemService::new(app_state);
+
+    OpenApiRouter::new()
+        .routes(routes!(system::list_systems, system::create_system))
+        .routes(routes!(system::get_system, system::update_system, system::delete_system))
+        .routes(routes!(system::list_configs))
+        .routes(routes!(system::get_config, system::upsert_config, system::delete_config))
+        .with_state(service)
+}
@@ -0,0 +1,17 @@
+use axum::middleware;
+use crate::config::state::AppState;
+use crate::handlers::tenant;
+use crate::services::tenant::TenantService;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+use crate::middlewares::tenant::platform_tenant_middleware;
+
+pub fn protected_routes(app_state: AppState) -> OpenApiRouter {
+    let service = TenantService::new(app_state);
+
+    OpenApiRouter::new()
+        .routes(routes!(tenant::list_tenants, tenant::create_tenant))
+        .routes(routes!(tenant::get_tenant, tenant::update_tenant, tenant::delete_tenant))
+        .with_state(service)
+        .layer(middleware::from_fn(platform_tenant_middleware))
+}
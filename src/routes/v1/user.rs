@@ -20,5 +20,6 @@ pub fn protected_routes(app_state: AppState) -> OpenApiRouter {
             user::assign_roles,
             user::revoke_roles
         ))
+        .routes(routes!(user::unlock_user))
         .with_state(service)
 }
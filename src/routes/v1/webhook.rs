@@ -0,0 +1,16 @@
+use crate::config::state::AppState;
+use crate::handlers::webhook;
+use crate::services::webhook::WebhookService;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+pub fn protected_routes(app_state: AppState) -> OpenApiRouter {
+    let service = WebhookService::new(app_state);
+
+    OpenApiRouter::new()
+        .routes(routes!(webhook::list_webhooks, webhook::create_webhook))
+        .routes(routes!(webhook::get_webhook, webhook::update_webhook, webhook::delete_webhook))
+        .routes(routes!(webhook::list_deliveries))
+        .routes(routes!(webhook::redeliver))
+        .with_state(service)
+}
@@ -0,0 +1,156 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::entity::access_review_items::Model as AccessReviewItemModel;
+
+// 复核活动状态
+pub const ACCESS_REVIEW_ACTIVE: &str = "active";
+pub const ACCESS_REVIEW_COMPLETED: &str = "completed";
+pub const ACCESS_REVIEW_CANCELLED: &str = "cancelled";
+
+// 复核项类型
+pub const ITEM_USER_ROLE: &str = "user_role";
+pub const ITEM_GROUP_ROLE: &str = "group_role";
+pub const ITEM_GROUP_MEMBER: &str = "group_member";
+
+// 复核结论
+pub const DECISION_PENDING: &str = "pending";
+// 复核人已认领, 正在执行撤销, 避免并发复核重复撤销
+pub const DECISION_DECIDING: &str = "deciding";
+pub const DECISION_APPROVED: &str = "approved";
+pub const DECISION_REVOKED: &str = "revoked";
+
+fn default_page() -> u64 {
+    1
+}
+
+fn default_page_size() -> u64 {
+    10
+}
+
+fn default_language() -> String {
+    "EN".to_string()
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate, Clone)]
+pub struct AccessReviewQueryParams {
+    #[serde(default = "default_page")]
+    pub page: u64,
+    #[serde(default = "default_page_size", alias = "pageSize")]
+    #[validate(range(min = 1, max = 100))]
+    pub page_size: u64,
+    /// 按状态过滤: active、completed、cancelled
+    pub status: Option<String>,
+    /// 只返回自己担任复核人的活动, 否则需要 ViewAccessReview 权限
+    #[serde(default)]
+    pub mine: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct CreateAccessReviewDto {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+    /// 复核范围: 部门及其子部门, 为空表示整个租户
+    pub dept_uuid: Option<String>,
+    /// 复核人UUID列表
+    #[validate(length(min = 1))]
+    pub reviewers: Vec<String>,
+    pub due_at: DateTime<Utc>,
+    /// 提醒邮件的语言
+    #[serde(default = "default_language")]
+    pub language: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate, Clone)]
+pub struct AccessReviewItemQueryParams {
+    #[serde(default = "default_page")]
+    pub page: u64,
+    #[serde(default = "default_page_size", alias = "pageSize")]
+    #[validate(range(min = 1, max = 100))]
+    pub page_size: u64,
+    /// 按复核结论过滤: pending、approved、revoked
+    pub decision: Option<String>,
+    /// 按类型过滤: user_role、group_role、group_member
+    pub item_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct ReviewDecisionDto {
+    /// approved: 保留；revoked: 撤销, 撤销立即生效
+    pub decision: String,
+    #[validate(length(max = 500))]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams, Clone)]
+pub struct AccessReviewReportParams {
+    /// 报告格式: json(默认) 或 csv
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReviewerInfo {
+    pub uuid: String,
+    pub username: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AccessReviewResponse {
+    pub uuid: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub scope_name: String,
+    pub status: String,
+    pub due_at: DateTime<Utc>,
+    pub reviewers: Vec<ReviewerInfo>,
+    pub created_by: Option<String>,
+    pub total_items: u64,
+    pub pending_items: u64,
+    pub revoked_items: u64,
+    pub last_reminded_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AccessReviewItemResponse {
+    pub uuid: String,
+    pub item_type: String,
+    pub subject_uuid: String,
+    pub subject_name: String,
+    pub target_uuid: String,
+    pub target_name: String,
+    pub decision: String,
+    pub reviewer_name: Option<String>,
+    pub comment: Option<String>,
+    pub decided_at: Option<DateTime<Utc>>,
+}
+
+impl From<AccessReviewItemModel> for AccessReviewItemResponse {
+    fn from(item: AccessReviewItemModel) -> Self {
+        Self {
+            uuid: item.item_uuid,
+            item_type: item.item_type,
+            subject_uuid: item.subject_uuid,
+            subject_name: item.subject_name,
+            target_uuid: item.target_uuid,
+            target_name: item.target_name,
+            decision: item.decision,
+            reviewer_name: item.reviewer_name,
+            comment: item.comment,
+            decided_at: item.decided_at,
+        }
+    }
+}
+
+/// 复核证据报告: 活动信息和全部复核项的结论
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AccessReviewReport {
+    pub review: AccessReviewResponse,
+    pub generated_at: DateTime<Utc>,
+    pub items: Vec<AccessReviewItemResponse>,
+}
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use utoipa::ToSchema;
 use validator::Validate;
+use crate::schemas::tenant::{default_tenant_id, DEFAULT_TENANT_ID};
 use crate::schemas::user::UserUUID;
 
 // 用户认证信息
@@ -11,6 +12,20 @@ pub struct CurrentUser {
     pub dept_uuid: String,
     pub username: String,
     pub is_super_admin: bool,
+    pub tenant_id: i32,
+}
+
+impl CurrentUser {
+    // 同步任务等非用户发起的操作, 作为领域事件和审计日志中的操作人
+    pub fn system(uuid: &str, username: &str) -> Self {
+        Self {
+            uuid: uuid.to_string(),
+            dept_uuid: String::new(),
+            username: username.to_string(),
+            is_super_admin: false,
+            tenant_id: DEFAULT_TENANT_ID,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
@@ -23,6 +38,8 @@ pub struct Credentials {
 pub struct AuthResponse {
     pub access_token: String,
     pub username: String,
+    /// 密码已过期, 必须先修改密码
+    pub password_expired: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -35,10 +52,18 @@ pub enum TokenType {
 pub struct Claims {
     pub sub: UserUUID,
     pub iat: u64,
+    // 签发时间(毫秒), 用于和会话失效时间比较; 旧令牌没有该字段
+    #[serde(default)]
+    pub iat_ms: u64,
     pub exp: u64,
     pub jti: Uuid,
     pub name: String,
     pub dept_id: String,
     pub token_type: TokenType,
     pub is_super_admin: bool,
+    #[serde(default)]
+    pub password_expired: bool,
+    // 旧令牌没有该字段, 视为默认租户
+    #[serde(default = "default_tenant_id")]
+    pub tenant_id: i32,
 }
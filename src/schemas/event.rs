@@ -0,0 +1,135 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::schemas::tenant::DEFAULT_TENANT_ID;
+
+/// 所有事件类型, 用于校验 Webhook 订阅的事件过滤条件
+pub const DOMAIN_EVENT_TYPES: &[&str] = &[
+    "UserCreated",
+    "UserUpdated",
+    "UserDisabled",
+    "UserDepartmentChanged",
+    "RoleAssigned",
+    "RoleRevoked",
+    "RoleUpdated",
+    "RoleDeleted",
+    "GroupMembersChanged",
+    "GroupRoleAssigned",
+    "GroupRoleRevoked",
+    "GroupUpdated",
+    "GroupDeleted",
+    "DepartmentCreated",
+    "DepartmentUpdated",
+    "DepartmentMoved",
+    "DepartmentDeleted",
+    "PolicyChanged",
+    "SchemaChanged",
+];
+
+/// 领域事件, 在数据库事务提交后发布
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum DomainEvent {
+    UserCreated { user_uuid: String, username: String },
+    UserUpdated { user_uuid: String },
+    UserDisabled { user_uuid: String },
+    UserDepartmentChanged { user_uuid: String, from_dept_uuid: String, to_dept_uuid: String },
+    RoleAssigned { user_uuid: String, role_uuid: String },
+    RoleRevoked { user_uuid: String, role_uuid: String },
+    RoleUpdated { role_uuid: String },
+    // 角色删除后关联关系随之删除, 受影响的用户需要在删除前确定
+    RoleDeleted { role_uuid: String, user_uuids: Vec<String> },
+    GroupMembersChanged { group_uuid: String, user_uuids: Vec<String> },
+    GroupRoleAssigned { group_uuid: String, role_uuid: String },
+    GroupRoleRevoked { group_uuid: String, role_uuid: String },
+    GroupUpdated { group_uuid: String },
+    GroupDeleted { group_uuid: String },
+    DepartmentCreated { dept_uuid: String },
+    DepartmentUpdated { dept_uuid: String },
+    DepartmentMoved { dept_uuid: String, from_parent_uuid: String, to_parent_uuid: String },
+    DepartmentDeleted { dept_uuid: String },
+    PolicyChanged { policy_uuid: Option<String> },
+    SchemaChanged,
+}
+
+impl DomainEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DomainEvent::UserCreated { .. } => "UserCreated",
+            DomainEvent::UserUpdated { .. } => "UserUpdated",
+            DomainEvent::UserDisabled { .. } => "UserDisabled",
+            DomainEvent::UserDepartmentChanged { .. } => "UserDepartmentChanged",
+            DomainEvent::RoleAssigned { .. } => "RoleAssigned",
+            DomainEvent::RoleRevoked { .. } => "RoleRevoked",
+            DomainEvent::RoleUpdated { .. } => "RoleUpdated",
+            DomainEvent::RoleDeleted { .. } => "RoleDeleted",
+            DomainEvent::GroupMembersChanged { .. } => "GroupMembersChanged",
+            DomainEvent::GroupRoleAssigned { .. } => "GroupRoleAssigned",
+            DomainEvent::GroupRoleRevoked { .. } => "GroupRoleRevoked",
+            DomainEvent::GroupUpdated { .. } => "GroupUpdated",
+            DomainEvent::GroupDeleted { .. } => "GroupDeleted",
+            DomainEvent::DepartmentCreated { .. } => "DepartmentCreated",
+            DomainEvent::DepartmentUpdated { .. } => "DepartmentUpdated",
+            DomainEvent::DepartmentMoved { .. } => "DepartmentMoved",
+            DomainEvent::DepartmentDeleted { .. } => "DepartmentDeleted",
+            DomainEvent::PolicyChanged { .. } => "PolicyChanged",
+            DomainEvent::SchemaChanged => "SchemaChanged",
+        }
+    }
+
+    /// 写入审计日志的摘要
+    pub fn summary(&self) -> String {
+        match self {
+            DomainEvent::UserCreated { user_uuid, username } => format!("创建用户 {} ({})", username, user_uuid),
+            DomainEvent::UserUpdated { user_uuid } => format!("更新用户 {}", user_uuid),
+            DomainEvent::UserDisabled { user_uuid } => format!("禁用用户 {}", user_uuid),
+            DomainEvent::UserDepartmentChanged { user_uuid, from_dept_uuid, to_dept_uuid } => {
+                format!("用户 {} 从部门 {} 调到 {}", user_uuid, from_dept_uuid, to_dept_uuid)
+            }
+            DomainEvent::RoleAssigned { user_uuid, role_uuid } => format!("为用户 {} 分配角色 {}", user_uuid, role_uuid),
+            DomainEvent::RoleRevoked { user_uuid, role_uuid } => format!("撤销用户 {} 的角色 {}", user_uuid, role_uuid),
+            DomainEvent::RoleUpdated { role_uuid } => format!("更新角色 {}", role_uuid),
+            DomainEvent::RoleDeleted { role_uuid, .. } => format!("删除角色 {}", role_uuid),
+            DomainEvent::GroupMembersChanged { group_uuid, user_uuids } => {
+                format!("用户组 {} 成员变更, 涉及 {} 个用户", group_uuid, user_uuids.len())
+            }
+            DomainEvent::GroupRoleAssigned { group_uuid, role_uuid } => format!("为用户组 {} 分配角色 {}", group_uuid, role_uuid),
+            DomainEvent::GroupRoleRevoked { group_uuid, role_uuid } => format!("撤销用户组 {} 的角色 {}", group_uuid, role_uuid),
+            DomainEvent::GroupUpdated { group_uuid } => format!("更新用户组 {}", group_uuid),
+            DomainEvent::GroupDeleted { group_uuid } => format!("删除用户组 {}", group_uuid),
+            DomainEvent::DepartmentCreated { dept_uuid } => format!("创建部门 {}", dept_uuid),
+            DomainEvent::DepartmentUpdated { dept_uuid } => format!("更新部门 {}", dept_uuid),
+            DomainEvent::DepartmentMoved { dept_uuid, from_parent_uuid, to_parent_uuid } => {
+                format!("部门 {} 从 {} 移动到 {}", dept_uuid, from_parent_uuid, to_parent_uuid)
+            }
+            DomainEvent::DepartmentDeleted { dept_uuid } => format!("删除部门 {}", dept_uuid),
+            DomainEvent::PolicyChanged { policy_uuid: Some(policy_uuid) } => format!("策略 {} 变更", policy_uuid),
+            DomainEvent::PolicyChanged { policy_uuid: None } => "重新加载策略".to_string(),
+            DomainEvent::SchemaChanged => "Cedar Schema 变更".to_string(),
+        }
+    }
+}
+
+/// 事件总线上传递的消息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventEnvelope {
+    pub id: String,
+    // 发布事件的节点, 该节点已在本地处理过, 收到自己的广播时跳过
+    pub node_id: String,
+    pub actor_uuid: Option<String>,
+    pub actor_name: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+    // 事件所属租户, Webhook 只投递给该租户的订阅
+    #[serde(default = "default_tenant_id")]
+    pub tenant_id: i32,
+    // 授权信息受影响的用户, 各节点据此清理本地缓存
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub affected_users: Vec<String>,
+    #[serde(flatten)]
+    pub event: DomainEvent,
+}
+
+// 升级前的节点广播的事件没有租户字段
+fn default_tenant_id() -> i32 {
+    DEFAULT_TENANT_ID
+}
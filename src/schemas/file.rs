@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+// 仅用于 OpenAPI 文档描述 multipart 表单
+#[derive(Debug, ToSchema)]
+#[allow(dead_code)]
+pub struct UploadForm {
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FileResponse {
+    pub key: String,
+    pub url: String,
+    pub thumbnail_key: String,
+    pub thumbnail_url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AvatarResponse {
+    pub avatar_url: String,
+    pub thumbnail_url: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DownloadQuery {
+    pub expires: i64,
+    pub signature: String,
+}
@@ -68,6 +68,10 @@ pub struct AssignUsersDto {
 #[derive(Default, Debug, Serialize, Deserialize, ToSchema, Validate)]
 pub struct AssignRolesDto {
     pub role_uuid: String,
+    /// 生效时间, 为空表示立即生效
+    pub valid_from: Option<DateTime<Utc>>,
+    /// 失效时间, 为空表示永久有效, 到期后自动撤销
+    pub valid_until: Option<DateTime<Utc>>,
 }
 
 
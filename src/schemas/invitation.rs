@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::entity::user_invitations::Model as InvitationModel;
+
+pub const INVITATION_PENDING: &str = "pending";
+pub const INVITATION_ACCEPTED: &str = "accepted";
+pub const INVITATION_EXPIRED: &str = "expired";
+pub const INVITATION_REVOKED: &str = "revoked";
+
+fn default_page() -> u64 {
+    1
+}
+
+fn default_page_size() -> u64 {
+    10
+}
+
+fn default_language() -> String {
+    "EN".to_string()
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate, Clone)]
+pub struct InvitationQueryParams {
+    #[serde(default = "default_page")]
+    pub page: u64,
+    #[serde(default = "default_page_size", alias = "pageSize")]
+    pub page_size: u64,
+    pub email: Option<String>,
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct CreateInvitationDto {
+    #[validate(email)]
+    pub email: String,
+    pub dept: String,
+    #[serde(default)]
+    pub groups: Vec<String>,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    #[serde(default = "default_language")]
+    pub language: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct AcceptInvitationDto {
+    #[validate(length(min = 3, max = 100))]
+    pub username: String,
+    pub password: String,
+    #[validate(length(max = 30))]
+    pub alias: Option<String>,
+    #[validate(length(max = 20))]
+    pub phone: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InvitationResponse {
+    pub uuid: String,
+    pub email: String,
+    pub status: String,
+    pub groups: Vec<String>,
+    pub roles: Vec<String>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+// 被邀请人打开邀请链接时看到的信息
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InvitationPreview {
+    pub email: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+pub fn json_to_uuids(value: &Option<serde_json::Value>) -> Vec<String> {
+    value
+        .clone()
+        .and_then(|v| serde_json::from_value::<Vec<String>>(v).ok())
+        .unwrap_or_default()
+}
+
+impl From<InvitationModel> for InvitationResponse {
+    fn from(invitation: InvitationModel) -> Self {
+        Self {
+            groups: json_to_uuids(&invitation.group_uuids),
+            roles: json_to_uuids(&invitation.role_uuids),
+            uuid: invitation.invitation_uuid,
+            email: invitation.invitee_email,
+            status: invitation.status,
+            expires_at: invitation.expires_at,
+            created_at: invitation.created_at,
+        }
+    }
+}
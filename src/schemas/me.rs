@@ -4,6 +4,7 @@ use sea_orm::FromQueryResult;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use utoipa::ToSchema;
+use validator::Validate;
 
 // 定义UI策略的类型别名，便于理解
 pub type UiPolicies = HashSet<String>;
@@ -29,4 +30,30 @@ pub struct Profile {
     pub info: Info,
     pub departments: Option<DeptResponse>,
     pub groups: Vec<GroupResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateProfileDto {
+    #[validate(length(max = 30))]
+    pub alias: Option<String>,
+    #[validate(length(max = 20))]
+    pub phone: Option<String>,
+    // 外部头像地址, 上传头像请使用 POST /me/avatar
+    #[validate(url, length(max = 255))]
+    pub avatar: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct ChangeEmailDto {
+    #[validate(email)]
+    #[serde(alias = "newEmail")]
+    pub new_email: String,
+    pub language: String,
+}
+
+// 待确认的邮箱变更, 存放在Redis中
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PendingEmailChange {
+    pub user_uuid: String,
+    pub new_email: String,
 }
\ No newline at end of file
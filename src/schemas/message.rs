@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::schemas::notification::NOTIFICATION_INFO;
+use crate::schemas::tenant::default_tenant_id;
+
+fn default_level() -> String {
+    NOTIFICATION_INFO.to_string()
+}
+
+fn default_include_children() -> bool {
+    true
+}
+
+/// 消息接收对象
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum MessageTarget {
+    /// 指定用户
+    User { uuid: String },
+    /// 用户组的所有成员
+    Group { uuid: String },
+    /// 部门的所有成员, 默认包含子部门
+    Department {
+        uuid: String,
+        #[serde(default = "default_include_children")]
+        include_children: bool,
+    },
+    /// 所有启用的用户
+    All,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct SendMessageDto {
+    pub target: MessageTarget,
+    #[validate(length(min = 1, max = 255))]
+    pub title: String,
+    #[validate(length(max = 5000))]
+    pub content: Option<String>,
+    /// 站内相对路径, 或指向前端站点(server.public_url)的 http(s) 链接
+    #[validate(length(max = 512))]
+    pub link: Option<String>,
+    #[serde(default = "default_level")]
+    #[validate(length(min = 1, max = 20))]
+    pub level: String,
+    /// 定时发送时间, 为空时立即发送
+    pub scheduled_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SendMessageResponse {
+    pub uuid: String,
+    /// 接收人数量, 消息由后台任务发送; 定时消息为 0
+    pub recipients: u64,
+    pub scheduled_at: Option<DateTime<Utc>>,
+}
+
+// 等待发送的定时消息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledMessage {
+    pub uuid: String,
+    pub sender_uuid: String,
+    #[serde(default = "default_tenant_id")]
+    pub tenant_id: i32,
+    pub message: SendMessageDto,
+}
@@ -8,3 +8,19 @@ pub mod user;
 pub mod groups;
 pub mod me;
 pub mod cedar_policy;
+pub mod file;
+pub mod invitation;
+pub mod notification;
+
+pub mod message;
+pub mod event;
+pub mod webhook;
+pub mod scim;
+pub mod oidc;
+pub mod oauth;
+pub mod personal_token;
+pub mod robot_account;
+pub mod system;
+pub mod tenant;
+pub mod role_request;
+pub mod access_review;
\ No newline at end of file
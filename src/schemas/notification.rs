@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::entity::notifications::Model as NotificationModel;
+
+pub const NOTIFICATION_INFO: &str = "info";
+pub const NOTIFICATION_WARNING: &str = "warning";
+
+pub const NOTIFICATION_CATEGORY_SECURITY: &str = "security";
+pub const NOTIFICATION_CATEGORY_INVITATION: &str = "invitation";
+pub const NOTIFICATION_CATEGORY_MESSAGE: &str = "message";
+pub const NOTIFICATION_CATEGORY_DIRECTORY: &str = "directory";
+pub const NOTIFICATION_CATEGORY_ACCESS: &str = "access";
+
+fn default_page() -> u64 {
+    1
+}
+
+fn default_page_size() -> u64 {
+    10
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate, Clone)]
+pub struct NotificationQueryParams {
+    #[serde(default = "default_page")]
+    pub page: u64,
+    #[serde(default = "default_page_size", alias = "pageSize")]
+    #[validate(range(min = 1, max = 100))]
+    pub page_size: u64,
+    pub category: Option<String>,
+    /// 只返回未读通知
+    #[serde(default, alias = "unreadOnly")]
+    pub unread_only: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct NotificationResponse {
+    pub uuid: String,
+    pub category: String,
+    pub level: String,
+    pub title: String,
+    pub content: Option<String>,
+    pub link: Option<String>,
+    pub is_read: bool,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UnreadCountResponse {
+    pub unread: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MarkAllReadResponse {
+    pub updated: u64,
+}
+
+// 其他模块发送通知时使用
+#[derive(Debug, Clone)]
+pub struct NewNotification {
+    pub category: String,
+    pub level: String,
+    pub title: String,
+    pub content: Option<String>,
+    pub link: Option<String>,
+}
+
+impl From<NotificationModel> for NotificationResponse {
+    fn from(notification: NotificationModel) -> Self {
+        Self {
+            uuid: notification.notification_uuid,
+            category: notification.category,
+            level: notification.level,
+            title: notification.title,
+            content: notification.content,
+            link: notification.link,
+            is_read: notification.is_read,
+            read_at: notification.read_at,
+            created_at: notification.created_at,
+        }
+    }
+}
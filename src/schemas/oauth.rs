@@ -0,0 +1,335 @@
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::entity::oauth_clients::Model as OAuthClientModel;
+use crate::errors::app_error::{AppError, ErrorType};
+
+pub const SCOPE_OPENID: &str = "openid";
+pub const SCOPE_OFFLINE_ACCESS: &str = "offline_access";
+
+/// 支持的 scope 及授权确认页面上的说明
+pub const SUPPORTED_SCOPES: &[(&str, &str)] = &[
+    (SCOPE_OPENID, "使用你的账号登录"),
+    ("profile", "用户名和显示名称"),
+    ("email", "邮箱地址"),
+    ("phone", "手机号"),
+    ("department", "所在部门"),
+    ("groups", "所在用户组"),
+    ("roles", "角色"),
+    (SCOPE_OFFLINE_ACCESS, "在你离线时保持访问"),
+];
+
+fn default_page() -> u64 {
+    1
+}
+
+fn default_page_size() -> u64 {
+    10
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_scopes() -> Vec<String> {
+    vec![SCOPE_OPENID.to_string(), "profile".to_string(), "email".to_string()]
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate, Clone)]
+pub struct OAuthClientQueryParams {
+    #[serde(default = "default_page")]
+    pub page: u64,
+    #[serde(default = "default_page_size", alias = "pageSize")]
+    #[validate(range(min = 1, max = 100))]
+    pub page_size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct CreateOAuthClientDto {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(max = 255))]
+    pub description: Option<String>,
+    #[validate(length(min = 1))]
+    pub redirect_uris: Vec<String>,
+    /// 允许申请的 scope, 默认 openid profile email
+    #[serde(default = "default_scopes")]
+    pub allowed_scopes: Vec<String>,
+    /// 机密客户端(有后端的应用)使用密钥认证, 公开客户端(单页应用等)必须使用 PKCE
+    #[serde(default = "default_true")]
+    pub is_confidential: bool,
+    /// 内部可信应用, 不需要用户确认授权
+    #[serde(default)]
+    pub skip_consent: bool,
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct UpdateOAuthClientDto {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    #[validate(length(max = 255))]
+    pub description: Option<String>,
+    #[validate(length(min = 1))]
+    pub redirect_uris: Option<Vec<String>>,
+    pub allowed_scopes: Option<Vec<String>>,
+    pub skip_consent: Option<bool>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OAuthClientResponse {
+    pub client_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub redirect_uris: Vec<String>,
+    pub allowed_scopes: Vec<String>,
+    pub is_confidential: bool,
+    pub skip_consent: bool,
+    pub is_active: bool,
+    /// 客户端密钥只在创建和重置时返回一次
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<OAuthClientModel> for OAuthClientResponse {
+    fn from(client: OAuthClientModel) -> Self {
+        Self {
+            client_id: client.client_id,
+            name: client.name,
+            description: client.description,
+            redirect_uris: serde_json::from_value(client.redirect_uris).unwrap_or_default(),
+            allowed_scopes: serde_json::from_value(client.allowed_scopes).unwrap_or_default(),
+            is_confidential: client.secret_hash.is_some(),
+            skip_consent: client.skip_consent,
+            is_active: client.is_active,
+            client_secret: None,
+            created_at: client.created_at,
+            updated_at: client.updated_at,
+        }
+    }
+}
+
+/// 授权端点的参数 (RFC 6749 4.1.1, RFC 7636)
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AuthorizeParams {
+    pub response_type: Option<String>,
+    pub client_id: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub scope: Option<String>,
+    pub state: Option<String>,
+    pub nonce: Option<String>,
+    pub code_challenge: Option<String>,
+    /// 只支持 S256
+    pub code_challenge_method: Option<String>,
+}
+
+/// 等待用户确认的授权请求, 保存在 Redis 中
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorizationRequest {
+    pub client_id: String,
+    pub redirect_uri: String,
+    pub scopes: Vec<String>,
+    pub state: Option<String>,
+    pub nonce: Option<String>,
+    pub code_challenge: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScopeDescription {
+    pub scope: String,
+    pub description: String,
+}
+
+/// 授权确认页面的内容
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuthorizationRequestInfo {
+    pub request_id: String,
+    pub client_id: String,
+    pub client_name: String,
+    pub client_description: Option<String>,
+    pub scopes: Vec<ScopeDescription>,
+    /// 用户此前已同意或应用不需要确认, 前端可以直接提交
+    pub consented: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AuthorizationDecision {
+    pub approve: bool,
+}
+
+/// 前端跳转到该地址, 将授权码或错误返回给应用
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuthorizationRedirect {
+    pub redirect_url: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OAuthConsentResponse {
+    pub client_id: String,
+    pub client_name: String,
+    pub scopes: Vec<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 授权码对应的授权, 保存在 Redis 中
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthorizationGrant {
+    pub client_id: String,
+    pub user_uuid: String,
+    pub redirect_uri: String,
+    pub scopes: Vec<String>,
+    pub nonce: Option<String>,
+    pub code_challenge: Option<String>,
+    pub auth_time: i64,
+}
+
+/// Refresh Token 对应的授权, 保存在 Redis 中
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RefreshGrant {
+    pub client_id: String,
+    pub user_uuid: String,
+    pub scopes: Vec<String>,
+    pub auth_time: i64,
+    /// 签发时间(毫秒), 早于用户会话失效时间的授权不能再刷新
+    #[serde(default)]
+    pub issued_at_ms: u64,
+}
+
+/// 令牌端点的参数 (application/x-www-form-urlencoded)
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TokenRequest {
+    /// authorization_code 或 refresh_token
+    pub grant_type: String,
+    pub code: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub code_verifier: Option<String>,
+    pub refresh_token: Option<String>,
+    pub scope: Option<String>,
+    /// 也可以使用 HTTP Basic 认证
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
+    pub scope: String,
+}
+
+/// 签发给应用的 Access Token
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccessTokenClaims {
+    pub iss: String,
+    pub sub: String,
+    pub aud: String,
+    pub iat: i64,
+    // 签发时间(毫秒), 用于和会话失效时间比较; 旧令牌没有该字段
+    #[serde(default)]
+    pub iat_ms: u64,
+    pub exp: i64,
+    pub jti: String,
+    pub client_id: String,
+    pub scope: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IdTokenClaims {
+    pub iss: String,
+    pub sub: String,
+    pub aud: String,
+    pub iat: i64,
+    pub exp: i64,
+    pub auth_time: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    /// 按 scope 返回的用户信息
+    #[serde(flatten)]
+    pub claims: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OAuthErrorBody {
+    pub error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_description: Option<String>,
+}
+
+/// 协议端点的错误, 按 RFC 6749 5.2 的格式返回
+#[derive(Debug)]
+pub struct OAuthError {
+    pub status: StatusCode,
+    pub error: &'static str,
+    pub description: Option<String>,
+}
+
+impl OAuthError {
+    pub fn new(status: StatusCode, error: &'static str, description: impl Into<String>) -> Self {
+        Self { status, error, description: Some(description.into()) }
+    }
+
+    pub fn invalid_request(description: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "invalid_request", description)
+    }
+
+    pub fn invalid_client(description: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "invalid_client", description)
+    }
+
+    pub fn invalid_grant(description: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "invalid_grant", description)
+    }
+
+    pub fn invalid_token(description: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "invalid_token", description)
+    }
+}
+
+impl From<AppError> for OAuthError {
+    fn from(err: AppError) -> Self {
+        match err.error_type {
+            ErrorType::InternalServerError => {
+                tracing::error!("Internal server error: {:?}", err.source);
+                Self { status: StatusCode::INTERNAL_SERVER_ERROR, error: "server_error", description: None }
+            }
+            _ => Self::new(err.status_code(), "invalid_request", err.source.to_string()),
+        }
+    }
+}
+
+impl IntoResponse for OAuthError {
+    fn into_response(self) -> Response {
+        let body = OAuthErrorBody {
+            error: self.error.to_string(),
+            error_description: self.description,
+        };
+        let mut response = (self.status, [(header::CACHE_CONTROL, "no-store")], Json(body)).into_response();
+        if self.status == StatusCode::UNAUTHORIZED {
+            let challenge = match self.error {
+                "invalid_client" => r#"Basic realm="oauth2""#.to_string(),
+                error => format!(r#"Bearer error="{}""#, error),
+            };
+            if let Ok(value) = challenge.parse() {
+                response.headers_mut().insert(header::WWW_AUTHENTICATE, value);
+            }
+        }
+        response
+    }
+}
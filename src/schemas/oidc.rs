@@ -0,0 +1,56 @@
+use jsonwebtoken::jwk::JwkSet;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OidcProviderInfo {
+    pub name: String,
+    pub display_name: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OidcAuthorizeResponse {
+    /// 前端跳转到该地址完成登录
+    pub authorization_url: String,
+    pub state: String,
+}
+
+/// 身份提供方回调前端时携带的参数
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct OidcCallback {
+    #[validate(length(min = 1))]
+    pub code: String,
+    #[validate(length(min = 1))]
+    pub state: String,
+}
+
+/// 发起登录时保存在 Redis 中, 回调时取出校验
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OidcLoginState {
+    pub provider: String,
+    pub code_verifier: String,
+    pub nonce: String,
+}
+
+/// 发现文档中用到的字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OidcProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+    pub userinfo_endpoint: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OidcCachedMetadata {
+    pub metadata: OidcProviderMetadata,
+    pub jwks: JwkSet,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OidcTokenResponse {
+    pub access_token: String,
+    pub id_token: Option<String>,
+}
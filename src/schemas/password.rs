@@ -14,4 +14,13 @@ pub struct ForgotPasswordDto {
 pub struct ResetPasswordDto {
     #[serde(alias = "newPassword")]
     pub new_password: String,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct ChangePasswordDto {
+    #[serde(alias = "currentPassword")]
+    pub current_password: String,
+    #[serde(alias = "newPassword")]
+    pub new_password: String,
 }
\ No newline at end of file
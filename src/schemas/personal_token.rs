@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::entity::personal_access_tokens::Model as PersonalTokenModel;
+
+/// 令牌前缀, 认证时据此区分个人访问令牌和 JWT
+pub const PERSONAL_TOKEN_PREFIX: &str = "ava_pat_";
+
+pub const SCOPE_READ: &str = "read";
+pub const SCOPE_WRITE: &str = "write";
+
+/// 可单独授权的接口模块, 即 /api/v1 下的第一级路径; scope 写作 `users:read`, 不带模块时表示全部模块
+pub const TOKEN_SCOPE_MODULES: &[&str] = &[
+    "users",
+    "roles",
+    "departments",
+    "groups",
+    "me",
+    "cedar_policies",
+    "cedar_schema",
+    "files",
+    "invitations",
+    "messages",
+    "webhooks",
+    "oauth-clients",
+    "robot-accounts",
+    "systems",
+    "tenants",
+    "role-requests",
+    "access-reviews",
+];
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct CreatePersonalTokenDto {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    /// read、write 或 `<模块>:read`、`<模块>:write`, write 包含 read
+    #[validate(length(min = 1))]
+    pub scopes: Vec<String>,
+    /// 有效期(天), 为空表示永不过期(需配置允许)
+    #[validate(range(min = 1, max = 3650))]
+    pub expires_in_days: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PersonalTokenResponse {
+    pub token_uuid: String,
+    pub name: String,
+    /// 令牌前几位, 用于识别
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub last_used_ip: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// 完整令牌只在创建时返回一次
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+impl From<PersonalTokenModel> for PersonalTokenResponse {
+    fn from(token: PersonalTokenModel) -> Self {
+        Self {
+            token_uuid: token.token_uuid,
+            name: token.name,
+            token_prefix: token.token_prefix,
+            scopes: serde_json::from_value(token.scopes).unwrap_or_default(),
+            expires_at: token.expires_at,
+            last_used_at: token.last_used_at,
+            last_used_ip: token.last_used_ip,
+            created_at: token.created_at,
+            token: None,
+        }
+    }
+}
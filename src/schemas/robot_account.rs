@@ -0,0 +1,118 @@
+use chrono::{DateTime, Utc};
+use sea_orm::FromQueryResult;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::entity::robot_accounts::Model as RobotAccountModel;
+
+/// 服务账号密钥前缀, 认证时据此区分服务账号和用户
+pub const ROBOT_TOKEN_PREFIX: &str = "ava_robot_";
+
+fn default_page() -> u64 {
+    1
+}
+
+fn default_page_size() -> u64 {
+    10
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate, Clone)]
+pub struct RobotAccountQueryParams {
+    #[serde(default = "default_page")]
+    pub page: u64,
+    #[serde(default = "default_page_size", alias = "pageSize")]
+    #[validate(range(min = 1, max = 100))]
+    pub page_size: u64,
+    /// 按名称模糊搜索
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct CreateRobotAccountDto {
+    #[validate(length(min = 3, max = 64))]
+    pub name: String,
+    #[validate(length(max = 255))]
+    pub description: Option<String>,
+    /// 直接分配的角色 UUID
+    #[serde(default)]
+    pub roles: Vec<String>,
+    /// 所属用户组 UUID
+    #[serde(default)]
+    pub groups: Vec<String>,
+    /// 密钥有效期(天), 为空表示永不过期
+    #[validate(range(min = 1, max = 3650))]
+    pub expires_in_days: Option<u32>,
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct UpdateRobotAccountDto {
+    #[validate(length(min = 3, max = 64))]
+    pub name: Option<String>,
+    #[validate(length(max = 255))]
+    pub description: Option<String>,
+    /// 传入时整体替换直接分配的角色
+    pub roles: Option<Vec<String>>,
+    /// 传入时整体替换所属用户组
+    pub groups: Option<Vec<String>>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct RotateRobotSecretDto {
+    /// 新密钥有效期(天), 为空表示永不过期
+    #[validate(range(min = 1, max = 3650))]
+    pub expires_in_days: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, FromQueryResult)]
+pub struct RobotMemberOf {
+    pub uuid: String,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RobotAccountResponse {
+    pub uuid: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// 密钥前几位, 用于识别
+    pub secret_prefix: String,
+    pub is_active: bool,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub last_used_ip: Option<String>,
+    pub roles: Vec<RobotMemberOf>,
+    pub groups: Vec<RobotMemberOf>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// 密钥只在创建和重置时返回一次
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+impl From<RobotAccountModel> for RobotAccountResponse {
+    fn from(robot: RobotAccountModel) -> Self {
+        Self {
+            uuid: robot.robot_uuid,
+            name: robot.name,
+            description: robot.description,
+            secret_prefix: robot.secret_prefix,
+            is_active: robot.is_active,
+            expires_at: robot.expires_at,
+            last_used_at: robot.last_used_at,
+            last_used_ip: robot.last_used_ip,
+            roles: Vec::new(),
+            groups: Vec::new(),
+            created_at: robot.created_at,
+            updated_at: robot.updated_at,
+            secret: None,
+        }
+    }
+}
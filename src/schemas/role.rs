@@ -29,6 +29,9 @@ pub struct CreateRoleDto {
     #[validate(length(min = 3, max = 100))]
     pub name: String,
     pub description: String,
+    /// 继承的父角色 UUID, 子角色拥有父角色的全部权限
+    #[serde(default)]
+    pub parent_roles: Vec<String>,
 }
 
 
@@ -38,6 +41,8 @@ pub struct UpdateRoleDto {
     #[validate(length(min = 3, max = 100))]
     pub name: Option<String>,
     pub description: Option<String>,
+    /// 替换全部父角色, 为空数组时取消继承; 不传则保持不变
+    pub parent_roles: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromQueryResult, ToSchema)]
@@ -59,6 +64,10 @@ pub struct RoleResponse {
     pub description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub created_at: Option<DateTime<Utc>>,
+    /// 直接父角色的 UUID
+    #[sea_orm(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_roles: Option<Vec<String>>,
 }
 
 impl From<RoleModel> for RoleResponse {
@@ -68,6 +77,7 @@ impl From<RoleModel> for RoleResponse {
             created_at: Some(role.created_at),
             name: Some(role.role_name),
             description: role.description,
+            parent_roles: None,
         }
     }
 }
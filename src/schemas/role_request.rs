@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+pub const ROLE_REQUEST_PENDING: &str = "pending";
+pub const ROLE_REQUEST_APPROVED: &str = "approved";
+pub const ROLE_REQUEST_REJECTED: &str = "rejected";
+pub const ROLE_REQUEST_CANCELLED: &str = "cancelled";
+pub const ROLE_REQUEST_EXPIRED: &str = "expired";
+
+fn default_page() -> u64 {
+    1
+}
+
+fn default_page_size() -> u64 {
+    10
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate, Clone)]
+pub struct RoleRequestQueryParams {
+    #[serde(default = "default_page")]
+    pub page: u64,
+    #[serde(default = "default_page_size", alias = "pageSize")]
+    #[validate(range(min = 1, max = 100))]
+    pub page_size: u64,
+    /// 按状态过滤: pending、approved、rejected、cancelled、expired
+    pub status: Option<String>,
+    /// 只返回自己提交的申请, 否则返回自己有权审批的申请
+    #[serde(default)]
+    pub mine: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct CreateRoleRequestDto {
+    pub role_uuid: String,
+    #[validate(length(min = 1, max = 500))]
+    pub reason: String,
+    /// 生效时间, 为空表示批准后立即生效
+    pub valid_from: Option<DateTime<Utc>>,
+    /// 失效时间, 为空表示永久
+    pub valid_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct ApproveRoleRequestDto {
+    #[validate(length(max = 500))]
+    pub comment: Option<String>,
+    /// 审批人可以提前失效时间, 但不能晚于申请的失效时间
+    pub valid_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct RejectRoleRequestDto {
+    #[validate(length(max = 500))]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RoleRequestResponse {
+    pub uuid: String,
+    pub requester_uuid: String,
+    pub requester_name: String,
+    pub role_uuid: String,
+    pub role_name: String,
+    pub reason: String,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub status: String,
+    pub approver_name: Option<String>,
+    pub decision_comment: Option<String>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
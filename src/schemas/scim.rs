@@ -0,0 +1,235 @@
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::errors::app_error::{AppError, ErrorType};
+
+pub const SCIM_USER_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+pub const SCIM_GROUP_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:Group";
+pub const SCIM_ENTERPRISE_USER_SCHEMA: &str = "urn:ietf:params:scim:schemas:extension:enterprise:2.0:User";
+pub const SCIM_LIST_RESPONSE_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+pub const SCIM_ERROR_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+pub const SCIM_SERVICE_PROVIDER_CONFIG_SCHEMA: &str =
+    "urn:ietf:params:scim:schemas:core:2.0:ServiceProviderConfig";
+pub const SCIM_CONTENT_TYPE: &str = "application/scim+json";
+
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(rename_all = "camelCase")]
+pub struct ScimListParams {
+    /// 过滤表达式, 如 userName eq "alice"
+    pub filter: Option<String>,
+    /// 起始位置, 从 1 开始
+    pub start_index: Option<u64>,
+    pub count: Option<u64>,
+    /// 不返回的属性, 目前只支持 members
+    pub excluded_attributes: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimName {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub family_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub given_name: Option<String>,
+}
+
+/// 邮箱、电话等多值属性
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScimMultiValue {
+    pub value: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary: Option<bool>,
+}
+
+/// 用户所在的组、组成员
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScimReference {
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+    #[serde(rename = "$ref", skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ScimEnterpriseUser {
+    /// 部门名称
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub department: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimMeta {
+    pub resource_type: String,
+    pub created: DateTime<Utc>,
+    pub last_modified: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUser {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// 身份提供方的标识, 不保存
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+    pub user_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<ScimName>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub emails: Vec<ScimMultiValue>,
+    #[serde(default)]
+    pub phone_numbers: Vec<ScimMultiValue>,
+    #[serde(default = "default_active")]
+    pub active: bool,
+    /// 只写属性, 为空时用户无法使用密码登录
+    #[serde(default, skip_serializing)]
+    pub password: Option<String>,
+    /// 只读属性, 由组成员关系决定
+    #[serde(default)]
+    pub groups: Vec<ScimReference>,
+    #[serde(
+        rename = "urn:ietf:params:scim:schemas:extension:enterprise:2.0:User",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub enterprise: Option<ScimEnterpriseUser>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<ScimMeta>,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimGroup {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+    pub display_name: String,
+    #[serde(default)]
+    pub members: Vec<ScimReference>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<ScimMeta>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimListResponse<T: ToSchema> {
+    pub schemas: Vec<String>,
+    pub total_results: u64,
+    pub start_index: u64,
+    pub items_per_page: u64,
+    #[serde(rename = "Resources")]
+    pub resources: Vec<T>,
+}
+
+impl<T: ToSchema> ScimListResponse<T> {
+    pub fn new(resources: Vec<T>, total_results: u64, start_index: u64) -> Self {
+        Self {
+            schemas: vec![SCIM_LIST_RESPONSE_SCHEMA.to_string()],
+            total_results,
+            start_index,
+            items_per_page: resources.len() as u64,
+            resources,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScimPatchRequest {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(rename = "Operations")]
+    pub operations: Vec<ScimPatchOperation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScimPatchOperation {
+    /// add、remove、replace, 不区分大小写
+    pub op: String,
+    pub path: Option<String>,
+    pub value: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimErrorBody {
+    pub schemas: Vec<String>,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scim_type: Option<String>,
+    pub detail: String,
+}
+
+/// SCIM 接口的响应, Content-Type 为 application/scim+json
+pub struct ScimResponse<T: Serialize>(pub StatusCode, pub T);
+
+impl<T: Serialize> IntoResponse for ScimResponse<T> {
+    fn into_response(self) -> Response {
+        (self.0, [(header::CONTENT_TYPE, SCIM_CONTENT_TYPE)], Json(self.1)).into_response()
+    }
+}
+
+/// SCIM 接口的错误, 按 RFC 7644 3.12 的格式返回
+#[derive(Debug)]
+pub struct ScimError(pub AppError);
+
+impl From<AppError> for ScimError {
+    fn from(err: AppError) -> Self {
+        Self(err)
+    }
+}
+
+impl IntoResponse for ScimError {
+    fn into_response(self) -> Response {
+        let status = self.0.status_code();
+        let detail = match self.0.error_type {
+            ErrorType::InternalServerError => {
+                tracing::error!("Internal server error: {:?}", self.0.source);
+                "Internal Server Error".to_string()
+            }
+            _ => {
+                tracing::warn!("SCIM client error ({}): {}", status.as_u16(), self.0.source);
+                self.0.source.to_string()
+            }
+        };
+        let scim_type = match self.0.error_type {
+            ErrorType::Conflict => Some("uniqueness".to_string()),
+            ErrorType::BadRequest if detail.starts_with("Invalid filter") => {
+                Some("invalidFilter".to_string())
+            }
+            ErrorType::BadRequest => Some("invalidValue".to_string()),
+            _ => None,
+        };
+        let body = ScimErrorBody {
+            schemas: vec![SCIM_ERROR_SCHEMA.to_string()],
+            status: status.as_u16().to_string(),
+            scim_type,
+            detail,
+        };
+        ScimResponse(status, body).into_response()
+    }
+}
@@ -0,0 +1,112 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::entity::cluster_config::Model as ClusterConfigModel;
+use crate::entity::systems::Model as SystemModel;
+
+fn default_page() -> u64 {
+    1
+}
+
+fn default_page_size() -> u64 {
+    10
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate, Clone)]
+pub struct SystemQueryParams {
+    #[serde(default = "default_page")]
+    pub page: u64,
+    #[serde(default = "default_page_size", alias = "pageSize")]
+    #[validate(range(min = 1, max = 100))]
+    pub page_size: u64,
+    /// 按名称或编码模糊搜索
+    pub keyword: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct CreateSystemDto {
+    /// 系统编码, 只能包含字母、数字、`-` 和 `_`, 创建后不可修改
+    #[validate(length(min = 1, max = 64))]
+    pub code: String,
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    pub description: Option<String>,
+    #[validate(url, length(max = 255))]
+    pub base_url: Option<String>,
+    /// 配置的 JSON Schema, 为空时不校验配置内容
+    pub config_schema: Option<Value>,
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct UpdateSystemDto {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    pub description: Option<String>,
+    #[validate(url, length(max = 255))]
+    pub base_url: Option<String>,
+    /// 修改后已有的配置必须仍然符合新的 Schema
+    pub config_schema: Option<Value>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SystemResponse {
+    pub system_uuid: String,
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub base_url: Option<String>,
+    pub config_schema: Option<Value>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<SystemModel> for SystemResponse {
+    fn from(system: SystemModel) -> Self {
+        Self {
+            system_uuid: system.system_uuid,
+            code: system.code,
+            name: system.name,
+            description: system.description,
+            base_url: system.base_url,
+            config_schema: system.config_schema,
+            is_active: system.is_active,
+            created_at: system.created_at,
+            updated_at: system.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpsertSystemConfigDto {
+    pub config: Value,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SystemConfigResponse {
+    pub name: String,
+    pub config: Value,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<ClusterConfigModel> for SystemConfigResponse {
+    fn from(config: ClusterConfigModel) -> Self {
+        Self {
+            name: config.name,
+            config: config.config,
+            created_at: config.created_at,
+            updated_at: config.updated_at,
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::entity::tenants::Model as TenantModel;
+
+/// 默认租户, 不设置 Cedar 命名空间; SCIM、LDAP、SSO 等全局功能只作用于默认租户
+pub const DEFAULT_TENANT_ID: i32 = 1;
+
+pub fn default_tenant_id() -> i32 {
+    DEFAULT_TENANT_ID
+}
+
+fn default_page() -> u64 {
+    1
+}
+
+fn default_page_size() -> u64 {
+    10
+}
+
+// 租户识别中间件解析出的当前请求所属租户
+#[derive(Clone, Debug)]
+pub struct CurrentTenant {
+    pub tenant_id: i32,
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate, Clone)]
+pub struct TenantQueryParams {
+    #[serde(default = "default_page")]
+    pub page: u64,
+    #[serde(default = "default_page_size", alias = "pageSize")]
+    #[validate(range(min = 1, max = 100))]
+    pub page_size: u64,
+    /// 按名称或编码模糊搜索
+    pub keyword: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct CreateTenantDto {
+    /// 租户编码, 同时用作子域名, 只能包含小写字母、数字和 `-`, 创建后不可修改
+    #[validate(length(min = 2, max = 63))]
+    pub code: String,
+    /// Cedar 命名空间, 以字母开头, 只能包含字母、数字和 `_`, 创建后不可修改
+    #[validate(length(min = 1, max = 64))]
+    pub namespace: String,
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    pub description: Option<String>,
+    /// 租户管理员, 创建时授予该租户的 SuperAdmin 角色
+    #[validate(length(min = 3, max = 100))]
+    pub admin_username: String,
+    #[validate(email)]
+    pub admin_email: String,
+    pub admin_password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct UpdateTenantDto {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// 停用后该租户的用户无法登录和访问接口
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TenantResponse {
+    pub tenant_uuid: String,
+    pub code: String,
+    pub namespace: Option<String>,
+    pub name: String,
+    pub description: Option<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<TenantModel> for TenantResponse {
+    fn from(tenant: TenantModel) -> Self {
+        Self {
+            tenant_uuid: tenant.tenant_uuid,
+            code: tenant.code,
+            namespace: tenant.namespace,
+            name: tenant.name,
+            description: tenant.description,
+            is_active: tenant.is_active,
+            created_at: tenant.created_at,
+            updated_at: tenant.updated_at,
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use chrono::{NaiveDateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use sea_orm::FromQueryResult;
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
@@ -33,6 +33,10 @@ pub struct QueryParams {
 #[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 pub struct AssignRoleDto {
     pub role_uuid: String,
+    /// 生效时间, 为空表示立即生效
+    pub valid_from: Option<DateTime<Utc>>,
+    /// 失效时间, 为空表示永久有效, 到期后自动撤销
+    pub valid_until: Option<DateTime<Utc>>,
 }
 
 fn default_true() -> bool {
@@ -44,7 +48,7 @@ pub struct CreateUserDto {
     pub email: String,
     #[validate(length(min = 3, max = 100))]
     pub username: String,
-    #[validate(length(min = 6))]
+    // 复杂度由配置的密码策略校验
     pub password: String,
     pub groups: Vec<String>,
     pub dept: String,
@@ -128,15 +132,20 @@ pub struct UserRoleInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub uuid: Option<String>,
     pub role_name: String,
-    pub source: String,      // "direct" 或 "group"
+    pub source: String,      // "direct"、"group" 或 "inherited"
     #[serde(skip_serializing_if = "Option::is_none")]
     pub group_name: Option<String>, // 如果来源是组，则包含组名
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inherited_from: Option<String>, // 如果来源是继承，则包含继承自的角色名
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<DateTime<Utc>>, // 限时分配的失效时间
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DirectRole {
     pub uuid: String,
     pub role_name: String,
+    pub valid_until: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -144,4 +153,5 @@ pub struct GroupRole {
     pub uuid: String,
     pub role_name: String,
     pub group_name: String,
+    pub valid_until: Option<DateTime<Utc>>,
 }
\ No newline at end of file
@@ -0,0 +1,151 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::entity::webhook_deliveries::Model as DeliveryModel;
+use crate::entity::webhooks::Model as WebhookModel;
+use crate::schemas::event::{DomainEvent, EventEnvelope};
+
+fn default_page() -> u64 {
+    1
+}
+
+fn default_page_size() -> u64 {
+    10
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate, Clone)]
+pub struct WebhookQueryParams {
+    #[serde(default = "default_page")]
+    pub page: u64,
+    #[serde(default = "default_page_size", alias = "pageSize")]
+    #[validate(range(min = 1, max = 100))]
+    pub page_size: u64,
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate, Clone)]
+pub struct DeliveryQueryParams {
+    #[serde(default = "default_page")]
+    pub page: u64,
+    #[serde(default = "default_page_size", alias = "pageSize")]
+    #[validate(range(min = 1, max = 100))]
+    pub page_size: u64,
+    /// 按投递状态过滤: pending、sending、succeeded、dead
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct CreateWebhookDto {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(url, length(max = 512))]
+    pub url: String,
+    /// 签名密钥, 为空时自动生成
+    #[validate(length(min = 16, max = 128))]
+    pub secret: Option<String>,
+    /// 订阅的事件类型, 为空时订阅全部事件
+    #[serde(default)]
+    pub events: Vec<String>,
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct UpdateWebhookDto {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    #[validate(url, length(max = 512))]
+    pub url: Option<String>,
+    #[validate(length(min = 16, max = 128))]
+    pub secret: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WebhookResponse {
+    pub uuid: String,
+    pub name: String,
+    pub url: String,
+    pub events: Vec<String>,
+    pub is_active: bool,
+    /// 签名密钥只在创建时返回一次
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WebhookDeliveryResponse {
+    pub uuid: String,
+    pub event_id: String,
+    pub event_type: String,
+    pub status: String,
+    pub attempts: i32,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 发送给订阅方的内容
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub occurred_at: DateTime<Utc>,
+    pub actor_uuid: Option<String>,
+    pub data: DomainEvent,
+}
+
+impl From<&EventEnvelope> for WebhookPayload {
+    fn from(envelope: &EventEnvelope) -> Self {
+        Self {
+            id: envelope.id.clone(),
+            event_type: envelope.event.name().to_string(),
+            occurred_at: envelope.occurred_at,
+            actor_uuid: envelope.actor_uuid.clone(),
+            data: envelope.event.clone(),
+        }
+    }
+}
+
+impl From<WebhookModel> for WebhookResponse {
+    fn from(webhook: WebhookModel) -> Self {
+        Self {
+            uuid: webhook.webhook_uuid,
+            name: webhook.name,
+            url: webhook.url,
+            events: serde_json::from_value(webhook.events).unwrap_or_default(),
+            is_active: webhook.is_active,
+            secret: None,
+            created_at: webhook.created_at,
+            updated_at: webhook.updated_at,
+        }
+    }
+}
+
+impl From<DeliveryModel> for WebhookDeliveryResponse {
+    fn from(delivery: DeliveryModel) -> Self {
+        Self {
+            uuid: delivery.delivery_uuid,
+            event_id: delivery.event_id,
+            event_type: delivery.event_type,
+            status: delivery.status,
+            attempts: delivery.attempts,
+            response_status: delivery.response_status,
+            last_error: delivery.last_error,
+            next_attempt_at: delivery.next_attempt_at,
+            delivered_at: delivery.delivered_at,
+            created_at: delivery.created_at,
+        }
+    }
+}
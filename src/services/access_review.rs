@@ -0,0 +1,871 @@
+// 访问权限复核: 创建时对范围内的用户角色、用户组角色和用户组成员关系做快照,
+// 复核人逐项保留或撤销, 撤销通过用户和用户组服务完成; 后台任务定期提醒复核人
+use chrono::{Duration as ChronoDuration, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use std::collections::{HashMap, HashSet};
+use tokio::time::{sleep, Duration};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::config::state::AppState;
+use crate::entity::{
+    access_review_items, access_review_reviewers, access_reviews, departments, group_roles, roles,
+    user_group_members, user_groups, user_roles, users,
+};
+use crate::errors::app_error::AppError;
+use crate::schemas::access_review::{
+    AccessReviewItemQueryParams, AccessReviewItemResponse, AccessReviewQueryParams,
+    AccessReviewReport, AccessReviewResponse, CreateAccessReviewDto, ReviewDecisionDto,
+    ReviewerInfo, ACCESS_REVIEW_ACTIVE, ACCESS_REVIEW_CANCELLED, ACCESS_REVIEW_COMPLETED,
+    DECISION_APPROVED, DECISION_DECIDING, DECISION_PENDING, DECISION_REVOKED, ITEM_GROUP_MEMBER, ITEM_GROUP_ROLE,
+    ITEM_USER_ROLE,
+};
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::notification::{
+    NewNotification, NOTIFICATION_CATEGORY_ACCESS, NOTIFICATION_INFO, NOTIFICATION_WARNING,
+};
+use crate::services::department::get_all_child_dept_ids;
+use crate::services::groups::GroupService;
+use crate::services::notification::notify_user;
+use crate::services::user::UserService;
+use crate::utils::cedar_utils::{AuthAction, ResourceType};
+use crate::utils::templates::EmailTemplate;
+use crate::{bad_request, conflict, forbidden, not_found};
+
+const ACCESS_REVIEW_REMINDER_POLL_INTERVAL: Duration = Duration::from_secs(3600);
+// 同一复核活动两次提醒的最小间隔(小时)
+const ACCESS_REVIEW_REMINDER_INTERVAL_HOURS: i64 = 24;
+// 快照按批写入
+const SNAPSHOT_BATCH_SIZE: usize = 500;
+
+#[derive(Clone)]
+pub struct AccessReviewService {
+    app_state: AppState,
+}
+
+impl AccessReviewService {
+    pub fn new(app_state: AppState) -> Self {
+        Self { app_state }
+    }
+
+    pub async fn list_reviews(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        params: AccessReviewQueryParams,
+    ) -> Result<(Vec<AccessReviewResponse>, u64), AppError> {
+        let mut query = access_reviews::Entity::find()
+            .filter(access_reviews::Column::TenantId.eq(current_user.tenant_id))
+            .order_by_desc(access_reviews::Column::Id);
+        if params.mine {
+            let reviewer = self.find_user(&current_user).await?;
+            let review_ids = access_review_reviewers::Entity::find()
+                .select_only()
+                .column(access_review_reviewers::Column::ReviewId)
+                .filter(access_review_reviewers::Column::UserId.eq(reviewer.user_id))
+                .into_tuple::<i32>()
+                .all(&self.app_state.db)
+                .await?;
+            query = query.filter(access_reviews::Column::Id.is_in(review_ids));
+        } else {
+            self.app_state
+                .auth_service
+                .check_permission(
+                    &current_user.uuid,
+                    context,
+                    AuthAction::ViewAccessReview,
+                    ResourceType::AccessReview(None),
+                )
+                .await?;
+        }
+        if let Some(status) = params.status.as_deref().filter(|status| !status.is_empty()) {
+            query = query.filter(access_reviews::Column::Status.eq(status));
+        }
+
+        let paginator = query.paginate(&self.app_state.db, params.page_size);
+        let total = paginator.num_items().await?;
+        let page_index = if params.page > 0 { params.page - 1 } else { 0 };
+        let reviews = paginator.fetch_page(page_index).await?;
+        Ok((to_responses(&self.app_state.db, reviews).await?, total))
+    }
+
+    pub async fn get_review(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        review_uuid: String,
+    ) -> Result<AccessReviewResponse, AppError> {
+        let review = self.viewable_review(&current_user, context, &review_uuid).await?;
+        to_response(&self.app_state.db, review).await
+    }
+
+    pub async fn create_review(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        dto: CreateAccessReviewDto,
+    ) -> Result<AccessReviewResponse, AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::CreateAccessReview,
+                ResourceType::AccessReview(None),
+            )
+            .await?;
+        let now = Utc::now();
+        if dto.due_at <= now {
+            return Err(bad_request!("due_at must be in the future"));
+        }
+        let db = &self.app_state.db;
+        let tenant_id = current_user.tenant_id;
+
+        let reviewer_uuids: HashSet<&String> = dto.reviewers.iter().collect();
+        let reviewers = users::Entity::find()
+            .filter(users::Column::TenantId.eq(tenant_id))
+            .filter(users::Column::UserUuid.is_in(reviewer_uuids.iter().map(|uuid| uuid.as_str())))
+            .all(db)
+            .await?;
+        if let Some(missing) = reviewer_uuids
+            .iter()
+            .find(|uuid| !reviewers.iter().any(|reviewer| &&reviewer.user_uuid == *uuid))
+        {
+            return Err(not_found!("Reviewer {} not found", missing));
+        }
+
+        // 复核范围: 部门及其子部门, 未指定时为整个租户
+        let (dept_id, scope_name, dept_ids) = match &dto.dept_uuid {
+            Some(dept_uuid) => {
+                let dept = departments::Entity::find()
+                    .filter(departments::Column::DeptUuid.eq(dept_uuid))
+                    .filter(departments::Column::TenantId.eq(tenant_id))
+                    .filter(departments::Column::IsDeleted.eq(false))
+                    .one(db)
+                    .await?
+                    .ok_or(not_found!("Department {} not found", dept_uuid))?;
+                let dept_ids = get_all_child_dept_ids(db, tenant_id, dept_uuid).await?;
+                (Some(dept.dept_id), dept.name, Some(dept_ids))
+            }
+            None => (None, "All departments".to_string(), None),
+        };
+        let snapshot = snapshot_access(db, tenant_id, dept_ids).await?;
+        if snapshot.is_empty() {
+            return Err(bad_request!("There is no access to review in {}", scope_name));
+        }
+
+        let creator = users::Entity::find()
+            .filter(users::Column::UserUuid.eq(&current_user.uuid))
+            .filter(users::Column::TenantId.eq(tenant_id))
+            .one(db)
+            .await?;
+        let txn = db.begin().await?;
+        let review = access_reviews::ActiveModel {
+            review_uuid: Set(Uuid::new_v4().to_string()),
+            tenant_id: Set(tenant_id),
+            name: Set(dto.name),
+            description: Set(dto.description),
+            dept_id: Set(dept_id),
+            scope_name: Set(scope_name),
+            status: Set(ACCESS_REVIEW_ACTIVE.to_string()),
+            language: Set(dto.language.to_uppercase()),
+            due_at: Set(dto.due_at),
+            created_by: Set(creator.map(|creator| creator.user_id)),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        access_review_reviewers::Entity::insert_many(reviewers.iter().map(|reviewer| {
+            access_review_reviewers::ActiveModel {
+                review_id: Set(review.id),
+                user_id: Set(reviewer.user_id),
+            }
+        }))
+        .exec(&txn)
+        .await?;
+        let items: Vec<access_review_items::ActiveModel> = snapshot
+            .into_iter()
+            .map(|item| access_review_items::ActiveModel {
+                item_uuid: Set(Uuid::new_v4().to_string()),
+                review_id: Set(review.id),
+                item_type: Set(item.item_type.to_string()),
+                subject_uuid: Set(item.subject_uuid),
+                subject_name: Set(item.subject_name),
+                target_uuid: Set(item.target_uuid),
+                target_name: Set(item.target_name),
+                decision: Set(DECISION_PENDING.to_string()),
+                created_at: Set(now),
+                ..Default::default()
+            })
+            .collect();
+        for batch in items.chunks(SNAPSHOT_BATCH_SIZE) {
+            access_review_items::Entity::insert_many(batch.to_vec()).exec(&txn).await?;
+        }
+        txn.commit().await?;
+
+        info!("{} 创建了访问权限复核 {}, 共 {} 项", current_user.username, review.name, items.len());
+        if let Err(e) = send_reminders(&self.app_state, &review).await {
+            warn!("提醒访问权限复核 {} 的复核人失败: {}", review.name, e);
+        }
+        let review = self.find_review(tenant_id, &review.review_uuid).await?;
+        to_response(db, review).await
+    }
+
+    pub async fn list_items(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        review_uuid: String,
+        params: AccessReviewItemQueryParams,
+    ) -> Result<(Vec<AccessReviewItemResponse>, u64), AppError> {
+        let review = self.viewable_review(&current_user, context, &review_uuid).await?;
+        let mut query = access_review_items::Entity::find()
+            .filter(access_review_items::Column::ReviewId.eq(review.id))
+            .order_by_asc(access_review_items::Column::Id);
+        if let Some(decision) = params.decision.as_deref().filter(|decision| !decision.is_empty()) {
+            query = query.filter(access_review_items::Column::Decision.eq(decision));
+        }
+        if let Some(item_type) = params.item_type.as_deref().filter(|item_type| !item_type.is_empty()) {
+            query = query.filter(access_review_items::Column::ItemType.eq(item_type));
+        }
+
+        let paginator = query.paginate(&self.app_state.db, params.page_size);
+        let total = paginator.num_items().await?;
+        let page_index = if params.page > 0 { params.page - 1 } else { 0 };
+        let items = paginator
+            .fetch_page(page_index)
+            .await?
+            .into_iter()
+            .map(AccessReviewItemResponse::from)
+            .collect();
+        Ok((items, total))
+    }
+
+    pub async fn decide_item(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        review_uuid: String,
+        item_uuid: String,
+        dto: ReviewDecisionDto,
+    ) -> Result<AccessReviewItemResponse, AppError> {
+        if dto.decision != DECISION_APPROVED && dto.decision != DECISION_REVOKED {
+            return Err(bad_request!("decision must be {} or {}", DECISION_APPROVED, DECISION_REVOKED));
+        }
+        let review = self.find_review(current_user.tenant_id, &review_uuid).await?;
+        let reviewer = self.find_user(&current_user).await?;
+        if !is_reviewer(&self.app_state.db, review.id, reviewer.user_id).await? {
+            return Err(forbidden!("You are not a reviewer of access review {}", review.name));
+        }
+        if review.status != ACCESS_REVIEW_ACTIVE {
+            return Err(conflict!("Access review {} is already {}", review.name, review.status));
+        }
+        let item = access_review_items::Entity::find()
+            .filter(access_review_items::Column::ItemUuid.eq(&item_uuid))
+            .filter(access_review_items::Column::ReviewId.eq(review.id))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("Access review item {} not found", item_uuid))?;
+        if item.decision != DECISION_PENDING {
+            return Err(conflict!("Access review item {} is already {}", item_uuid, item.decision));
+        }
+        // 复核人不能复核自己的权限
+        if item.item_type != ITEM_GROUP_ROLE && item.subject_uuid == current_user.uuid {
+            return Err(forbidden!("You cannot review your own access"));
+        }
+
+        // 先认领复核项, 只有认领成功的请求执行撤销
+        let claimed = access_review_items::Entity::update_many()
+            .col_expr(access_review_items::Column::Decision, Expr::value(DECISION_DECIDING))
+            .filter(access_review_items::Column::Id.eq(item.id))
+            .filter(access_review_items::Column::Decision.eq(DECISION_PENDING))
+            .exec(&self.app_state.db)
+            .await?
+            .rows_affected;
+        if claimed == 0 {
+            return Err(conflict!("Access review item {} has already been decided", item_uuid));
+        }
+
+        // 撤销走原有的服务方法, 复核人需要有对应的撤销权限; 撤销失败时释放认领
+        if dto.decision == DECISION_REVOKED
+            && let Err(e) = self.revoke_item(&current_user, context, &item).await
+        {
+            access_review_items::Entity::update_many()
+                .col_expr(access_review_items::Column::Decision, Expr::value(DECISION_PENDING))
+                .filter(access_review_items::Column::Id.eq(item.id))
+                .filter(access_review_items::Column::Decision.eq(DECISION_DECIDING))
+                .exec(&self.app_state.db)
+                .await?;
+            return Err(e);
+        }
+
+        let now = Utc::now();
+        let decided = access_review_items::Entity::update_many()
+            .col_expr(access_review_items::Column::Decision, Expr::value(&dto.decision))
+            .col_expr(access_review_items::Column::ReviewerId, Expr::value(reviewer.user_id))
+            .col_expr(access_review_items::Column::ReviewerName, Expr::value(&reviewer.username))
+            .col_expr(access_review_items::Column::Comment, Expr::value(dto.comment.clone()))
+            .col_expr(access_review_items::Column::DecidedAt, Expr::value(now))
+            .filter(access_review_items::Column::Id.eq(item.id))
+            .filter(access_review_items::Column::Decision.eq(DECISION_DECIDING))
+            .exec(&self.app_state.db)
+            .await?
+            .rows_affected;
+        if decided == 0 {
+            return Err(conflict!("Access review item {} has already been decided", item_uuid));
+        }
+
+        // 所有项复核完毕后自动完成
+        let pending = access_review_items::Entity::find()
+            .filter(access_review_items::Column::ReviewId.eq(review.id))
+            .filter(access_review_items::Column::Decision.is_in([DECISION_PENDING, DECISION_DECIDING]))
+            .count(&self.app_state.db)
+            .await?;
+        if pending == 0 {
+            finish_review(&self.app_state.db, review.id, ACCESS_REVIEW_COMPLETED).await?;
+        }
+
+        let item = access_review_items::Entity::find_by_id(item.id)
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("Access review item {} not found", item_uuid))?;
+        Ok(AccessReviewItemResponse::from(item))
+    }
+
+    async fn revoke_item(
+        &self,
+        current_user: &CurrentUser,
+        context: CedarContext,
+        item: &access_review_items::Model,
+    ) -> Result<(), AppError> {
+        match item.item_type.as_str() {
+            ITEM_USER_ROLE => {
+                UserService::new(self.app_state.clone())
+                    .revoke_roles(current_user.clone(), context, item.subject_uuid.clone(), item.target_uuid.clone())
+                    .await
+            }
+            ITEM_GROUP_ROLE => {
+                GroupService::new(self.app_state.clone())
+                    .revoke_roles(current_user.clone(), context, item.subject_uuid.clone(), item.target_uuid.clone())
+                    .await
+            }
+            ITEM_GROUP_MEMBER => {
+                GroupService::new(self.app_state.clone())
+                    .revoke_user(current_user.clone(), context, item.target_uuid.clone(), item.subject_uuid.clone())
+                    .await
+            }
+            other => Err(bad_request!("Unknown access review item type {}", other)),
+        }
+    }
+
+    /// 提前结束复核, 未复核的项保持 pending 记入报告
+    pub async fn complete_review(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        review_uuid: String,
+    ) -> Result<AccessReviewResponse, AppError> {
+        self.close_review(current_user, context, review_uuid, ACCESS_REVIEW_COMPLETED).await
+    }
+
+    pub async fn cancel_review(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        review_uuid: String,
+    ) -> Result<AccessReviewResponse, AppError> {
+        self.close_review(current_user, context, review_uuid, ACCESS_REVIEW_CANCELLED).await
+    }
+
+    pub async fn remind_reviewers(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        review_uuid: String,
+    ) -> Result<(), AppError> {
+        let review = self
+            .updatable_review(&current_user, context, &review_uuid)
+            .await?;
+        if review.status != ACCESS_REVIEW_ACTIVE {
+            return Err(conflict!("Access review {} is already {}", review.name, review.status));
+        }
+        send_reminders(&self.app_state, &review).await
+    }
+
+    pub async fn get_report(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        review_uuid: String,
+    ) -> Result<AccessReviewReport, AppError> {
+        let review = self.viewable_review(&current_user, context, &review_uuid).await?;
+        let items = access_review_items::Entity::find()
+            .filter(access_review_items::Column::ReviewId.eq(review.id))
+            .order_by_asc(access_review_items::Column::Id)
+            .all(&self.app_state.db)
+            .await?
+            .into_iter()
+            .map(AccessReviewItemResponse::from)
+            .collect();
+        Ok(AccessReviewReport {
+            review: to_response(&self.app_state.db, review).await?,
+            generated_at: Utc::now(),
+            items,
+        })
+    }
+
+    async fn close_review(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        review_uuid: String,
+        status: &str,
+    ) -> Result<AccessReviewResponse, AppError> {
+        let review = self
+            .updatable_review(&current_user, context, &review_uuid)
+            .await?;
+        if !finish_review(&self.app_state.db, review.id, status).await? {
+            return Err(conflict!("Access review {} is already {}", review.name, review.status));
+        }
+        info!("{} 将访问权限复核 {} 设为 {}", current_user.username, review.name, status);
+        let review = self.find_review(current_user.tenant_id, &review_uuid).await?;
+        to_response(&self.app_state.db, review).await
+    }
+
+    // 复核人可以查看所负责的活动, 其他人需要 ViewAccessReview 权限
+    async fn viewable_review(
+        &self,
+        current_user: &CurrentUser,
+        context: CedarContext,
+        review_uuid: &str,
+    ) -> Result<access_reviews::Model, AppError> {
+        let review = self.find_review(current_user.tenant_id, review_uuid).await?;
+        let user = users::Entity::find()
+            .filter(users::Column::UserUuid.eq(&current_user.uuid))
+            .filter(users::Column::TenantId.eq(current_user.tenant_id))
+            .one(&self.app_state.db)
+            .await?;
+        if let Some(user) = user
+            && is_reviewer(&self.app_state.db, review.id, user.user_id).await?
+        {
+            return Ok(review);
+        }
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::ViewAccessReview,
+                ResourceType::AccessReview(Some(review_uuid.to_string())),
+            )
+            .await?;
+        Ok(review)
+    }
+
+    async fn updatable_review(
+        &self,
+        current_user: &CurrentUser,
+        context: CedarContext,
+        review_uuid: &str,
+    ) -> Result<access_reviews::Model, AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::UpdateAccessReview,
+                ResourceType::AccessReview(Some(review_uuid.to_string())),
+            )
+            .await?;
+        self.find_review(current_user.tenant_id, review_uuid).await
+    }
+
+    async fn find_review(&self, tenant_id: i32, review_uuid: &str) -> Result<access_reviews::Model, AppError> {
+        access_reviews::Entity::find()
+            .filter(access_reviews::Column::ReviewUuid.eq(review_uuid))
+            .filter(access_reviews::Column::TenantId.eq(tenant_id))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("Access review {} not found", review_uuid))
+    }
+
+    // 只有用户可以担任复核人
+    async fn find_user(&self, current_user: &CurrentUser) -> Result<users::Model, AppError> {
+        users::Entity::find()
+            .filter(users::Column::UserUuid.eq(&current_user.uuid))
+            .filter(users::Column::TenantId.eq(current_user.tenant_id))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(forbidden!("Only users can review access"))
+    }
+}
+
+// 快照中的一项
+struct SnapshotItem {
+    item_type: &'static str,
+    subject_uuid: String,
+    subject_name: String,
+    target_uuid: String,
+    target_name: String,
+}
+
+// 对范围内用户的角色、用户组成员关系, 以及这些用户所在用户组的角色做快照; 范围为空表示整个租户
+async fn snapshot_access(
+    db: &DatabaseConnection,
+    tenant_id: i32,
+    dept_ids: Option<Vec<i32>>,
+) -> Result<Vec<SnapshotItem>, AppError> {
+    let mut user_query = users::Entity::find().filter(users::Column::TenantId.eq(tenant_id));
+    if let Some(dept_ids) = dept_ids.clone() {
+        user_query = user_query.filter(users::Column::DeptId.is_in(dept_ids));
+    }
+    let users: HashMap<i32, users::Model> = user_query
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|user| (user.user_id, user))
+        .collect();
+    let roles: HashMap<i32, roles::Model> = roles::Entity::find()
+        .filter(roles::Column::TenantId.eq(tenant_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|role| (role.role_id, role))
+        .collect();
+    let groups: HashMap<i32, user_groups::Model> = user_groups::Entity::find()
+        .filter(user_groups::Column::TenantId.eq(tenant_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|group| (group.user_group_id, group))
+        .collect();
+    let user_ids: Vec<i32> = users.keys().copied().collect();
+    let mut items = Vec::new();
+
+    let assignments = user_roles::Entity::find()
+        .filter(user_roles::Column::UserId.is_in(user_ids.clone()))
+        .order_by_asc(user_roles::Column::UserId)
+        .all(db)
+        .await?;
+    for assignment in assignments {
+        if let (Some(user), Some(role)) = (users.get(&assignment.user_id), roles.get(&assignment.role_id)) {
+            items.push(SnapshotItem {
+                item_type: ITEM_USER_ROLE,
+                subject_uuid: user.user_uuid.clone(),
+                subject_name: user.username.clone(),
+                target_uuid: role.role_uuid.clone(),
+                target_name: role.role_name.clone(),
+            });
+        }
+    }
+
+    let memberships = user_group_members::Entity::find()
+        .filter(user_group_members::Column::UserId.is_in(user_ids))
+        .order_by_asc(user_group_members::Column::UserId)
+        .all(db)
+        .await?;
+    let mut scoped_group_ids = HashSet::new();
+    for membership in memberships {
+        if let (Some(user), Some(group)) = (users.get(&membership.user_id), groups.get(&membership.group_id)) {
+            scoped_group_ids.insert(group.user_group_id);
+            items.push(SnapshotItem {
+                item_type: ITEM_GROUP_MEMBER,
+                subject_uuid: user.user_uuid.clone(),
+                subject_name: user.username.clone(),
+                target_uuid: group.user_group_uuid.clone(),
+                target_name: group.name.clone(),
+            });
+        }
+    }
+
+    let group_ids: Vec<i32> = match dept_ids {
+        Some(_) => scoped_group_ids.into_iter().collect(),
+        None => groups.keys().copied().collect(),
+    };
+    let group_assignments = group_roles::Entity::find()
+        .filter(group_roles::Column::GroupId.is_in(group_ids))
+        .order_by_asc(group_roles::Column::GroupId)
+        .all(db)
+        .await?;
+    for assignment in group_assignments {
+        if let (Some(group), Some(role)) = (groups.get(&assignment.group_id), roles.get(&assignment.role_id)) {
+            items.push(SnapshotItem {
+                item_type: ITEM_GROUP_ROLE,
+                subject_uuid: group.user_group_uuid.clone(),
+                subject_name: group.name.clone(),
+                target_uuid: role.role_uuid.clone(),
+                target_name: role.role_name.clone(),
+            });
+        }
+    }
+    Ok(items)
+}
+
+async fn is_reviewer(db: &DatabaseConnection, review_id: i32, user_id: i32) -> Result<bool, AppError> {
+    let count = access_review_reviewers::Entity::find()
+        .filter(access_review_reviewers::Column::ReviewId.eq(review_id))
+        .filter(access_review_reviewers::Column::UserId.eq(user_id))
+        .count(db)
+        .await?;
+    Ok(count > 0)
+}
+
+// 只结束进行中的活动, 返回是否结束成功
+async fn finish_review(db: &DatabaseConnection, review_id: i32, status: &str) -> Result<bool, AppError> {
+    let now = Utc::now();
+    let finished = access_reviews::Entity::update_many()
+        .col_expr(access_reviews::Column::Status, Expr::value(status))
+        .col_expr(access_reviews::Column::CompletedAt, Expr::value(now))
+        .col_expr(access_reviews::Column::UpdatedAt, Expr::value(now))
+        .filter(access_reviews::Column::Id.eq(review_id))
+        .filter(access_reviews::Column::Status.eq(ACCESS_REVIEW_ACTIVE))
+        .exec(db)
+        .await?
+        .rows_affected;
+    Ok(finished > 0)
+}
+
+async fn to_response(db: &DatabaseConnection, review: access_reviews::Model) -> Result<AccessReviewResponse, AppError> {
+    to_responses(db, vec![review])
+        .await?
+        .pop()
+        .ok_or(not_found!("Access review not found"))
+}
+
+async fn to_responses(
+    db: &DatabaseConnection,
+    reviews: Vec<access_reviews::Model>,
+) -> Result<Vec<AccessReviewResponse>, AppError> {
+    let review_ids: Vec<i32> = reviews.iter().map(|review| review.id).collect();
+    let reviewer_links = access_review_reviewers::Entity::find()
+        .filter(access_review_reviewers::Column::ReviewId.is_in(review_ids.clone()))
+        .all(db)
+        .await?;
+    let user_ids: HashSet<i32> = reviewer_links
+        .iter()
+        .map(|link| link.user_id)
+        .chain(reviews.iter().filter_map(|review| review.created_by))
+        .collect();
+    let users: HashMap<i32, users::Model> = users::Entity::find()
+        .filter(users::Column::UserId.is_in(user_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|user| (user.user_id, user))
+        .collect();
+    let counts = access_review_items::Entity::find()
+        .select_only()
+        .column(access_review_items::Column::ReviewId)
+        .column(access_review_items::Column::Decision)
+        .column_as(Expr::col(access_review_items::Column::Id).count(), "count")
+        .filter(access_review_items::Column::ReviewId.is_in(review_ids))
+        .group_by(access_review_items::Column::ReviewId)
+        .group_by(access_review_items::Column::Decision)
+        .into_tuple::<(i32, String, i64)>()
+        .all(db)
+        .await?;
+    let count_of = |review_id: i32, decision: Option<&str>| -> u64 {
+        counts
+            .iter()
+            .filter(|(id, item_decision, _)| *id == review_id && decision.is_none_or(|d| d == item_decision))
+            .map(|(_, _, count)| *count as u64)
+            .sum()
+    };
+
+    let responses = reviews
+        .into_iter()
+        .map(|review| AccessReviewResponse {
+            reviewers: reviewer_links
+                .iter()
+                .filter(|link| link.review_id == review.id)
+                .filter_map(|link| users.get(&link.user_id))
+                .map(|user| ReviewerInfo {
+                    uuid: user.user_uuid.clone(),
+                    username: user.username.clone(),
+                })
+                .collect(),
+            created_by: review
+                .created_by
+                .and_then(|user_id| users.get(&user_id))
+                .map(|user| user.username.clone()),
+            total_items: count_of(review.id, None),
+            pending_items: count_of(review.id, Some(DECISION_PENDING)),
+            revoked_items: count_of(review.id, Some(DECISION_REVOKED)),
+            uuid: review.review_uuid,
+            name: review.name,
+            description: review.description,
+            scope_name: review.scope_name,
+            status: review.status,
+            due_at: review.due_at,
+            last_reminded_at: review.last_reminded_at,
+            completed_at: review.completed_at,
+            created_at: review.created_at,
+        })
+        .collect();
+    Ok(responses)
+}
+
+/// 证据报告导出为 CSV, 每行一个复核项
+pub fn report_to_csv(report: &AccessReviewReport) -> String {
+    let review = &report.review;
+    let mut csv = String::new();
+    let rows: Vec<Vec<String>> = vec![
+        vec!["review".into(), review.name.clone()],
+        vec!["scope".into(), review.scope_name.clone()],
+        vec!["status".into(), review.status.clone()],
+        vec!["due_at".into(), review.due_at.to_rfc3339()],
+        vec!["completed_at".into(), review.completed_at.map(|at| at.to_rfc3339()).unwrap_or_default()],
+        vec!["generated_at".into(), report.generated_at.to_rfc3339()],
+        vec![],
+        [
+            "item_uuid", "item_type", "subject_uuid", "subject_name", "target_uuid", "target_name",
+            "decision", "reviewer", "comment", "decided_at",
+        ]
+        .iter()
+        .map(|header| header.to_string())
+        .collect(),
+    ];
+    let item_rows = report.items.iter().map(|item| {
+        vec![
+            item.uuid.clone(),
+            item.item_type.clone(),
+            item.subject_uuid.clone(),
+            item.subject_name.clone(),
+            item.target_uuid.clone(),
+            item.target_name.clone(),
+            item.decision.clone(),
+            item.reviewer_name.clone().unwrap_or_default(),
+            item.comment.clone().unwrap_or_default(),
+            item.decided_at.map(|at| at.to_rfc3339()).unwrap_or_default(),
+        ]
+    });
+    for row in rows.into_iter().chain(item_rows) {
+        let fields: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+// 含逗号、引号或换行的字段加引号; 以公式字符开头的字段加单引号, 防止在表格软件中被当作公式执行
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+// 通过站内通知和邮件提醒复核人, 没有待复核项时不提醒
+async fn send_reminders(app_state: &AppState, review: &access_reviews::Model) -> Result<(), AppError> {
+    let db = &app_state.db;
+    let pending = access_review_items::Entity::find()
+        .filter(access_review_items::Column::ReviewId.eq(review.id))
+        .filter(access_review_items::Column::Decision.eq(DECISION_PENDING))
+        .count(db)
+        .await?;
+    if pending == 0 {
+        return Ok(());
+    }
+    let reviewer_ids = access_review_reviewers::Entity::find()
+        .select_only()
+        .column(access_review_reviewers::Column::UserId)
+        .filter(access_review_reviewers::Column::ReviewId.eq(review.id))
+        .into_tuple::<i32>()
+        .all(db)
+        .await?;
+    let reviewers = users::Entity::find()
+        .filter(users::Column::UserId.is_in(reviewer_ids))
+        .filter(users::Column::IsActive.eq(true))
+        .all(db)
+        .await?;
+
+    let now = Utc::now();
+    let overdue = review.due_at <= now;
+    let due_at = review.due_at.format("%Y-%m-%d %H:%M UTC").to_string();
+    let link = format!("/access-reviews/{}", review.review_uuid);
+    let review_url = app_state
+        .config
+        .server
+        .public_link(&link)
+        .map_err(|e| AppError::internal_server_error(anyhow::anyhow!(e)))?;
+    for reviewer in reviewers {
+        let notification = NewNotification {
+            category: NOTIFICATION_CATEGORY_ACCESS.to_string(),
+            level: if overdue { NOTIFICATION_WARNING } else { NOTIFICATION_INFO }.to_string(),
+            title: format!("Access review {} needs your decision", review.name),
+            content: Some(format!("{} item(s) pending, due at {}", pending, due_at)),
+            link: Some(link.clone()),
+        };
+        if let Err(e) = notify_user(app_state, reviewer.user_id, &reviewer.user_uuid, notification).await {
+            warn!("通知复核人 {} 失败: {:?}", reviewer.username, e);
+        }
+        let template = EmailTemplate::AccessReviewReminder {
+            review_name: &review.name,
+            pending,
+            review_url: review_url.as_str(),
+            due_at: &due_at,
+        };
+        if let Err(e) = app_state.email_service.enqueue(&reviewer.email, template, &review.language).await {
+            warn!("发送复核提醒邮件给 {} 失败: {:?}", reviewer.username, e);
+        }
+    }
+
+    access_reviews::Entity::update_many()
+        .col_expr(access_reviews::Column::LastRemindedAt, Expr::value(now))
+        .filter(access_reviews::Column::Id.eq(review.id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+// 后台任务：定期提醒进行中复核活动的复核人
+pub async fn run_access_review_reminders(state: AppState) {
+    loop {
+        if let Err(e) = remind_due_reviews(&state).await {
+            error!("发送访问权限复核提醒失败: {}", e);
+        }
+        sleep(ACCESS_REVIEW_REMINDER_POLL_INTERVAL).await;
+    }
+}
+
+async fn remind_due_reviews(state: &AppState) -> Result<(), AppError> {
+    let threshold = Utc::now() - ChronoDuration::hours(ACCESS_REVIEW_REMINDER_INTERVAL_HOURS);
+    let due_condition = Condition::any()
+        .add(access_reviews::Column::LastRemindedAt.is_null())
+        .add(access_reviews::Column::LastRemindedAt.lte(threshold));
+    let reviews = access_reviews::Entity::find()
+        .filter(access_reviews::Column::Status.eq(ACCESS_REVIEW_ACTIVE))
+        .filter(due_condition.clone())
+        .all(&state.db)
+        .await?;
+    for review in reviews {
+        // 多节点部署时只有更新成功的节点发送提醒
+        let claimed = access_reviews::Entity::update_many()
+            .col_expr(access_reviews::Column::LastRemindedAt, Expr::value(Utc::now()))
+            .filter(access_reviews::Column::Id.eq(review.id))
+            .filter(due_condition.clone())
+            .exec(&state.db)
+            .await?
+            .rows_affected;
+        if claimed == 0 {
+            continue;
+        }
+        if let Err(e) = send_reminders(state, &review).await {
+            warn!("提醒访问权限复核 {} 的复核人失败: {}", review.name, e);
+        }
+    }
+    Ok(())
+}
@@ -39,4 +39,26 @@ impl AuditLogService {
 
         Ok(())
     }
+
+    /// 记录非请求类的安全事件(如账户锁定/解锁)
+    pub async fn log_event(
+        &self,
+        user_id: UserUUID,
+        username: String,
+        module: String,
+        summary: String,
+    ) -> Result<(), AppError> {
+        let audit_log = AuditLogActiveModel {
+            user_id: Set(user_id),
+            username: Set(username),
+            module: Set(module),
+            summary: Set(summary),
+            created_at: Set(chrono::Local::now().naive_local()),
+            updated_at: Set(chrono::Local::now().naive_local()),
+            ..Default::default()
+        };
+        audit_log.insert(&self.app_state.db).await?;
+
+        Ok(())
+    }
 }
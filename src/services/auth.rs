@@ -1,19 +1,23 @@
-use crate::config::app::BLACK_LIST_JTI;
+use crate::config::app::{BLACK_LIST_JTI, TOKENS_VALID_AFTER};
 use crate::config::auth::{ACCESS_TOKEN_EXPIRATION, REFRESH_TOKEN_EXPIRATION};
 // 认证相关路由（登录、SSO等）
 use crate::config::state::AppState;
 use crate::entity::{
-    departments,
+    departments, personal_access_tokens,
     roles::{Column as RoleColumn, Entity as RoleEntity, Relation as RoleRelation},
     user_roles::Column as UserRoleColumn,
-    users::{ActiveModel as UserActiveModel, Column as UserColumn, Entity as UserEntity},
+    users::{ActiveModel as UserActiveModel, Column as UserColumn, Entity as UserEntity, Model as UserModel},
 };
 use crate::errors::app_error::AppError;
 use crate::schemas::auth::{AuthResponse, Claims, Credentials, TokenType};
+use crate::services::auth_backend::{is_external_user, AuthBackend, Backend};
+use crate::services::login_guard::LoginGuardService;
+use crate::services::password::password_expired;
+use crate::services::role::active_assignment;
 use crate::services::user::{get_user_entities, UserService};
 use crate::utils::{
     jwt::{create_access_token, decode_token},
-    crypto::verify_password,
+    crypto::{dummy_password_hash, verify_password},
     cedar_utils::USER_ENTITIES_CACHE_PREFIX
 };
 use crate::{not_found, unauthorized};
@@ -24,9 +28,11 @@ use chrono::{Duration, Utc};
 use cookie::{time::Duration as CookieDuration, SameSite};
 use redis::{AsyncCommands, RedisResult};
 use sea_orm::JoinType::InnerJoin;
-use sea_orm::{ActiveModelTrait, ColIdx, ColumnTrait, EntityTrait, ModelTrait, PaginatorTrait, QueryFilter, QuerySelect, QueryTrait, RelationTrait, Set};
+use sea_orm::{ActiveModelTrait, ColIdx, DatabaseConnection, ColumnTrait, EntityTrait, ModelTrait, PaginatorTrait, QueryFilter, QuerySelect, QueryTrait, RelationTrait, Set};
 use crate::schemas::user::UserUUID;
 
+const INVALID_CREDENTIALS: &str = "Invalid username or password";
+
 #[derive(Clone)]
 pub struct AuthService {
     app_state: AppState,
@@ -44,33 +50,55 @@ impl AuthService {
     pub async fn authenticate(
         &self,
         jar: CookieJar,
+        tenant_id: i32,
         dto: Credentials,
+        client_ip: String,
     ) -> Result<(CookieJar, AuthResponse), AppError> {
         // 验证用户名和密码
         // 生成 JWT
         // 返回 JWT
+        let login_guard = LoginGuardService::new(self.app_state.clone(), tenant_id);
+        login_guard.check(&dto.username, &client_ip).await?;
+
         let user = UserEntity::find()
             .filter(UserColumn::Username.eq(&dto.username))
+            .filter(UserColumn::TenantId.eq(tenant_id))
             .one(&self.app_state.db)
-            .await?
-            .ok_or(not_found!("User Not found".to_string()))?;
-
-        let verified = verify_password(&dto.password, user.password.as_str())?;
-        if !verified {
-            return Err(unauthorized!("Invalid credentials".to_string()));
-        }
+            .await?;
 
-        if !user.is_active {
-            return Err(unauthorized!("User is inactive".to_string()));
-        }
+        // 用户不存在、密码错误、用户被禁用统一返回同一个错误, 避免用户名枚举
+        let (verified, backend) = match &user {
+            Some(user) => {
+                let backend = Backend::for_user(&self.app_state, user).await?;
+                (backend.verify(user, &dto.password).await? && user.is_active, Some(backend))
+            }
+            None => {
+                let _ = verify_password(&dto.password, dummy_password_hash());
+                (false, None)
+            }
+        };
+        let (user, backend) = match (user, backend) {
+            (Some(user), Some(backend)) if verified => (user, backend),
+            _ => {
+                login_guard.record_failure(&dto.username, &client_ip).await?;
+                return Err(unauthorized!(INVALID_CREDENTIALS.to_string()));
+            }
+        };
+        login_guard.record_success(&dto.username).await?;
+        tracing::debug!("用户 {} 通过 {} 认证", user.username, backend.name());
+        let password_expired = !is_external_user(&self.app_state, user.user_id).await?
+            && password_expired(&self.app_state.config.security.password, &user);
+        self.issue_session(jar, user, password_expired).await
+    }
 
-        let is_super_admin = RoleEntity::find()
-            .join(InnerJoin, RoleRelation::UserRoles.def())
-            .filter(UserRoleColumn::UserId.eq(user.user_id))
-            .filter(RoleColumn::RoleName.eq("SuperAdmin"))
-            .count(&self.app_state.db)
-            .await?;
-        let is_super_admin = is_super_admin > 0;
+    /// 签发 Access Token 和 Refresh Token Cookie, 密码登录和单点登录共用
+    pub async fn issue_session(
+        &self,
+        jar: CookieJar,
+        user: UserModel,
+        password_expired: bool,
+    ) -> Result<(CookieJar, AuthResponse), AppError> {
+        let is_super_admin = is_super_admin(&self.app_state.db, user.user_id).await?;
 
         let dept_uuid = departments::Entity::find_by_id(user.dept_id)
             .select_only()
@@ -85,11 +113,14 @@ impl AuthService {
             sub: user.user_uuid.clone(),
             jti: uuid::Uuid::new_v4(),
             iat: Utc::now().timestamp() as u64,
+            iat_ms: Utc::now().timestamp_millis() as u64,
             exp: expires.timestamp() as u64,
             name: user.username.clone(),
             dept_id: dept_uuid.clone(),
+            tenant_id: user.tenant_id,
             token_type: TokenType::Access,
             is_super_admin,
+            password_expired,
         };
         let access_token = create_access_token(payload).unwrap();
 
@@ -98,11 +129,14 @@ impl AuthService {
             sub: user.user_uuid.clone(),
             jti: uuid::Uuid::new_v4(),
             iat: Utc::now().timestamp() as u64,
+            iat_ms: Utc::now().timestamp_millis() as u64,
             exp: expires.timestamp() as u64,
             name: user.username.clone(),
             dept_id: dept_uuid,
+            tenant_id: user.tenant_id,
             token_type: TokenType::Refresh,
             is_super_admin,
+            password_expired,
         };
 
         let refresh_token = create_access_token(payload).unwrap();
@@ -115,10 +149,11 @@ impl AuthService {
             .secure(true)
             .build();
 
-        let _ = &self.cache_user_entities(user.user_uuid.clone()).await?;
+        let _ = &self.cache_user_entities(user.user_uuid.clone(), user.tenant_id).await?;
 
 
         // 更新用户最后登录时间
+        let user_name = user.username;
         let user = UserActiveModel {
             user_id: Set(user.user_id),
             last_login: Set(Some(Utc::now().naive_local())),
@@ -129,7 +164,8 @@ impl AuthService {
 
         let auth_response = AuthResponse {
             access_token,
-            username: dto.username,
+            username: user_name,
+            password_expired,
         };
         Ok((jar.add(refresh_cookie), auth_response))
     }
@@ -155,23 +191,35 @@ impl AuthService {
         let is_blacklisted: bool = redis_conn
             .exists(format!("{}:{}", BLACK_LIST_JTI, refresh_claims.jti))
             .await?;
-        if is_blacklisted {
+        if is_blacklisted || is_token_revoked(&mut redis_conn, &refresh_claims).await? {
             return Err(unauthorized!(
                 "Refresh token is blacklisted".to_string(),
             ));
         }
 
+        // 密码可能在刷新前已修改或过期, 重新计算
+        let user = UserEntity::find()
+            .filter(UserColumn::UserUuid.eq(&refresh_claims.sub))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(unauthorized!("Invalid refresh token".to_string()))?;
+        let password_expired = !is_external_user(&self.app_state, user.user_id).await?
+            && password_expired(&self.app_state.config.security.password, &user);
+
         // 签发新的JWT
         let expires = Utc::now() + Duration::seconds(ACCESS_TOKEN_EXPIRATION);
         let new_claims = Claims {
             sub: refresh_claims.sub.clone(),
             jti: uuid::Uuid::new_v4(),
             iat: Utc::now().timestamp() as u64,
+            iat_ms: Utc::now().timestamp_millis() as u64,
             exp: expires.timestamp() as u64,
             name: refresh_claims.name.clone(),
             dept_id: refresh_claims.dept_id.clone(),
+            tenant_id: user.tenant_id,
             token_type: TokenType::Access,
             is_super_admin: refresh_claims.is_super_admin,
+            password_expired,
         };
         let new_access_token = create_access_token(new_claims).unwrap();
 
@@ -185,16 +233,19 @@ impl AuthService {
             )
             .await;
 
-        let expires = Utc::now() + Duration::minutes(REFRESH_TOKEN_EXPIRATION);
+        let expires = Utc::now() + Duration::seconds(REFRESH_TOKEN_EXPIRATION);
         let new_claims = Claims {
             sub: refresh_claims.sub,
             jti: uuid::Uuid::new_v4(),
             iat: Utc::now().timestamp() as u64,
+            iat_ms: Utc::now().timestamp_millis() as u64,
             exp: expires.timestamp() as u64,
             name: refresh_claims.name.clone(),
             dept_id: refresh_claims.dept_id,
+            tenant_id: user.tenant_id,
             token_type: TokenType::Refresh,
             is_super_admin: refresh_claims.is_super_admin,
+            password_expired,
         };
         let new_refresh_token = create_access_token(new_claims).unwrap();
 
@@ -209,6 +260,7 @@ impl AuthService {
         let auth_response = AuthResponse {
             access_token: new_access_token,
             username: refresh_claims.name,
+            password_expired,
         };
 
         Ok((jar.add(new_refresh_cookie), auth_response))
@@ -255,10 +307,10 @@ impl AuthService {
     }
 
     // 当前用户的 Entities 不应该过期; 不然速度太慢了.
-    async fn cache_user_entities(&self, user_id: UserUUID) -> Result<(), AppError> {
+    async fn cache_user_entities(&self, user_id: UserUUID, tenant_id: i32) -> Result<(), AppError> {
         let cache_key = format!("{}:{}", USER_ENTITIES_CACHE_PREFIX, user_id);
-        let schema = self.app_state.auth_service.get_schema_copy().await;
-        let user_entities = get_user_entities(&self.app_state.db, user_id, &schema).await?;
+        let schema = self.app_state.auth_service.get_schema_copy(tenant_id).await;
+        let user_entities = get_user_entities(&self.app_state.db, tenant_id, user_id, &schema).await?;
         self.app_state
             .cache_service
             .cache_entities(cache_key, user_entities)
@@ -268,3 +320,64 @@ impl AuthService {
     }
     
 }
+
+/// 是否直接分配了 SuperAdmin 角色
+pub async fn is_super_admin(db: &DatabaseConnection, user_id: i32) -> Result<bool, AppError> {
+    let count = RoleEntity::find()
+        .join(InnerJoin, RoleRelation::UserRoles.def())
+        .filter(UserRoleColumn::UserId.eq(user_id))
+        .filter(active_assignment(UserRoleColumn::ValidFrom, UserRoleColumn::ValidUntil))
+        .filter(RoleColumn::RoleName.eq("SuperAdmin"))
+        .count(db)
+        .await?;
+    Ok(count > 0)
+}
+
+/// 使该用户此前签发的所有令牌失效, 在此之前签发的令牌都会被拒绝; 个人访问令牌直接撤销
+pub async fn invalidate_user_sessions(app_state: &AppState, user_id: i32, user_uuid: &str) -> Result<(), AppError> {
+    let mut redis_conn = app_state.redis.get_multiplexed_async_connection().await?;
+    // 毫秒时间戳, 失效后同一秒内重新登录签发的令牌仍然有效
+    // 保留到最长的 Refresh Token (含 OAuth 客户端的) 过期
+    let ttl = (REFRESH_TOKEN_EXPIRATION as u64).max(app_state.config.identity_provider.refresh_token_ttl_seconds);
+    let _: () = redis_conn
+        .set_ex(
+            format!("{}:{}", TOKENS_VALID_AFTER, user_uuid),
+            Utc::now().timestamp_millis(),
+            ttl,
+        )
+        .await?;
+
+    // 个人访问令牌可以不过期, 无法只靠失效时间拒绝
+    personal_access_tokens::Entity::delete_many()
+        .filter(personal_access_tokens::Column::UserId.eq(user_id))
+        .exec(&app_state.db)
+        .await?;
+    Ok(())
+}
+
+/// 会话失效时间(毫秒), 在此之前签发的令牌和授权都无效
+pub async fn sessions_valid_after(
+    redis_conn: &mut redis::aio::MultiplexedConnection,
+    user_uuid: &str,
+) -> Result<Option<u64>, AppError> {
+    Ok(redis_conn
+        .get(format!("{}:{}", TOKENS_VALID_AFTER, user_uuid))
+        .await?)
+}
+
+/// 令牌是否签发于会话失效之前
+pub async fn is_token_revoked(
+    redis_conn: &mut redis::aio::MultiplexedConnection,
+    claims: &Claims,
+) -> Result<bool, AppError> {
+    let valid_after = sessions_valid_after(redis_conn, &claims.sub).await?;
+    Ok(valid_after.is_some_and(|valid_after| issued_at_ms(claims) < valid_after))
+}
+
+// 旧令牌没有毫秒签发时间, 按秒计算
+fn issued_at_ms(claims: &Claims) -> u64 {
+    match claims.iat_ms {
+        0 => claims.iat.saturating_mul(1000),
+        iat_ms => iat_ms,
+    }
+}
@@ -0,0 +1,87 @@
+// 认证后端: 本地密码或 LDAP 绑定, 按用户的来源选择
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+use std::future::Future;
+
+use crate::config::ldap::LdapConfig;
+use crate::config::state::AppState;
+use crate::entity::{directory_links, users};
+use crate::errors::app_error::AppError;
+use crate::services::ldap::{is_ldap_user, verify_credentials, ENTRY_TYPE_USER};
+use crate::utils::crypto::verify_password;
+
+pub trait AuthBackend {
+    fn name(&self) -> &'static str;
+
+    /// 校验用户的密码, 密码错误时返回 false
+    fn verify(&self, user: &users::Model, password: &str) -> impl Future<Output = Result<bool, AppError>> + Send;
+}
+
+/// 校验本地保存的 argon2 哈希
+pub struct LocalBackend;
+
+impl AuthBackend for LocalBackend {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    async fn verify(&self, user: &users::Model, password: &str) -> Result<bool, AppError> {
+        Ok(verify_password(password, &user.password)?)
+    }
+}
+
+/// 以用户的 DN 和密码绑定 LDAP 目录
+pub struct LdapBackend<'a> {
+    config: &'a LdapConfig,
+}
+
+impl AuthBackend for LdapBackend<'_> {
+    fn name(&self) -> &'static str {
+        "ldap"
+    }
+
+    async fn verify(&self, user: &users::Model, password: &str) -> Result<bool, AppError> {
+        verify_credentials(self.config, &user.username, password).await
+    }
+}
+
+pub enum Backend<'a> {
+    Local(LocalBackend),
+    Ldap(LdapBackend<'a>),
+}
+
+impl<'a> Backend<'a> {
+    /// 从目录同步的用户使用 LDAP 认证, 其他用户使用本地密码
+    pub async fn for_user(app_state: &'a AppState, user: &users::Model) -> Result<Self, AppError> {
+        let config = &app_state.config.ldap;
+        if config.enabled && is_ldap_user(app_state, user.user_id).await? {
+            return Ok(Backend::Ldap(LdapBackend { config }));
+        }
+        Ok(Backend::Local(LocalBackend))
+    }
+}
+
+impl AuthBackend for Backend<'_> {
+    fn name(&self) -> &'static str {
+        match self {
+            Backend::Local(backend) => backend.name(),
+            Backend::Ldap(backend) => backend.name(),
+        }
+    }
+
+    async fn verify(&self, user: &users::Model, password: &str) -> Result<bool, AppError> {
+        match self {
+            Backend::Local(backend) => backend.verify(user, password).await,
+            Backend::Ldap(backend) => backend.verify(user, password).await,
+        }
+    }
+}
+
+/// 从目录或单点登录同步的用户, 密码有效期等本地密码策略对其不生效
+pub async fn is_external_user(app_state: &AppState, user_id: i32) -> Result<bool, AppError> {
+    let count = directory_links::Entity::find()
+        .filter(directory_links::Column::EntryType.eq(ENTRY_TYPE_USER))
+        .filter(directory_links::Column::LocalId.eq(user_id))
+        .count(&app_state.db)
+        .await?;
+    Ok(count > 0)
+}
@@ -1,10 +1,8 @@
 use crate::errors::app_error::AppError;
-use cedar_policy::{Entities, Schema};
+use cedar_policy::Entities;
 use moka::future::Cache;
 use redis::{AsyncCommands, Client as RedisClient};
-use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::RwLock;
 use tracing::{debug, error, instrument, warn};
 use crate::utils::cedar_utils::entities2json;
 
@@ -16,11 +14,10 @@ const DEFAULT_REDIS_TTL_SECS: u64 = 3600; // 1 hour
 pub struct CacheService {
     redis_client: RedisClient,
     local_cache: Cache<String, String>,
-    schema: Arc<RwLock<Schema>>,
 }
 
 impl CacheService {
-    pub fn new(redis_client: RedisClient, schema: Schema) -> Self {
+    pub fn new(redis_client: RedisClient) -> Self {
         let local_cache = Cache::builder()
             .max_capacity(DEFAULT_LOCAL_CACHE_SIZE)
             .time_to_live(Duration::from_secs(DEFAULT_LOCAL_TTL_SECS))
@@ -29,23 +26,21 @@ impl CacheService {
         Self {
             redis_client,
             local_cache,
-            schema: Arc::new(RwLock::new(schema)),
         }
     }
 
+    /// 实体在写入前已按所属租户的 Schema 校验过, 读取时不再依赖 Schema
     pub async fn get_entities(&self, cache_key: String) -> Result<Option<Entities>, AppError> {
 
         if let Some(cache_value) = self.local_cache.get(&cache_key).await {
             // debug!("在本地缓存中找到的用户实体[UserID:{}]", user_id);
-            let schema = self.schema.read().await;
-            let entities = Entities::from_json_str(&cache_value, Some(&*schema))?;
+            let entities = Entities::from_json_str(&cache_value, None)?;
             return Ok(Some(entities));
         }
 
         match self.get_from_redis(&cache_key).await {
             Ok(Some(cache_value)) => {
-                let schema = self.schema.read().await;
-                let entities = Entities::from_json_str(&cache_value, Some(&*schema))?;
+                let entities = Entities::from_json_str(&cache_value, None)?;
                 self.local_cache.insert(cache_key.clone(), cache_value).await;
                 debug!("从 Redis 加载实体[CacheKey:{}]并缓存在本地", cache_key);
                 Ok(Some(entities))
@@ -66,10 +61,7 @@ impl CacheService {
         cache_key: String,
         entities: Entities,
     ) -> Result<(), AppError> {
-        // 解析实体以验证格式并存入本地缓存
-        let schema = self.schema.read().await;
-        let parsed_entities = Entities::from_entities(entities, Some(&schema))?;
-        let entities_json_str = entities2json(&parsed_entities)?;
+        let entities_json_str = entities2json(&entities)?;
         self.set_cache(cache_key, entities_json_str.as_str(), None).await?;
         Ok(())
     }
@@ -88,12 +80,9 @@ impl CacheService {
         Ok(())
     }
 
-    pub async fn update_schema(&self, new_schema: Schema) {
-        let mut schema = self.schema.write().await;
-        *schema = new_schema;
-        // 清空本地缓存，因为schema变更可能影响实体解析
-        self.local_cache.invalidate_all();
-        debug!("缓存架构已更新，本地缓存已清除");
+    /// 只清理本节点的本地缓存, 其他节点更新了 Redis 中的数据后调用
+    pub async fn evict_local(&self, cache_key: &str) {
+        self.local_cache.invalidate(cache_key).await;
     }
 
     // 获取缓存统计信息
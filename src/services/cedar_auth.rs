@@ -9,32 +9,31 @@ use tracing::{info, warn, instrument, debug, error};
 use crate::forbidden;
 use crate::schemas::cedar_policy::{CedarContext, TemplateLinkRecord};
 use crate::schemas::user::UserUUID;
-use crate::utils::cedar_utils::{AuthAction, AuthorizationBuilder, ResourceType, POLICIES_AND_TEMPLATES_CACHE_KEY, TEMPLATE_LINKS_CACHE_KEY, USER_ENTITIES_CACHE_PREFIX};
+use crate::utils::cedar_utils::{principal_type_of, AuthAction, AuthorizationBuilder, ResourceType, POLICIES_AND_TEMPLATES_CACHE_KEY, TEMPLATE_LINKS_CACHE_KEY, USER_ENTITIES_CACHE_PREFIX};
+
+/// 单个租户的 Cedar 配置: 命名空间和 Schema, 策略集按租户分别缓存
+#[derive(Clone)]
+pub struct TenantAuthz {
+    pub namespace: Option<String>,
+    pub schema: Schema,
+}
 
 #[derive(Clone)]
 pub struct CedarAuthService {
     authorizer: Arc<Authorizer>,
     cache_service: Arc<CacheService>,
-    schema: Arc<RwLock<Schema>>,
+    tenants: Arc<RwLock<HashMap<i32, TenantAuthz>>>,
 }
 
 impl CedarAuthService {
     pub fn new(
         cache_service: Arc<CacheService>,
-        schema: Schema,
+        tenants: HashMap<i32, TenantAuthz>,
     ) -> Self {
-        tokio::spawn({
-            let cache_service = cache_service.clone();
-            let schema = schema.clone();
-            async move {
-                cache_service.update_schema(schema).await;
-            }
-        });
-
         Self {
             authorizer: Arc::new(Authorizer::new()),
             cache_service,
-            schema: Arc::new(RwLock::new(schema)),
+            tenants: Arc::new(RwLock::new(tenants)),
         }
     }
 
@@ -45,12 +44,18 @@ impl CedarAuthService {
         action: AuthAction,
         resource: ResourceType,
     ) -> Result<bool, AppError> {
+        let principal_entities = self.get_principal_entities(user_id).await?;
+        // 主体实体的命名空间决定了使用哪个租户的 Schema 和策略集
+        let (principal_type, namespace) = principal_type_of(&principal_entities, user_id)?;
+        let tenant_id = self.tenant_of_namespace(namespace.as_deref()).await?;
         let (request, resource_entities) = AuthorizationBuilder::new(user_id.clone(), context)
+            .principal_type(principal_type)
+            .namespace(namespace)
             .action(action)
             .resource(resource)
             .build()?;
 
-        self.is_authorized(user_id, &request, resource_entities)
+        self.is_authorized(user_id, tenant_id, &request, principal_entities, resource_entities)
             .await
     }
 
@@ -63,36 +68,45 @@ impl CedarAuthService {
         resource: ResourceType,
         resource_entities: Entities,
     ) -> Result<bool, AppError> {
+        let principal_entities = self.get_principal_entities(user_id).await?;
+        // 主体实体的命名空间决定了使用哪个租户的 Schema 和策略集
+        let (principal_type, namespace) = principal_type_of(&principal_entities, user_id)?;
+        let tenant_id = self.tenant_of_namespace(namespace.as_deref()).await?;
         let (request, _) = AuthorizationBuilder::new(user_id.clone(), context)
+            .principal_type(principal_type)
+            .namespace(namespace)
             .action(action)
             .resource(resource)
             .resource_entities(resource_entities.clone())
             .build()?;
 
-        self.is_authorized(user_id, &request, resource_entities)
+        self.is_authorized(user_id, tenant_id, &request, principal_entities, resource_entities)
             .await
     }
 
+    // 从缓存获取主体(用户或服务账号)的实体
+    async fn get_principal_entities(&self, user_id: &UserUUID) -> Result<Entities, AppError> {
+        let cache_key = format!("{}:{}", USER_ENTITIES_CACHE_PREFIX, user_id);
+
+        self.cache_service
+            .get_entities(cache_key)
+            .await?
+            .ok_or_else(||forbidden!(format!("UserID[{}] Entities Not Found", user_id)))
+    }
+
     pub async fn is_authorized(
         &self,
         user_id: &UserUUID,
+        tenant_id: i32,
         request: &Request,
+        user_entities: Entities,
         resource_entities: Entities,
     ) -> Result<bool, AppError> {
-        // 从缓存获取用户实体
-        let cache_key = format!("{}:{}", USER_ENTITIES_CACHE_PREFIX, user_id);
-
-        let user_entities = self
-            .cache_service
-            .get_entities(cache_key)
-            .await?
-            .ok_or_else(||forbidden!(format!("UserID[{}] Entities Not Found", user_id)))?;
-
 
-        let mut effective_policies = self.get_policies_and_templates_from_cache().await?
+        let mut effective_policies = self.get_policies_and_templates_from_cache(tenant_id).await?
             .unwrap_or_else(PolicySet::new);
 
-        let link_records = self.get_template_link_records_from_cache().await?
+        let link_records = self.get_template_link_records_from_cache(tenant_id).await?
             .unwrap_or_default();
 
         if !link_records.is_empty() {
@@ -114,7 +128,7 @@ impl CedarAuthService {
         }
         // debug!("effective_policies: {}", effective_policies);
         // 合并资源实体
-        let schema = self.schema.read().await;
+        let schema = self.get_schema_copy(tenant_id).await;
         let combined_entities = user_entities.add_entities(
             resource_entities,
             Some(&schema),
@@ -157,8 +171,9 @@ impl CedarAuthService {
         }
     }
 
-    pub async fn get_policies_and_templates_from_cache(&self) -> Result<Option<PolicySet>, AppError> {
-        if let Some(policy_string) = self.cache_service.get_cache(POLICIES_AND_TEMPLATES_CACHE_KEY).await? {
+    pub async fn get_policies_and_templates_from_cache(&self, tenant_id: i32) -> Result<Option<PolicySet>, AppError> {
+        let cache_key = format!("{}:{}", POLICIES_AND_TEMPLATES_CACHE_KEY, tenant_id);
+        if let Some(policy_string) = self.cache_service.get_cache(&cache_key).await? {
             let policy_set = PolicySet::from_str(&policy_string)?;
             Ok(Some(policy_set))
         } else {
@@ -166,8 +181,9 @@ impl CedarAuthService {
         }
     }
 
-    pub async fn get_template_link_records_from_cache(&self) -> Result<Option<Vec<TemplateLinkRecord>>, AppError> {
-        if let Some(json_str) = self.cache_service.get_cache(TEMPLATE_LINKS_CACHE_KEY).await? {
+    pub async fn get_template_link_records_from_cache(&self, tenant_id: i32) -> Result<Option<Vec<TemplateLinkRecord>>, AppError> {
+        let cache_key = format!("{}:{}", TEMPLATE_LINKS_CACHE_KEY, tenant_id);
+        if let Some(json_str) = self.cache_service.get_cache(&cache_key).await? {
             let records: Vec<TemplateLinkRecord> = serde_json::from_str(&json_str)?;
             Ok(Some(records))
         } else {
@@ -175,24 +191,44 @@ impl CedarAuthService {
         }
     }
 
-    pub async fn update_policies_and_templates_in_cache(&self, new_set: &PolicySet) -> Result<(), AppError> {
+    pub async fn update_policies_and_templates_in_cache(&self, tenant_id: i32, new_set: &PolicySet) -> Result<(), AppError> {
         let policy_string = new_set.to_string();
-        self.cache_service.set_cache(POLICIES_AND_TEMPLATES_CACHE_KEY.to_string(), &policy_string, None).await
+        let cache_key = format!("{}:{}", POLICIES_AND_TEMPLATES_CACHE_KEY, tenant_id);
+        self.cache_service.set_cache(cache_key, &policy_string, None).await
     }
 
-    pub async fn update_template_link_records_in_cache(&self, records: &[TemplateLinkRecord]) -> Result<(), AppError> {
+    pub async fn update_template_link_records_in_cache(&self, tenant_id: i32, records: &[TemplateLinkRecord]) -> Result<(), AppError> {
         let json_str = serde_json::to_string(records)?;
-        self.cache_service.set_cache(TEMPLATE_LINKS_CACHE_KEY.to_string(), &json_str, None).await
+        let cache_key = format!("{}:{}", TEMPLATE_LINKS_CACHE_KEY, tenant_id);
+        self.cache_service.set_cache(cache_key, &json_str, None).await
     }
 
-    pub async fn update_schema(&self, new_schema: Schema) {
-        let mut schema_guard = self.schema.write().await;
-        *schema_guard = new_schema.clone();
-        self.cache_service.update_schema(new_schema).await;
-        info!("CacheService Schema已更新。");
+    /// 替换全部租户的 Schema, 已删除或停用的租户随之移除
+    pub async fn update_tenants(&self, tenants: HashMap<i32, TenantAuthz>) {
+        let mut tenants_guard = self.tenants.write().await;
+        *tenants_guard = tenants;
+        info!("已更新 {} 个租户的 Schema。", tenants_guard.len());
     }
 
-    pub async fn get_schema_copy(&self) -> Schema {
-        self.schema.read().await.clone()
+    /// 租户不存在时返回空 Schema, 按它构造实体会失败, 从而拒绝访问
+    pub async fn get_schema_copy(&self, tenant_id: i32) -> Schema {
+        match self.tenants.read().await.get(&tenant_id) {
+            Some(tenant) => tenant.schema.clone(),
+            None => {
+                warn!("租户[{}]没有可用的 Schema", tenant_id);
+                Schema::from_schema_fragments([]).expect("empty schema is always valid")
+            }
+        }
+    }
+
+    // 按主体实体的命名空间找到所属租户
+    async fn tenant_of_namespace(&self, namespace: Option<&str>) -> Result<i32, AppError> {
+        self.tenants
+            .read()
+            .await
+            .iter()
+            .find(|(_, tenant)| tenant.namespace.as_deref() == namespace)
+            .map(|(tenant_id, _)| *tenant_id)
+            .ok_or_else(|| forbidden!(format!("Tenant namespace {:?} not found", namespace)))
     }
 }
\ No newline at end of file
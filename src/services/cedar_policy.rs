@@ -6,16 +6,17 @@ use crate::schemas::cedar_policy::{CedarContext,
                                    CedarPolicyResponse,
                                    CreatePolicyDto,
                                    QueryParams};
-use crate::utils::cedar_utils::{AuthAction, ResourceType, ENTITY_TYPE_POLICY, ENTITY_ATTR_NAME};
+use crate::utils::cedar_utils::{entity_type_name, AuthAction, ResourceType, ENTITY_TYPE_POLICY, ENTITY_ATTR_NAME};
 use crate::{bad_request, conflict, not_found};
-use cedar_policy::{Entities, Entity, EntityId, EntityTypeName, EntityUid, Policy, RestrictedExpression};
+use cedar_policy::{Entities, Entity, EntityId, EntityUid, Policy, RestrictedExpression};
 use core::str::FromStr;
 use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, JoinType, PaginatorTrait, QueryFilter, QuerySelect, RelationTrait, Select, Set};
 use serde_json::Value;
 use sha2::{Digest, Sha256};
 use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
-use crate::utils::function::reload_policies_and_schema;
+use crate::schemas::event::DomainEvent;
+use crate::services::event_bus::publish_event;
 
 
 #[derive(Clone)]
@@ -28,17 +29,21 @@ impl CedarPolicyService {
         Self { app_state: state }
     }
 
-    async fn get_policy_entities(&self, policy_uuid: &str) -> Result<Entities, AppError> {
+    async fn get_policy_entities(&self, tenant_id: i32, policy_uuid: &str) -> Result<Entities, AppError> {
         let policy = cedar_policy_set::Entity::find()
             .filter(cedar_policy_set::Column::PolicyUuid.eq(policy_uuid))
+            .filter(cedar_policy_set::Column::TenantId.eq(tenant_id))
             .one(&self.app_state.db)
             .await?;
         let entities: Entities = match policy {
             Some(policy) => {
                 let mut entities = HashSet::new();
+                let schema = self.app_state
+                    .auth_service
+                    .get_schema_copy(tenant_id).await;
 
                 let policy_uid = EntityId::from_str(&*policy.policy_uuid.to_string())?;
-                let policy_typename = EntityTypeName::from_str(ENTITY_TYPE_POLICY)?;
+                let policy_typename = entity_type_name(&schema, ENTITY_TYPE_POLICY)?;
                 let policy_e_uid = EntityUid::from_type_name_and_id(policy_typename, policy_uid);
 
                 let mut attrs = HashMap::new();
@@ -49,10 +54,6 @@ impl CedarPolicyService {
                 let policy_entity = Entity::new(policy_e_uid, attrs, parents)?;
                 entities.insert(policy_entity);
 
-                let schema = self.app_state
-                    .auth_service
-                    .get_schema_copy().await;
-
                 Entities::from_entities(entities, Some(&schema))?
             },
             None => {
@@ -88,7 +89,8 @@ impl CedarPolicyService {
         });
 
         let mut query = cedar_policy_set::Entity::find()
-            .join(JoinType::InnerJoin, cedar_policy_set::Relation::Users.def());
+            .join(JoinType::InnerJoin, cedar_policy_set::Relation::Users.def())
+            .filter(cedar_policy_set::Column::TenantId.eq(current_user.tenant_id));
 
         if let Some(effect) = &params.effect {
             query = query.filter(cedar_policy_set::Column::Effect.eq(effect));
@@ -127,7 +129,7 @@ impl CedarPolicyService {
         context: CedarContext,
         policy_uuid: String,
     ) -> Result<CedarPolicyResponse, AppError> {
-        let es = self.get_policy_entities(&policy_uuid).await?;
+        let es = self.get_policy_entities(current_user.tenant_id, &policy_uuid).await?;
 
         self.app_state
             .auth_service
@@ -141,6 +143,7 @@ impl CedarPolicyService {
 
         let result = cedar_policy_set::Entity::find()
             .filter(cedar_policy_set::Column::PolicyUuid.eq(&policy_uuid))
+            .filter(cedar_policy_set::Column::TenantId.eq(current_user.tenant_id))
             .find_also_related(users::Entity)
             .one(&self.app_state.db)
             .await?
@@ -185,6 +188,7 @@ impl CedarPolicyService {
         let policy_hash = Self::hash_policy_content(&policy.to_string())?;
         if cedar_policy_set::Entity::find()
             .filter(cedar_policy_set::Column::PolicyHash.eq(&policy_hash))
+            .filter(cedar_policy_set::Column::TenantId.eq(current_user.tenant_id))
             .one(&self.app_state.db)
             .await?
             .is_some()
@@ -213,11 +217,16 @@ impl CedarPolicyService {
             is_active: Set(dto.is_active),
             description: Set(dto.description),
             created_by: Set(user_id),
+            tenant_id: Set(current_user.tenant_id),
             ..Default::default()
         };
 
         let new_model = new_policy_model.insert(&self.app_state.db)
             .await?;
+
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::PolicyChanged {
+            policy_uuid: Some(new_model.policy_uuid.clone()),
+        }).await;
         
         let response = CedarPolicyResponse{
             uuid: Some(new_model.policy_uuid),
@@ -235,7 +244,7 @@ impl CedarPolicyService {
         Ok(response)
     }
 
-    fn hash_policy_content(policy_text: &String) -> Result<String, AppError> {
+    pub(crate) fn hash_policy_content(policy_text: &String) -> Result<String, AppError> {
         let mut hasher = Sha256::new();
         hasher.update(policy_text.as_bytes());
         let hash_bytes = hasher.finalize();
@@ -249,7 +258,7 @@ impl CedarPolicyService {
         policy_uuid: String,
         dto: CreatePolicyDto,
     ) -> Result<CedarPolicyResponse, AppError> {
-        let es = self.get_policy_entities(&policy_uuid).await?;
+        let es = self.get_policy_entities(current_user.tenant_id, &policy_uuid).await?;
         self.app_state
         .auth_service
             .check_permission_with_entities(
@@ -269,6 +278,7 @@ impl CedarPolicyService {
 
         let mut policy_model: cedar_policy_set::ActiveModel = cedar_policy_set::Entity::find()
             .filter(cedar_policy_set::Column::PolicyUuid.eq(&policy_uuid))
+            .filter(cedar_policy_set::Column::TenantId.eq(current_user.tenant_id))
             .one(&self.app_state.db)
         .await?
         .ok_or(not_found!("Policy {} not found", policy_uuid))?
@@ -294,6 +304,10 @@ impl CedarPolicyService {
 
         let new_model = policy_model.update(&self.app_state.db).await?;
 
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::PolicyChanged {
+            policy_uuid: Some(policy_uuid),
+        }).await;
+
         let creator = users::Entity::find_by_id(new_model.created_by)
             .one(&self.app_state.db)
             .await?
@@ -321,7 +335,7 @@ impl CedarPolicyService {
         context: CedarContext,
         policy_uuid: String,
     ) -> Result<(), AppError> {
-        let es = self.get_policy_entities(&policy_uuid).await?;
+        let es = self.get_policy_entities(current_user.tenant_id, &policy_uuid).await?;
         self.app_state
         .auth_service
             .check_permission_with_entities(
@@ -333,9 +347,14 @@ impl CedarPolicyService {
             ).await?;
 
         cedar_policy_set::Entity::delete_many()
-            .filter(cedar_policy_set::Column::PolicyUuid.eq(policy_uuid))
+            .filter(cedar_policy_set::Column::PolicyUuid.eq(&policy_uuid))
+            .filter(cedar_policy_set::Column::TenantId.eq(current_user.tenant_id))
             .exec(&self.app_state.db).await?;
 
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::PolicyChanged {
+            policy_uuid: Some(policy_uuid),
+        }).await;
+
         Ok(())
     }
     
@@ -353,7 +372,8 @@ impl CedarPolicyService {
                 ResourceType::Policy(None),
             ).await?;
 
-        reload_policies_and_schema(&self.app_state).await?;
+        // 所有节点都需要重新加载
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::PolicyChanged { policy_uuid: None }).await;
         
         Ok(())
     }
@@ -1,11 +1,14 @@
-use sea_orm::{ActiveModelTrait, EntityTrait, Set};
+use cedar_policy::Schema;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
 use crate::{bad_request, not_found};
 use crate::config::state::AppState;
-use crate::entity::cedar_schema;
+use crate::entity::{cedar_schema, tenants};
 use crate::errors::app_error::AppError;
 use crate::schemas::auth::CurrentUser;
 use crate::schemas::cedar_policy::{CedarContext, CedarSchemaResponse, UpdateSchema};
-use crate::utils::cedar_utils::{AuthAction, ResourceType};
+use crate::schemas::event::DomainEvent;
+use crate::services::event_bus::publish_event;
+use crate::utils::cedar_utils::{schema_namespace, AuthAction, ResourceType};
 
 #[derive(Clone)]
 pub struct CedarSchemaService {
@@ -33,6 +36,7 @@ impl CedarSchemaService {
             ).await?;
 
         let model = cedar_schema::Entity::find()
+            .filter(cedar_schema::Column::TenantId.eq(current_user.tenant_id))
             .one(&self.app_state.db)
             .await?
             .ok_or(bad_request!("Not found Schema"))?;
@@ -65,16 +69,33 @@ impl CedarSchemaService {
         ).await?;
 
         let mut schema: cedar_schema::ActiveModel = cedar_schema::Entity::find_by_id(schema_id)
+            .filter(cedar_schema::Column::TenantId.eq(current_user.tenant_id))
             .one(&self.app_state.db)
             .await?
             .ok_or(not_found!("Schema {} not found", schema_id))?
             .into();
 
+        // 保存前先解析, 且命名空间必须与租户一致, 否则重新加载时该租户会被跳过
+        let (parsed, _) = Schema::from_cedarschema_str(&dto.schema)?;
+        let tenant_namespace = tenants::Entity::find_by_id(current_user.tenant_id)
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("Tenant {} not found", current_user.tenant_id))?
+            .namespace;
+        if schema_namespace(&parsed) != tenant_namespace {
+            return Err(bad_request!(
+                "Schema namespace must be {}",
+                tenant_namespace.as_deref().unwrap_or("empty")
+            ));
+        }
+
         schema.schema=Set(dto.schema);
         schema.description=Set(dto.description);
 
         let new_model = schema.update(&self.app_state.db).await?;
 
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::SchemaChanged).await;
+
         let response = CedarSchemaResponse{
             uuid: new_model.schema_uuid,
             schema: new_model.schema,
@@ -1,5 +1,5 @@
 use crate::{bad_request, conflict, errors::app_error::AppError, forbidden, not_found};
-use cedar_policy::{Entities, Entity, EntityId, EntityTypeName, EntityUid, RestrictedExpression, Schema};
+use cedar_policy::{Entities, Entity, EntityId, EntityUid, RestrictedExpression, Schema};
 use sea_orm::{ColumnTrait, DatabaseTransaction, EntityTrait, QueryFilter, QuerySelect, TransactionTrait, entity::prelude::*, Statement, DbBackend, TryGetableMany, JoinType, Condition, QueryOrder};
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::str::FromStr;
@@ -8,9 +8,12 @@ use crate::config::state::AppState;
 use crate::entity::{departments, user_group_members, user_groups, users};
 use crate::schemas::auth::CurrentUser;
 use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::event::DomainEvent;
+use crate::services::event_bus::publish_event;
 use crate::schemas::department::{CreateDepartmentDto, DepartmentResponse, DeptTreeNode};
 use crate::schemas::user::{DeptResponse, GroupResponse, UserResponse};
-use crate::utils::cedar_utils::{entities2json, AuthAction, ResourceType, ENTITY_TYPE_DEPARTMENT};
+use crate::services::storage::StorageService;
+use crate::utils::cedar_utils::{entities2json, entity_type_name, AuthAction, ResourceType, ENTITY_TYPE_DEPARTMENT};
 use sea_orm::ActiveValue::Set;
 use tracing::{debug, warn};
 
@@ -31,12 +34,14 @@ impl DepartmentService {
     async fn get_dept_id_from_uuid(
         &self,
         db: &impl ConnectionTrait,
+        tenant_id: i32,
         dept_uuid: &str,
     ) -> Result<i32, AppError> {
         departments::Entity::find()
             .select_only()
             .column(departments::Column::DeptId)
             .filter(departments::Column::DeptUuid.eq(dept_uuid))
+            .filter(departments::Column::TenantId.eq(tenant_id))
             .into_tuple::<i32>()
             .one(db)
             .await?
@@ -59,6 +64,7 @@ impl DepartmentService {
             .await?;
 
         let all_departments = departments::Entity::find()
+            .filter(departments::Column::TenantId.eq(current_user.tenant_id))
             .filter(departments::Column::IsDeleted.eq(false))
             .all(&self.app_state.db)
             .await?;
@@ -70,6 +76,7 @@ impl DepartmentService {
                 .select_only()
                 .column(users::Column::DeptId)
                 .filter(users::Column::UserUuid.eq(&current_user.uuid))
+                .filter(users::Column::TenantId.eq(current_user.tenant_id))
                 .into_tuple::<i32>()
                 .one(&self.app_state.db)
                 .await?
@@ -106,12 +113,13 @@ impl DepartmentService {
         let parent_id = if dto.parent_uuid == ROOT_DEPARTMENT_UUID {
             ROOT_DEPARTMENT_ID
         } else {
-            self.get_dept_id_from_uuid(&self.app_state.db, &dto.parent_uuid).await?
+            self.get_dept_id_from_uuid(&self.app_state.db, current_user.tenant_id, &dto.parent_uuid).await?
         };
 
         if departments::Entity::find()
             .filter(
                 Condition::all()
+                    .add(departments::Column::TenantId.eq(current_user.tenant_id))
                     .add(departments::Column::Name.eq(&dto.name))
                     .add(departments::Column::ParentId.eq(parent_id))
                     .add(departments::Column::IsDeleted.eq(false))
@@ -129,11 +137,16 @@ impl DepartmentService {
             desc: Set(Some(dto.desc)),
             order: Set(dto.order),
             parent_id: Set(parent_id),
+            tenant_id: Set(current_user.tenant_id),
             ..Default::default()
         };
 
         let saved_department = new_department.insert(&self.app_state.db).await?;
 
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::DepartmentCreated {
+            dept_uuid: saved_department.dept_uuid.clone(),
+        }).await;
+
         Ok(DepartmentResponse {
             uuid: saved_department.dept_uuid,
             name: saved_department.name,
@@ -150,8 +163,8 @@ impl DepartmentService {
         dept_uuid: String,
         dto: CreateDepartmentDto,
     ) -> Result<DepartmentResponse, AppError> {
-        let schema = self.app_state.auth_service.get_schema_copy().await;
-        let es = get_dept_entities(&self.app_state.db, &dept_uuid, &schema).await?;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
+        let es = get_dept_entities(&self.app_state.db, current_user.tenant_id, &dept_uuid, &schema).await?;
         self.app_state
             .auth_service
             .check_permission_with_entities(
@@ -168,11 +181,12 @@ impl DepartmentService {
         let new_parent_id = if dto.parent_uuid == ROOT_DEPARTMENT_UUID {
             ROOT_DEPARTMENT_ID
         } else {
-            self.get_dept_id_from_uuid(&txn, &dto.parent_uuid).await?
+            self.get_dept_id_from_uuid(&txn, current_user.tenant_id, &dto.parent_uuid).await?
         };
 
         let mut department: departments::ActiveModel = departments::Entity::find()
             .filter(departments::Column::DeptUuid.eq(&dept_uuid))
+            .filter(departments::Column::TenantId.eq(current_user.tenant_id))
             .one(&txn)
             .await?
             .ok_or(not_found!("department not found".to_string()))?
@@ -186,7 +200,7 @@ impl DepartmentService {
                     &current_user.uuid,
                     context,
                     AuthAction::MoveDepartment,
-                    ResourceType::Department(Some(dept_uuid)),
+                    ResourceType::Department(Some(dept_uuid.clone())),
                 )
                 .await?;
         }
@@ -197,7 +211,27 @@ impl DepartmentService {
         department.parent_id = Set(new_parent_id);
 
         let updated_department = department.update(&txn).await?;
+        let from_parent_uuid = if original_parent_id == ROOT_DEPARTMENT_ID {
+            ROOT_DEPARTMENT_UUID.to_string()
+        } else {
+            departments::Entity::find_by_id(original_parent_id)
+                .one(&txn)
+                .await?
+                .map(|parent| parent.dept_uuid)
+                .unwrap_or_default()
+        };
         txn.commit().await?;
+
+        let event = if original_parent_id != new_parent_id {
+            DomainEvent::DepartmentMoved {
+                dept_uuid,
+                from_parent_uuid,
+                to_parent_uuid: dto.parent_uuid.clone(),
+            }
+        } else {
+            DomainEvent::DepartmentUpdated { dept_uuid }
+        };
+        publish_event(&self.app_state, Some(&current_user), event).await;
         Ok(DepartmentResponse {
             uuid: updated_department.dept_uuid,
             name: updated_department.name,
@@ -213,8 +247,8 @@ impl DepartmentService {
         context: CedarContext,
         dept_uuid: String,
     ) -> Result<(), AppError> {
-        let schema = self.app_state.auth_service.get_schema_copy().await;
-        let es = get_dept_entities(&self.app_state.db, &dept_uuid, &schema).await?;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
+        let es = get_dept_entities(&self.app_state.db, current_user.tenant_id, &dept_uuid, &schema).await?;
         self.app_state
             .auth_service
             .check_permission_with_entities(
@@ -230,6 +264,7 @@ impl DepartmentService {
 
         let dept_model = departments::Entity::find()
             .filter(departments::Column::DeptUuid.eq(&dept_uuid))
+            .filter(departments::Column::TenantId.eq(current_user.tenant_id))
             .one(&txn)
             .await?
             .ok_or_else(|| not_found!("Department to delete not found"))?;
@@ -256,6 +291,8 @@ impl DepartmentService {
         active_model.update(&txn).await?;
 
         txn.commit().await?;
+
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::DepartmentDeleted { dept_uuid }).await;
         Ok(())
     }
 
@@ -292,8 +329,8 @@ impl DepartmentService {
                                   context: CedarContext,
                                   dept_uuid: String) -> Result<Vec<UserResponse>, AppError> {
 
-        let schema = self.app_state.auth_service.get_schema_copy().await;
-        let es = get_dept_entities(&self.app_state.db, &dept_uuid, &schema).await?;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
+        let es = get_dept_entities(&self.app_state.db, current_user.tenant_id, &dept_uuid, &schema).await?;
         self.app_state
             .auth_service
             .check_permission_with_entities(
@@ -312,9 +349,10 @@ impl DepartmentService {
                 users::Relation::Departments.def(),
             )
             .filter(departments::Column::DeptUuid.eq(dept_uuid))
+            .filter(departments::Column::TenantId.eq(current_user.tenant_id))
             .all(&self.app_state.db)
             .await?;
-        let users = assemble_user_info(&self.app_state.db, users_with_dept).await?;
+        let users = assemble_user_info(&self.app_state.db, &self.app_state.storage_service, users_with_dept).await?;
         Ok(users)
     }
 }
@@ -322,18 +360,21 @@ impl DepartmentService {
 
 pub async fn get_all_child_dept_ids(
     db: &DatabaseConnection,
+    tenant_id: i32,
     parent_dept_uuid: &str,
 ) -> Result<Vec<i32>, AppError> {
     let parent_dept_id = departments::Entity::find()
         .select_only()
         .column(departments::Column::DeptId)
         .filter(departments::Column::DeptUuid.eq(parent_dept_uuid))
+        .filter(departments::Column::TenantId.eq(tenant_id))
         .into_tuple::<i32>()
         .one(db)
         .await?
         .ok_or(not_found!("department not found".to_string()))?;
 
     let all_depts = departments::Entity::find()
+        .filter(departments::Column::TenantId.eq(tenant_id))
         .filter(departments::Column::IsDeleted.eq(false))
         .all(db)
         .await?;
@@ -368,11 +409,12 @@ pub async fn get_all_child_dept_ids(
 
 // 获取指定部门的Entities
 
-pub async fn get_dept_entities(db: &DatabaseConnection, dept_uuid: &str, schema: &Schema) -> Result<Entities, AppError> {
+pub async fn get_dept_entities(db: &DatabaseConnection, tenant_id: i32, dept_uuid: &str, schema: &Schema) -> Result<Entities, AppError> {
     let dept_name = departments::Entity::find()
         .select_only()
         .column(departments::Column::Name)
         .filter(departments::Column::DeptUuid.eq(dept_uuid))
+        .filter(departments::Column::TenantId.eq(tenant_id))
         .into_tuple::<String>()
         .one(db)
         .await?;
@@ -383,7 +425,7 @@ pub async fn get_dept_entities(db: &DatabaseConnection, dept_uuid: &str, schema:
     };
 
     let dept_eid = EntityId::from_str(dept_uuid.as_ref())?;
-    let dept_typename = EntityTypeName::from_str(ENTITY_TYPE_DEPARTMENT)?;
+    let dept_typename = entity_type_name(schema, ENTITY_TYPE_DEPARTMENT)?;
     let dept_e_uid = EntityUid::from_type_name_and_id(dept_typename, dept_eid);
 
     let mut attrs = HashMap::new();
@@ -400,9 +442,15 @@ pub async fn get_dept_entities(db: &DatabaseConnection, dept_uuid: &str, schema:
 }
 
 // 获取指定部门所有的子部门Entities
-pub async fn find_descendants_entities(db: &DatabaseConnection, dept_id: i32) -> Result<Entities, AppError> {
-    // 1. 一次性获取所有未被软删除的部门
+pub async fn find_descendants_entities(
+    db: &DatabaseConnection,
+    tenant_id: i32,
+    dept_id: i32,
+    schema: &Schema,
+) -> Result<Entities, AppError> {
+    // 1. 一次性获取本租户所有未被软删除的部门
     let all_depts: Vec<departments::Model> = departments::Entity::find()
+        .filter(departments::Column::TenantId.eq(tenant_id))
         .filter(departments::Column::IsDeleted.eq(false))
         .all(db)
         .await?;
@@ -439,7 +487,7 @@ pub async fn find_descendants_entities(db: &DatabaseConnection, dept_id: i32) ->
 
         // 从索引中获取当前部门的模型，并将其转换为Cedar实体
         if let Some(current_dept_model) = depts_by_id.get(&current_dept_id) {
-            let entity = try_dept_model_to_cedar_entity(current_dept_model)?;
+            let entity = try_dept_model_to_cedar_entity(current_dept_model, schema)?;
             entities.insert(entity);
 
             // 查找并添加所有直接子部门到处理队列中
@@ -459,9 +507,10 @@ pub async fn find_descendants_entities(db: &DatabaseConnection, dept_id: i32) ->
 
 fn try_dept_model_to_cedar_entity(
     dept: &departments::Model,
+    schema: &Schema,
 ) -> Result<Entity, AppError> {
     let dept_eid = EntityId::from_str(&dept.dept_uuid)?;
-    let dept_typename = EntityTypeName::from_str(ENTITY_TYPE_DEPARTMENT)?;
+    let dept_typename = entity_type_name(schema, ENTITY_TYPE_DEPARTMENT)?;
     let dept_e_uid = EntityUid::from_type_name_and_id(dept_typename, dept_eid);
 
     let mut attrs = HashMap::new();
@@ -611,6 +660,7 @@ pub async fn children_dept(
 // 组装用户信息(用户信息、角色信息、部门信息)
 pub async fn assemble_user_info(
     db: &DatabaseConnection,
+    storage: &StorageService,
     users_with_dept: Vec<(users::Model, Option<departments::Model>)>,
 ) -> Result<Vec<UserResponse>, AppError> {
     // 2. 收集所有用户ID
@@ -641,7 +691,7 @@ pub async fn assemble_user_info(
     }
 
     // 5. 构建最终结果
-    let result = users_with_dept
+    let mut result: Vec<UserResponse> = users_with_dept
         .into_iter()
         .map(|(user, dept)| UserResponse {
             uuid: user.user_uuid,
@@ -662,5 +712,8 @@ pub async fn assemble_user_info(
             last_login: user.last_login,
         })
         .collect();
+    for user in &mut result {
+        user.avatar = storage.avatar_url(&user.uuid, user.avatar.take()).await?;
+    }
     Ok(result)
 }
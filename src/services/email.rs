@@ -1,81 +1,516 @@
-use core::str::FromStr;
-use anyhow::Result;
-use lettre::{message::Mailbox, Address, message::header::ContentType, transport::smtp::authentication::Credentials, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor, SmtpTransport, Transport};
-use tracing::{info, instrument};
+// 邮件服务: 邮件先写入发件箱, 由后台任务投递, 失败后按指数退避重试
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use chrono::{Duration, Utc};
+use lettre::{message::Mailbox, message::header::ContentType, transport::smtp::authentication::Credentials, AsyncFileTransport, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor, SmtpTransport, Transport};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+use tokio::sync::Notify;
+use tracing::{error, info, instrument, warn};
+use crate::bad_request;
+use crate::config::email::{EmailConfig, MailTransport};
 use crate::config::smtp::SmtpConfig;
+use crate::entity::email_outbox;
 use crate::errors::app_error::AppError;
+use crate::utils::crypto::{decrypt_data, encrypt_data};
+use crate::utils::templates::EmailTemplate;
+
+// 发件箱中邮件的状态
+pub const EMAIL_PENDING: &str = "pending";
+pub const EMAIL_SENDING: &str = "sending";
+pub const EMAIL_SENT: &str = "sent";
+pub const EMAIL_DEAD: &str = "dead";
+
+// 正文加密的用途标识
+const EMAIL_BODY_PURPOSE: &str = "email-outbox";
 
 enum Mailer {
     Secure(AsyncSmtpTransport<Tokio1Executor>),
     Insecure(SmtpTransport),
+    File(AsyncFileTransport<Tokio1Executor>),
 }
 
 pub struct EmailService {
     mailer: Mailer,
+    sender: Mailbox,
+    db: DatabaseConnection,
+    config: EmailConfig,
+    // 有新邮件入队时唤醒后台任务
+    notify: Arc<Notify>,
 }
 
 impl EmailService {
 
-    pub fn new(config: &SmtpConfig) -> Self {
-        let mailer = match config.tls {
+    pub fn new(smtp: &SmtpConfig, config: &EmailConfig, db: DatabaseConnection) -> Result<Self, AppError> {
+        let credentials = match (&smtp.username, &smtp.password) {
+            (Some(username), Some(password)) if !username.is_empty() => {
+                Some(Credentials::new(username.clone(), password.clone()))
+            }
+            _ => None,
+        };
+
+        let mailer = match (&config.transport, smtp.tls) {
+            (MailTransport::File, _) => {
+                std::fs::create_dir_all(&config.file_dir)
+                    .map_err(|e| AppError::internal_server_error(anyhow::anyhow!(e)))?;
+                info!("邮件将写入本地目录: {}", config.file_dir);
+                Mailer::File(AsyncFileTransport::new(&config.file_dir))
+            }
             // --- 处理加密连接 (异步) ---
-            true => {
-                let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
-                    .expect("Failed to create STARTTLS relay");
-                builder = builder.port(config.port);
-                if let (Some(username), Some(password)) = (&config.username, &config.password) {
-                    if !username.is_empty() {
-                        let creds = Credentials::new(username.clone(), password.clone());
-                        builder = builder.credentials(creds);
-                    }
+            (MailTransport::Smtp, true) => {
+                let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&smtp.host)
+                    .map_err(anyhow::Error::from)?
+                    .port(smtp.port);
+                if let Some(creds) = credentials {
+                    builder = builder.credentials(creds);
                 }
                 Mailer::Secure(builder.build())
             }
             // --- 处理不加密连接 (同步) ---
-            false => {
-                let mut builder = SmtpTransport::builder_dangerous(&config.host);
-                builder = builder.port(config.port);
-                if let (Some(username), Some(password)) = (&config.username, &config.password) {
-                    if !username.is_empty() {
-                        let creds = Credentials::new(username.clone(), password.clone());
-                        builder = builder.credentials(creds);
-                    }
+            (MailTransport::Smtp, false) => {
+                let mut builder = SmtpTransport::builder_dangerous(&smtp.host).port(smtp.port);
+                if let Some(creds) = credentials {
+                    builder = builder.credentials(creds);
                 }
                 Mailer::Insecure(builder.build())
             }
         };
 
-        Self { mailer }
+        let sender = Mailbox::new(
+            Some(config.from_name.clone()),
+            config.from_address.parse().map_err(anyhow::Error::from)?,
+        );
+
+        Ok(Self {
+            mailer,
+            sender,
+            db,
+            config: config.clone(),
+            notify: Arc::new(Notify::new()),
+        })
+    }
+
+    /// 渲染模板并写入发件箱, 实际发送由后台任务完成
+    /// 正文中含有重置令牌、邀请码等一次性凭据, 加密后入库
+    pub async fn enqueue(&self, to: &str, template: EmailTemplate<'_>, language: &str) -> Result<(), AppError> {
+        to.parse::<Mailbox>()
+            .map_err(|_| bad_request!("Invalid email address: {}", to))?;
+        let (subject, body) = template.render(language)?;
+        let body = encrypt_data(EMAIL_BODY_PURPOSE, &body)?;
+
+        let now = Utc::now();
+        email_outbox::ActiveModel {
+            email_uuid: Set(uuid::Uuid::new_v4().to_string()),
+            recipient: Set(to.to_string()),
+            subject: Set(subject),
+            body: Set(body),
+            template: Set(template.name().to_string()),
+            language: Set(language.to_uppercase()),
+            status: Set(EMAIL_PENDING.to_string()),
+            attempts: Set(0),
+            next_attempt_at: Set(now),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&self.db)
+        .await?;
+
+        self.notify.notify_one();
+        Ok(())
+    }
+
+    /// 发件箱后台任务, 随服务启动一直运行
+    pub async fn run_outbox_worker(&self) {
+        let poll_interval = StdDuration::from_secs(self.config.outbox.poll_interval_seconds);
+        loop {
+            match self.process_outbox().await {
+                // 取满一批说明可能还有积压, 立即继续
+                Ok(count) if count as u64 >= self.config.outbox.batch_size => continue,
+                Ok(_) => {}
+                Err(e) => error!("处理邮件发件箱失败: {:?}", e),
+            }
+            tokio::select! {
+                _ = self.notify.notified() => {}
+                _ = tokio::time::sleep(poll_interval) => {}
+            }
+        }
+    }
+
+    /// 取出一批到期的邮件并发送, 返回取出的数量
+    async fn process_outbox(&self) -> Result<usize, AppError> {
+        self.reclaim_expired_leases().await?;
+        let due = email_outbox::Entity::find()
+            .filter(email_outbox::Column::Status.eq(EMAIL_PENDING))
+            .filter(email_outbox::Column::NextAttemptAt.lte(Utc::now()))
+            .order_by_asc(email_outbox::Column::Id)
+            .limit(self.config.outbox.batch_size)
+            .all(&self.db)
+            .await?;
+        let count = due.len();
+
+        for email in due {
+            // 以状态作为条件抢占, 多实例部署时同一封邮件只会被发送一次
+            let claimed = email_outbox::Entity::update_many()
+                .col_expr(email_outbox::Column::Status, Expr::value(EMAIL_SENDING))
+                .col_expr(email_outbox::Column::ClaimedAt, Expr::value(Utc::now()))
+                .filter(email_outbox::Column::Id.eq(email.id))
+                .filter(email_outbox::Column::Status.eq(EMAIL_PENDING))
+                .exec(&self.db)
+                .await?;
+            if claimed.rows_affected == 0 {
+                continue;
+            }
+
+            let result = match decrypt_data(EMAIL_BODY_PURPOSE, &email.body) {
+                Ok(body) => self.deliver(&email.recipient, &email.subject, &body).await,
+                Err(e) => Err(e),
+            };
+            let attempts = email.attempts + 1;
+            let email_uuid = email.email_uuid.clone();
+            let mut email: email_outbox::ActiveModel = email.into();
+            email.attempts = Set(attempts);
+            email.claimed_at = Set(None);
+            email.updated_at = Set(Utc::now());
+            match result {
+                // 发送成功或进入死信后正文不再需要, 清空以免留存其中的凭据
+                Ok(()) => {
+                    email.status = Set(EMAIL_SENT.to_string());
+                    email.sent_at = Set(Some(Utc::now()));
+                    email.last_error = Set(None);
+                    email.body = Set(String::new());
+                }
+                Err(e) if attempts >= self.config.outbox.max_attempts => {
+                    warn!(email_uuid = %email_uuid, "邮件重试 {} 次后仍发送失败, 已移入死信: {}", attempts, e);
+                    email.status = Set(EMAIL_DEAD.to_string());
+                    email.last_error = Set(Some(e.to_string()));
+                    email.body = Set(String::new());
+                }
+                Err(e) => {
+                    warn!(email_uuid = %email_uuid, "邮件第 {} 次发送失败: {}", attempts, e);
+                    email.status = Set(EMAIL_PENDING.to_string());
+                    email.last_error = Set(Some(e.to_string()));
+                    email.next_attempt_at = Set(Utc::now() + self.retry_delay(attempts));
+                }
+            }
+            email.update(&self.db).await?;
+        }
+        Ok(count)
+    }
+
+    // 发送中的邮件只有租约过期(发送的实例已崩溃或卡住)后才重新排队, 不影响其他实例正在发送的邮件
+    async fn reclaim_expired_leases(&self) -> Result<(), AppError> {
+        let expired_before = Utc::now() - Duration::seconds(self.config.outbox.lease_seconds);
+        let reclaimed = email_outbox::Entity::update_many()
+            .col_expr(email_outbox::Column::Status, Expr::value(EMAIL_PENDING))
+            .col_expr(email_outbox::Column::ClaimedAt, Expr::value(Option::<chrono::DateTime<Utc>>::None))
+            .filter(email_outbox::Column::Status.eq(EMAIL_SENDING))
+            .filter(
+                Condition::any()
+                    .add(email_outbox::Column::ClaimedAt.is_null())
+                    .add(email_outbox::Column::ClaimedAt.lte(expired_before)),
+            )
+            .exec(&self.db)
+            .await?;
+        if reclaimed.rows_affected > 0 {
+            warn!("{} 封邮件的发送租约已过期, 重新排队", reclaimed.rows_affected);
+        }
+        Ok(())
+    }
+
+    /// 第 N 次失败后的重试间隔
+    fn retry_delay(&self, attempts: i32) -> Duration {
+        let outbox = &self.config.outbox;
+        let exponent = (attempts - 1).clamp(0, 30) as u32;
+        let seconds = outbox
+            .retry_base_seconds
+            .saturating_mul(2i64.saturating_pow(exponent))
+            .min(outbox.retry_max_seconds);
+        Duration::seconds(seconds)
     }
 
     // 发送邮件的方法
     #[instrument(skip(self, to, subject, body))]
-    pub async fn send(&self, from:&str, to: &str, subject: &str, body: &str) -> Result<(), AppError> {
-        let address = Address::from_str(from).unwrap();
-        let mailbox = Mailbox::new(Some("AxumVueAdmin".to_string()), address);
+    async fn deliver(&self, to: &str, subject: &str, body: &str) -> Result<(), AppError> {
         let email = Message::builder()
-            .from(mailbox)
-            .to(to.parse().unwrap())
+            .from(self.sender.clone())
+            .to(to.parse().map_err(anyhow::Error::from)?)
             .subject(subject)
             .header(ContentType::TEXT_HTML)
             .body(String::from(body))
-            .unwrap();
+            .map_err(anyhow::Error::from)?;
 
         info!(recipient = %to, "正在发送邮件...");
 
-        return match &self.mailer {
+        match &self.mailer {
             Mailer::Secure(mailer) => {
                 mailer.send(email).await?;
-                info!("邮件发送成功");
-                Ok(())
             }
             Mailer::Insecure(mailer) => {
                 let mailer_clone = mailer.clone();
                 tokio::task::spawn_blocking(move || mailer_clone.send(&email))
                     .await??; // 第一个 ? 处理 JoinError, 第二个 ? 处理 SmtpError
-                info!("邮件发送成功");
-                Ok(())
             }
+            Mailer::File(mailer) => {
+                mailer.send(email).await.map_err(anyhow::Error::from)?;
+            }
+        }
+        info!("邮件发送成功");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::email::OutboxConfig;
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use sea_orm::{ConnectOptions, ConnectionTrait, Database, DbBackend, Schema};
+    use std::path::{Path, PathBuf};
+    use crate::utils::crypto::init_data_key;
+
+    const RECIPIENT: &str = "alice@example.com";
+
+    // 内存 SQLite 代替 MySQL, 只建发件箱一张表
+    async fn outbox_db() -> DatabaseConnection {
+        init_data_key(&"11".repeat(32)).unwrap();
+        let mut opt = ConnectOptions::new("sqlite::memory:");
+        opt.max_connections(1).sqlx_logging(false);
+        let db = Database::connect(opt).await.unwrap();
+        let table = Schema::new(DbBackend::Sqlite).create_table_from_entity(email_outbox::Entity);
+        db.execute(db.get_database_backend().build(&table)).await.unwrap();
+        db
+    }
+
+    fn mail_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("outbox-test-{}", uuid::Uuid::new_v4()))
+    }
+
+    async fn file_service(dir: &Path, max_attempts: i32) -> EmailService {
+        let config = EmailConfig {
+            transport: MailTransport::File,
+            file_dir: dir.to_string_lossy().to_string(),
+            outbox: OutboxConfig { max_attempts, ..OutboxConfig::default() },
+            ..EmailConfig::default()
         };
+        EmailService::new(&SmtpConfig::default(), &config, outbox_db().await).unwrap()
+    }
+
+    async fn only_email(service: &EmailService) -> email_outbox::Model {
+        let mut emails = email_outbox::Entity::find().all(&service.db).await.unwrap();
+        assert_eq!(emails.len(), 1);
+        emails.remove(0)
     }
-}
\ No newline at end of file
+
+    // 写入目录的邮件: (收件人, 主题, 正文)
+    fn sent_mails(dir: &Path) -> Vec<(String, String, String)> {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| parse_eml(&std::fs::read_to_string(entry.unwrap().path()).unwrap()))
+            .collect()
+    }
+
+    fn parse_eml(raw: &str) -> (String, String, String) {
+        let (head, body) = raw.split_once("\r\n\r\n").unwrap();
+        // 折行的头部先拼接回一行
+        let head = head.replace("\r\n ", " ").replace("\r\n\t", " ");
+        let header = |name: &str| {
+            head.lines()
+                .find_map(|line| line.strip_prefix(&format!("{}: ", name)))
+                .unwrap_or_default()
+                .to_string()
+        };
+        // RFC 2047: 相邻编码字之间的空白忽略
+        let mut subject = String::new();
+        let mut prev_encoded = false;
+        for (i, word) in header("Subject").split(' ').enumerate() {
+            match word.strip_prefix("=?utf-8?b?").and_then(|w| w.strip_suffix("?=")) {
+                Some(encoded) => {
+                    if i > 0 && !prev_encoded {
+                        subject.push(' ');
+                    }
+                    subject.push_str(&String::from_utf8(STANDARD.decode(encoded).unwrap()).unwrap());
+                    prev_encoded = true;
+                }
+                None => {
+                    if i > 0 {
+                        subject.push(' ');
+                    }
+                    subject.push_str(word);
+                    prev_encoded = false;
+                }
+            }
+        }
+        let body = match header("Content-Transfer-Encoding").as_str() {
+            "base64" => String::from_utf8(STANDARD.decode(body.replace("\r\n", "")).unwrap()).unwrap(),
+            "quoted-printable" => decode_quoted_printable(body),
+            _ => body.to_string(),
+        };
+        (header("To"), subject, body)
+    }
+
+    fn decode_quoted_printable(body: &str) -> String {
+        let joined = body.replace("=\r\n", "");
+        let mut bytes = Vec::new();
+        let mut rest = joined.as_bytes();
+        while let Some((&b, tail)) = rest.split_first() {
+            if b == b'=' && tail.len() >= 2 {
+                bytes.push(u8::from_str_radix(std::str::from_utf8(&tail[..2]).unwrap(), 16).unwrap());
+                rest = &tail[2..];
+            } else {
+                bytes.push(b);
+                rest = tail;
+            }
+        }
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn delivers_english_mail_through_file_transport() {
+        let dir = mail_dir();
+        let service = file_service(&dir, 5).await;
+        let reset_url = "https://admin.example.com/reset-password?token=abc";
+        service.enqueue(RECIPIENT, EmailTemplate::PasswordReset { reset_url }, "en").await.unwrap();
+
+        // 入库的正文是密文
+        let queued = only_email(&service).await;
+        assert_eq!(queued.status, EMAIL_PENDING);
+        assert!(!queued.body.contains(reset_url));
+
+        assert_eq!(service.process_outbox().await.unwrap(), 1);
+
+        let mails = sent_mails(&dir);
+        assert_eq!(mails.len(), 1);
+        let (to, subject, body) = &mails[0];
+        assert_eq!(to, RECIPIENT);
+        assert_eq!(subject, "[Axum Vue Admin] Password Reset Request");
+        assert!(body.contains(reset_url));
+
+        let sent = only_email(&service).await;
+        assert_eq!(sent.status, EMAIL_SENT);
+        assert_eq!(sent.attempts, 1);
+        assert!(sent.sent_at.is_some());
+        assert!(sent.body.is_empty());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn renders_chinese_template() {
+        let dir = mail_dir();
+        let service = file_service(&dir, 5).await;
+        let template = EmailTemplate::Invitation {
+            inviter: "张三",
+            accept_url: "https://admin.example.com/accept-invitation?code=xyz",
+            expires_at: "2026-10-25 12:00",
+        };
+        service.enqueue(RECIPIENT, template, "cn").await.unwrap();
+        assert_eq!(only_email(&service).await.language, "CN");
+
+        service.process_outbox().await.unwrap();
+
+        let mails = sent_mails(&dir);
+        let (to, subject, body) = &mails[0];
+        assert_eq!(to, RECIPIENT);
+        assert_eq!(subject, "[Axum Vue Admin] 邀请加入");
+        assert!(body.contains("张三"));
+        assert!(body.contains("https://admin.example.com/accept-invitation?code=xyz"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_recipient() {
+        let dir = mail_dir();
+        let service = file_service(&dir, 5).await;
+        let template = EmailTemplate::PasswordReset { reset_url: "https://admin.example.com" };
+        assert!(service.enqueue("not-an-address", template, "en").await.is_err());
+        assert!(email_outbox::Entity::find().all(&service.db).await.unwrap().is_empty());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn failed_delivery_is_retried_then_moved_to_dead() {
+        let dir = mail_dir();
+        let service = file_service(&dir, 2).await;
+        service
+            .enqueue(RECIPIENT, EmailTemplate::PasswordReset { reset_url: "https://admin.example.com" }, "en")
+            .await
+            .unwrap();
+        // 删除写入目录, 让文件投递失败
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let before = Utc::now();
+        service.process_outbox().await.unwrap();
+        let retrying = only_email(&service).await;
+        assert_eq!(retrying.status, EMAIL_PENDING);
+        assert_eq!(retrying.attempts, 1);
+        assert!(retrying.last_error.is_some());
+        assert!(!retrying.body.is_empty());
+        assert!(retrying.claimed_at.is_none());
+        assert!(retrying.next_attempt_at >= before + Duration::seconds(30));
+
+        // 未到重试时间不会再次发送
+        service.process_outbox().await.unwrap();
+        assert_eq!(only_email(&service).await.attempts, 1);
+
+        let mut due: email_outbox::ActiveModel = retrying.into();
+        due.next_attempt_at = Set(Utc::now() - Duration::seconds(1));
+        due.update(&service.db).await.unwrap();
+        service.process_outbox().await.unwrap();
+
+        let dead = only_email(&service).await;
+        assert_eq!(dead.status, EMAIL_DEAD);
+        assert_eq!(dead.attempts, 2);
+        assert!(dead.last_error.is_some());
+        assert!(dead.body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn reclaims_only_expired_leases() {
+        let dir = mail_dir();
+        let service = file_service(&dir, 5).await;
+        for recipient in ["stale@example.com", "active@example.com"] {
+            service
+                .enqueue(recipient, EmailTemplate::PasswordReset { reset_url: "https://admin.example.com" }, "en")
+                .await
+                .unwrap();
+        }
+        // 模拟两封正在发送的邮件: 一封租约已过期(发送的实例崩溃), 一封仍在其他实例发送中
+        let lease = Duration::seconds(service.config.outbox.lease_seconds);
+        for (recipient, claimed_at) in [
+            ("stale@example.com", Utc::now() - lease - Duration::seconds(1)),
+            ("active@example.com", Utc::now()),
+        ] {
+            email_outbox::Entity::update_many()
+                .col_expr(email_outbox::Column::Status, Expr::value(EMAIL_SENDING))
+                .col_expr(email_outbox::Column::ClaimedAt, Expr::value(claimed_at))
+                .filter(email_outbox::Column::Recipient.eq(recipient))
+                .exec(&service.db)
+                .await
+                .unwrap();
+        }
+
+        service.process_outbox().await.unwrap();
+
+        let mails = sent_mails(&dir);
+        assert_eq!(mails.len(), 1);
+        assert_eq!(mails[0].0, "stale@example.com");
+        let active = email_outbox::Entity::find()
+            .filter(email_outbox::Column::Recipient.eq("active@example.com"))
+            .one(&service.db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(active.status, EMAIL_SENDING);
+        assert_eq!(active.attempts, 0);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn retry_delay_doubles_up_to_the_maximum() {
+        let dir = mail_dir();
+        let service = file_service(&dir, 5).await;
+        let delays: Vec<i64> = (1..=9).map(|attempts| service.retry_delay(attempts).num_seconds()).collect();
+        assert_eq!(delays, vec![30, 60, 120, 240, 480, 960, 1920, 3600, 3600]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
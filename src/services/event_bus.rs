@@ -0,0 +1,339 @@
+// 领域事件总线
+// 业务操作在事务提交后发布事件: 发布节点立即在本地处理(刷新缓存、重新加载策略),
+// 再通过 Redis 广播给其他节点清理本地缓存; 审计日志、SSE 通知、Webhook 等只在发布节点执行一次
+use chrono::Utc;
+use futures_util::StreamExt as _;
+use redis::AsyncCommands;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect};
+use std::collections::HashSet;
+use tokio::time::{sleep, Duration};
+use tracing::{debug, error, info, warn};
+
+use crate::config::app::DOMAIN_EVENTS_CHANNEL;
+use crate::config::state::AppState;
+use crate::entity::{departments, roles, user_groups, users};
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::event::{DomainEvent, EventEnvelope};
+use crate::schemas::tenant::DEFAULT_TENANT_ID;
+use crate::services::audit_log::AuditLogService;
+use crate::services::department::find_parents_dept_id;
+use crate::services::groups::get_group_member_ids;
+use crate::services::role::get_role_user_uuids;
+use crate::services::user::{get_user_entities, get_user_tenant_id};
+use crate::services::webhook::enqueue_webhook_deliveries;
+use crate::utils::cedar_utils::USER_ENTITIES_CACHE_PREFIX;
+use crate::utils::function::reload_policies_and_schema;
+use crate::utils::sse::{sse_push_message, SSE_EVENT_PERMISSIONS_CHANGED};
+
+const AUDIT_MODULE: &str = "event";
+const MAX_RETRY_ATTEMPTS: u32 = 3;
+const CONNECTION_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// 发布领域事件, 须在数据库事务提交之后调用
+/// 事件处理失败只记录日志, 不影响已经完成的业务操作
+pub async fn publish_event(app_state: &AppState, actor: Option<&CurrentUser>, event: DomainEvent) {
+    let affected_users = match affected_users(&app_state.db, &event).await {
+        Ok(users) => users,
+        Err(e) => {
+            error!("解析事件 {} 影响的用户失败: {}", event.name(), e);
+            Vec::new()
+        }
+    };
+    let tenant_id = match event_tenant_id(&app_state.db, &event).await {
+        Ok(Some(tenant_id)) => tenant_id,
+        Ok(None) => actor.map_or(DEFAULT_TENANT_ID, |a| a.tenant_id),
+        Err(e) => {
+            error!("解析事件 {} 所属租户失败: {}", event.name(), e);
+            actor.map_or(DEFAULT_TENANT_ID, |a| a.tenant_id)
+        }
+    };
+    let envelope = EventEnvelope {
+        id: uuid::Uuid::new_v4().to_string(),
+        node_id: app_state.node_id.clone(),
+        actor_uuid: actor.map(|a| a.uuid.clone()),
+        actor_name: actor.map(|a| a.username.clone()),
+        occurred_at: Utc::now(),
+        tenant_id,
+        affected_users,
+        event,
+    };
+    debug!("发布领域事件 {} [{}]", envelope.event.name(), envelope.id);
+
+    // 本节点同步处理, 保证请求返回后立即生效
+    apply_event(app_state, &envelope, true).await;
+
+    if let Err(e) = broadcast(app_state, &envelope).await {
+        error!("广播领域事件 {} 失败: {}", envelope.id, e);
+    }
+
+    let state = app_state.clone();
+    tokio::spawn(async move {
+        dispatch_subscribers(&state, &envelope).await;
+    });
+}
+
+async fn broadcast(app_state: &AppState, envelope: &EventEnvelope) -> Result<(), AppError> {
+    let mut redis_conn = app_state.redis.get_multiplexed_async_connection().await?;
+    let _: i64 = redis_conn
+        .publish(DOMAIN_EVENTS_CHANNEL, serde_json::to_string(envelope)?)
+        .await?;
+    Ok(())
+}
+
+/// 每个节点都要执行的处理: 策略重新加载和用户实体缓存
+/// 发布节点重建共享缓存, 其他节点只需清理本地缓存, 下次访问时从 Redis 读取
+async fn apply_event(app_state: &AppState, envelope: &EventEnvelope, origin: bool) {
+    if matches!(
+        envelope.event,
+        DomainEvent::PolicyChanged { .. } | DomainEvent::SchemaChanged
+    ) && let Err(e) = reload_policies_and_schema(app_state).await
+    {
+        error!("重新加载策略失败: {}", e);
+    }
+
+    for user_uuid in &envelope.affected_users {
+        let cache_key = format!("{}:{}", USER_ENTITIES_CACHE_PREFIX, user_uuid);
+        let result = match (&envelope.event, origin) {
+            // 禁用的用户不再保留实体缓存, 后续的权限检查都会被拒绝
+            (DomainEvent::UserDisabled { .. }, true) => {
+                app_state.cache_service.invalidate_user_entities(cache_key).await
+            }
+            (_, true) => refresh_user_entities(app_state, user_uuid, cache_key).await,
+            (_, false) => {
+                app_state.cache_service.evict_local(&cache_key).await;
+                Ok(())
+            }
+        };
+        if let Err(e) = result {
+            warn!("刷新用户 {} 的实体缓存失败: {}", user_uuid, e);
+        }
+    }
+}
+
+// 只刷新已缓存的用户, 未登录的用户在登录时会重新生成
+async fn refresh_user_entities(app_state: &AppState, user_uuid: &str, cache_key: String) -> Result<(), AppError> {
+    if app_state.cache_service.get_cache(&cache_key).await?.is_none() {
+        return Ok(());
+    }
+    let tenant_id = get_user_tenant_id(&app_state.db, user_uuid).await?;
+    let schema = app_state.auth_service.get_schema_copy(tenant_id).await;
+    let entities = get_user_entities(&app_state.db, tenant_id, user_uuid.to_string(), &schema).await?;
+    app_state.cache_service.cache_entities(cache_key, entities).await
+}
+
+/// 只在发布节点执行一次的订阅者
+async fn dispatch_subscribers(app_state: &AppState, envelope: &EventEnvelope) {
+    let audit = AuditLogService::new(app_state.clone())
+        .log_event(
+            envelope.actor_uuid.clone().unwrap_or_else(|| "0".to_string()),
+            envelope.actor_name.clone().unwrap_or_default(),
+            AUDIT_MODULE.to_string(),
+            envelope.event.summary(),
+        )
+        .await;
+    if let Err(e) = audit {
+        error!("记录领域事件 {} 的审计日志失败: {}", envelope.id, e);
+    }
+
+    if let Err(e) = enqueue_webhook_deliveries(app_state, envelope).await {
+        error!("为领域事件 {} 创建 Webhook 投递失败: {}", envelope.id, e);
+    }
+
+    // 通知在线的用户权限已变化, 前端据此刷新菜单和按钮
+    if !matches!(envelope.event, DomainEvent::UserDisabled { .. }) {
+        for user_uuid in &envelope.affected_users {
+            if let Err(e) = sse_push_message(
+                app_state,
+                user_uuid.clone(),
+                SSE_EVENT_PERMISSIONS_CHANGED,
+                &envelope.event,
+            )
+            .await
+            {
+                warn!("向用户 {} 推送权限变更失败: {}", user_uuid, e);
+            }
+        }
+    }
+}
+
+/// 事件主体所属的租户; 主体已删除或事件没有主体时返回 None, 由调用方使用操作人的租户
+/// 后台任务和 SCIM、LDAP、单点登录等系统操作人的租户不一定是事件所属租户, 因此优先按主体确定
+async fn event_tenant_id(db: &DatabaseConnection, event: &DomainEvent) -> Result<Option<i32>, AppError> {
+    let tenant_id = match event {
+        DomainEvent::UserCreated { user_uuid, .. }
+        | DomainEvent::UserUpdated { user_uuid }
+        | DomainEvent::UserDisabled { user_uuid }
+        | DomainEvent::UserDepartmentChanged { user_uuid, .. }
+        | DomainEvent::RoleAssigned { user_uuid, .. }
+        | DomainEvent::RoleRevoked { user_uuid, .. } => users::Entity::find()
+            .select_only()
+            .column(users::Column::TenantId)
+            .filter(users::Column::UserUuid.eq(user_uuid))
+            .into_tuple::<i32>()
+            .one(db)
+            .await?,
+        DomainEvent::RoleUpdated { role_uuid } | DomainEvent::RoleDeleted { role_uuid, .. } => roles::Entity::find()
+            .select_only()
+            .column(roles::Column::TenantId)
+            .filter(roles::Column::RoleUuid.eq(role_uuid))
+            .into_tuple::<i32>()
+            .one(db)
+            .await?,
+        DomainEvent::GroupMembersChanged { group_uuid, .. }
+        | DomainEvent::GroupRoleAssigned { group_uuid, .. }
+        | DomainEvent::GroupRoleRevoked { group_uuid, .. }
+        | DomainEvent::GroupUpdated { group_uuid }
+        | DomainEvent::GroupDeleted { group_uuid } => user_groups::Entity::find()
+            .select_only()
+            .column(user_groups::Column::TenantId)
+            .filter(user_groups::Column::UserGroupUuid.eq(group_uuid))
+            .into_tuple::<i32>()
+            .one(db)
+            .await?,
+        DomainEvent::DepartmentCreated { dept_uuid }
+        | DomainEvent::DepartmentUpdated { dept_uuid }
+        | DomainEvent::DepartmentMoved { dept_uuid, .. }
+        | DomainEvent::DepartmentDeleted { dept_uuid } => departments::Entity::find()
+            .select_only()
+            .column(departments::Column::TenantId)
+            .filter(departments::Column::DeptUuid.eq(dept_uuid))
+            .into_tuple::<i32>()
+            .one(db)
+            .await?,
+        DomainEvent::PolicyChanged { .. } | DomainEvent::SchemaChanged => None,
+    };
+    Ok(tenant_id)
+}
+
+/// 授权信息(用户实体)受事件影响的用户
+async fn affected_users(db: &DatabaseConnection, event: &DomainEvent) -> Result<Vec<String>, AppError> {
+    let users = match event {
+        DomainEvent::UserUpdated { user_uuid }
+        | DomainEvent::UserDisabled { user_uuid }
+        | DomainEvent::UserDepartmentChanged { user_uuid, .. }
+        | DomainEvent::RoleAssigned { user_uuid, .. }
+        | DomainEvent::RoleRevoked { user_uuid, .. } => vec![user_uuid.clone()],
+        DomainEvent::RoleUpdated { role_uuid } => get_role_user_uuids(db, role_uuid).await?,
+        DomainEvent::RoleDeleted { user_uuids, .. }
+        | DomainEvent::GroupMembersChanged { user_uuids, .. } => user_uuids.clone(),
+        DomainEvent::GroupRoleAssigned { group_uuid, .. }
+        | DomainEvent::GroupRoleRevoked { group_uuid, .. }
+        | DomainEvent::GroupUpdated { group_uuid } => {
+            let user_ids = get_group_member_ids(db, group_uuid).await?;
+            users::Entity::find()
+                .select_only()
+                .column(users::Column::UserUuid)
+                .filter(users::Column::UserId.is_in(user_ids))
+                .into_tuple::<String>()
+                .all(db)
+                .await?
+        }
+        // 用户实体包含所在部门及其所有子部门, 部门变化影响其所有上级部门的用户
+        DomainEvent::DepartmentCreated { dept_uuid }
+        | DomainEvent::DepartmentUpdated { dept_uuid }
+        | DomainEvent::DepartmentDeleted { dept_uuid } => {
+            users_in_dept_path(db, &[dept_uuid.as_str()]).await?
+        }
+        DomainEvent::DepartmentMoved { dept_uuid, from_parent_uuid, .. } => {
+            users_in_dept_path(db, &[dept_uuid.as_str(), from_parent_uuid.as_str()]).await?
+        }
+        DomainEvent::UserCreated { .. }
+        | DomainEvent::GroupDeleted { .. }
+        | DomainEvent::PolicyChanged { .. }
+        | DomainEvent::SchemaChanged => Vec::new(),
+    };
+    Ok(users)
+}
+
+// 所在部门为指定部门或其上级部门的用户
+async fn users_in_dept_path(db: &DatabaseConnection, dept_uuids: &[&str]) -> Result<Vec<String>, AppError> {
+    let depts = departments::Entity::find()
+        .select_only()
+        .columns([departments::Column::DeptId, departments::Column::ParentId])
+        .filter(departments::Column::DeptUuid.is_in(dept_uuids.iter().copied()))
+        .into_tuple::<(i32, i32)>()
+        .all(db)
+        .await?;
+
+    let mut dept_ids = HashSet::new();
+    for (dept_id, parent_id) in depts {
+        dept_ids.insert(dept_id);
+        // 已删除的部门不在部门树中, 从父部门开始向上查找
+        dept_ids.insert(parent_id);
+        dept_ids.extend(find_parents_dept_id(db, parent_id).await?);
+    }
+    if dept_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let user_uuids = users::Entity::find()
+        .select_only()
+        .column(users::Column::UserUuid)
+        .filter(users::Column::DeptId.is_in(dept_ids))
+        .into_tuple::<String>()
+        .all(db)
+        .await?;
+    Ok(user_uuids)
+}
+
+// 后台任务：接收其他节点发布的领域事件
+pub async fn subscribe_to_domain_events(state: AppState) {
+    let mut consecutive_failures = 0;
+
+    loop {
+        info!("尝试订阅Redis频道 '{}'...", DOMAIN_EVENTS_CHANNEL);
+
+        match establish_subscription(&state).await {
+            Ok(()) => {
+                consecutive_failures = 0;
+                info!("Redis订阅会话结束，准备重新连接...");
+            }
+            Err(e) => {
+                consecutive_failures += 1;
+                error!(
+                    "Redis订阅失败 (尝试 {}/{}): {}",
+                    consecutive_failures, MAX_RETRY_ATTEMPTS, e
+                );
+
+                if consecutive_failures >= MAX_RETRY_ATTEMPTS {
+                    error!("连续失败次数过多，延长重试间隔");
+                    sleep(CONNECTION_RETRY_DELAY * consecutive_failures).await;
+                } else {
+                    sleep(CONNECTION_RETRY_DELAY).await;
+                }
+            }
+        }
+    }
+}
+
+async fn establish_subscription(state: &AppState) -> Result<(), AppError> {
+    let pub_sub = state.redis.get_async_pubsub().await?;
+    let (mut sink, mut stream) = pub_sub.split();
+    sink.subscribe(DOMAIN_EVENTS_CHANNEL).await?;
+
+    info!("成功订阅Redis频道，等待领域事件...");
+
+    while let Some(msg) = stream.next().await {
+        let envelope = match msg
+            .get_payload::<String>()
+            .map_err(AppError::from)
+            .and_then(|p| serde_json::from_str::<EventEnvelope>(&p).map_err(AppError::from))
+        {
+            Ok(envelope) => envelope,
+            Err(e) => {
+                warn!("无法解析领域事件: {}，跳过。", e);
+                continue;
+            }
+        };
+
+        if envelope.node_id == state.node_id {
+            continue;
+        }
+        debug!("收到领域事件 {} [{}]", envelope.event.name(), envelope.id);
+        apply_event(state, &envelope, false).await;
+    }
+
+    warn!("Redis消息流结束");
+    Ok(())
+}
@@ -0,0 +1,65 @@
+// 文件上传与下载
+use bytes::Bytes;
+use image::ImageFormat;
+use crate::config::state::AppState;
+use crate::errors::app_error::AppError;
+use crate::forbidden;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::file::{DownloadQuery, FileResponse};
+use crate::services::storage::resize_square;
+
+#[derive(Clone)]
+pub struct FileService {
+    app_state: AppState,
+}
+
+impl FileService {
+    pub fn new(app_state: AppState) -> Self {
+        Self { app_state }
+    }
+
+    /// 上传图片, 同时生成缩略图
+    pub async fn upload(
+        &self,
+        current_user: CurrentUser,
+        content_type: Option<String>,
+        data: Bytes,
+    ) -> Result<FileResponse, AppError> {
+        let storage = &self.app_state.storage_service;
+        let format = storage.validate_image(content_type.as_deref(), &data)?;
+        let extension = format.extensions_str().first().copied().unwrap_or("bin");
+
+        let file_id = uuid::Uuid::new_v4();
+        let key = format!("uploads/{}/{}.{}", current_user.uuid, file_id, extension);
+        let thumbnail_key = format!("uploads/{}/{}_thumb.png", current_user.uuid, file_id);
+
+        let thumbnail = resize_square(data.clone(), storage.thumbnail_size()).await?;
+        storage.put(&key, data).await?;
+        storage.put(&thumbnail_key, thumbnail).await?;
+
+        Ok(FileResponse {
+            url: storage.signed_url(&key).await?,
+            thumbnail_url: storage.signed_url(&thumbnail_key).await?,
+            key,
+            thumbnail_key,
+        })
+    }
+
+    /// 通过签名链接下载本地存储的文件
+    pub async fn download(
+        &self,
+        key: String,
+        query: DownloadQuery,
+    ) -> Result<(&'static str, Bytes), AppError> {
+        let storage = &self.app_state.storage_service;
+        if !storage.verify_signature(&key, query.expires, &query.signature) {
+            return Err(forbidden!("Invalid or expired signature"));
+        }
+
+        let content_type = ImageFormat::from_path(&key)
+            .map(|format| format.to_mime_type())
+            .unwrap_or("application/octet-stream");
+        let data = storage.get(&key).await?;
+        Ok((content_type, data))
+    }
+}
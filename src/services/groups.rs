@@ -8,14 +8,17 @@ use crate::schemas::groups::AssignRolesDto;
 use crate::schemas::groups::{
     AssignUsersDto, CreateGroupDto, GroupResponse, GroupRoleResponse, QueryParams,
 };
-use crate::services::role::get_role_entities;
+use crate::schemas::event::DomainEvent;
+use crate::services::event_bus::publish_event;
+use crate::services::role::{get_role_entities, validate_assignment_period};
 use crate::utils::cedar_utils::{
-    AuthAction, ENTITY_ATTR_NAME, ENTITY_TYPE_GROUP, ResourceType, entities2json,
+    AuthAction, ENTITY_ATTR_NAME, ENTITY_TYPE_GROUP, ResourceType, entities2json, entity_type_name,
 };
 use crate::{bad_request, conflict, not_found};
 use cedar_policy::{
-    Entities, Entity, EntityId, EntityTypeName, EntityUid, RestrictedExpression, Schema,
+    Entities, Entity, EntityId, EntityUid, RestrictedExpression, Schema,
 };
+use chrono::Utc;
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, JoinType,
     ModelTrait, PaginatorTrait, QueryFilter, QuerySelect, RelationTrait, Select, Set,
@@ -64,7 +67,8 @@ impl GroupService {
                     .collect()
             });
 
-        let mut query = user_groups::Entity::find();
+        let mut query = user_groups::Entity::find()
+            .filter(user_groups::Column::TenantId.eq(current_user.tenant_id));
         if let Some(name) = &params.name {
             query = query.filter(user_groups::Column::Name.contains(name));
         }
@@ -111,6 +115,7 @@ impl GroupService {
 
         if user_groups::Entity::find()
             .filter(user_groups::Column::Name.eq(&create_group_dto.name))
+            .filter(user_groups::Column::TenantId.eq(current_user.tenant_id))
             .one(&txn)
             .await?
             .is_some()
@@ -120,6 +125,7 @@ impl GroupService {
 
         let insert_model = user_groups::ActiveModel {
             user_group_uuid: Set(Uuid::new_v4().to_string()),
+            tenant_id: Set(current_user.tenant_id),
             name: Set(create_group_dto.name),
             description: Set(create_group_dto.description),
             ..Default::default()
@@ -137,8 +143,8 @@ impl GroupService {
         context: CedarContext,
         group_uuid: String,
     ) -> Result<GroupResponse, AppError> {
-        let schema = self.app_state.auth_service.get_schema_copy().await;
-        let es = get_group_entities(&self.app_state.db, &vec![group_uuid.clone()], &schema).await?;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
+        let es = get_group_entities(&self.app_state.db, current_user.tenant_id, &vec![group_uuid.clone()], &schema).await?;
         self.app_state
             .auth_service
             .check_permission_with_entities(
@@ -152,6 +158,7 @@ impl GroupService {
 
         let group = user_groups::Entity::find()
             .filter(user_groups::Column::UserGroupUuid.eq(group_uuid))
+            .filter(user_groups::Column::TenantId.eq(current_user.tenant_id))
             .one(&self.app_state.db)
             .await?
             .ok_or(not_found!("group not found".to_string()))?;
@@ -166,8 +173,8 @@ impl GroupService {
         group_uuid: String,
         update_group_dto: CreateGroupDto,
     ) -> Result<GroupResponse, AppError> {
-        let schema = self.app_state.auth_service.get_schema_copy().await;
-        let es = get_group_entities(&self.app_state.db, &vec![group_uuid.clone()], &schema).await?;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
+        let es = get_group_entities(&self.app_state.db, current_user.tenant_id, &vec![group_uuid.clone()], &schema).await?;
         self.app_state
             .auth_service
             .check_permission_with_entities(
@@ -180,7 +187,8 @@ impl GroupService {
             .await?;
 
         let mut group: user_groups::ActiveModel = user_groups::Entity::find()
-            .filter(user_groups::Column::UserGroupUuid.eq(group_uuid))
+            .filter(user_groups::Column::UserGroupUuid.eq(&group_uuid))
+            .filter(user_groups::Column::TenantId.eq(current_user.tenant_id))
             .one(&self.app_state.db)
             .await?
             .ok_or(not_found!("group not found".to_string()))?
@@ -191,6 +199,7 @@ impl GroupService {
 
         let group = group.update(&self.app_state.db).await?;
 
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::GroupUpdated { group_uuid }).await;
         Ok(GroupResponse::from(group))
     }
 
@@ -200,8 +209,8 @@ impl GroupService {
         context: CedarContext,
         group_uuid: String,
     ) -> Result<(), AppError> {
-        let schema = self.app_state.auth_service.get_schema_copy().await;
-        let es = get_group_entities(&self.app_state.db, &vec![group_uuid.clone()], &schema).await?;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
+        let es = get_group_entities(&self.app_state.db, current_user.tenant_id, &vec![group_uuid.clone()], &schema).await?;
         self.app_state
             .auth_service
             .check_permission_with_entities(
@@ -218,6 +227,8 @@ impl GroupService {
         let group_id = user_groups::Entity::find()
             .select_only()
             .column(user_groups::Column::UserGroupId)
+            .filter(user_groups::Column::UserGroupUuid.eq(&group_uuid))
+            .filter(user_groups::Column::TenantId.eq(current_user.tenant_id))
             .into_tuple::<i32>()
             .one(&txn)
             .await?
@@ -244,6 +255,8 @@ impl GroupService {
             .await?;
 
         txn.commit().await?;
+
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::GroupDeleted { group_uuid }).await;
         Ok(())
     }
 
@@ -254,9 +267,9 @@ impl GroupService {
         group_uuid: String,
         dto: AssignUsersDto,
     ) -> Result<(), AppError> {
-        let schema = self.app_state.auth_service.get_schema_copy().await;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
         let groups_es =
-            get_group_entities(&self.app_state.db, &vec![group_uuid.clone()], &schema).await?;
+            get_group_entities(&self.app_state.db, current_user.tenant_id, &vec![group_uuid.clone()], &schema).await?;
 
         self.app_state
             .auth_service
@@ -273,12 +286,25 @@ impl GroupService {
         let group_id = user_groups::Entity::find()
             .select_only()
             .column(user_groups::Column::UserGroupId)
-            .filter(user_groups::Column::UserGroupUuid.eq(group_uuid))
+            .filter(user_groups::Column::UserGroupUuid.eq(&group_uuid))
+            .filter(user_groups::Column::TenantId.eq(current_user.tenant_id))
             .into_tuple::<i32>()
             .one(&txn)
             .await?
             .ok_or(not_found!("group not found".to_string()))?;
 
+        // 原有成员和新成员的授权信息都会变化
+        let mut changed_users: HashSet<String> = users::Entity::find()
+            .select_only()
+            .column(users::Column::UserUuid)
+            .join(JoinType::InnerJoin, users::Relation::UserGroupMembers.def())
+            .filter(user_group_members::Column::GroupId.eq(group_id))
+            .into_tuple::<String>()
+            .all(&txn)
+            .await?
+            .into_iter()
+            .collect();
+
         user_group_members::Entity::delete_many()
             .filter(user_group_members::Column::GroupId.eq(group_id))
             .exec(&txn)
@@ -286,6 +312,10 @@ impl GroupService {
 
         if dto.user_uuids.is_empty() {
             txn.commit().await?;
+            publish_event(&self.app_state, Some(&current_user), DomainEvent::GroupMembersChanged {
+                group_uuid,
+                user_uuids: changed_users.into_iter().collect(),
+            }).await;
             return Ok(());
         }
 
@@ -293,6 +323,7 @@ impl GroupService {
             .select_only()
             .column(users::Column::UserId)
             .filter(users::Column::UserUuid.is_in(&dto.user_uuids))
+            .filter(users::Column::TenantId.eq(current_user.tenant_id))
             .into_tuple::<i32>()
             .all(&txn)
             .await?;
@@ -313,6 +344,11 @@ impl GroupService {
 
         txn.commit().await?;
 
+        changed_users.extend(dto.user_uuids);
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::GroupMembersChanged {
+            group_uuid,
+            user_uuids: changed_users.into_iter().collect(),
+        }).await;
         Ok(())
     }
 
@@ -323,9 +359,9 @@ impl GroupService {
         group_uuid: String,
         user_uuid: String,
     ) -> Result<(), AppError> {
-        let schema = self.app_state.auth_service.get_schema_copy().await;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
         let groups_es =
-            get_group_entities(&self.app_state.db, &vec![group_uuid.clone()], &schema).await?;
+            get_group_entities(&self.app_state.db, current_user.tenant_id, &vec![group_uuid.clone()], &schema).await?;
 
         self.app_state
             .auth_service
@@ -341,7 +377,8 @@ impl GroupService {
         let group_id = user_groups::Entity::find()
             .select_only()
             .column(user_groups::Column::UserGroupId)
-            .filter(user_groups::Column::UserGroupUuid.eq(group_uuid))
+            .filter(user_groups::Column::UserGroupUuid.eq(&group_uuid))
+            .filter(user_groups::Column::TenantId.eq(current_user.tenant_id))
             .into_tuple::<i32>()
             .one(&self.app_state.db)
             .await?
@@ -351,6 +388,7 @@ impl GroupService {
             .select_only()
             .column(users::Column::UserId)
             .filter(users::Column::UserUuid.eq(&user_uuid))
+            .filter(users::Column::TenantId.eq(current_user.tenant_id))
             .into_tuple::<i32>()
             .one(&self.app_state.db)
             .await?
@@ -365,6 +403,10 @@ impl GroupService {
             .exec(&self.app_state.db)
             .await?;
 
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::GroupMembersChanged {
+            group_uuid,
+            user_uuids: vec![user_uuid],
+        }).await;
         Ok(())
     }
 
@@ -390,9 +432,9 @@ impl GroupService {
         context: CedarContext,
         group_uuid: String,
     ) -> Result<Vec<GroupRoleResponse>, AppError> {
-        let schema = self.app_state.auth_service.get_schema_copy().await;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
         let groups_es =
-            get_group_entities(&self.app_state.db, &vec![group_uuid.clone()], &schema).await?;
+            get_group_entities(&self.app_state.db, current_user.tenant_id, &vec![group_uuid.clone()], &schema).await?;
 
         self.app_state
             .auth_service
@@ -409,6 +451,7 @@ impl GroupService {
             .select_only()
             .column(user_groups::Column::UserGroupId)
             .filter(user_groups::Column::UserGroupUuid.eq(&group_uuid))
+            .filter(user_groups::Column::TenantId.eq(current_user.tenant_id))
             .into_tuple::<i32>()
             .one(&self.app_state.db)
             .await?
@@ -434,11 +477,12 @@ impl GroupService {
         group_uuid: String,
         dto: AssignRolesDto,
     ) -> Result<(), AppError> {
-        let schema = self.app_state.auth_service.get_schema_copy().await;
+        let active = validate_assignment_period(dto.valid_from, dto.valid_until)?;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
         let role_es =
-            get_role_entities(&self.app_state.db, &vec![dto.role_uuid.clone()], &schema).await?;
+            get_role_entities(&self.app_state.db, current_user.tenant_id, &vec![dto.role_uuid.clone()], &schema).await?;
         let groups_es =
-            get_group_entities(&self.app_state.db, &vec![group_uuid.clone()], &schema).await?;
+            get_group_entities(&self.app_state.db, current_user.tenant_id, &vec![group_uuid.clone()], &schema).await?;
 
         self.app_state
             .auth_service
@@ -467,6 +511,7 @@ impl GroupService {
             .select_only()
             .column(user_groups::Column::UserGroupId)
             .filter(user_groups::Column::UserGroupUuid.eq(&group_uuid))
+            .filter(user_groups::Column::TenantId.eq(current_user.tenant_id))
             .into_tuple::<i32>()
             .one(&txn)
             .await?
@@ -476,6 +521,7 @@ impl GroupService {
             .select_only()
             .column(roles::Column::RoleId)
             .filter(roles::Column::RoleUuid.eq(&dto.role_uuid))
+            .filter(roles::Column::TenantId.eq(current_user.tenant_id))
             .into_tuple::<i32>()
             .one(&txn)
             .await?
@@ -486,16 +532,23 @@ impl GroupService {
             .exec(&txn)
             .await?;
 
+        // 未到生效时间的分配由后台任务在生效时激活
         group_roles::Entity::insert(group_roles::ActiveModel {
             group_id: Set(group_id),
             role_id: Set(role_id),
-            ..Default::default()
+            valid_from: Set(dto.valid_from),
+            valid_until: Set(dto.valid_until),
+            activated_at: Set(active.then(Utc::now)),
         })
         .exec(&txn)
         .await?;
 
         txn.commit().await?;
 
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::GroupRoleAssigned {
+            group_uuid,
+            role_uuid: dto.role_uuid,
+        }).await;
         Ok(())
     }
 
@@ -506,11 +559,11 @@ impl GroupService {
         group_uuid: String,
         role_uuid: String,
     ) -> Result<(), AppError> {
-        let schema = self.app_state.auth_service.get_schema_copy().await;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
         let role_es =
-            get_role_entities(&self.app_state.db, &vec![role_uuid.clone()], &schema).await?;
+            get_role_entities(&self.app_state.db, current_user.tenant_id, &vec![role_uuid.clone()], &schema).await?;
         let groups_es =
-            get_group_entities(&self.app_state.db, &vec![group_uuid.clone()], &schema).await?;
+            get_group_entities(&self.app_state.db, current_user.tenant_id, &vec![group_uuid.clone()], &schema).await?;
         self.app_state
             .auth_service
             .check_permission_with_entities(
@@ -537,6 +590,7 @@ impl GroupService {
             .select_only()
             .column(user_groups::Column::UserGroupId)
             .filter(user_groups::Column::UserGroupUuid.eq(&group_uuid))
+            .filter(user_groups::Column::TenantId.eq(current_user.tenant_id))
             .into_tuple::<i32>()
             .one(&self.app_state.db)
             .await?
@@ -546,6 +600,7 @@ impl GroupService {
             .select_only()
             .column(roles::Column::RoleId)
             .filter(roles::Column::RoleUuid.eq(&role_uuid))
+            .filter(roles::Column::TenantId.eq(current_user.tenant_id))
             .into_tuple::<i32>()
             .one(&self.app_state.db)
             .await?
@@ -560,6 +615,10 @@ impl GroupService {
             .exec(&self.app_state.db)
             .await?;
 
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::GroupRoleRevoked {
+            group_uuid,
+            role_uuid,
+        }).await;
         Ok(())
     }
 }
@@ -567,11 +626,13 @@ impl GroupService {
 // 获取用户组实体信息
 pub async fn get_group_entities(
     db: &DatabaseConnection,
+    tenant_id: i32,
     group_uuids: &[String],
     schema: &Schema,
 ) -> Result<Entities, AppError> {
     let groups = user_groups::Entity::find()
         .column(user_groups::Column::Name)
+        .filter(user_groups::Column::TenantId.eq(tenant_id))
         .filter(user_groups::Column::UserGroupUuid.is_in(group_uuids.to_vec()))
         .all(db)
         .await?;
@@ -579,7 +640,7 @@ pub async fn get_group_entities(
     let mut entities = HashSet::new();
     for group in groups {
         let group_eid = EntityId::from_str(&group.user_group_uuid.to_string())?;
-        let group_typename = EntityTypeName::from_str(ENTITY_TYPE_GROUP)?;
+        let group_typename = entity_type_name(schema, ENTITY_TYPE_GROUP)?;
         let group_e_uid = EntityUid::from_type_name_and_id(group_typename, group_eid);
 
         let mut attrs = HashMap::new();
@@ -596,3 +657,27 @@ pub async fn get_group_entities(
     debug!("Groups:{:?}; Entities Json: {}", group_uuids, entities_json);
     Ok(verified_entities)
 }
+
+// 获取用户组成员的用户ID
+pub async fn get_group_member_ids(
+    db: &DatabaseConnection,
+    group_uuid: &str,
+) -> Result<Vec<i32>, AppError> {
+    let group_id = user_groups::Entity::find()
+        .select_only()
+        .column(user_groups::Column::UserGroupId)
+        .filter(user_groups::Column::UserGroupUuid.eq(group_uuid))
+        .into_tuple::<i32>()
+        .one(db)
+        .await?
+        .ok_or(not_found!("Group not found"))?;
+
+    let user_ids = user_group_members::Entity::find()
+        .select_only()
+        .column(user_group_members::Column::UserId)
+        .filter(user_group_members::Column::GroupId.eq(group_id))
+        .into_tuple::<i32>()
+        .all(db)
+        .await?;
+    Ok(user_ids)
+}
@@ -0,0 +1,483 @@
+// 用户邀请
+use chrono::{Duration, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set, TransactionTrait,
+};
+use serde_json::json;
+use tracing::warn;
+use crate::config::auth::INVITATION_EXPIRATION;
+use crate::config::state::AppState;
+use crate::entity::{departments, roles, user_group_members, user_groups, user_invitations, user_roles, users};
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::event::DomainEvent;
+use crate::schemas::invitation::{
+    json_to_uuids, AcceptInvitationDto, CreateInvitationDto, InvitationPreview,
+    InvitationQueryParams, InvitationResponse, INVITATION_ACCEPTED, INVITATION_EXPIRED,
+    INVITATION_PENDING, INVITATION_REVOKED,
+};
+use crate::schemas::notification::{NewNotification, NOTIFICATION_CATEGORY_INVITATION, NOTIFICATION_INFO};
+use crate::services::department::get_dept_entities;
+use crate::services::event_bus::publish_event;
+use crate::services::groups::get_group_entities;
+use crate::services::password::{hash_new_password, record_password_history};
+use crate::services::notification::notify_user;
+use crate::services::role::get_role_entities;
+use crate::utils::cedar_utils::{AuthAction, ResourceType};
+use crate::utils::crypto::hash_token;
+use crate::utils::templates::EmailTemplate;
+use crate::{bad_request, conflict, not_found};
+
+#[derive(Clone)]
+pub struct InvitationService {
+    app_state: AppState,
+}
+
+impl InvitationService {
+    pub fn new(app_state: AppState) -> Self {
+        Self { app_state }
+    }
+
+    pub async fn list_invitations(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        params: InvitationQueryParams,
+    ) -> Result<(Vec<InvitationResponse>, u64), AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::ViewUser,
+                ResourceType::User(None),
+            )
+            .await?;
+
+        self.expire_invitations().await?;
+
+        let mut query = user_invitations::Entity::find()
+            .filter(user_invitations::Column::TenantId.eq(current_user.tenant_id));
+        if let Some(email) = &params.email {
+            query = query.filter(user_invitations::Column::InviteeEmail.contains(email));
+        }
+        if let Some(status) = &params.status {
+            query = query.filter(user_invitations::Column::Status.eq(status));
+        }
+
+        let paginator = query
+            .order_by_desc(user_invitations::Column::CreatedAt)
+            .paginate(&self.app_state.db, params.page_size);
+        let total = paginator.num_items().await?;
+        let page_index = if params.page > 0 { params.page - 1 } else { 0 };
+        let results = paginator
+            .fetch_page(page_index)
+            .await?
+            .into_iter()
+            .map(InvitationResponse::from)
+            .collect();
+
+        Ok((results, total))
+    }
+
+    pub async fn create_invitation(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        dto: CreateInvitationDto,
+    ) -> Result<InvitationResponse, AppError> {
+        self.check_invite_permission(&current_user, context, &dto.dept, &dto.groups, &dto.roles)
+            .await?;
+
+        if users::Entity::find()
+            .filter(users::Column::Email.eq(&dto.email))
+            .filter(users::Column::TenantId.eq(current_user.tenant_id))
+            .one(&self.app_state.db)
+            .await?
+            .is_some()
+        {
+            return Err(conflict!("Email already exists"));
+        }
+        if user_invitations::Entity::find()
+            .filter(user_invitations::Column::InviteeEmail.eq(&dto.email))
+            .filter(user_invitations::Column::TenantId.eq(current_user.tenant_id))
+            .filter(user_invitations::Column::Status.eq(INVITATION_PENDING))
+            .filter(user_invitations::Column::ExpiresAt.gt(Utc::now()))
+            .one(&self.app_state.db)
+            .await?
+            .is_some()
+        {
+            return Err(conflict!("A pending invitation already exists for this email"));
+        }
+
+        let dept_id = departments::Entity::find()
+            .select_only()
+            .column(departments::Column::DeptId)
+            .filter(departments::Column::DeptUuid.eq(&dto.dept))
+            .filter(departments::Column::TenantId.eq(current_user.tenant_id))
+            .into_tuple::<i32>()
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("Department not found"))?;
+        let inviter_id = self.user_id(&current_user.uuid).await?;
+
+        let code = uuid::Uuid::new_v4().to_string();
+        let invitation = user_invitations::ActiveModel {
+            inviter_user_id: Set(inviter_id),
+            invitee_email: Set(dto.email),
+            invitation_uuid: Set(uuid::Uuid::new_v4().to_string()),
+            invitation_code: Set(hash_token(&code)),
+            dept_id: Set(dept_id),
+            tenant_id: Set(current_user.tenant_id),
+            group_uuids: Set(Some(json!(dto.groups))),
+            role_uuids: Set(Some(json!(dto.roles))),
+            language: Set(dto.language.to_uppercase()),
+            status: Set(INVITATION_PENDING.to_string()),
+            expires_at: Set(Utc::now() + Duration::seconds(INVITATION_EXPIRATION)),
+            created_at: Set(Utc::now()),
+            updated_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(&self.app_state.db)
+        .await?;
+
+        self.send_invitation(&invitation, &code, &current_user.username)
+            .await?;
+        Ok(InvitationResponse::from(invitation))
+    }
+
+    /// 重新发送邀请, 生成新的邀请码并顺延过期时间
+    pub async fn resend_invitation(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        invitation_uuid: String,
+    ) -> Result<InvitationResponse, AppError> {
+        let invitation = self.find_by_uuid(current_user.tenant_id, &invitation_uuid).await?;
+        // 重新发送的邀请码同样会授予部门、用户组和角色, 按创建时的规则检查当前操作者
+        let dept_uuid = departments::Entity::find_by_id(invitation.dept_id)
+            .select_only()
+            .column(departments::Column::DeptUuid)
+            .into_tuple::<String>()
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("Department not found"))?;
+        let groups = json_to_uuids(&invitation.group_uuids);
+        let roles = json_to_uuids(&invitation.role_uuids);
+        self.check_invite_permission(&current_user, context, &dept_uuid, &groups, &roles)
+            .await?;
+
+        if invitation.status == INVITATION_ACCEPTED || invitation.status == INVITATION_REVOKED {
+            return Err(bad_request!("Invitation is already {}", invitation.status));
+        }
+
+        let code = uuid::Uuid::new_v4().to_string();
+        let mut invitation: user_invitations::ActiveModel = invitation.into();
+        invitation.invitation_code = Set(hash_token(&code));
+        invitation.status = Set(INVITATION_PENDING.to_string());
+        invitation.expires_at = Set(Utc::now() + Duration::seconds(INVITATION_EXPIRATION));
+        invitation.updated_at = Set(Utc::now());
+        let invitation = invitation.update(&self.app_state.db).await?;
+
+        self.send_invitation(&invitation, &code, &current_user.username)
+            .await?;
+        Ok(InvitationResponse::from(invitation))
+    }
+
+    // 邀请等同于创建用户并分配角色, 需要相同的权限
+    async fn check_invite_permission(
+        &self,
+        current_user: &CurrentUser,
+        context: CedarContext,
+        dept_uuid: &str,
+        groups: &[String],
+        roles: &[String],
+    ) -> Result<(), AppError> {
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
+        let dept_es = get_dept_entities(&self.app_state.db, current_user.tenant_id, dept_uuid, &schema).await?;
+        let group_es = get_group_entities(&self.app_state.db, current_user.tenant_id, groups, &schema).await?;
+        let role_es = get_role_entities(&self.app_state.db, current_user.tenant_id, &roles.to_vec(), &schema).await?;
+        let merged_es = dept_es
+            .add_entities(group_es, Some(&schema))?
+            .add_entities(role_es, Some(&schema))?;
+
+        self.app_state
+            .auth_service
+            .check_permission_with_entities(
+                &current_user.uuid,
+                context.clone(),
+                AuthAction::CreateUser,
+                ResourceType::User(None),
+                merged_es.clone(),
+            )
+            .await?;
+        for group_uuid in groups {
+            self.app_state
+                .auth_service
+                .check_permission_with_entities(
+                    &current_user.uuid,
+                    context.clone(),
+                    AuthAction::CreateUser,
+                    ResourceType::Group(Some(group_uuid.clone())),
+                    merged_es.clone(),
+                )
+                .await?;
+        }
+        for role_uuid in roles {
+            self.app_state
+                .auth_service
+                .check_permission_with_entities(
+                    &current_user.uuid,
+                    context.clone(),
+                    AuthAction::AssignRole,
+                    ResourceType::Role(Some(role_uuid.clone())),
+                    merged_es.clone(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn revoke_invitation(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        invitation_uuid: String,
+    ) -> Result<(), AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::CreateUser,
+                ResourceType::User(None),
+            )
+            .await?;
+
+        let invitation = self.find_by_uuid(current_user.tenant_id, &invitation_uuid).await?;
+        if invitation.status == INVITATION_ACCEPTED {
+            return Err(bad_request!("Invitation is already accepted"));
+        }
+
+        let mut invitation: user_invitations::ActiveModel = invitation.into();
+        invitation.status = Set(INVITATION_REVOKED.to_string());
+        invitation.updated_at = Set(Utc::now());
+        invitation.update(&self.app_state.db).await?;
+        Ok(())
+    }
+
+    /// 被邀请人查看邀请信息
+    pub async fn preview_invitation(&self, code: String) -> Result<InvitationPreview, AppError> {
+        let invitation = self.find_pending_by_code(&code).await?;
+        Ok(InvitationPreview {
+            email: invitation.invitee_email,
+            expires_at: invitation.expires_at,
+        })
+    }
+
+    /// 接受邀请: 设置用户名和密码, 创建用户并分配预设的部门、用户组和角色
+    pub async fn accept_invitation(
+        &self,
+        code: String,
+        dto: AcceptInvitationDto,
+    ) -> Result<(), AppError> {
+        let invitation = self.find_pending_by_code(&code).await?;
+
+        let policy = &self.app_state.config.security.password;
+        let txn = self.app_state.db.begin().await?;
+
+        if users::Entity::find()
+            .filter(users::Column::Email.eq(&invitation.invitee_email))
+            .filter(users::Column::TenantId.eq(invitation.tenant_id))
+            .one(&txn)
+            .await?
+            .is_some()
+        {
+            return Err(conflict!("Email already exists"));
+        }
+
+        let password_hash =
+            hash_new_password(&txn, policy, None, &dto.username, &dto.password).await?;
+        let user = users::ActiveModel {
+            user_uuid: Set(uuid::Uuid::new_v4().to_string()),
+            username: Set(dto.username),
+            email: Set(invitation.invitee_email.clone()),
+            password: Set(password_hash.clone()),
+            dept_id: Set(invitation.dept_id),
+            tenant_id: Set(invitation.tenant_id),
+            alias: Set(dto.alias),
+            phone: Set(dto.phone),
+            is_active: Set(true),
+            password_changed_at: Set(Some(chrono::Local::now().naive_local())),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        record_password_history(&txn, policy, user.user_id, &password_hash).await?;
+
+        // 邀请发出后被删除的用户组或角色直接忽略
+        let group_uuids = json_to_uuids(&invitation.group_uuids);
+        if !group_uuids.is_empty() {
+            let memberships = user_groups::Entity::find()
+                .select_only()
+                .column(user_groups::Column::UserGroupId)
+                .filter(user_groups::Column::UserGroupUuid.is_in(group_uuids))
+                .filter(user_groups::Column::TenantId.eq(invitation.tenant_id))
+                .into_tuple::<i32>()
+                .all(&txn)
+                .await?
+                .into_iter()
+                .map(|group_id| user_group_members::ActiveModel {
+                    user_id: Set(user.user_id),
+                    group_id: Set(group_id),
+                    ..Default::default()
+                })
+                .collect::<Vec<_>>();
+            if !memberships.is_empty() {
+                user_group_members::Entity::insert_many(memberships).exec(&txn).await?;
+            }
+        }
+
+        let role_uuids = json_to_uuids(&invitation.role_uuids);
+        if !role_uuids.is_empty() {
+            let assignments = roles::Entity::find()
+                .select_only()
+                .column(roles::Column::RoleId)
+                .filter(roles::Column::RoleUuid.is_in(role_uuids))
+                .filter(roles::Column::TenantId.eq(invitation.tenant_id))
+                .into_tuple::<i32>()
+                .all(&txn)
+                .await?
+                .into_iter()
+                .map(|role_id| user_roles::ActiveModel {
+                    user_id: Set(user.user_id),
+                    role_id: Set(role_id),
+                    ..Default::default()
+                })
+                .collect::<Vec<_>>();
+            if !assignments.is_empty() {
+                user_roles::Entity::insert_many(assignments).exec(&txn).await?;
+            }
+        }
+
+        let inviter_user_id = invitation.inviter_user_id;
+        let mut invitation: user_invitations::ActiveModel = invitation.into();
+        invitation.status = Set(INVITATION_ACCEPTED.to_string());
+        invitation.invitee_user_id = Set(Some(user.user_id));
+        invitation.updated_at = Set(Utc::now());
+        invitation.update(&txn).await?;
+
+        txn.commit().await?;
+
+        publish_event(&self.app_state, None, DomainEvent::UserCreated {
+            user_uuid: user.user_uuid.clone(),
+            username: user.username.clone(),
+        }).await;
+
+        // 通知邀请人, 通知失败不影响接受邀请
+        if let Err(e) = self.notify_inviter(inviter_user_id, &user).await {
+            warn!("通知邀请人失败: {:?}", e);
+        }
+        Ok(())
+    }
+
+    async fn notify_inviter(&self, inviter_user_id: i32, invitee: &users::Model) -> Result<(), AppError> {
+        let Some(inviter) = users::Entity::find_by_id(inviter_user_id)
+            .one(&self.app_state.db)
+            .await?
+        else {
+            return Ok(());
+        };
+        notify_user(
+            &self.app_state,
+            inviter.user_id,
+            &inviter.user_uuid,
+            NewNotification {
+                category: NOTIFICATION_CATEGORY_INVITATION.to_string(),
+                level: NOTIFICATION_INFO.to_string(),
+                title: format!("{} accepted your invitation", invitee.username),
+                content: Some(invitee.email.clone()),
+                link: Some("/users".to_string()),
+            },
+        )
+        .await?;
+        Ok(())
+    }
+
+    // 将已过期但仍为 pending 的邀请标记为 expired
+    async fn expire_invitations(&self) -> Result<(), AppError> {
+        user_invitations::Entity::update_many()
+            .col_expr(user_invitations::Column::Status, INVITATION_EXPIRED.into())
+            .filter(user_invitations::Column::Status.eq(INVITATION_PENDING))
+            .filter(user_invitations::Column::ExpiresAt.lte(Utc::now()))
+            .exec(&self.app_state.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn find_by_uuid(&self, tenant_id: i32, invitation_uuid: &str) -> Result<user_invitations::Model, AppError> {
+        user_invitations::Entity::find()
+            .filter(user_invitations::Column::InvitationUuid.eq(invitation_uuid))
+            .filter(user_invitations::Column::TenantId.eq(tenant_id))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("Invitation not found"))
+    }
+
+    async fn find_pending_by_code(&self, code: &str) -> Result<user_invitations::Model, AppError> {
+        let invitation = user_invitations::Entity::find()
+            .filter(user_invitations::Column::InvitationCode.eq(hash_token(code)))
+            .filter(user_invitations::Column::Status.eq(INVITATION_PENDING))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("Invitation not found or no longer valid"))?;
+
+        if invitation.expires_at <= Utc::now() {
+            self.expire_invitations().await?;
+            return Err(not_found!("Invitation not found or no longer valid"));
+        }
+        Ok(invitation)
+    }
+
+    async fn user_id(&self, user_uuid: &str) -> Result<i32, AppError> {
+        users::Entity::find()
+            .select_only()
+            .column(users::Column::UserId)
+            .filter(users::Column::UserUuid.eq(user_uuid))
+            .into_tuple::<i32>()
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("User not found"))
+    }
+
+    async fn send_invitation(
+        &self,
+        invitation: &user_invitations::Model,
+        code: &str,
+        inviter: &str,
+    ) -> Result<(), AppError> {
+        let accept_url = self
+            .app_state
+            .config
+            .server
+            .public_link(&format!("/accept-invitation/{}", code))
+            .map_err(|e| AppError::internal_server_error(anyhow::anyhow!(e)))?;
+        let expires_at = invitation.expires_at.format("%Y-%m-%d %H:%M UTC").to_string();
+
+        self.app_state
+            .email_service
+            .enqueue(
+                &invitation.invitee_email,
+                EmailTemplate::Invitation {
+                    inviter,
+                    accept_url: accept_url.as_str(),
+                    expires_at: &expires_at,
+                },
+                &invitation.language,
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,963 @@
+// LDAP 目录: 登录时的绑定校验, 以及定时将组织单元、用户组和账号同步到 departments、user_groups、users
+// 目录中的条目通过 directory_links 对应到本地记录, 目录是这些记录的权威来源, 只同步到默认租户
+use chrono::Utc;
+use ldap3::adapters::{Adapter, EntriesOnly, PagedResults};
+use ldap3::{ldap_escape, Ldap, LdapConnAsync, LdapConnSettings, Scope, SearchEntry};
+use redis::{AsyncCommands, ExistenceCheck, SetExpiry, SetOptions};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseTransaction, EntityTrait, JoinType,
+    PaginatorTrait, QueryFilter, QuerySelect, RelationTrait, Set, TransactionTrait,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+use tokio::time::{sleep, Duration};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::config::app::{LDAP_SYNC_CONFLICTS, LDAP_SYNC_LOCK};
+use crate::config::ldap::LdapConfig;
+use crate::config::state::AppState;
+use crate::entity::{
+    departments, directory_links, group_roles, roles, user_group_members, user_groups, user_roles,
+    users,
+};
+use crate::errors::app_error::AppError;
+use crate::not_found;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::event::DomainEvent;
+use crate::schemas::tenant::DEFAULT_TENANT_ID;
+use crate::schemas::notification::{NewNotification, NOTIFICATION_CATEGORY_DIRECTORY, NOTIFICATION_WARNING};
+use crate::services::event_bus::publish_event;
+use crate::services::notification::notify_user;
+use crate::services::role::active_assignment;
+use crate::utils::crypto::{generate_secret, hash_password, hash_token};
+
+pub const DIRECTORY_SOURCE_LDAP: &str = "ldap";
+pub const ENTRY_TYPE_USER: &str = "user";
+pub const ENTRY_TYPE_GROUP: &str = "group";
+pub const ENTRY_TYPE_DEPARTMENT: &str = "department";
+
+const SEARCH_PAGE_SIZE: i32 = 500;
+// 通知中最多列出的冲突数量
+const MAX_REPORTED_CONFLICTS: usize = 50;
+// 部门表中根部门的上级
+const ROOT_PARENT_ID: i32 = 0;
+const ROOT_PARENT_UUID: &str = "0";
+// 同步期间锁的有效期(秒), 每隔三分之一有效期续期一次, 节点崩溃后锁很快失效
+const SYNC_LOCK_TTL_SECONDS: u64 = 60;
+
+// 只有锁仍属于自己时才修改有效期
+static EXTEND_LOCK_SCRIPT: LazyLock<redis::Script> = LazyLock::new(|| {
+    redis::Script::new(
+        r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('EXPIRE', KEYS[1], ARGV[2])
+end
+return 0
+"#,
+    )
+});
+
+/// 连接目录并以服务账号绑定
+pub async fn connect(config: &LdapConfig) -> Result<Ldap, AppError> {
+    let timeout = Duration::from_secs(config.timeout_seconds);
+    let settings = LdapConnSettings::new()
+        .set_conn_timeout(timeout)
+        .set_starttls(config.starttls);
+    let (conn, mut ldap) = LdapConnAsync::with_settings(settings, &config.url).await?;
+    ldap3::drive!(conn);
+    ldap.with_timeout(timeout)
+        .simple_bind(&config.bind_dn, &config.bind_password)
+        .await?
+        .success()?;
+    Ok(ldap)
+}
+
+/// 分页查询子树, 避免超过服务器的单次返回数量限制
+async fn search(
+    ldap: &mut Ldap,
+    config: &LdapConfig,
+    base: &str,
+    filter: &str,
+    attrs: Vec<String>,
+) -> Result<Vec<SearchEntry>, AppError> {
+    let adapters: Vec<Box<dyn Adapter<_, _>>> = vec![
+        Box::new(EntriesOnly::new()),
+        Box::new(PagedResults::new(SEARCH_PAGE_SIZE)),
+    ];
+    let mut stream = ldap
+        .with_timeout(Duration::from_secs(config.timeout_seconds))
+        .streaming_search_with(adapters, base, Scope::Subtree, filter, attrs)
+        .await?;
+    let mut entries = Vec::new();
+    while let Some(entry) = stream.next().await? {
+        entries.push(SearchEntry::construct(entry));
+    }
+    stream.finish().await.success()?;
+    Ok(entries)
+}
+
+/// 先以服务账号查找用户的 DN, 再以用户的 DN 和密码绑定
+pub async fn verify_credentials(config: &LdapConfig, username: &str, password: &str) -> Result<bool, AppError> {
+    // 空密码的绑定会被当作匿名绑定而成功
+    if password.is_empty() {
+        return Ok(false);
+    }
+
+    let mut ldap = connect(config).await?;
+    let filter = format!("(&{}({}={}))", config.user_filter, config.username_attr, ldap_escape(username));
+    let entries = search(&mut ldap, config, config.user_base(), &filter, vec!["1.1".to_string()]).await?;
+    let [entry] = entries.as_slice() else {
+        debug!("LDAP 中找到 {} 个用户名为 {} 的用户", entries.len(), username);
+        let _ = ldap.unbind().await;
+        return Ok(false);
+    };
+
+    let result = ldap
+        .with_timeout(Duration::from_secs(config.timeout_seconds))
+        .simple_bind(&entry.dn, password)
+        .await?;
+    let _ = ldap.unbind().await;
+    Ok(result.rc == 0)
+}
+
+/// 用户是否来自 LDAP 目录
+pub async fn is_ldap_user(app_state: &AppState, user_id: i32) -> Result<bool, AppError> {
+    let count = directory_links::Entity::find()
+        .filter(directory_links::Column::Source.eq(DIRECTORY_SOURCE_LDAP))
+        .filter(directory_links::Column::EntryType.eq(ENTRY_TYPE_USER))
+        .filter(directory_links::Column::LocalId.eq(user_id))
+        .count(&app_state.db)
+        .await?;
+    Ok(count > 0)
+}
+
+#[derive(Debug, Default)]
+pub struct SyncStats {
+    pub created: u32,
+    pub updated: u32,
+    pub removed: u32,
+}
+
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    pub departments: SyncStats,
+    pub users: SyncStats,
+    pub groups: SyncStats,
+    /// 无法同步的条目, 需要管理员处理
+    pub conflicts: Vec<String>,
+}
+
+// 后台任务：定时同步 LDAP 目录
+pub async fn run_ldap_sync(state: AppState) {
+    let config = &state.config.ldap;
+    if !config.enabled || config.sync_interval_seconds == 0 {
+        return;
+    }
+
+    let interval = Duration::from_secs(config.sync_interval_seconds);
+    loop {
+        let lock_token = generate_secret();
+        match acquire_sync_lock(&state, &lock_token).await {
+            Ok(true) => match sync_with_lock(&state, &lock_token).await {
+                Some(Ok(report)) => {
+                    info!(
+                        "LDAP 同步完成: 部门 +{} ~{} -{}, 用户 +{} ~{} -{}, 用户组 +{} ~{} -{}, 冲突 {}",
+                        report.departments.created, report.departments.updated, report.departments.removed,
+                        report.users.created, report.users.updated, report.users.removed,
+                        report.groups.created, report.groups.updated, report.groups.removed,
+                        report.conflicts.len()
+                    );
+                    if let Err(e) = report_conflicts(&state, &report).await {
+                        error!("发送 LDAP 同步冲突报告失败: {}", e);
+                    }
+                }
+                Some(Err(e)) => error!("LDAP 同步失败: {}", e),
+                None => error!("LDAP 同步锁已失效, 本次同步已回滚"),
+            },
+            Ok(false) => debug!("LDAP 同步已由其他节点执行"),
+            Err(e) => error!("获取 LDAP 同步锁失败: {}", e),
+        }
+        sleep(interval).await;
+    }
+}
+
+// 多节点部署时每个同步周期只由一个节点执行
+async fn acquire_sync_lock(state: &AppState, token: &str) -> Result<bool, AppError> {
+    let mut redis_conn = state.redis.get_multiplexed_async_connection().await?;
+    let acquired: Option<String> = redis_conn
+        .set_options(
+            LDAP_SYNC_LOCK,
+            token,
+            SetOptions::default()
+                .conditional_set(ExistenceCheck::NX)
+                .with_expiration(SetExpiry::EX(SYNC_LOCK_TTL_SECONDS)),
+        )
+        .await?;
+    Ok(acquired.is_some())
+}
+
+async fn extend_sync_lock(state: &AppState, token: &str, ttl_seconds: u64) -> Result<bool, AppError> {
+    let mut redis_conn = state.redis.get_multiplexed_async_connection().await?;
+    let extended: i64 = EXTEND_LOCK_SCRIPT
+        .key(LDAP_SYNC_LOCK)
+        .arg(token)
+        .arg(ttl_seconds)
+        .invoke_async(&mut redis_conn)
+        .await?;
+    Ok(extended == 1)
+}
+
+// 同步期间定期续期, 锁丢失(被其他节点取得)时返回
+async fn keep_sync_lock(state: &AppState, token: &str) {
+    loop {
+        sleep(Duration::from_secs(SYNC_LOCK_TTL_SECONDS / 3)).await;
+        match extend_sync_lock(state, token, SYNC_LOCK_TTL_SECONDS).await {
+            Ok(true) => {}
+            Ok(false) => return,
+            // Redis 暂时不可用时锁仍可能有效, 下次再续期
+            Err(e) => warn!("LDAP 同步锁续期失败: {}", e),
+        }
+    }
+}
+
+/// 持有锁执行同步, 锁丢失时中止同步(事务回滚)并返回 None;
+/// 结束后锁保留到本周期结束, 其他节点在同一周期内不会重复同步
+async fn sync_with_lock(state: &AppState, token: &str) -> Option<Result<SyncReport, AppError>> {
+    let result = tokio::select! {
+        result = sync_directory(state) => Some(result),
+        _ = keep_sync_lock(state, token) => None,
+    };
+    let hold_seconds = state.config.ldap.sync_interval_seconds.saturating_sub(1).max(1);
+    if let Err(e) = extend_sync_lock(state, token, hold_seconds).await {
+        warn!("设置 LDAP 同步锁有效期失败: {}", e);
+    }
+    result
+}
+
+/// 同步目录, 所有变更在一个事务中完成, 提交后发布领域事件
+pub async fn sync_directory(app_state: &AppState) -> Result<SyncReport, AppError> {
+    let config = &app_state.config.ldap;
+    let mut ldap = connect(config).await?;
+    let ous = search(
+        &mut ldap,
+        config,
+        &config.base_dn,
+        &config.ou_filter,
+        vec![config.ou_name_attr.clone(), config.id_attr.clone()],
+    )
+    .await?;
+    let accounts = search(
+        &mut ldap,
+        config,
+        config.user_base(),
+        &config.user_filter,
+        vec![
+            config.username_attr.clone(),
+            config.email_attr.clone(),
+            config.display_name_attr.clone(),
+            config.phone_attr.clone(),
+            config.id_attr.clone(),
+        ],
+    )
+    .await?;
+    let groups = search(
+        &mut ldap,
+        config,
+        config.group_base(),
+        &config.group_filter,
+        vec![
+            config.group_name_attr.clone(),
+            config.group_member_attr.clone(),
+            config.id_attr.clone(),
+        ],
+    )
+    .await?;
+    let _ = ldap.unbind().await;
+
+    let txn = app_state.db.begin().await?;
+    let mut sync = DirectorySync {
+        config,
+        txn: &txn,
+        root_dept_id: root_dept_id(&txn, config).await?,
+        dept_by_dn: HashMap::new(),
+        user_by_dn: HashMap::new(),
+        stale_departments: Vec::new(),
+        report: SyncReport::default(),
+        events: Vec::new(),
+    };
+    sync.sync_departments(ous).await?;
+    sync.sync_users(accounts).await?;
+    sync.sync_groups(groups).await?;
+    // 用户移出后再删除部门
+    sync.remove_stale_departments().await?;
+    let DirectorySync { report, events, .. } = sync;
+    txn.commit().await?;
+
+    let actor = CurrentUser::system(DIRECTORY_SOURCE_LDAP, "LDAP");
+    for event in events {
+        publish_event(app_state, Some(&actor), event).await;
+    }
+    Ok(report)
+}
+
+struct DirectorySync<'a> {
+    config: &'a LdapConfig,
+    txn: &'a DatabaseTransaction,
+    root_dept_id: i32,
+    // 规范化的 DN -> 本地ID
+    dept_by_dn: HashMap<String, i32>,
+    user_by_dn: HashMap<String, i32>,
+    stale_departments: Vec<(String, i32)>,
+    report: SyncReport,
+    events: Vec<DomainEvent>,
+}
+
+impl DirectorySync<'_> {
+    async fn sync_departments(&mut self, mut ous: Vec<SearchEntry>) -> Result<(), AppError> {
+        let base_dn = normalize_dn(&self.config.base_dn);
+        self.dept_by_dn.insert(base_dn.clone(), self.root_dept_id);
+        let mut links = load_links(self.txn, ENTRY_TYPE_DEPARTMENT).await?;
+        let mut seen = HashSet::new();
+
+        // 上级组织单元先于下级处理
+        ous.retain(|entry| normalize_dn(&entry.dn) != base_dn);
+        ous.sort_by_key(|entry| entry.dn.matches(',').count());
+        for entry in ous {
+            let dn = normalize_dn(&entry.dn);
+            let external_id = external_id(&entry, self.config);
+            let name = attr(&entry, &self.config.ou_name_attr).unwrap_or_else(|| rdn_value(&entry.dn));
+            let parent_id = self.parent_dept_id(&dn);
+            seen.insert(external_id.clone());
+
+            let linked = match links.get(&external_id) {
+                Some(dept_id) => departments::Entity::find_by_id(*dept_id).one(self.txn).await?,
+                None => None,
+            };
+            let dept_id = match linked {
+                Some(dept) => self.update_department(dept, name, parent_id).await?,
+                None => {
+                    if links.remove(&external_id).is_some() {
+                        delete_link(self.txn, ENTRY_TYPE_DEPARTMENT, &external_id).await?;
+                    }
+                    // 同一上级下已有同名部门时直接关联, 避免重复创建
+                    let existing = departments::Entity::find()
+                        .filter(departments::Column::Name.eq(&name))
+                        .filter(departments::Column::TenantId.eq(DEFAULT_TENANT_ID))
+                        .filter(departments::Column::ParentId.eq(parent_id))
+                        .filter(departments::Column::IsDeleted.eq(false))
+                        .one(self.txn)
+                        .await?;
+                    match existing {
+                        Some(dept) if links.values().any(|id| *id == dept.dept_id) => {
+                            self.conflict(format!("{}: 部门 {} 已关联到目录中的其他组织单元", entry.dn, dept.name));
+                            continue;
+                        }
+                        Some(dept) => dept.dept_id,
+                        None => {
+                            let dept = departments::ActiveModel {
+                                dept_uuid: Set(Uuid::new_v4().to_string()),
+                                name: Set(name),
+                                desc: Set(Some(entry.dn.clone())),
+                                order: Set(0),
+                                parent_id: Set(parent_id),
+                                tenant_id: Set(DEFAULT_TENANT_ID),
+                                ..Default::default()
+                            }
+                            .insert(self.txn)
+                            .await?;
+                            self.report.departments.created += 1;
+                            self.events.push(DomainEvent::DepartmentCreated { dept_uuid: dept.dept_uuid });
+                            dept.dept_id
+                        }
+                    }
+                }
+            };
+            create_link(self.txn, ENTRY_TYPE_DEPARTMENT, &external_id, dept_id).await?;
+            links.insert(external_id, dept_id);
+            self.dept_by_dn.insert(dn, dept_id);
+        }
+
+        self.stale_departments = links.into_iter().filter(|(id, _)| !seen.contains(id)).collect();
+        Ok(())
+    }
+
+    async fn update_department(&mut self, dept: departments::Model, name: String, parent_id: i32) -> Result<i32, AppError> {
+        let dept_id = dept.dept_id;
+        let dept_uuid = dept.dept_uuid.clone();
+        let from_parent_id = dept.parent_id;
+        let renamed = dept.name != name || dept.is_deleted;
+        let moved = dept.parent_id != parent_id;
+        if !renamed && !moved {
+            return Ok(dept_id);
+        }
+
+        let mut dept: departments::ActiveModel = dept.into();
+        dept.name = Set(name);
+        dept.parent_id = Set(parent_id);
+        dept.is_deleted = Set(false);
+        dept.updated_at = Set(Utc::now());
+        dept.update(self.txn).await?;
+
+        self.report.departments.updated += 1;
+        if moved {
+            self.events.push(DomainEvent::DepartmentMoved {
+                dept_uuid,
+                from_parent_uuid: dept_uuid_of(self.txn, from_parent_id).await?,
+                to_parent_uuid: dept_uuid_of(self.txn, parent_id).await?,
+            });
+        } else {
+            self.events.push(DomainEvent::DepartmentUpdated { dept_uuid });
+        }
+        Ok(dept_id)
+    }
+
+    async fn remove_stale_departments(&mut self) -> Result<(), AppError> {
+        // 下级部门通常比上级部门后创建, 先处理
+        let mut stale = std::mem::take(&mut self.stale_departments);
+        stale.sort_by_key(|(_, dept_id)| std::cmp::Reverse(*dept_id));
+        for (external_id, dept_id) in stale {
+            let Some(dept) = departments::Entity::find_by_id(dept_id).one(self.txn).await? else {
+                delete_link(self.txn, ENTRY_TYPE_DEPARTMENT, &external_id).await?;
+                continue;
+            };
+            let children = departments::Entity::find()
+                .filter(departments::Column::ParentId.eq(dept_id))
+                .filter(departments::Column::IsDeleted.eq(false))
+                .count(self.txn)
+                .await?;
+            let members = users::Entity::find()
+                .filter(users::Column::DeptId.eq(dept_id))
+                .count(self.txn)
+                .await?;
+            if children > 0 || members > 0 {
+                self.conflict(format!("部门 {} 对应的组织单元已从目录删除, 但仍有用户或下级部门, 未删除", dept.name));
+                continue;
+            }
+
+            let dept_uuid = dept.dept_uuid.clone();
+            let mut dept: departments::ActiveModel = dept.into();
+            dept.is_deleted = Set(true);
+            dept.update(self.txn).await?;
+            delete_link(self.txn, ENTRY_TYPE_DEPARTMENT, &external_id).await?;
+            self.report.departments.removed += 1;
+            self.events.push(DomainEvent::DepartmentDeleted { dept_uuid });
+        }
+        Ok(())
+    }
+
+    async fn sync_users(&mut self, accounts: Vec<SearchEntry>) -> Result<(), AppError> {
+        let mut links = load_links(self.txn, ENTRY_TYPE_USER).await?;
+        let mut seen = HashSet::new();
+        // 目录用户通过 LDAP 绑定登录, 本地密码设为随机值
+        let unusable_password = hash_password(&generate_secret())?;
+
+        for entry in accounts {
+            let dn = normalize_dn(&entry.dn);
+            let external_id = external_id(&entry, self.config);
+            let (Some(username), Some(email)) = (
+                attr(&entry, &self.config.username_attr),
+                attr(&entry, &self.config.email_attr),
+            ) else {
+                self.conflict(format!(
+                    "{}: 缺少 {} 或 {} 属性",
+                    entry.dn, self.config.username_attr, self.config.email_attr
+                ));
+                continue;
+            };
+            seen.insert(external_id.clone());
+            let alias = attr(&entry, &self.config.display_name_attr);
+            let phone = attr(&entry, &self.config.phone_attr);
+            let dept_id = self.parent_dept_id(&dn);
+
+            let linked = match links.get(&external_id) {
+                Some(user_id) => users::Entity::find_by_id(*user_id).one(self.txn).await?,
+                None => None,
+            };
+            if linked.is_none() && links.remove(&external_id).is_some() {
+                delete_link(self.txn, ENTRY_TYPE_USER, &external_id).await?;
+            }
+
+            // 用户名或邮箱已被本地的其他用户使用
+            let mut taken_query = users::Entity::find()
+                .filter(
+                    Condition::any()
+                        .add(users::Column::Username.eq(&username))
+                        .add(users::Column::Email.eq(&email)),
+                )
+                .filter(users::Column::TenantId.eq(DEFAULT_TENANT_ID));
+            if let Some(user) = &linked {
+                taken_query = taken_query.filter(users::Column::UserId.ne(user.user_id));
+            }
+            if let Some(other) = taken_query.one(self.txn).await? {
+                self.conflict(format!(
+                    "{}: 用户名 {} 或邮箱 {} 已被本地用户 {} 使用",
+                    entry.dn, username, email, other.username
+                ));
+                if let Some(user) = linked {
+                    self.user_by_dn.insert(dn, user.user_id);
+                }
+                continue;
+            }
+
+            let user_id = match linked {
+                Some(user) => {
+                    let user_id = user.user_id;
+                    let user_uuid = user.user_uuid.clone();
+                    let from_dept_id = user.dept_id;
+                    if user.username != username
+                        || user.email != email
+                        || user.alias != alias
+                        || user.phone != phone
+                        || user.dept_id != dept_id
+                    {
+                        let mut user: users::ActiveModel = user.into();
+                        user.username = Set(username);
+                        user.email = Set(email);
+                        user.alias = Set(alias);
+                        user.phone = Set(phone);
+                        user.dept_id = Set(dept_id);
+                        user.updated_at = Set(Utc::now().naive_utc());
+                        user.update(self.txn).await?;
+
+                        self.report.users.updated += 1;
+                        self.events.push(DomainEvent::UserUpdated { user_uuid: user_uuid.clone() });
+                        if from_dept_id != dept_id {
+                            self.events.push(DomainEvent::UserDepartmentChanged {
+                                user_uuid,
+                                from_dept_uuid: dept_uuid_of(self.txn, from_dept_id).await?,
+                                to_dept_uuid: dept_uuid_of(self.txn, dept_id).await?,
+                            });
+                        }
+                    }
+                    user_id
+                }
+                None => {
+                    let user = users::ActiveModel {
+                        user_uuid: Set(Uuid::new_v4().to_string()),
+                        username: Set(username),
+                        email: Set(email),
+                        password: Set(unusable_password.clone()),
+                        dept_id: Set(dept_id),
+                        tenant_id: Set(DEFAULT_TENANT_ID),
+                        alias: Set(alias),
+                        phone: Set(phone),
+                        is_active: Set(true),
+                        password_changed_at: Set(Some(chrono::Local::now().naive_local())),
+                        ..Default::default()
+                    }
+                    .insert(self.txn)
+                    .await?;
+                    create_link(self.txn, ENTRY_TYPE_USER, &external_id, user.user_id).await?;
+                    links.insert(external_id, user.user_id);
+                    self.report.users.created += 1;
+                    self.events.push(DomainEvent::UserCreated {
+                        user_uuid: user.user_uuid,
+                        username: user.username,
+                    });
+                    user.user_id
+                }
+            };
+            self.user_by_dn.insert(dn, user_id);
+        }
+
+        if !self.config.disable_missing_users {
+            return Ok(());
+        }
+        let linked_count = links.len();
+        let mut missing = Vec::new();
+        for (external_id, user_id) in links {
+            if seen.contains(&external_id) {
+                continue;
+            }
+            let Some(user) = users::Entity::find_by_id(user_id).one(self.txn).await? else {
+                delete_link(self.txn, ENTRY_TYPE_USER, &external_id).await?;
+                continue;
+            };
+            if user.is_active {
+                missing.push(user);
+            }
+        }
+        if let Err(reason) = check_disable_limit(seen.len(), linked_count, missing.len(), self.config.max_disable_percent) {
+            self.conflict(reason);
+            return Ok(());
+        }
+        for user in missing {
+            let user_uuid = user.user_uuid.clone();
+            let mut user: users::ActiveModel = user.into();
+            user.is_active = Set(false);
+            user.update(self.txn).await?;
+            self.report.users.removed += 1;
+            self.events.push(DomainEvent::UserDisabled { user_uuid });
+        }
+        Ok(())
+    }
+
+    async fn sync_groups(&mut self, groups: Vec<SearchEntry>) -> Result<(), AppError> {
+        let mut links = load_links(self.txn, ENTRY_TYPE_GROUP).await?;
+        let mut seen = HashSet::new();
+
+        for entry in groups {
+            let external_id = external_id(&entry, self.config);
+            let name = attr(&entry, &self.config.group_name_attr).unwrap_or_else(|| rdn_value(&entry.dn));
+            // 只同步已同步的用户, 嵌套的用户组忽略
+            let members: HashSet<i32> = attr_values(&entry, &self.config.group_member_attr)
+                .iter()
+                .filter_map(|dn| self.user_by_dn.get(&normalize_dn(dn)).copied())
+                .collect();
+            seen.insert(external_id.clone());
+
+            let linked = match links.get(&external_id) {
+                Some(group_id) => user_groups::Entity::find_by_id(*group_id).one(self.txn).await?,
+                None => None,
+            };
+            if linked.is_none() && links.remove(&external_id).is_some() {
+                delete_link(self.txn, ENTRY_TYPE_GROUP, &external_id).await?;
+            }
+            let name_taken = user_groups::Entity::find()
+                .filter(user_groups::Column::Name.eq(&name))
+                .filter(user_groups::Column::TenantId.eq(DEFAULT_TENANT_ID))
+                .filter(user_groups::Column::UserGroupId.ne(linked.as_ref().map(|g| g.user_group_id).unwrap_or(0)))
+                .one(self.txn)
+                .await?
+                .is_some();
+
+            let group = match linked {
+                Some(group) if group.name != name && name_taken => {
+                    self.conflict(format!("{}: 用户组名称 {} 已被本地用户组使用, 未改名", entry.dn, name));
+                    group
+                }
+                Some(group) if group.name != name => {
+                    let mut group: user_groups::ActiveModel = group.into();
+                    group.name = Set(name);
+                    group.updated_at = Set(Utc::now());
+                    let group = group.update(self.txn).await?;
+                    self.report.groups.updated += 1;
+                    self.events.push(DomainEvent::GroupUpdated { group_uuid: group.user_group_uuid.clone() });
+                    group
+                }
+                Some(group) => group,
+                None if name_taken => {
+                    self.conflict(format!("{}: 用户组名称 {} 已被本地用户组使用", entry.dn, name));
+                    continue;
+                }
+                None => {
+                    let group = user_groups::ActiveModel {
+                        user_group_uuid: Set(Uuid::new_v4().to_string()),
+                        name: Set(name),
+                        description: Set(Some(entry.dn.clone())),
+                        tenant_id: Set(DEFAULT_TENANT_ID),
+                        ..Default::default()
+                    }
+                    .insert(self.txn)
+                    .await?;
+                    create_link(self.txn, ENTRY_TYPE_GROUP, &external_id, group.user_group_id).await?;
+                    links.insert(external_id, group.user_group_id);
+                    self.report.groups.created += 1;
+                    group
+                }
+            };
+            self.set_group_members(&group, members).await?;
+        }
+
+        for (external_id, group_id) in links {
+            if seen.contains(&external_id) {
+                continue;
+            }
+            if let Some(group) = user_groups::Entity::find_by_id(group_id).one(self.txn).await? {
+                self.set_group_members(&group, HashSet::new()).await?;
+                group_roles::Entity::delete_many()
+                    .filter(group_roles::Column::GroupId.eq(group_id))
+                    .exec(self.txn)
+                    .await?;
+                user_groups::Entity::delete_by_id(group_id).exec(self.txn).await?;
+                self.report.groups.removed += 1;
+                self.events.push(DomainEvent::GroupDeleted { group_uuid: group.user_group_uuid });
+            }
+            delete_link(self.txn, ENTRY_TYPE_GROUP, &external_id).await?;
+        }
+        Ok(())
+    }
+
+    // 用户组成员与目录保持一致
+    async fn set_group_members(&mut self, group: &user_groups::Model, target: HashSet<i32>) -> Result<(), AppError> {
+        let group_id = group.user_group_id;
+        let current: HashSet<i32> = user_group_members::Entity::find()
+            .select_only()
+            .column(user_group_members::Column::UserId)
+            .filter(user_group_members::Column::GroupId.eq(group_id))
+            .into_tuple::<i32>()
+            .all(self.txn)
+            .await?
+            .into_iter()
+            .collect();
+        let added: Vec<i32> = target.difference(&current).copied().collect();
+        let removed: Vec<i32> = current.difference(&target).copied().collect();
+        if added.is_empty() && removed.is_empty() {
+            return Ok(());
+        }
+
+        if !removed.is_empty() {
+            user_group_members::Entity::delete_many()
+                .filter(user_group_members::Column::GroupId.eq(group_id))
+                .filter(user_group_members::Column::UserId.is_in(removed.clone()))
+                .exec(self.txn)
+                .await?;
+        }
+        if !added.is_empty() {
+            user_group_members::Entity::insert_many(added.iter().map(|user_id| user_group_members::ActiveModel {
+                group_id: Set(group_id),
+                user_id: Set(*user_id),
+                ..Default::default()
+            }))
+            .exec(self.txn)
+            .await?;
+        }
+
+        let user_uuids = users::Entity::find()
+            .select_only()
+            .column(users::Column::UserUuid)
+            .filter(users::Column::UserId.is_in(added.into_iter().chain(removed)))
+            .into_tuple::<String>()
+            .all(self.txn)
+            .await?;
+        self.events.push(DomainEvent::GroupMembersChanged {
+            group_uuid: group.user_group_uuid.clone(),
+            user_uuids,
+        });
+        Ok(())
+    }
+
+    // 条目的上级组织单元对应的部门, 不在已同步的组织单元中时放在根部门
+    fn parent_dept_id(&self, dn: &str) -> i32 {
+        dn.split_once(',')
+            .and_then(|(_, parent)| self.dept_by_dn.get(parent))
+            .copied()
+            .unwrap_or(self.root_dept_id)
+    }
+
+    fn conflict(&mut self, message: String) {
+        debug!("LDAP 同步冲突: {}", message);
+        self.report.conflicts.push(message);
+    }
+}
+
+/// 冲突有变化时通知超级管理员, 相同的冲突只通知一次
+async fn report_conflicts(state: &AppState, report: &SyncReport) -> Result<(), AppError> {
+    let mut redis_conn = state.redis.get_multiplexed_async_connection().await?;
+    if report.conflicts.is_empty() {
+        let _: () = redis_conn.del(LDAP_SYNC_CONFLICTS).await?;
+        return Ok(());
+    }
+    let digest = hash_token(&report.conflicts.join("\n"));
+    let previous: Option<String> = redis_conn.get(LDAP_SYNC_CONFLICTS).await?;
+    if previous.as_deref() == Some(digest.as_str()) {
+        return Ok(());
+    }
+    let _: () = redis_conn.set(LDAP_SYNC_CONFLICTS, &digest).await?;
+
+    let mut content = report
+        .conflicts
+        .iter()
+        .take(MAX_REPORTED_CONFLICTS)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("\n");
+    if report.conflicts.len() > MAX_REPORTED_CONFLICTS {
+        content.push_str(&format!("\n... 另有 {} 个冲突", report.conflicts.len() - MAX_REPORTED_CONFLICTS));
+    }
+
+    let admins = users::Entity::find()
+        .select_only()
+        .column(users::Column::UserId)
+        .column(users::Column::UserUuid)
+        .join(JoinType::InnerJoin, users::Relation::UserRoles.def())
+        .join(JoinType::InnerJoin, user_roles::Relation::Roles.def())
+        .filter(roles::Column::RoleName.eq("SuperAdmin"))
+        .filter(active_assignment(user_roles::Column::ValidFrom, user_roles::Column::ValidUntil))
+        .filter(users::Column::TenantId.eq(DEFAULT_TENANT_ID))
+        .filter(users::Column::IsActive.eq(true))
+        .into_tuple::<(i32, String)>()
+        .all(&state.db)
+        .await?;
+    for (user_id, user_uuid) in admins {
+        let notification = NewNotification {
+            category: NOTIFICATION_CATEGORY_DIRECTORY.to_string(),
+            level: NOTIFICATION_WARNING.to_string(),
+            title: format!("LDAP 同步发现 {} 个冲突", report.conflicts.len()),
+            content: Some(content.clone()),
+            link: None,
+        };
+        notify_user(state, user_id, &user_uuid, notification).await?;
+    }
+    Ok(())
+}
+
+// base_dn 对应的本地部门
+async fn root_dept_id(txn: &DatabaseTransaction, config: &LdapConfig) -> Result<i32, AppError> {
+    let query = departments::Entity::find()
+        .select_only()
+        .column(departments::Column::DeptId)
+        .filter(departments::Column::TenantId.eq(DEFAULT_TENANT_ID))
+        .filter(departments::Column::IsDeleted.eq(false));
+    let query = if config.root_dept_uuid.is_empty() {
+        query.filter(departments::Column::ParentId.eq(ROOT_PARENT_ID))
+    } else {
+        query.filter(departments::Column::DeptUuid.eq(&config.root_dept_uuid))
+    };
+    query
+        .into_tuple::<i32>()
+        .one(txn)
+        .await?
+        .ok_or(not_found!("LDAP root department not found"))
+}
+
+async fn dept_uuid_of(txn: &DatabaseTransaction, dept_id: i32) -> Result<String, AppError> {
+    if dept_id == ROOT_PARENT_ID {
+        return Ok(ROOT_PARENT_UUID.to_string());
+    }
+    departments::Entity::find_by_id(dept_id)
+        .select_only()
+        .column(departments::Column::DeptUuid)
+        .into_tuple::<String>()
+        .one(txn)
+        .await?
+        .ok_or(not_found!("Department not found"))
+}
+
+/// 已关联的条目, external_id -> local_id
+async fn load_links(txn: &DatabaseTransaction, entry_type: &str) -> Result<HashMap<String, i32>, AppError> {
+    let links = directory_links::Entity::find()
+        .select_only()
+        .column(directory_links::Column::ExternalId)
+        .column(directory_links::Column::LocalId)
+        .filter(directory_links::Column::Source.eq(DIRECTORY_SOURCE_LDAP))
+        .filter(directory_links::Column::EntryType.eq(entry_type))
+        .into_tuple::<(String, i32)>()
+        .all(txn)
+        .await?
+        .into_iter()
+        .collect();
+    Ok(links)
+}
+
+async fn create_link(txn: &DatabaseTransaction, entry_type: &str, external_id: &str, local_id: i32) -> Result<(), AppError> {
+    let exists = directory_links::Entity::find()
+        .filter(directory_links::Column::Source.eq(DIRECTORY_SOURCE_LDAP))
+        .filter(directory_links::Column::EntryType.eq(entry_type))
+        .filter(directory_links::Column::ExternalId.eq(external_id))
+        .count(txn)
+        .await?;
+    if exists > 0 {
+        return Ok(());
+    }
+    directory_links::ActiveModel {
+        source: Set(DIRECTORY_SOURCE_LDAP.to_string()),
+        entry_type: Set(entry_type.to_string()),
+        external_id: Set(external_id.to_string()),
+        local_id: Set(local_id),
+        created_at: Set(Utc::now()),
+        updated_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(txn)
+    .await?;
+    Ok(())
+}
+
+async fn delete_link(txn: &DatabaseTransaction, entry_type: &str, external_id: &str) -> Result<(), AppError> {
+    directory_links::Entity::delete_many()
+        .filter(directory_links::Column::Source.eq(DIRECTORY_SOURCE_LDAP))
+        .filter(directory_links::Column::EntryType.eq(entry_type))
+        .filter(directory_links::Column::ExternalId.eq(external_id))
+        .exec(txn)
+        .await?;
+    Ok(())
+}
+
+// 属性名不区分大小写
+fn attr_values(entry: &SearchEntry, name: &str) -> Vec<String> {
+    entry
+        .attrs
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, values)| values.clone())
+        .unwrap_or_default()
+}
+
+fn attr(entry: &SearchEntry, name: &str) -> Option<String> {
+    attr_values(entry, name).into_iter().next().filter(|value| !value.is_empty())
+}
+
+// 唯一标识, 二进制值(如 objectGUID)转为十六进制, 没有时使用 DN
+fn external_id(entry: &SearchEntry, config: &LdapConfig) -> String {
+    attr(entry, &config.id_attr)
+        .or_else(|| {
+            entry
+                .bin_attrs
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(&config.id_attr))
+                .and_then(|(_, values)| values.first())
+                .map(hex::encode)
+        })
+        .unwrap_or_else(|| normalize_dn(&entry.dn))
+}
+
+// 目录没有返回任何用户, 或需要禁用的用户超过比例时不禁用
+fn check_disable_limit(seen: usize, linked: usize, missing: usize, max_percent: u32) -> Result<(), String> {
+    if missing == 0 {
+        return Ok(());
+    }
+    if seen == 0 {
+        return Err(format!("目录未返回任何用户, 未禁用 {} 个本地用户, 请检查目录连接和用户过滤条件", missing));
+    }
+    if missing * 100 > linked * max_percent as usize {
+        return Err(format!(
+            "需要禁用 {}/{} 个已关联用户, 超过 {}% 的上限, 未禁用, 请确认后调整 max_disable_percent",
+            missing, linked, max_percent
+        ));
+    }
+    Ok(())
+}
+
+// DN 比较时不区分大小写, 忽略分隔符两侧的空格
+fn normalize_dn(dn: &str) -> String {
+    dn.split(',')
+        .map(|part| part.trim().to_lowercase())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// ou=Engineering,dc=example,dc=org -> Engineering
+fn rdn_value(dn: &str) -> String {
+    let rdn = dn.split(',').next().unwrap_or(dn);
+    rdn.split_once('=').map(|(_, value)| value).unwrap_or(rdn).trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_not_disable_when_directory_returns_no_users() {
+        assert!(check_disable_limit(0, 10, 10, 100).is_err());
+        assert!(check_disable_limit(0, 0, 0, 20).is_ok());
+    }
+
+    #[test]
+    fn disable_limit_is_a_percentage_of_linked_users() {
+        assert!(check_disable_limit(90, 100, 20, 20).is_ok());
+        assert!(check_disable_limit(79, 100, 21, 20).is_err());
+        assert!(check_disable_limit(9, 10, 1, 0).is_err());
+        assert!(check_disable_limit(10, 10, 0, 0).is_ok());
+        assert!(check_disable_limit(1, 10, 9, 100).is_ok());
+    }
+
+    #[test]
+    fn normalizes_dn_for_comparison() {
+        assert_eq!(normalize_dn("OU=Engineering, DC=Example,dc=org "), "ou=engineering,dc=example,dc=org");
+        assert_eq!(rdn_value("ou=Engineering,dc=example,dc=org"), "Engineering");
+        assert_eq!(rdn_value("cn= Alice Smith ,ou=People"), "Alice Smith");
+        assert_eq!(rdn_value("noequals"), "noequals");
+    }
+}
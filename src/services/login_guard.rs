@@ -0,0 +1,173 @@
+// 登录防爆破: 按租户内用户名和IP统计失败次数, 指数退避并临时锁定
+use crate::config::security::{
+    LOGIN_FAILURES_IP, LOGIN_FAILURES_USER, LOGIN_LOCK_IP, LOGIN_LOCK_USER,
+};
+use crate::config::state::AppState;
+use crate::entity::users::{Column as UserColumn, Entity as UserEntity};
+use crate::errors::app_error::AppError;
+use crate::services::audit_log::AuditLogService;
+use crate::too_many_requests;
+use redis::AsyncCommands;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QuerySelect};
+
+const AUDIT_MODULE: &str = "auth";
+
+#[derive(Clone)]
+pub struct LoginGuardService {
+    app_state: AppState,
+    tenant_id: i32,
+}
+
+impl LoginGuardService {
+    pub fn new(app_state: AppState, tenant_id: i32) -> Self {
+        Self { app_state, tenant_id }
+    }
+
+    // 不同租户可以有同名用户, 用户名维度的计数按租户隔离
+    fn user_key(&self, prefix: &str, username: &str) -> String {
+        format!("{}:{}:{}", prefix, self.tenant_id, username)
+    }
+
+    /// 登录前检查用户名和IP是否处于退避或锁定期
+    pub async fn check(&self, username: &str, ip: &str) -> Result<(), AppError> {
+        let mut redis_conn = self
+            .app_state
+            .redis
+            .get_multiplexed_async_connection()
+            .await?;
+        let user_ttl: i64 = redis_conn
+            .ttl(self.user_key(LOGIN_LOCK_USER, username))
+            .await?;
+        let ip_ttl: i64 = redis_conn
+            .ttl(format!("{}:{}", LOGIN_LOCK_IP, ip))
+            .await?;
+
+        let retry_after = user_ttl.max(ip_ttl);
+        if retry_after > 0 {
+            return Err(too_many_requests!(
+                "Too many failed login attempts, please retry after {} seconds",
+                retry_after
+            )
+            .with_retry_after(retry_after as u64));
+        }
+        Ok(())
+    }
+
+    /// 记录一次登录失败, 达到阈值后进入退避或锁定
+    pub async fn record_failure(&self, username: &str, ip: &str) -> Result<(), AppError> {
+        let config = &self.app_state.config.security.login;
+        let window = config.failure_window_seconds as i64;
+        let user_key = self.user_key(LOGIN_FAILURES_USER, username);
+        let ip_key = format!("{}:{}", LOGIN_FAILURES_IP, ip);
+
+        let mut redis_conn = self
+            .app_state
+            .redis
+            .get_multiplexed_async_connection()
+            .await?;
+        let (user_failures, ip_failures): (u32, u32) = redis::pipe()
+            .atomic()
+            .incr(&user_key, 1)
+            .expire(&user_key, window)
+            .ignore()
+            .incr(&ip_key, 1)
+            .expire(&ip_key, window)
+            .ignore()
+            .query_async(&mut redis_conn)
+            .await?;
+
+        let user_lock_key = self.user_key(LOGIN_LOCK_USER, username);
+        if user_failures >= config.max_username_failures {
+            let _: () = redis_conn
+                .set_ex(&user_lock_key, user_failures, config.lockout_seconds)
+                .await?;
+            self.audit(
+                username,
+                format!("用户名 {} 连续登录失败 {} 次, 已锁定 {} 秒 (IP: {})",
+                        username, user_failures, config.lockout_seconds, ip),
+            )
+            .await;
+        } else if let Some(delay) = self.backoff_seconds(user_failures) {
+            let _: () = redis_conn.set_ex(&user_lock_key, user_failures, delay).await?;
+        }
+
+        // 同一IP后可能是整个办公网络, 只在达到上限后锁定, 不做退避, 避免个别用户输错密码拖慢其他用户
+        let ip_lock_key = format!("{}:{}", LOGIN_LOCK_IP, ip);
+        if ip_failures >= config.max_ip_failures {
+            let _: () = redis_conn
+                .set_ex(&ip_lock_key, ip_failures, config.lockout_seconds)
+                .await?;
+            self.audit(
+                username,
+                format!("IP {} 连续登录失败 {} 次, 已锁定 {} 秒",
+                        ip, ip_failures, config.lockout_seconds),
+            )
+            .await;
+        }
+
+        Ok(())
+    }
+
+    /// 登录成功后清除该用户名的失败计数
+    pub async fn record_success(&self, username: &str) -> Result<(), AppError> {
+        let mut redis_conn = self
+            .app_state
+            .redis
+            .get_multiplexed_async_connection()
+            .await?;
+        let _: () = redis_conn
+            .del(&[
+                self.user_key(LOGIN_FAILURES_USER, username),
+                self.user_key(LOGIN_LOCK_USER, username),
+            ])
+            .await?;
+        Ok(())
+    }
+
+    /// 管理员解锁用户
+    pub async fn unlock(&self, username: &str, operator: &str) -> Result<(), AppError> {
+        self.record_success(username).await?;
+        self.audit(username, format!("用户名 {} 已被 {} 解锁", username, operator))
+            .await;
+        Ok(())
+    }
+
+    // 失败次数超过 backoff_after 后, 每次失败退避时长翻倍, 不超过 backoff_max_seconds
+    fn backoff_seconds(&self, failures: u32) -> Option<u64> {
+        let config = &self.app_state.config.security.login;
+        if failures < config.backoff_after {
+            return None;
+        }
+        let factor = 1u64
+            .checked_shl(failures - config.backoff_after)
+            .unwrap_or(u64::MAX);
+        Some(
+            config
+                .backoff_base_seconds
+                .saturating_mul(factor)
+                .min(config.backoff_max_seconds),
+        )
+    }
+
+    // 审计日志写入失败不影响登录流程
+    async fn audit(&self, username: &str, summary: String) {
+        let user_uuid = UserEntity::find()
+            .select_only()
+            .column(UserColumn::UserUuid)
+            .filter(UserColumn::Username.eq(username))
+            .filter(UserColumn::TenantId.eq(self.tenant_id))
+            .into_tuple::<String>()
+            .one(&self.app_state.db)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| "0".to_string());
+
+        if let Err(e) = AuditLogService::new(self.app_state.clone())
+            .log_event(user_uuid, username.to_string(), AUDIT_MODULE.to_string(), summary)
+            .await
+        {
+            tracing::error!("Failed to log audit: {}", e);
+        }
+    }
+}
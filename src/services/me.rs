@@ -3,18 +3,33 @@ use crate::entity::{departments::{Column as DepartmentColumn, Entity as Departme
 use crate::errors::app_error::AppError;
 use crate::config::state::AppState;
 
-use crate::schemas::me::{Info, Profile, UiPolicies};
+use crate::schemas::me::{ChangeEmailDto, Info, PendingEmailChange, Profile, UiPolicies, UpdateProfileDto};
 use crate::schemas::user::{DeptResponse, GroupResponse};
 use crate::schemas::{auth::CurrentUser};
-use sea_orm::{ColumnTrait, ConnectionTrait, DbBackend, DbErr, EntityTrait, JoinType, ModelTrait, QueryFilter, QuerySelect, QueryTrait, RelationTrait, Statement};
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, Set, TransactionTrait, DbBackend, DbErr, EntityTrait, JoinType, ModelTrait, QueryFilter, QuerySelect, QueryTrait, RelationTrait, Statement};
 use tokio::task::JoinSet;
-use crate::not_found;
+use crate::{bad_request, conflict, not_found};
+use crate::config::app::EMAIL_CHANGE_PREFIX;
+use crate::config::auth::EMAIL_CHANGE_EXPIRATION;
+use crate::utils::templates::EmailTemplate;
+use bytes::Bytes;
+use crate::schemas::file::AvatarResponse;
+use crate::services::storage::{avatar_prefix, resize_square};
+use redis::AsyncCommands;
+use crate::schemas::password::ChangePasswordDto;
+use crate::services::password::{hash_new_password, record_password_history};
+use crate::services::auth::invalidate_user_sessions;
+use crate::utils::crypto::verify_password;
 use crate::schemas::cedar_policy::CedarContext;
-use crate::services::role::get_role_models_by_user_uuid;
+use crate::services::role::{get_inherited_roles, get_role_models_by_user_uuid, get_role_parent_map};
 use crate::services::user::UserService;
 use crate::utils::cedar_utils::{AuthAction, ResourceType};
 
 
+fn avatar_thumbnail_key(avatar_key: &str) -> String {
+    format!("{}_thumb.png", avatar_key.trim_end_matches(".png"))
+}
+
 type UiKey = &'static str;
 type UiActionMap = &'static [(UiKey, AuthAction)];
 
@@ -62,6 +77,200 @@ impl MeService {
         Self { app_state }
     }
 
+    /// 修改当前用户的基本资料
+    pub async fn update_profile(
+        &self,
+        current_user: CurrentUser,
+        dto: UpdateProfileDto,
+    ) -> Result<Info, AppError> {
+        let user = UserEntity::find()
+            .filter(UserColumn::UserUuid.eq(&current_user.uuid))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("User not found"))?;
+
+        let mut user: users::ActiveModel = user.into();
+        if let Some(alias) = dto.alias {
+            user.alias = Set(Some(alias));
+        }
+        if let Some(phone) = dto.phone {
+            user.phone = Set(Some(phone));
+        }
+        if let Some(avatar) = dto.avatar {
+            user.avatar = Set(Some(avatar));
+        }
+        user.save(&self.app_state.db).await?;
+
+        self.info(&current_user).await
+    }
+
+    /// 上传头像, 生成头像和缩略图并替换旧头像
+    pub async fn upload_avatar(
+        &self,
+        current_user: CurrentUser,
+        content_type: Option<String>,
+        data: Bytes,
+    ) -> Result<AvatarResponse, AppError> {
+        let storage = &self.app_state.storage_service;
+        storage.validate_image(content_type.as_deref(), &data)?;
+
+        let user = UserEntity::find()
+            .filter(UserColumn::UserUuid.eq(&current_user.uuid))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("User not found"))?;
+
+        let file_id = uuid::Uuid::new_v4();
+        let owner_prefix = avatar_prefix(&current_user.uuid);
+        let avatar_key = format!("{}{}.png", owner_prefix, file_id);
+        let thumbnail_key = avatar_thumbnail_key(&avatar_key);
+
+        let avatar = resize_square(data.clone(), storage.avatar_size()).await?;
+        let thumbnail = resize_square(data, storage.thumbnail_size()).await?;
+        storage.put(&avatar_key, avatar).await?;
+        storage.put(&thumbnail_key, thumbnail).await?;
+
+        let old_avatar = user.avatar.clone();
+        let mut user: users::ActiveModel = user.into();
+        user.avatar = Set(Some(avatar_key.clone()));
+        user.save(&self.app_state.db).await?;
+
+        // 只删除用户自己目录下的旧头像, 删除失败不影响本次上传
+        if let Some(old_avatar) = old_avatar.filter(|a| a.starts_with(&owner_prefix)) {
+            for key in [avatar_thumbnail_key(&old_avatar), old_avatar] {
+                if let Err(e) = storage.delete(&key).await {
+                    tracing::warn!("Failed to delete old avatar {}: {}", key, e);
+                }
+            }
+        }
+
+        Ok(AvatarResponse {
+            avatar_url: storage.signed_url(&avatar_key).await?,
+            thumbnail_url: storage.signed_url(&thumbnail_key).await?,
+        })
+    }
+
+    /// 申请修改邮箱, 向新邮箱发送确认邮件
+    pub async fn request_email_change(
+        &self,
+        current_user: CurrentUser,
+        dto: ChangeEmailDto,
+    ) -> Result<(), AppError> {
+        if UserEntity::find()
+            .filter(UserColumn::Email.eq(&dto.new_email))
+            .filter(UserColumn::TenantId.eq(current_user.tenant_id))
+            .one(&self.app_state.db)
+            .await?
+            .is_some()
+        {
+            return Err(conflict!("Email already exists"));
+        }
+
+        let token = uuid::Uuid::new_v4().to_string();
+        let pending = PendingEmailChange {
+            user_uuid: current_user.uuid.clone(),
+            new_email: dto.new_email.clone(),
+        };
+        let mut redis_conn = self
+            .app_state
+            .redis
+            .get_multiplexed_async_connection()
+            .await?;
+        let _: () = redis_conn
+            .set_ex(
+                format!("{}:{}", EMAIL_CHANGE_PREFIX, token),
+                serde_json::to_string(&pending)?,
+                EMAIL_CHANGE_EXPIRATION,
+            )
+            .await?;
+
+        let confirm_url = self
+            .app_state
+            .config
+            .server
+            .public_link(&format!("/confirm-email/{}", token))
+            .map_err(|e| AppError::internal_server_error(anyhow::anyhow!(e)))?;
+
+        self.app_state
+            .email_service
+            .enqueue(
+                &dto.new_email,
+                EmailTemplate::EmailChange { confirm_url: confirm_url.as_str() },
+                &dto.language,
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// 确认邮箱修改
+    pub async fn confirm_email_change(
+        &self,
+        current_user: CurrentUser,
+        token: String,
+    ) -> Result<Info, AppError> {
+        let key = format!("{}:{}", EMAIL_CHANGE_PREFIX, token);
+        let mut redis_conn = self
+            .app_state
+            .redis
+            .get_multiplexed_async_connection()
+            .await?;
+        let pending: Option<String> = redis_conn.get(&key).await?;
+        let pending: PendingEmailChange = match pending {
+            Some(pending) => serde_json::from_str(&pending)?,
+            None => return Err(not_found!("Email change token not found or expired")),
+        };
+        if pending.user_uuid != current_user.uuid {
+            return Err(not_found!("Email change token not found or expired"));
+        }
+
+        let user = UserEntity::find()
+            .filter(UserColumn::UserUuid.eq(&current_user.uuid))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("User not found"))?;
+        let mut user: users::ActiveModel = user.into();
+        user.email = Set(pending.new_email);
+        // 邮箱唯一约束冲突会转换为 409
+        user.save(&self.app_state.db).await?;
+
+        let _: () = redis_conn.del(&key).await?;
+        self.info(&current_user).await
+    }
+
+    /// 修改当前用户密码
+    pub async fn change_password(
+        &self,
+        current_user: CurrentUser,
+        dto: ChangePasswordDto,
+    ) -> Result<(), AppError> {
+        let user = UserEntity::find()
+            .filter(UserColumn::UserUuid.eq(&current_user.uuid))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("User not found"))?;
+
+        if !verify_password(&dto.current_password, &user.password)? {
+            return Err(bad_request!("Current password is incorrect"));
+        }
+
+        let policy = &self.app_state.config.security.password;
+        let txn = self.app_state.db.begin().await?;
+        let password_hash = hash_new_password(&txn, policy, Some(&user), &user.username, &dto.new_password).await?;
+
+        let user_id = user.user_id;
+        let mut user: users::ActiveModel = user.into();
+        user.password = Set(password_hash.clone());
+        user.password_changed_at = Set(Some(chrono::Local::now().naive_local()));
+        user.save(&txn).await?;
+
+        record_password_history(&txn, policy, user_id, &password_hash).await?;
+        txn.commit().await?;
+
+        // 修改密码后注销该用户的所有会话, 需要重新登录
+        invalidate_user_sessions(&self.app_state, user_id, &current_user.uuid).await?;
+        Ok(())
+    }
+
     pub async fn profile(&self,
                          current_user: CurrentUser,
                          context: CedarContext
@@ -165,19 +374,25 @@ impl MeService {
     }
 
     async fn info(&self, current_user: &CurrentUser) -> Result<Info, AppError> {
-        let user = UserEntity::find()
+        let mut user = UserEntity::find()
             .filter(users::Column::UserUuid.eq(&current_user.uuid))
             .into_model::<Info>()
             .one(&self.app_state.db)
             .await?
             .ok_or(not_found!("User not found".to_string()))?;
+
+        user.avatar = self.app_state.storage_service.avatar_url(&current_user.uuid, user.avatar.take()).await?;
         Ok(user)
     }
 
     async fn roles(&self, current_user: &CurrentUser) -> Result<Vec<String>, AppError> {
-        let role_names = get_role_models_by_user_uuid(&self.app_state.db, current_user.uuid.clone())
-            .await?
+        let granted_roles = get_role_models_by_user_uuid(&self.app_state.db, current_user.uuid.clone()).await?;
+        // 包含通过继承获得的角色
+        let parent_map = get_role_parent_map(&self.app_state.db, current_user.tenant_id).await?;
+        let inherited_roles = get_inherited_roles(&self.app_state.db, &parent_map, &granted_roles).await?;
+        let role_names = granted_roles
             .into_iter()
+            .chain(inherited_roles.into_iter().map(|(role, _)| role))
             .map(|x| x.role_name)
             .collect::<Vec<String>>();
 
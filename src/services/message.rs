@@ -0,0 +1,269 @@
+// 消息发送: 按用户、用户组、部门或全员发送通知, 支持定时发送
+use chrono::Utc;
+use redis::AsyncCommands;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QuerySelect};
+use tokio::time::{sleep, Duration};
+use tracing::{error, info, warn};
+
+use crate::config::app::{SCHEDULED_MESSAGES_JOBS, SCHEDULED_MESSAGES_QUEUE};
+use crate::config::state::AppState;
+use crate::entity::{departments, users};
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::message::{MessageTarget, ScheduledMessage, SendMessageDto, SendMessageResponse};
+use crate::schemas::notification::{NewNotification, NOTIFICATION_CATEGORY_MESSAGE};
+use crate::services::department::{get_all_child_dept_ids, get_dept_entities};
+use crate::services::groups::{get_group_entities, get_group_member_ids};
+use crate::services::notification::notify_user;
+use crate::services::user::get_user_entities;
+use crate::utils::cedar_utils::{AuthAction, ResourceType};
+use crate::{bad_request, not_found};
+
+// 定时消息的检查间隔
+const SCHEDULE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Clone)]
+pub struct MessageService {
+    app_state: AppState,
+}
+
+impl MessageService {
+    pub fn new(app_state: AppState) -> Self {
+        Self { app_state }
+    }
+
+    pub async fn send_message(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        dto: SendMessageDto,
+    ) -> Result<SendMessageResponse, AppError> {
+        self.check_send_permission(&current_user, context, &dto.target).await?;
+        // 链接会推送给所有接收人, 只允许站内链接
+        if let Some(link) = &dto.link
+            && !self.app_state.config.server.is_public_link(link)
+        {
+            return Err(bad_request!(
+                "Link must be a relative path or a URL on {}",
+                self.app_state.config.server.public_url
+            ));
+        }
+
+        // 立即发送的消息同样放入队列, 由后台任务逐个通知接收人, 请求不等待发送完成
+        let (scheduled_at, recipients) = match dto.scheduled_at {
+            Some(scheduled_at) if scheduled_at > Utc::now() => (Some(scheduled_at), 0),
+            _ => {
+                let recipients = resolve_recipients(&self.app_state, current_user.tenant_id, &dto.target).await?;
+                (None, recipients.len() as u64)
+            }
+        };
+        let uuid = uuid::Uuid::new_v4().to_string();
+        let job = ScheduledMessage {
+            uuid: uuid.clone(),
+            sender_uuid: current_user.uuid,
+            tenant_id: current_user.tenant_id,
+            message: dto,
+        };
+        let send_at = scheduled_at.unwrap_or_else(Utc::now);
+        let mut redis_conn = self.app_state.redis.get_multiplexed_async_connection().await?;
+        let _: () = redis::pipe()
+            .atomic()
+            .hset(SCHEDULED_MESSAGES_JOBS, &uuid, serde_json::to_string(&job)?)
+            .zadd(SCHEDULED_MESSAGES_QUEUE, &uuid, send_at.timestamp())
+            .query_async(&mut redis_conn)
+            .await?;
+        Ok(SendMessageResponse {
+            uuid,
+            recipients,
+            scheduled_at,
+        })
+    }
+
+    /// 取消尚未发送的定时消息
+    pub async fn cancel_scheduled_message(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        message_uuid: String,
+    ) -> Result<(), AppError> {
+        let mut redis_conn = self.app_state.redis.get_multiplexed_async_connection().await?;
+        let job: Option<String> = redis_conn.hget(SCHEDULED_MESSAGES_JOBS, &message_uuid).await?;
+        let job: ScheduledMessage = serde_json::from_str(
+            &job.ok_or(not_found!("Scheduled message not found"))?,
+        )?;
+        if job.tenant_id != current_user.tenant_id {
+            return Err(not_found!("Scheduled message not found"));
+        }
+        self.check_send_permission(&current_user, context, &job.message.target).await?;
+
+        let removed: i64 = redis_conn.zrem(SCHEDULED_MESSAGES_QUEUE, &message_uuid).await?;
+        if removed == 0 {
+            return Err(not_found!("Scheduled message not found"));
+        }
+        let _: () = redis_conn.hdel(SCHEDULED_MESSAGES_JOBS, &message_uuid).await?;
+        Ok(())
+    }
+
+    async fn check_send_permission(
+        &self,
+        current_user: &CurrentUser,
+        context: CedarContext,
+        target: &MessageTarget,
+    ) -> Result<(), AppError> {
+        let db = &self.app_state.db;
+        let auth_service = &self.app_state.auth_service;
+        let schema = auth_service.get_schema_copy(current_user.tenant_id).await;
+        match target {
+            MessageTarget::User { uuid } => {
+                let es = get_user_entities(db, current_user.tenant_id, uuid.clone(), &schema).await?;
+                auth_service
+                    .check_permission_with_entities(
+                        &current_user.uuid,
+                        context,
+                        AuthAction::SendMessage,
+                        ResourceType::User(Some(uuid.clone())),
+                        es,
+                    )
+                    .await?;
+            }
+            MessageTarget::Group { uuid } => {
+                let es = get_group_entities(db, current_user.tenant_id, std::slice::from_ref(uuid), &schema).await?;
+                auth_service
+                    .check_permission_with_entities(
+                        &current_user.uuid,
+                        context,
+                        AuthAction::SendMessage,
+                        ResourceType::Group(Some(uuid.clone())),
+                        es,
+                    )
+                    .await?;
+            }
+            MessageTarget::Department { uuid, .. } => {
+                let es = get_dept_entities(db, current_user.tenant_id, uuid, &schema).await?;
+                auth_service
+                    .check_permission_with_entities(
+                        &current_user.uuid,
+                        context,
+                        AuthAction::SendMessage,
+                        ResourceType::Department(Some(uuid.clone())),
+                        es,
+                    )
+                    .await?;
+            }
+            MessageTarget::All => {
+                auth_service
+                    .check_permission(
+                        &current_user.uuid,
+                        context,
+                        AuthAction::SendMessage,
+                        ResourceType::User(None),
+                    )
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 解析接收人: 租户内启用的用户, 没有接收人时返回错误
+async fn resolve_recipients(
+    app_state: &AppState,
+    tenant_id: i32,
+    target: &MessageTarget,
+) -> Result<Vec<(i32, String)>, AppError> {
+    let db = &app_state.db;
+    let mut query = users::Entity::find()
+        .select_only()
+        .column(users::Column::UserId)
+        .column(users::Column::UserUuid)
+        .filter(users::Column::TenantId.eq(tenant_id))
+        .filter(users::Column::IsActive.eq(true));
+    query = match target {
+        MessageTarget::User { uuid } => query.filter(users::Column::UserUuid.eq(uuid)),
+        MessageTarget::Group { uuid } => {
+            query.filter(users::Column::UserId.is_in(get_group_member_ids(db, uuid).await?))
+        }
+        MessageTarget::Department { uuid, include_children } => {
+            let dept_ids = if *include_children {
+                get_all_child_dept_ids(db, tenant_id, uuid).await?
+            } else {
+                departments::Entity::find()
+                    .select_only()
+                    .column(departments::Column::DeptId)
+                    .filter(departments::Column::DeptUuid.eq(uuid))
+                    .filter(departments::Column::TenantId.eq(tenant_id))
+                    .into_tuple::<i32>()
+                    .all(db)
+                    .await?
+            };
+            query.filter(users::Column::DeptId.is_in(dept_ids))
+        }
+        MessageTarget::All => query,
+    };
+    let recipients = query.into_tuple::<(i32, String)>().all(db).await?;
+    if recipients.is_empty() {
+        return Err(bad_request!("No recipients found"));
+    }
+    Ok(recipients)
+}
+
+/// 逐个发送通知, 返回送达的用户数量
+async fn deliver_message(app_state: &AppState, tenant_id: i32, dto: &SendMessageDto) -> Result<u64, AppError> {
+    let recipients = resolve_recipients(app_state, tenant_id, &dto.target).await?;
+    let mut delivered = 0;
+    for (user_id, user_uuid) in recipients {
+        let notification = NewNotification {
+            category: NOTIFICATION_CATEGORY_MESSAGE.to_string(),
+            level: dto.level.clone(),
+            title: dto.title.clone(),
+            content: dto.content.clone(),
+            link: dto.link.clone(),
+        };
+        match notify_user(app_state, user_id, &user_uuid, notification).await {
+            Ok(_) => delivered += 1,
+            Err(e) => warn!("向用户 {} 发送消息失败: {}", user_uuid, e),
+        }
+    }
+    Ok(delivered)
+}
+
+// 后台任务：发送队列中到期的消息
+pub async fn run_scheduled_messages(state: AppState) {
+    loop {
+        if let Err(e) = send_due_messages(&state).await {
+            error!("发送定时消息失败: {}", e);
+        }
+        sleep(SCHEDULE_POLL_INTERVAL).await;
+    }
+}
+
+async fn send_due_messages(state: &AppState) -> Result<(), AppError> {
+    let mut redis_conn = state.redis.get_multiplexed_async_connection().await?;
+    let due: Vec<String> = redis_conn
+        .zrangebyscore(SCHEDULED_MESSAGES_QUEUE, "-inf", Utc::now().timestamp())
+        .await?;
+
+    for message_uuid in due {
+        // ZREM 成功的节点负责发送, 多节点部署时不会重复发送
+        let claimed: i64 = redis_conn.zrem(SCHEDULED_MESSAGES_QUEUE, &message_uuid).await?;
+        if claimed == 0 {
+            continue;
+        }
+        let job: Option<String> = redis_conn.hget(SCHEDULED_MESSAGES_JOBS, &message_uuid).await?;
+        let _: () = redis_conn.hdel(SCHEDULED_MESSAGES_JOBS, &message_uuid).await?;
+        let Some(job) = job.and_then(|job| serde_json::from_str::<ScheduledMessage>(&job).ok()) else {
+            warn!("定时消息 {} 的内容丢失或无法解析，跳过。", message_uuid);
+            continue;
+        };
+
+        match deliver_message(state, job.tenant_id, &job.message).await {
+            Ok(delivered) => info!(
+                "定时消息 {} (发送人 {}) 已发送给 {} 个用户",
+                job.uuid, job.sender_uuid, delivered
+            ),
+            Err(e) => error!("定时消息 {} 发送失败: {}", job.uuid, e),
+        }
+    }
+    Ok(())
+}
@@ -13,3 +13,23 @@ pub mod cedar_policy;
 pub mod cedar_schema;
 pub mod email;
 pub mod policy_link_manager;
+pub mod login_guard;
+pub mod storage;
+pub mod file;
+pub mod invitation;
+pub mod notification;
+pub mod message;
+pub mod event_bus;
+pub mod webhook;
+pub mod scim;
+pub mod auth_backend;
+pub mod ldap;
+pub mod oidc;
+pub mod oauth;
+pub mod oauth_client;
+pub mod personal_token;
+pub mod robot_account;
+pub mod system;
+pub mod tenant;
+pub mod role_request;
+pub mod access_review;
\ No newline at end of file
@@ -0,0 +1,137 @@
+// 通知中心: 通知持久化到数据库, 在线用户通过 SSE 实时推送
+use chrono::Utc;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+
+use crate::config::state::AppState;
+use crate::entity::{notifications, users};
+use crate::errors::app_error::AppError;
+use crate::not_found;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::notification::{NewNotification, NotificationQueryParams, NotificationResponse};
+use crate::utils::sse::{sse_push_message, SSE_EVENT_NOTIFICATION};
+
+#[derive(Clone)]
+pub struct NotificationService {
+    app_state: AppState,
+}
+
+impl NotificationService {
+    pub fn new(app_state: AppState) -> Self {
+        Self { app_state }
+    }
+
+    pub async fn list_notifications(
+        &self,
+        current_user: CurrentUser,
+        params: NotificationQueryParams,
+    ) -> Result<(Vec<NotificationResponse>, u64), AppError> {
+        let user_id = self.user_id(&current_user.uuid).await?;
+
+        let mut query = notifications::Entity::find()
+            .filter(notifications::Column::UserId.eq(user_id));
+        if let Some(category) = &params.category {
+            query = query.filter(notifications::Column::Category.eq(category));
+        }
+        if params.unread_only {
+            query = query.filter(notifications::Column::IsRead.eq(false));
+        }
+
+        let paginator = query
+            .order_by_desc(notifications::Column::Id)
+            .paginate(&self.app_state.db, params.page_size);
+        let total = paginator.num_items().await?;
+        let page_index = if params.page > 0 { params.page - 1 } else { 0 };
+        let results = paginator
+            .fetch_page(page_index)
+            .await?
+            .into_iter()
+            .map(NotificationResponse::from)
+            .collect();
+
+        Ok((results, total))
+    }
+
+    pub async fn unread_count(&self, current_user: CurrentUser) -> Result<u64, AppError> {
+        let user_id = self.user_id(&current_user.uuid).await?;
+        let count = notifications::Entity::find()
+            .filter(notifications::Column::UserId.eq(user_id))
+            .filter(notifications::Column::IsRead.eq(false))
+            .count(&self.app_state.db)
+            .await?;
+        Ok(count)
+    }
+
+    pub async fn mark_read(
+        &self,
+        current_user: CurrentUser,
+        notification_uuid: String,
+    ) -> Result<(), AppError> {
+        let user_id = self.user_id(&current_user.uuid).await?;
+        let notification = notifications::Entity::find()
+            .filter(notifications::Column::NotificationUuid.eq(&notification_uuid))
+            .filter(notifications::Column::UserId.eq(user_id))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("Notification not found"))?;
+
+        if !notification.is_read {
+            let mut notification: notifications::ActiveModel = notification.into();
+            notification.is_read = Set(true);
+            notification.read_at = Set(Some(Utc::now()));
+            notification.update(&self.app_state.db).await?;
+        }
+        Ok(())
+    }
+
+    /// 全部标记为已读, 返回更新的条数
+    pub async fn mark_all_read(&self, current_user: CurrentUser) -> Result<u64, AppError> {
+        let user_id = self.user_id(&current_user.uuid).await?;
+        let result = notifications::Entity::update_many()
+            .col_expr(notifications::Column::IsRead, Expr::value(true))
+            .col_expr(notifications::Column::ReadAt, Expr::value(Utc::now()))
+            .filter(notifications::Column::UserId.eq(user_id))
+            .filter(notifications::Column::IsRead.eq(false))
+            .exec(&self.app_state.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    async fn user_id(&self, user_uuid: &str) -> Result<i32, AppError> {
+        users::Entity::find()
+            .select_only()
+            .column(users::Column::UserId)
+            .filter(users::Column::UserUuid.eq(user_uuid))
+            .into_tuple::<i32>()
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("User not found"))
+    }
+}
+
+/// 保存通知并推送给用户, 用户不在线时进入离线队列, 上线后补发
+pub async fn notify_user(
+    app_state: &AppState,
+    user_id: i32,
+    user_uuid: &str,
+    notification: NewNotification,
+) -> Result<NotificationResponse, AppError> {
+    let notification = notifications::ActiveModel {
+        notification_uuid: Set(uuid::Uuid::new_v4().to_string()),
+        user_id: Set(user_id),
+        category: Set(notification.category),
+        level: Set(notification.level),
+        title: Set(notification.title),
+        content: Set(notification.content),
+        link: Set(notification.link),
+        is_read: Set(false),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(&app_state.db)
+    .await?;
+
+    let response = NotificationResponse::from(notification);
+    sse_push_message(app_state, user_uuid.to_string(), SSE_EVENT_NOTIFICATION, &response).await?;
+    Ok(response)
+}
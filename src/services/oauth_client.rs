@@ -0,0 +1,279 @@
+// OAuth2 客户端应用的注册与管理
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+use url::Url;
+
+use crate::config::state::AppState;
+use crate::entity::{oauth_clients, users};
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::oauth::{
+    CreateOAuthClientDto, OAuthClientQueryParams, OAuthClientResponse, UpdateOAuthClientDto,
+    SUPPORTED_SCOPES,
+};
+use crate::utils::cedar_utils::{AuthAction, ResourceType};
+use crate::utils::crypto::{generate_secret, hash_token};
+use crate::{bad_request, conflict, not_found};
+
+#[derive(Clone)]
+pub struct OAuthClientService {
+    app_state: AppState,
+}
+
+impl OAuthClientService {
+    pub fn new(app_state: AppState) -> Self {
+        Self { app_state }
+    }
+
+    pub async fn list_clients(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        params: OAuthClientQueryParams,
+    ) -> Result<(Vec<OAuthClientResponse>, u64), AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::ViewOAuthClient,
+                ResourceType::OAuthClient(None),
+            )
+            .await?;
+
+        let paginator = oauth_clients::Entity::find()
+            .filter(oauth_clients::Column::TenantId.eq(current_user.tenant_id))
+            .order_by_desc(oauth_clients::Column::Id)
+            .paginate(&self.app_state.db, params.page_size);
+        let total = paginator.num_items().await?;
+        let page_index = if params.page > 0 { params.page - 1 } else { 0 };
+        let results = paginator
+            .fetch_page(page_index)
+            .await?
+            .into_iter()
+            .map(OAuthClientResponse::from)
+            .collect();
+
+        Ok((results, total))
+    }
+
+    pub async fn get_client(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        client_id: String,
+    ) -> Result<OAuthClientResponse, AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::ViewOAuthClient,
+                ResourceType::OAuthClient(Some(client_id.clone())),
+            )
+            .await?;
+
+        Ok(OAuthClientResponse::from(self.find_client(&current_user, &client_id).await?))
+    }
+
+    pub async fn create_client(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        dto: CreateOAuthClientDto,
+    ) -> Result<OAuthClientResponse, AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::CreateOAuthClient,
+                ResourceType::OAuthClient(None),
+            )
+            .await?;
+        validate_redirect_uris(&dto.redirect_uris)?;
+        validate_scopes(&dto.allowed_scopes)?;
+        self.ensure_name_available(&current_user, &dto.name, None).await?;
+
+        let created_by = users::Entity::find()
+            .select_only()
+            .column(users::Column::UserId)
+            .filter(users::Column::UserUuid.eq(&current_user.uuid))
+            .into_tuple::<i32>()
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("User {} not found", current_user.uuid))?;
+
+        let client_secret = dto.is_confidential.then(generate_secret);
+        let now = Utc::now();
+        let client = oauth_clients::ActiveModel {
+            client_id: Set(uuid::Uuid::new_v4().simple().to_string()),
+            tenant_id: Set(current_user.tenant_id),
+            name: Set(dto.name),
+            description: Set(dto.description),
+            secret_hash: Set(client_secret.as_deref().map(hash_token)),
+            redirect_uris: Set(serde_json::to_value(dto.redirect_uris)?),
+            allowed_scopes: Set(serde_json::to_value(dto.allowed_scopes)?),
+            skip_consent: Set(dto.skip_consent),
+            is_active: Set(dto.is_active),
+            created_by: Set(created_by),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&self.app_state.db)
+        .await?;
+
+        let mut response = OAuthClientResponse::from(client);
+        response.client_secret = client_secret;
+        Ok(response)
+    }
+
+    pub async fn update_client(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        client_id: String,
+        dto: UpdateOAuthClientDto,
+    ) -> Result<OAuthClientResponse, AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::UpdateOAuthClient,
+                ResourceType::OAuthClient(Some(client_id.clone())),
+            )
+            .await?;
+
+        let client = self.find_client(&current_user, &client_id).await?;
+        if let Some(name) = &dto.name {
+            self.ensure_name_available(&current_user, name, Some(client.id)).await?;
+        }
+        let mut client: oauth_clients::ActiveModel = client.into();
+        if let Some(name) = dto.name { client.name = Set(name); }
+        if let Some(description) = dto.description { client.description = Set(Some(description)); }
+        if let Some(skip_consent) = dto.skip_consent { client.skip_consent = Set(skip_consent); }
+        if let Some(is_active) = dto.is_active { client.is_active = Set(is_active); }
+        if let Some(redirect_uris) = dto.redirect_uris {
+            validate_redirect_uris(&redirect_uris)?;
+            client.redirect_uris = Set(serde_json::to_value(redirect_uris)?);
+        }
+        if let Some(allowed_scopes) = dto.allowed_scopes {
+            validate_scopes(&allowed_scopes)?;
+            client.allowed_scopes = Set(serde_json::to_value(allowed_scopes)?);
+        }
+        client.updated_at = Set(Utc::now());
+
+        let client = client.update(&self.app_state.db).await?;
+        Ok(OAuthClientResponse::from(client))
+    }
+
+    /// 重置机密客户端的密钥, 旧密钥立即失效
+    pub async fn rotate_secret(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        client_id: String,
+    ) -> Result<OAuthClientResponse, AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::UpdateOAuthClient,
+                ResourceType::OAuthClient(Some(client_id.clone())),
+            )
+            .await?;
+
+        let client = self.find_client(&current_user, &client_id).await?;
+        if client.secret_hash.is_none() {
+            return Err(bad_request!("Public clients have no secret"));
+        }
+        let client_secret = generate_secret();
+        let mut client: oauth_clients::ActiveModel = client.into();
+        client.secret_hash = Set(Some(hash_token(&client_secret)));
+        client.updated_at = Set(Utc::now());
+        let client = client.update(&self.app_state.db).await?;
+
+        let mut response = OAuthClientResponse::from(client);
+        response.client_secret = Some(client_secret);
+        Ok(response)
+    }
+
+    pub async fn delete_client(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        client_id: String,
+    ) -> Result<(), AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::DeleteOAuthClient,
+                ResourceType::OAuthClient(Some(client_id.clone())),
+            )
+            .await?;
+
+        let result = oauth_clients::Entity::delete_many()
+            .filter(oauth_clients::Column::ClientId.eq(&client_id))
+            .filter(oauth_clients::Column::TenantId.eq(current_user.tenant_id))
+            .exec(&self.app_state.db)
+            .await?;
+        if result.rows_affected == 0 {
+            return Err(not_found!("OAuth client {} not found", client_id));
+        }
+        Ok(())
+    }
+
+    async fn find_client(&self, current_user: &CurrentUser, client_id: &str) -> Result<oauth_clients::Model, AppError> {
+        oauth_clients::Entity::find()
+            .filter(oauth_clients::Column::ClientId.eq(client_id))
+            .filter(oauth_clients::Column::TenantId.eq(current_user.tenant_id))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("OAuth client {} not found", client_id))
+    }
+
+    // 应用名称在租户内唯一, 更新时排除自身
+    async fn ensure_name_available(
+        &self,
+        current_user: &CurrentUser,
+        name: &str,
+        exclude_id: Option<i32>,
+    ) -> Result<(), AppError> {
+        let mut query = oauth_clients::Entity::find()
+            .filter(oauth_clients::Column::TenantId.eq(current_user.tenant_id))
+            .filter(oauth_clients::Column::Name.eq(name));
+        if let Some(id) = exclude_id {
+            query = query.filter(oauth_clients::Column::Id.ne(id));
+        }
+        if query.count(&self.app_state.db).await? > 0 {
+            return Err(conflict!("OAuth client {} already exists", name));
+        }
+        Ok(())
+    }
+}
+
+// 回调地址必须是不带 fragment 的绝对地址, 授权时按完整字符串匹配
+fn validate_redirect_uris(redirect_uris: &[String]) -> Result<(), AppError> {
+    for redirect_uri in redirect_uris {
+        let url = Url::parse(redirect_uri).map_err(|_| bad_request!("Invalid redirect URI {}", redirect_uri))?;
+        if url.fragment().is_some() {
+            return Err(bad_request!("Redirect URI {} must not contain a fragment", redirect_uri));
+        }
+    }
+    Ok(())
+}
+
+fn validate_scopes(scopes: &[String]) -> Result<(), AppError> {
+    for scope in scopes {
+        if !SUPPORTED_SCOPES.iter().any(|(name, _)| name == scope) {
+            return Err(bad_request!("Unsupported scope {}", scope));
+        }
+    }
+    Ok(())
+}
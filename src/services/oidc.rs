@@ -0,0 +1,897 @@
+// OpenID Connect 单点登录: 授权码 + PKCE, 校验 ID Token 后按声明创建或更新用户
+// 登录用户通过 directory_links 关联, source 为 oidc:{provider}, external_id 为 sub, 只在默认租户中创建和匹配用户
+use axum_extra::extract::cookie::{Cookie, CookieJar};
+use chrono::Utc;
+use cookie::{time::Duration as CookieDuration, SameSite};
+use jsonwebtoken::jwk::{Jwk, JwkSet};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use redis::AsyncCommands;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseTransaction, EntityTrait, QueryFilter,
+    QuerySelect, Set, TransactionTrait,
+};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::time::Duration as StdDuration;
+use url::Url;
+use uuid::Uuid;
+
+use crate::config::app::{OIDC_METADATA, OIDC_STATE};
+use crate::config::oidc::OidcProviderConfig;
+use crate::config::state::AppState;
+use crate::entity::{departments, directory_links, roles, user_group_members, user_groups, user_roles, users};
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::{AuthResponse, CurrentUser};
+use crate::schemas::event::DomainEvent;
+use crate::schemas::tenant::DEFAULT_TENANT_ID;
+use crate::schemas::oidc::{
+    OidcAuthorizeResponse, OidcCachedMetadata, OidcCallback, OidcLoginState, OidcProviderInfo,
+    OidcProviderMetadata, OidcTokenResponse,
+};
+use crate::services::auth::AuthService;
+use crate::services::event_bus::publish_event;
+use crate::services::ldap::ENTRY_TYPE_USER;
+use crate::utils::crypto::{generate_secret, hash_password, hash_token, pkce_challenge};
+use crate::{conflict, not_found, unauthorized};
+
+// 保存 state 哈希的 Cookie, 回调时校验 state 由同一浏览器发起, 防止登录 CSRF
+const OIDC_STATE_COOKIE: &str = "oidc_state";
+const OIDC_STATE_COOKIE_PATH: &str = "/api/v1/auth/oidc";
+
+#[derive(Clone)]
+pub struct OidcService {
+    app_state: AppState,
+    auth_service: AuthService,
+    client: reqwest::Client,
+}
+
+impl OidcService {
+    pub fn new(app_state: AppState) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(StdDuration::from_secs(app_state.config.oidc.timeout_seconds))
+            .build()
+            .unwrap_or_default();
+        Self {
+            auth_service: AuthService::new(app_state.clone()),
+            app_state,
+            client,
+        }
+    }
+
+    pub fn list_providers(&self) -> Vec<OidcProviderInfo> {
+        self.app_state
+            .config
+            .oidc
+            .providers
+            .iter()
+            .map(|provider| OidcProviderInfo {
+                name: provider.name.clone(),
+                display_name: if provider.display_name.is_empty() {
+                    provider.name.clone()
+                } else {
+                    provider.display_name.clone()
+                },
+            })
+            .collect()
+    }
+
+    fn provider(&self, name: &str) -> Result<&OidcProviderConfig, AppError> {
+        self.app_state
+            .config
+            .oidc
+            .provider(name)
+            .ok_or(not_found!("Identity provider {} not found", name))
+    }
+
+    /// 生成授权地址, state、nonce 和 code_verifier 保存在 Redis 中, state 的哈希写入 Cookie
+    pub async fn authorize(
+        &self,
+        jar: CookieJar,
+        provider_name: &str,
+    ) -> Result<(CookieJar, OidcAuthorizeResponse), AppError> {
+        let provider = self.provider(provider_name)?;
+        let cached = self.metadata(provider, false).await?;
+
+        let state = generate_secret();
+        let login_state = OidcLoginState {
+            provider: provider.name.clone(),
+            code_verifier: generate_secret(),
+            nonce: generate_secret(),
+        };
+        let mut redis_conn = self.app_state.redis.get_multiplexed_async_connection().await?;
+        let _: () = redis_conn
+            .set_ex(
+                format!("{}:{}", OIDC_STATE, state),
+                serde_json::to_string(&login_state)?,
+                self.app_state.config.oidc.state_ttl_seconds,
+            )
+            .await?;
+
+        let authorization_url = Url::parse_with_params(
+            &cached.metadata.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", provider.client_id.as_str()),
+                ("redirect_uri", provider.redirect_uri.as_str()),
+                ("scope", provider.scopes.join(" ").as_str()),
+                ("state", state.as_str()),
+                ("nonce", login_state.nonce.as_str()),
+                ("code_challenge", pkce_challenge(&login_state.code_verifier).as_str()),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|e| AppError::internal_server_error(anyhow::anyhow!(e)))?;
+
+        let state_cookie = Cookie::build((OIDC_STATE_COOKIE, hash_token(&state)))
+            .path(OIDC_STATE_COOKIE_PATH)
+            .max_age(CookieDuration::seconds(self.app_state.config.oidc.state_ttl_seconds as i64))
+            .same_site(SameSite::Strict)
+            .http_only(true)
+            .secure(true)
+            .build();
+        Ok((
+            jar.add(state_cookie),
+            OidcAuthorizeResponse {
+                authorization_url: authorization_url.to_string(),
+                state,
+            },
+        ))
+    }
+
+    /// 用授权码换取令牌, 校验 ID Token 后签发本系统的令牌
+    pub async fn callback(
+        &self,
+        jar: CookieJar,
+        provider_name: &str,
+        dto: OidcCallback,
+    ) -> Result<(CookieJar, AuthResponse), AppError> {
+        let provider = self.provider(provider_name)?;
+
+        // state 必须由当前浏览器发起的登录生成
+        let state_hash = jar.get(OIDC_STATE_COOKIE).map(|cookie| cookie.value().to_string());
+        if state_hash.as_deref() != Some(hash_token(&dto.state).as_str()) {
+            return Err(unauthorized!("Invalid or expired login state"));
+        }
+        let jar = jar.remove(Cookie::build(OIDC_STATE_COOKIE).path(OIDC_STATE_COOKIE_PATH));
+
+        // state 只能使用一次
+        let mut redis_conn = self.app_state.redis.get_multiplexed_async_connection().await?;
+        let login_state: Option<String> = redis_conn.get_del(format!("{}:{}", OIDC_STATE, dto.state)).await?;
+        let login_state: OidcLoginState = match login_state {
+            Some(login_state) => serde_json::from_str(&login_state)?,
+            None => return Err(unauthorized!("Invalid or expired login state")),
+        };
+        if login_state.provider != provider.name {
+            return Err(unauthorized!("Invalid or expired login state"));
+        }
+
+        let cached = self.metadata(provider, false).await?;
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", dto.code.as_str()),
+            ("redirect_uri", provider.redirect_uri.as_str()),
+            ("client_id", provider.client_id.as_str()),
+            ("code_verifier", login_state.code_verifier.as_str()),
+        ];
+        if !provider.client_secret.is_empty() {
+            form.push(("client_secret", provider.client_secret.as_str()));
+        }
+        let response = self.client.post(&cached.metadata.token_endpoint).form(&form).send().await?;
+        if !response.status().is_success() {
+            tracing::warn!("OIDC 令牌请求失败 ({}): {}", provider.name, response.text().await.unwrap_or_default());
+            return Err(unauthorized!("Authorization code exchange failed"));
+        }
+        let tokens: OidcTokenResponse = response.json().await?;
+        let id_token = tokens.id_token.ok_or(unauthorized!("Identity provider returned no ID token"))?;
+
+        let mut claims = self.verify_id_token(provider, &id_token, &login_state.nonce).await?;
+        // ID Token 中缺少的声明从 UserInfo 接口补充
+        if let Some(userinfo_endpoint) = &cached.metadata.userinfo_endpoint
+            && (claim_str(&claims, &provider.claims.username).is_none()
+                || claim_str(&claims, &provider.claims.email).is_none())
+        {
+            let userinfo: Map<String, Value> = self
+                .client
+                .get(userinfo_endpoint)
+                .bearer_auth(&tokens.access_token)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            // UserInfo 的 sub 必须与 ID Token 一致
+            if userinfo.get("sub") == claims.get("sub") {
+                for (key, value) in userinfo {
+                    claims.entry(key).or_insert(value);
+                }
+            }
+        }
+
+        let user = self.provision_user(provider, &claims).await?;
+        if !user.is_active {
+            return Err(unauthorized!("User is disabled"));
+        }
+        // 单点登录用户的密码不由本系统管理
+        self.auth_service.issue_session(jar, user, false).await
+    }
+
+    /// 发现文档和 JWKS, 缓存在 Redis 中, refresh 为 true 时重新获取(签名密钥轮换)
+    async fn metadata(&self, provider: &OidcProviderConfig, refresh: bool) -> Result<OidcCachedMetadata, AppError> {
+        let cache_key = format!("{}:{}", OIDC_METADATA, provider.name);
+        let mut redis_conn = self.app_state.redis.get_multiplexed_async_connection().await?;
+        if !refresh {
+            let cached: Option<String> = redis_conn.get(&cache_key).await?;
+            if let Some(cached) = cached.and_then(|cached| serde_json::from_str(&cached).ok()) {
+                return Ok(cached);
+            }
+        }
+
+        let discovery_url = format!("{}/.well-known/openid-configuration", provider.issuer.trim_end_matches('/'));
+        let metadata: OidcProviderMetadata = self
+            .client
+            .get(&discovery_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if metadata.issuer.trim_end_matches('/') != provider.issuer.trim_end_matches('/') {
+            return Err(AppError::internal_server_error(anyhow::anyhow!(
+                "Issuer mismatch for provider {}: {}",
+                provider.name,
+                metadata.issuer
+            )));
+        }
+        let jwks: JwkSet = self
+            .client
+            .get(&metadata.jwks_uri)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let cached = OidcCachedMetadata { metadata, jwks };
+        let ttl = self.app_state.config.oidc.metadata_cache_seconds;
+        if ttl > 0 {
+            let _: () = redis_conn.set_ex(&cache_key, serde_json::to_string(&cached)?, ttl).await?;
+        }
+        Ok(cached)
+    }
+
+    async fn verify_id_token(
+        &self,
+        provider: &OidcProviderConfig,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<Map<String, Value>, AppError> {
+        validate_id_token(provider, id_token, nonce, |refresh| self.metadata(provider, refresh)).await
+    }
+
+    /// 按 sub 找到关联的用户, 没有时按邮箱关联或自动创建, 再按声明同步部门、用户组和角色
+    async fn provision_user(
+        &self,
+        provider: &OidcProviderConfig,
+        claims: &Map<String, Value>,
+    ) -> Result<users::Model, AppError> {
+        let source = format!("oidc:{}", provider.name);
+        let subject = claim_str(claims, "sub").ok_or(unauthorized!("ID token has no subject"))?;
+        let mapping = &provider.claims;
+        let username = claim_str(claims, &mapping.username);
+        let email = claim_str(claims, &mapping.email);
+        let email_verified = claims.get("email_verified").and_then(Value::as_bool).unwrap_or(false);
+        let alias = claim_str(claims, &mapping.display_name);
+        let phone = claim_str(claims, &mapping.phone);
+
+        let mut events = Vec::new();
+        let txn = self.app_state.db.begin().await?;
+        let link = directory_links::Entity::find()
+            .filter(directory_links::Column::Source.eq(&source))
+            .filter(directory_links::Column::EntryType.eq(ENTRY_TYPE_USER))
+            .filter(directory_links::Column::ExternalId.eq(&subject))
+            .one(&txn)
+            .await?;
+        let linked = match &link {
+            Some(link) => users::Entity::find_by_id(link.local_id).one(&txn).await?,
+            None => None,
+        };
+        if let (Some(link), None) = (&link, &linked) {
+            directory_links::Entity::delete_by_id(link.id).exec(&txn).await?;
+        }
+
+        let mut user = match linked {
+            Some(user) => self.update_profile(&txn, user, email, alias, phone, &mut events).await?,
+            None => {
+                let existing = match &email {
+                    Some(email) if provider.link_by_email && email_verified => {
+                        users::Entity::find()
+                            .filter(users::Column::Email.eq(email))
+                            .filter(users::Column::TenantId.eq(DEFAULT_TENANT_ID))
+                            .one(&txn)
+                            .await?
+                    }
+                    _ => None,
+                };
+                let user = match existing {
+                    Some(user) => user,
+                    None if provider.auto_create_users => {
+                        let (Some(username), Some(email)) = (username, email) else {
+                            return Err(unauthorized!("ID token is missing the username or email claim"));
+                        };
+                        let exists = users::Entity::find()
+                            .filter(
+                                Condition::any()
+                                    .add(users::Column::Username.eq(&username))
+                                    .add(users::Column::Email.eq(&email)),
+                            )
+                            .filter(users::Column::TenantId.eq(DEFAULT_TENANT_ID))
+                            .one(&txn)
+                            .await?;
+                        if exists.is_some() {
+                            return Err(conflict!("User with username {} or email {} already exists", username, email));
+                        }
+                        let user = users::ActiveModel {
+                            user_uuid: Set(Uuid::new_v4().to_string()),
+                            username: Set(username),
+                            email: Set(email),
+                            // 只能通过单点登录或重置密码登录
+                            password: Set(hash_password(&generate_secret())?),
+                            dept_id: Set(default_dept_id(&txn, provider).await?),
+                            tenant_id: Set(DEFAULT_TENANT_ID),
+                            alias: Set(alias),
+                            phone: Set(phone),
+                            is_active: Set(true),
+                            password_changed_at: Set(Some(chrono::Local::now().naive_local())),
+                            ..Default::default()
+                        }
+                        .insert(&txn)
+                        .await?;
+                        events.push(DomainEvent::UserCreated {
+                            user_uuid: user.user_uuid.clone(),
+                            username: user.username.clone(),
+                        });
+                        user
+                    }
+                    None => return Err(unauthorized!("User is not provisioned for {}", provider.name)),
+                };
+                directory_links::ActiveModel {
+                    source: Set(source.clone()),
+                    entry_type: Set(ENTRY_TYPE_USER.to_string()),
+                    external_id: Set(subject),
+                    local_id: Set(user.user_id),
+                    created_at: Set(Utc::now()),
+                    updated_at: Set(Utc::now()),
+                    ..Default::default()
+                }
+                .insert(&txn)
+                .await?;
+                user
+            }
+        };
+
+        if let Some(department) = claim_strings(claims, &mapping.department).first() {
+            user = self.sync_department(&txn, provider, user, department, &mut events).await?;
+        }
+        if !provider.group_mapping.is_empty() {
+            let values = claim_strings(claims, &mapping.groups);
+            sync_groups(&txn, provider, &user, &values, &mut events).await?;
+        }
+        if !provider.role_mapping.is_empty() {
+            let values = claim_strings(claims, &mapping.roles);
+            sync_roles(&txn, provider, &user, &values, &mut events).await?;
+        }
+        txn.commit().await?;
+
+        let actor = CurrentUser::system(&source, &provider.name);
+        for event in events {
+            publish_event(&self.app_state, Some(&actor), event).await;
+        }
+        Ok(user)
+    }
+
+    // 邮箱被其他用户使用时保留原邮箱
+    async fn update_profile(
+        &self,
+        txn: &DatabaseTransaction,
+        user: users::Model,
+        email: Option<String>,
+        alias: Option<String>,
+        phone: Option<String>,
+        events: &mut Vec<DomainEvent>,
+    ) -> Result<users::Model, AppError> {
+        let email = match email {
+            Some(email) if email != user.email => {
+                let taken = users::Entity::find()
+                    .filter(users::Column::Email.eq(&email))
+                    .filter(users::Column::TenantId.eq(user.tenant_id))
+                    .filter(users::Column::UserId.ne(user.user_id))
+                    .one(txn)
+                    .await?;
+                if taken.is_some() {
+                    tracing::warn!("用户 {} 的邮箱 {} 已被其他用户使用, 未更新", user.username, email);
+                    user.email.clone()
+                } else {
+                    email
+                }
+            }
+            _ => user.email.clone(),
+        };
+        let alias = alias.or(user.alias.clone());
+        let phone = phone.or(user.phone.clone());
+        if email == user.email && alias == user.alias && phone == user.phone {
+            return Ok(user);
+        }
+
+        let user_uuid = user.user_uuid.clone();
+        let mut user: users::ActiveModel = user.into();
+        user.email = Set(email);
+        user.alias = Set(alias);
+        user.phone = Set(phone);
+        user.updated_at = Set(Utc::now().naive_utc());
+        let user = user.update(txn).await?;
+        events.push(DomainEvent::UserUpdated { user_uuid });
+        Ok(user)
+    }
+
+    // 部门声明的值先按映射找部门UUID, 再按部门名称匹配, 都找不到时不修改
+    async fn sync_department(
+        &self,
+        txn: &DatabaseTransaction,
+        provider: &OidcProviderConfig,
+        user: users::Model,
+        value: &str,
+        events: &mut Vec<DomainEvent>,
+    ) -> Result<users::Model, AppError> {
+        let query = departments::Entity::find()
+            .select_only()
+            .column(departments::Column::DeptId)
+            .column(departments::Column::DeptUuid)
+            .filter(departments::Column::TenantId.eq(user.tenant_id))
+            .filter(departments::Column::IsDeleted.eq(false));
+        let query = match provider.department_mapping.get(value) {
+            Some(dept_uuid) => query.filter(departments::Column::DeptUuid.eq(dept_uuid)),
+            None => query.filter(departments::Column::Name.eq(value)),
+        };
+        let Some((dept_id, to_dept_uuid)) = query.into_tuple::<(i32, String)>().one(txn).await? else {
+            tracing::warn!("身份提供方 {} 的部门 {} 没有对应的部门", provider.name, value);
+            return Ok(user);
+        };
+        if dept_id == user.dept_id {
+            return Ok(user);
+        }
+
+        let from_dept_uuid = departments::Entity::find_by_id(user.dept_id)
+            .select_only()
+            .column(departments::Column::DeptUuid)
+            .into_tuple::<String>()
+            .one(txn)
+            .await?
+            .unwrap_or_default();
+        let user_uuid = user.user_uuid.clone();
+        let mut user: users::ActiveModel = user.into();
+        user.dept_id = Set(dept_id);
+        user.updated_at = Set(Utc::now().naive_utc());
+        let user = user.update(txn).await?;
+        events.push(DomainEvent::UserDepartmentChanged {
+            user_uuid,
+            from_dept_uuid,
+            to_dept_uuid,
+        });
+        Ok(user)
+    }
+}
+
+// 只同步 group_mapping 中配置的用户组, 其他用户组的成员关系不变
+async fn sync_groups(
+    txn: &DatabaseTransaction,
+    provider: &OidcProviderConfig,
+    user: &users::Model,
+    values: &[String],
+    events: &mut Vec<DomainEvent>,
+) -> Result<(), AppError> {
+    let managed = user_groups::Entity::find()
+        .filter(user_groups::Column::Name.is_in(provider.group_mapping.values().cloned()))
+        .filter(user_groups::Column::TenantId.eq(user.tenant_id))
+        .all(txn)
+        .await?;
+    let wanted = mapped_names(&provider.group_mapping, values);
+    let current: HashSet<i32> = user_group_members::Entity::find()
+        .select_only()
+        .column(user_group_members::Column::GroupId)
+        .filter(user_group_members::Column::UserId.eq(user.user_id))
+        .into_tuple::<i32>()
+        .all(txn)
+        .await?
+        .into_iter()
+        .collect();
+
+    for group in managed {
+        let is_member = current.contains(&group.user_group_id);
+        if wanted.contains(&group.name) && !is_member {
+            user_group_members::Entity::insert(user_group_members::ActiveModel {
+                group_id: Set(group.user_group_id),
+                user_id: Set(user.user_id),
+                ..Default::default()
+            })
+            .exec(txn)
+            .await?;
+        } else if !wanted.contains(&group.name) && is_member {
+            user_group_members::Entity::delete_many()
+                .filter(user_group_members::Column::GroupId.eq(group.user_group_id))
+                .filter(user_group_members::Column::UserId.eq(user.user_id))
+                .exec(txn)
+                .await?;
+        } else {
+            continue;
+        }
+        events.push(DomainEvent::GroupMembersChanged {
+            group_uuid: group.user_group_uuid,
+            user_uuids: vec![user.user_uuid.clone()],
+        });
+    }
+    Ok(())
+}
+
+// 只同步 role_mapping 中配置的角色, 其他角色不变
+async fn sync_roles(
+    txn: &DatabaseTransaction,
+    provider: &OidcProviderConfig,
+    user: &users::Model,
+    values: &[String],
+    events: &mut Vec<DomainEvent>,
+) -> Result<(), AppError> {
+    let managed = roles::Entity::find()
+        .filter(roles::Column::RoleName.is_in(provider.role_mapping.values().cloned()))
+        .filter(roles::Column::TenantId.eq(user.tenant_id))
+        .all(txn)
+        .await?;
+    let wanted = mapped_names(&provider.role_mapping, values);
+    let current: HashSet<i32> = user_roles::Entity::find()
+        .select_only()
+        .column(user_roles::Column::RoleId)
+        .filter(user_roles::Column::UserId.eq(user.user_id))
+        .into_tuple::<i32>()
+        .all(txn)
+        .await?
+        .into_iter()
+        .collect();
+
+    for role in managed {
+        let has_role = current.contains(&role.role_id);
+        if wanted.contains(&role.role_name) && !has_role {
+            user_roles::Entity::insert(user_roles::ActiveModel {
+                user_id: Set(user.user_id),
+                role_id: Set(role.role_id),
+                ..Default::default()
+            })
+            .exec(txn)
+            .await?;
+            events.push(DomainEvent::RoleAssigned {
+                user_uuid: user.user_uuid.clone(),
+                role_uuid: role.role_uuid,
+            });
+        } else if !wanted.contains(&role.role_name) && has_role {
+            user_roles::Entity::delete_many()
+                .filter(user_roles::Column::UserId.eq(user.user_id))
+                .filter(user_roles::Column::RoleId.eq(role.role_id))
+                .exec(txn)
+                .await?;
+            events.push(DomainEvent::RoleRevoked {
+                user_uuid: user.user_uuid.clone(),
+                role_uuid: role.role_uuid,
+            });
+        }
+    }
+    Ok(())
+}
+
+// 声明的值按映射转换为本地名称, 未配置的值忽略
+fn mapped_names<'a>(mapping: &'a HashMap<String, String>, values: &[String]) -> HashSet<&'a String> {
+    values.iter().filter_map(|value| mapping.get(value)).collect()
+}
+
+async fn default_dept_id(txn: &DatabaseTransaction, provider: &OidcProviderConfig) -> Result<i32, AppError> {
+    let query = departments::Entity::find()
+        .select_only()
+        .column(departments::Column::DeptId)
+        .filter(departments::Column::TenantId.eq(DEFAULT_TENANT_ID))
+        .filter(departments::Column::IsDeleted.eq(false));
+    let query = if provider.default_dept_uuid.is_empty() {
+        query.filter(departments::Column::ParentId.eq(0))
+    } else {
+        query.filter(departments::Column::DeptUuid.eq(&provider.default_dept_uuid))
+    };
+    query
+        .into_tuple::<i32>()
+        .one(txn)
+        .await?
+        .ok_or(not_found!("Default department not found"))
+}
+
+// 校验 ID Token 的签名、签发者、受众和 nonce; load_metadata(true) 重新获取 JWKS, 用于签名密钥轮换
+async fn validate_id_token<F, Fut>(
+    provider: &OidcProviderConfig,
+    id_token: &str,
+    nonce: &str,
+    load_metadata: F,
+) -> Result<Map<String, Value>, AppError>
+where
+    F: Fn(bool) -> Fut,
+    Fut: Future<Output = Result<OidcCachedMetadata, AppError>>,
+{
+    let header = decode_header(id_token).map_err(|_| unauthorized!("Invalid ID token"))?;
+    // 对称算法使用 client_secret 签名, 不接受
+    if matches!(header.alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512) {
+        return Err(unauthorized!("Unsupported ID token algorithm {:?}", header.alg));
+    }
+
+    let mut cached = load_metadata(false).await?;
+    let jwk = match find_jwk(&cached.jwks, header.kid.as_deref()) {
+        Some(jwk) => jwk,
+        None => {
+            cached = load_metadata(true).await?;
+            find_jwk(&cached.jwks, header.kid.as_deref()).ok_or(unauthorized!("Unknown ID token signing key"))?
+        }
+    };
+    let key = DecodingKey::from_jwk(&jwk).map_err(|_| unauthorized!("Invalid ID token signing key"))?;
+
+    let mut validation = Validation::new(header.alg);
+    validation.set_issuer(&[&cached.metadata.issuer]);
+    validation.set_audience(&[&provider.client_id]);
+    validation.set_required_spec_claims(&["exp", "iat", "iss", "aud", "sub"]);
+    let claims = decode::<Map<String, Value>>(id_token, &key, &validation)
+        .map_err(|e| unauthorized!("Invalid ID token: {}", e))?
+        .claims;
+
+    if claims.get("nonce").and_then(Value::as_str) != Some(nonce) {
+        return Err(unauthorized!("Invalid ID token nonce"));
+    }
+    Ok(claims)
+}
+
+// 没有 kid 时只有一个密钥才能确定
+fn find_jwk(jwks: &JwkSet, kid: Option<&str>) -> Option<Jwk> {
+    match kid {
+        Some(kid) => jwks.find(kid).cloned(),
+        None if jwks.keys.len() == 1 => jwks.keys.first().cloned(),
+        None => None,
+    }
+}
+
+// 声明名称可以是 realm_access.roles 这样的嵌套路径
+fn claim<'a>(claims: &'a Map<String, Value>, path: &str) -> Option<&'a Value> {
+    if path.is_empty() {
+        return None;
+    }
+    if let Some(value) = claims.get(path) {
+        return Some(value);
+    }
+    let mut parts = path.split('.');
+    let mut value = claims.get(parts.next()?)?;
+    for part in parts {
+        value = value.get(part)?;
+    }
+    Some(value)
+}
+
+fn claim_str(claims: &Map<String, Value>, path: &str) -> Option<String> {
+    claim(claims, path)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+// 字符串或字符串数组
+fn claim_strings(claims: &Map<String, Value>, path: &str) -> Vec<String> {
+    match claim(claims, path) {
+        Some(Value::Array(values)) => values.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+        Some(Value::String(value)) if !value.is_empty() => vec![value.clone()],
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use ring::rand::SystemRandom;
+    use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING};
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const ISSUER: &str = "https://idp.example.com";
+    const CLIENT_ID: &str = "admin-console";
+    const NONCE: &str = "nonce-1";
+
+    // 测试用的 ES256 签名密钥
+    struct SigningKey {
+        kid: String,
+        encoding: EncodingKey,
+        jwk: Jwk,
+    }
+
+    fn signing_key(kid: &str) -> SigningKey {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng).unwrap();
+        let key_pair = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8.as_ref(), &rng).unwrap();
+        // 未压缩的公钥: 0x04 || x || y
+        let public_key = key_pair.public_key().as_ref();
+        let jwk = serde_json::from_value(json!({
+            "kty": "EC",
+            "crv": "P-256",
+            "use": "sig",
+            "alg": "ES256",
+            "kid": kid,
+            "x": URL_SAFE_NO_PAD.encode(&public_key[1..33]),
+            "y": URL_SAFE_NO_PAD.encode(&public_key[33..65]),
+        }))
+        .unwrap();
+        SigningKey {
+            kid: kid.to_string(),
+            encoding: EncodingKey::from_ec_der(pkcs8.as_ref()),
+            jwk,
+        }
+    }
+
+    fn provider() -> OidcProviderConfig {
+        OidcProviderConfig {
+            name: "test".to_string(),
+            issuer: ISSUER.to_string(),
+            client_id: CLIENT_ID.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn cached(keys: &[&SigningKey]) -> OidcCachedMetadata {
+        OidcCachedMetadata {
+            metadata: OidcProviderMetadata {
+                issuer: ISSUER.to_string(),
+                authorization_endpoint: format!("{}/authorize", ISSUER),
+                token_endpoint: format!("{}/token", ISSUER),
+                jwks_uri: format!("{}/jwks", ISSUER),
+                userinfo_endpoint: None,
+            },
+            jwks: JwkSet { keys: keys.iter().map(|key| key.jwk.clone()).collect() },
+        }
+    }
+
+    fn claims() -> Value {
+        let now = Utc::now().timestamp();
+        json!({
+            "iss": ISSUER,
+            "aud": CLIENT_ID,
+            "sub": "user-1",
+            "iat": now,
+            "exp": now + 300,
+            "nonce": NONCE,
+            "preferred_username": "alice",
+        })
+    }
+
+    fn sign(key: &SigningKey, claims: &Value) -> String {
+        let mut header = Header::new(Algorithm::ES256);
+        header.kid = Some(key.kid.clone());
+        encode(&header, claims, &key.encoding).unwrap()
+    }
+
+    async fn verify(metadata: OidcCachedMetadata, id_token: &str) -> Result<Map<String, Value>, AppError> {
+        validate_id_token(&provider(), id_token, NONCE, |_| {
+            let metadata = metadata.clone();
+            async move { Ok(metadata) }
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn accepts_valid_id_token() {
+        let key = signing_key("k1");
+        let claims = verify(cached(&[&key]), &sign(&key, &claims())).await.unwrap();
+        assert_eq!(claim_str(&claims, "sub").as_deref(), Some("user-1"));
+        assert_eq!(claim_str(&claims, "preferred_username").as_deref(), Some("alice"));
+    }
+
+    #[tokio::test]
+    async fn rejects_wrong_nonce_audience_and_issuer() {
+        let key = signing_key("k1");
+        for (name, value) in [("nonce", "other-nonce"), ("aud", "other-client"), ("iss", "https://evil.example.com")] {
+            let mut claims = claims();
+            claims[name] = json!(value);
+            assert!(verify(cached(&[&key]), &sign(&key, &claims)).await.is_err(), "{} should be checked", name);
+        }
+        let mut claims = claims();
+        claims.as_object_mut().unwrap().remove("nonce");
+        assert!(verify(cached(&[&key]), &sign(&key, &claims)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_expired_token_and_foreign_signature() {
+        let key = signing_key("k1");
+        let mut expired = claims();
+        expired["exp"] = json!(Utc::now().timestamp() - 3600);
+        assert!(verify(cached(&[&key]), &sign(&key, &expired)).await.is_err());
+
+        // kid 相同但由其他密钥签名
+        let forged = signing_key("k1");
+        assert!(verify(cached(&[&key]), &sign(&forged, &claims())).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_symmetric_and_unsigned_algorithms() {
+        let key = signing_key("k1");
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some("k1".to_string());
+        let hs256 = encode(&header, &claims(), &EncodingKey::from_secret(b"client-secret")).unwrap();
+        assert!(verify(cached(&[&key]), &hs256).await.is_err());
+
+        let none_header = URL_SAFE_NO_PAD.encode(r#"{"alg":"none","kid":"k1"}"#);
+        let payload = URL_SAFE_NO_PAD.encode(claims().to_string());
+        let unsigned = format!("{}.{}.", none_header, payload);
+        assert!(verify(cached(&[&key]), &unsigned).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn refreshes_jwks_when_kid_is_unknown() {
+        let old_key = signing_key("old");
+        let new_key = signing_key("new");
+        let refreshes = AtomicUsize::new(0);
+        let load = |refresh: bool| {
+            if refresh {
+                refreshes.fetch_add(1, Ordering::SeqCst);
+            }
+            let metadata = if refresh { cached(&[&old_key, &new_key]) } else { cached(&[&old_key]) };
+            async move { Ok(metadata) }
+        };
+
+        // 缓存中的密钥可以直接校验, 不重新获取
+        validate_id_token(&provider(), &sign(&old_key, &claims()), NONCE, load).await.unwrap();
+        assert_eq!(refreshes.load(Ordering::SeqCst), 0);
+
+        // 密钥轮换后重新获取 JWKS
+        validate_id_token(&provider(), &sign(&new_key, &claims()), NONCE, load).await.unwrap();
+        assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+
+        // 重新获取后仍找不到密钥
+        let unknown = signing_key("unknown");
+        assert!(validate_id_token(&provider(), &sign(&unknown, &claims()), NONCE, load).await.is_err());
+        assert_eq!(refreshes.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn maps_nested_and_multi_valued_claims() {
+        let claims = json!({
+            "preferred_username": "  alice  ",
+            "email": "",
+            "realm_access": { "roles": ["admin", "auditor", 42] },
+            "department": "Engineering",
+            "realm_access.flat": "dotted key",
+        });
+        let claims = claims.as_object().unwrap();
+
+        assert_eq!(claim_str(claims, "preferred_username").as_deref(), Some("alice"));
+        assert_eq!(claim_str(claims, "email"), None);
+        assert_eq!(claim_str(claims, "missing"), None);
+        assert_eq!(claim_str(claims, ""), None);
+        // 顶层存在带点的声明名时优先使用
+        assert_eq!(claim_str(claims, "realm_access.flat").as_deref(), Some("dotted key"));
+        assert_eq!(claim_strings(claims, "realm_access.roles"), vec!["admin", "auditor"]);
+        assert_eq!(claim_strings(claims, "department"), vec!["Engineering"]);
+        assert!(claim_strings(claims, "email").is_empty());
+        assert!(claim_strings(claims, "realm_access.missing").is_empty());
+    }
+
+    #[test]
+    fn maps_only_configured_values() {
+        let mapping = HashMap::from([
+            ("idp-admins".to_string(), "Admin".to_string()),
+            ("idp-auditors".to_string(), "Auditor".to_string()),
+        ]);
+        let values = vec!["idp-admins".to_string(), "unmapped".to_string()];
+        let names = mapped_names(&mapping, &values);
+        assert_eq!(names.len(), 1);
+        assert!(names.contains(&"Admin".to_string()));
+        assert!(mapped_names(&mapping, &[]).is_empty());
+    }
+}
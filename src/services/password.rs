@@ -1,13 +1,21 @@
-use askama::Template;
+use crate::config::security::PasswordPolicyConfig;
 use crate::config::state::AppState;
-use crate::entity::users;
+use crate::entity::{user_password_history, users};
 use crate::errors::app_error::AppError;
-use crate::{bad_request, not_found};
+use crate::config::app::PASSWORD_RESET_THROTTLE;
+use crate::schemas::notification::{NewNotification, NOTIFICATION_CATEGORY_SECURITY, NOTIFICATION_WARNING};
+use crate::services::auth::invalidate_user_sessions;
+use crate::services::notification::notify_user;
+use crate::{bad_request, not_found, too_many_requests};
 use crate::schemas::password::{ForgotPasswordDto, ResetPasswordDto};
-use crate::utils::templates::{CNPasswordResetTemplate, ENPasswordResetTemplate};
-use url::Url;
-use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, EntityTrait, QueryFilter, Set};
-use crate::utils::crypto::hash_password;
+use crate::utils::templates::EmailTemplate;
+use chrono::Duration;
+use tracing::warn;
+use redis::{AsyncCommands, ExistenceCheck, SetExpiry, SetOptions};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait};
+use crate::utils::crypto::{hash_password, hash_token};
+use crate::utils::password_policy::{ensure_not_reused, validate_password};
 
 #[derive(Clone)]
 pub struct PasswordService {
@@ -19,73 +27,69 @@ impl PasswordService {
         Self { app_state }
     }
 
-    pub async fn forgot_password(&self,
-                                 referer_url: String,
-                                 dto: ForgotPasswordDto
-    ) -> Result<(), AppError> {
+    pub async fn forgot_password(&self, tenant_id: i32, dto: ForgotPasswordDto) -> Result<(), AppError> {
+        // 同一邮箱在间隔内只允许申请一次
+        let throttle_seconds = self.app_state.config.security.password_reset.throttle_seconds;
+        if throttle_seconds > 0 {
+            let mut redis_conn = self.app_state.redis.get_multiplexed_async_connection().await?;
+            let acquired: Option<String> = redis_conn
+                .set_options(
+                    format!("{}:{}:{}", PASSWORD_RESET_THROTTLE, tenant_id, dto.email.to_lowercase()),
+                    true,
+                    SetOptions::default()
+                        .conditional_set(ExistenceCheck::NX)
+                        .with_expiration(SetExpiry::EX(throttle_seconds)),
+                )
+                .await?;
+            if acquired.is_none() {
+                return Err(too_many_requests!("Password reset already requested, please try again later"));
+            }
+        }
 
-        let mut  user: users::ActiveModel = users::Entity::find()
+        let user = users::Entity::find()
             .filter(
                 Condition::all()
                     .add(
                         users::Column::Email.eq(&dto.email)
                     )
+                    .add(
+                        users::Column::TenantId.eq(tenant_id)
+                    )
                     .add(
                         users::Column::IsActive.eq(true)
                     )
             )
         .one(&self.app_state.db)
-        .await?
-            .ok_or(not_found!("No user with this email"))?
-            .into();
+        .await?;
+        // 邮箱不存在时同样返回成功, 避免通过该接口探测已注册的邮箱
+        let Some(user) = user else {
+            return Ok(());
+        };
+        let mut user: users::ActiveModel = user.into();
 
+        // 数据库只保存令牌的哈希, 原始令牌仅出现在邮件链接中
         let reset_token = uuid::Uuid::new_v4().to_string();
 
-        user.reset_token = Set(Some(reset_token.clone()));
+        user.reset_token = Set(Some(hash_token(&reset_token)));
         user.reset_triggered = Set(Some(chrono::Utc::now().naive_utc()));
 
         user.save(&self.app_state.db).await?;
 
-        let base_url = match Url::parse(referer_url.as_str()) {
-            Ok(base_url) => base_url,
-            Err(_) => {
-                return Err(bad_request!("Bad Request".to_string()))
-            }
-        };
-
-        let reset_url = match base_url.join(format!("/reset-password/{}", reset_token).as_str()) {
-            Ok(reset_url) => reset_url,
-            Err(_) => {
-                return Err(bad_request!("Bad Request".to_string()))
-            }
-        };
-
-        let (subject, rendered_html) = match dto.language.to_uppercase().as_str() {
-            "CN" => {
-                let subject = "[Axum Vue Admin] 密码重置请求";
-                let template = CNPasswordResetTemplate { reset_url: reset_url.as_str() };
-                (subject, template.render()?)
-            },
-            "EN" => {
-                let subject = "[Axum Vue Admin] Password Reset Request";
-                let template = ENPasswordResetTemplate { reset_url: reset_url.as_str() };
-                (subject, template.render()?)
-            },
-            _ => {
-                let subject = "[Axum Vue Admin] Password Reset Request";
-                let template = ENPasswordResetTemplate { reset_url: reset_url.as_str() };
-                (subject, template.render()?)
-            }
-        };
+        let reset_url = self
+            .app_state
+            .config
+            .server
+            .public_link(&format!("/reset-password/{}", reset_token))
+            .map_err(|e| AppError::internal_server_error(anyhow::anyhow!(e)))?;
 
         self.app_state
             .email_service
-            .send(
-                "no-reply@axum_vue_admin.com",
-                dto.email.as_str(),
-                subject,
-                rendered_html.as_str(),
-            ).await?;
+            .enqueue(
+                &dto.email,
+                EmailTemplate::PasswordReset { reset_url: reset_url.as_str() },
+                &dto.language,
+            )
+            .await?;
         Ok(())
     }
 
@@ -95,19 +99,145 @@ impl PasswordService {
         reset_token: String,
         dto: ResetPasswordDto
     ) -> Result<(), AppError> {
-        let mut user: users::ActiveModel = users::Entity::find()
-            .filter(users::Column::ResetToken.eq(reset_token))
+        let token_hash = hash_token(&reset_token);
+        let user = users::Entity::find()
+            .filter(users::Column::ResetToken.eq(&token_hash))
         .one(&self.app_state.db)
             .await?
-            .ok_or(not_found!("RestToken Not Found"))?
-            .into();
-        
-        user.reset_token = Set(None);
-        
-        user.password = Set(hash_password(dto.new_password.as_str())?);
-        
-        user.save(&self.app_state.db).await?;
-        
+            .ok_or(not_found!("RestToken Not Found"))?;
+
+        let expiration = Duration::minutes(self.app_state.config.security.password_reset.token_expiration_minutes);
+        let expired = user
+            .reset_triggered
+            .is_none_or(|triggered| triggered + expiration < chrono::Utc::now().naive_utc());
+        if expired {
+            let mut user: users::ActiveModel = user.into();
+            user.reset_token = Set(None);
+            user.reset_triggered = Set(None);
+            user.save(&self.app_state.db).await?;
+            return Err(bad_request!("Reset token expired"));
+        }
+
+        let policy = &self.app_state.config.security.password;
+        let txn = self.app_state.db.begin().await?;
+        // 令牌只能使用一次: 以令牌哈希为条件清空, 并发请求中只有一个能成功, 失败回滚后令牌仍可使用
+        let consumed = users::Entity::update_many()
+            .col_expr(users::Column::ResetToken, Expr::value(Option::<String>::None))
+            .col_expr(users::Column::ResetTriggered, Expr::value(Option::<chrono::NaiveDateTime>::None))
+            .filter(users::Column::UserId.eq(user.user_id))
+            .filter(users::Column::ResetToken.eq(&token_hash))
+            .exec(&txn)
+            .await?
+            .rows_affected;
+        if consumed != 1 {
+            return Err(not_found!("RestToken Not Found"));
+        }
+        let password_hash = hash_new_password(&txn, policy, Some(&user), &user.username, &dto.new_password).await?;
+
+        let user_id = user.user_id;
+        let user_uuid = user.user_uuid.clone();
+        let mut user: users::ActiveModel = user.into();
+        user.password = Set(password_hash.clone());
+        user.password_changed_at = Set(Some(chrono::Local::now().naive_local()));
+        user.save(&txn).await?;
+
+        record_password_history(&txn, policy, user_id, &password_hash).await?;
+        txn.commit().await?;
+
+        // 重置成功后注销该用户的所有会话
+        invalidate_user_sessions(&self.app_state, user_id, &user_uuid).await?;
+
+        let notification = NewNotification {
+            category: NOTIFICATION_CATEGORY_SECURITY.to_string(),
+            level: NOTIFICATION_WARNING.to_string(),
+            title: "Your password has been reset".to_string(),
+            content: Some("If this wasn't you, contact your administrator immediately.".to_string()),
+            link: None,
+        };
+        if let Err(e) = notify_user(&self.app_state, user_id, &user_uuid, notification).await {
+            warn!("发送密码重置通知失败: {:?}", e);
+        }
+
         Ok(())
     }
 }
+
+/// 校验新密码是否满足密码策略且未在最近 N 次中使用过, 返回新密码的哈希
+pub async fn hash_new_password<C: ConnectionTrait>(
+    db: &C,
+    policy: &PasswordPolicyConfig,
+    user: Option<&users::Model>,
+    username: &str,
+    new_password: &str,
+) -> Result<String, AppError> {
+    validate_password(policy, new_password, username)?;
+
+    if let Some(user) = user
+        && policy.history_count > 0
+    {
+        let mut used_hashes = user_password_history::Entity::find()
+            .select_only()
+            .column(user_password_history::Column::PasswordHash)
+            .filter(user_password_history::Column::UserId.eq(user.user_id))
+            .order_by_desc(user_password_history::Column::Id)
+            .limit(policy.history_count as u64)
+            .into_tuple::<String>()
+            .all(db)
+            .await?;
+        used_hashes.push(user.password.clone());
+        ensure_not_reused(policy, new_password, &used_hashes)?;
+    }
+
+    Ok(hash_password(new_password)?)
+}
+
+/// 记录密码历史, 只保留最近 N 条
+pub async fn record_password_history<C: ConnectionTrait>(
+    db: &C,
+    policy: &PasswordPolicyConfig,
+    user_id: i32,
+    password_hash: &str,
+) -> Result<(), AppError> {
+    if policy.history_count == 0 {
+        return Ok(());
+    }
+
+    user_password_history::ActiveModel {
+        user_id: Set(user_id),
+        password_hash: Set(password_hash.to_string()),
+        created_at: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    // MySQL 不支持单独使用 OFFSET, 在内存中跳过最近的 N 条
+    let expired_ids = user_password_history::Entity::find()
+        .select_only()
+        .column(user_password_history::Column::Id)
+        .filter(user_password_history::Column::UserId.eq(user_id))
+        .order_by_desc(user_password_history::Column::Id)
+        .into_tuple::<i64>()
+        .all(db)
+        .await?
+        .into_iter()
+        .skip(policy.history_count as usize)
+        .collect::<Vec<_>>();
+    if !expired_ids.is_empty() {
+        user_password_history::Entity::delete_many()
+            .filter(user_password_history::Column::Id.is_in(expired_ids))
+            .exec(db)
+            .await?;
+    }
+    Ok(())
+}
+
+/// 密码是否已超过最长有效期
+pub fn password_expired(policy: &PasswordPolicyConfig, user: &users::Model) -> bool {
+    if policy.max_age_days == 0 {
+        return false;
+    }
+    // created_at 由数据库按本地时间写入, password_changed_at 同样使用本地时间
+    let changed_at = user.password_changed_at.unwrap_or(user.created_at);
+    chrono::Local::now().naive_local() - changed_at > chrono::Duration::days(policy.max_age_days as i64)
+}
@@ -0,0 +1,317 @@
+// 个人访问令牌: 创建、查看、撤销, 以及在认证中间件中校验
+use axum::http::Method;
+use chrono::{Duration, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+
+use crate::config::rate_limit::matches_prefix;
+use crate::config::state::AppState;
+use crate::entity::{departments, personal_access_tokens, users};
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::personal_token::{
+    CreatePersonalTokenDto, PersonalTokenResponse, PERSONAL_TOKEN_PREFIX, SCOPE_READ, SCOPE_WRITE,
+    TOKEN_SCOPE_MODULES,
+};
+use crate::services::auth::{is_super_admin, sessions_valid_after};
+use crate::services::auth_backend::is_external_user;
+use crate::services::password::password_expired;
+use crate::services::user::ensure_user_entities;
+use crate::utils::crypto::{generate_secret, hash_token};
+use crate::{bad_request, forbidden, not_found, unauthorized};
+
+// 令牌不能用来管理令牌、修改登录凭据或代替用户同意 OAuth 授权
+const PERSONAL_TOKEN_FORBIDDEN_PATHS: &[&str] = &[
+    "/api/v1/me/tokens",
+    "/api/v1/me/password",
+    "/api/v1/me/email",
+    "/api/v1/auth",
+    "/api/v1/oauth",
+];
+
+// 展示的令牌前缀长度, 含 PERSONAL_TOKEN_PREFIX
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+#[derive(Clone)]
+pub struct PersonalTokenService {
+    app_state: AppState,
+}
+
+impl PersonalTokenService {
+    pub fn new(app_state: AppState) -> Self {
+        Self { app_state }
+    }
+
+    pub async fn list_tokens(&self, current_user: CurrentUser) -> Result<Vec<PersonalTokenResponse>, AppError> {
+        let user_id = self.user_id(&current_user.uuid).await?;
+        let tokens = personal_access_tokens::Entity::find()
+            .filter(personal_access_tokens::Column::UserId.eq(user_id))
+            .order_by_desc(personal_access_tokens::Column::Id)
+            .all(&self.app_state.db)
+            .await?;
+        Ok(tokens.into_iter().map(PersonalTokenResponse::from).collect())
+    }
+
+    pub async fn create_token(
+        &self,
+        current_user: CurrentUser,
+        dto: CreatePersonalTokenDto,
+    ) -> Result<PersonalTokenResponse, AppError> {
+        let config = &self.app_state.config.personal_token;
+        if !config.enabled {
+            return Err(forbidden!("Personal access tokens are disabled"));
+        }
+        validate_scopes(&dto.scopes)?;
+        let expires_at = match dto.expires_in_days {
+            Some(days) if config.max_expires_in_days > 0 && days > config.max_expires_in_days => {
+                return Err(bad_request!("expires_in_days must not exceed {}", config.max_expires_in_days));
+            }
+            Some(days) => Some(Utc::now() + Duration::days(days as i64)),
+            None if config.max_expires_in_days > 0 => return Err(bad_request!("expires_in_days is required")),
+            None => None,
+        };
+
+        let user_id = self.user_id(&current_user.uuid).await?;
+        let count = personal_access_tokens::Entity::find()
+            .filter(personal_access_tokens::Column::UserId.eq(user_id))
+            .count(&self.app_state.db)
+            .await?;
+        if count >= config.max_tokens_per_user {
+            return Err(bad_request!("At most {} personal access tokens are allowed", config.max_tokens_per_user));
+        }
+
+        let token = format!("{}{}", PERSONAL_TOKEN_PREFIX, generate_secret());
+        let model = personal_access_tokens::ActiveModel {
+            token_uuid: Set(uuid::Uuid::new_v4().to_string()),
+            user_id: Set(user_id),
+            name: Set(dto.name),
+            token_prefix: Set(token[..DISPLAY_PREFIX_LEN].to_string()),
+            token_hash: Set(hash_token(&token)),
+            scopes: Set(serde_json::to_value(dto.scopes)?),
+            expires_at: Set(expires_at),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(&self.app_state.db)
+        .await?;
+
+        let mut response = PersonalTokenResponse::from(model);
+        response.token = Some(token);
+        Ok(response)
+    }
+
+    pub async fn revoke_token(&self, current_user: CurrentUser, token_uuid: String) -> Result<(), AppError> {
+        let user_id = self.user_id(&current_user.uuid).await?;
+        let result = personal_access_tokens::Entity::delete_many()
+            .filter(personal_access_tokens::Column::TokenUuid.eq(&token_uuid))
+            .filter(personal_access_tokens::Column::UserId.eq(user_id))
+            .exec(&self.app_state.db)
+            .await?;
+        if result.rows_affected == 0 {
+            return Err(not_found!("Personal access token {} not found", token_uuid));
+        }
+        Ok(())
+    }
+
+    async fn user_id(&self, user_uuid: &str) -> Result<i32, AppError> {
+        users::Entity::find()
+            .select_only()
+            .column(users::Column::UserId)
+            .filter(users::Column::UserUuid.eq(user_uuid))
+            .into_tuple::<i32>()
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("User {} not found", user_uuid))
+    }
+}
+
+/// 校验个人访问令牌, 返回令牌所属用户; path 为完整的请求路径
+pub async fn authenticate_personal_token(
+    app_state: &AppState,
+    token: &str,
+    method: &Method,
+    path: &str,
+    source_ip: &str,
+) -> Result<CurrentUser, AppError> {
+    let config = &app_state.config.personal_token;
+    if !config.enabled {
+        return Err(unauthorized!("InvalidToken"));
+    }
+    let (token, user) = personal_access_tokens::Entity::find()
+        .filter(personal_access_tokens::Column::TokenHash.eq(hash_token(token)))
+        .find_also_related(users::Entity)
+        .one(&app_state.db)
+        .await?
+        .ok_or(unauthorized!("InvalidToken"))?;
+    let user = user
+        .filter(|user| user.is_active)
+        .ok_or(unauthorized!("InvalidToken"))?;
+    let now = Utc::now();
+    if token.expires_at.is_some_and(|expires_at| expires_at <= now) {
+        return Err(unauthorized!("Token expired"));
+    }
+    // 会话失效时令牌已被删除, 这里再拒绝失效前创建的令牌, 防止并发创建的令牌漏删
+    let mut redis_conn = app_state.redis.get_multiplexed_async_connection().await?;
+    let valid_after = sessions_valid_after(&mut redis_conn, &user.user_uuid).await?;
+    if valid_after.is_some_and(|valid_after| (token.created_at.timestamp_millis() as u64) < valid_after) {
+        return Err(unauthorized!("Token revoked"));
+    }
+
+    // 与登录一致, 密码过期的本地用户必须先修改密码, 令牌不能绕过这一要求
+    if password_expired(&app_state.config.security.password, &user)
+        && !is_external_user(app_state, user.user_id).await?
+    {
+        return Err(forbidden!("Password expired, please change your password"));
+    }
+
+    if is_forbidden_path(path) {
+        return Err(forbidden!("Personal access tokens cannot be used for this endpoint"));
+    }
+    let scopes: Vec<String> = serde_json::from_value(token.scopes.clone()).unwrap_or_default();
+    if !scope_allows(&scopes, method, path) {
+        return Err(forbidden!("Token scope does not allow {} {}", method, path));
+    }
+
+    // 只在超过更新间隔时写入, 条件更新避免并发请求重复写
+    let used_before = now - Duration::seconds(config.last_used_update_interval_seconds);
+    personal_access_tokens::Entity::update_many()
+        .col_expr(personal_access_tokens::Column::LastUsedAt, Expr::value(now))
+        .col_expr(personal_access_tokens::Column::LastUsedIp, Expr::value(source_ip))
+        .filter(personal_access_tokens::Column::Id.eq(token.id))
+        .filter(
+            Condition::any()
+                .add(personal_access_tokens::Column::LastUsedAt.is_null())
+                .add(personal_access_tokens::Column::LastUsedAt.lt(used_before)),
+        )
+        .exec(&app_state.db)
+        .await?;
+
+    ensure_user_entities(app_state, &user.user_uuid).await?;
+    let dept_uuid = departments::Entity::find_by_id(user.dept_id)
+        .select_only()
+        .column(departments::Column::DeptUuid)
+        .into_tuple::<String>()
+        .one(&app_state.db)
+        .await?
+        .unwrap_or_default();
+
+    Ok(CurrentUser {
+        is_super_admin: is_super_admin(&app_state.db, user.user_id).await?,
+        uuid: user.user_uuid,
+        dept_uuid,
+        username: user.username,
+        tenant_id: user.tenant_id,
+    })
+}
+
+// 按路径段匹配, /api/v1/oauth 不会误伤 /api/v1/oauth-clients
+fn is_forbidden_path(path: &str) -> bool {
+    PERSONAL_TOKEN_FORBIDDEN_PATHS.iter().any(|prefix| matches_prefix(path, prefix))
+}
+
+// GET/HEAD 需要 read, 其他方法需要 write
+fn scope_allows(scopes: &[String], method: &Method, path: &str) -> bool {
+    let module = path
+        .strip_prefix("/api/v1/")
+        .and_then(|rest| rest.split('/').next())
+        .unwrap_or_default();
+    let is_write = !matches!(*method, Method::GET | Method::HEAD);
+    scopes.iter().any(|scope| {
+        let (scope_module, access) = parse_scope(scope);
+        scope_module.is_none_or(|scope_module| scope_module == module)
+            && (access == SCOPE_WRITE || (access == SCOPE_READ && !is_write))
+    })
+}
+
+// `users:read` 拆分为模块和访问级别, 不带模块时适用于全部模块
+fn parse_scope(scope: &str) -> (Option<&str>, &str) {
+    match scope.split_once(':') {
+        Some((module, access)) => (Some(module), access),
+        None => (None, scope),
+    }
+}
+
+fn validate_scopes(scopes: &[String]) -> Result<(), AppError> {
+    for scope in scopes {
+        let (module, access) = parse_scope(scope);
+        if access != SCOPE_READ && access != SCOPE_WRITE {
+            return Err(bad_request!("Invalid scope {}", scope));
+        }
+        if let Some(module) = module
+            && !TOKEN_SCOPE_MODULES.contains(&module)
+        {
+            return Err(bad_request!("Unknown scope module {}", module));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scopes(scopes: &[&str]) -> Vec<String> {
+        scopes.iter().map(|scope| scope.to_string()).collect()
+    }
+
+    #[test]
+    fn module_scope_only_matches_its_module() {
+        let scopes = scopes(&["users:read"]);
+        assert!(scope_allows(&scopes, &Method::GET, "/api/v1/users"));
+        assert!(scope_allows(&scopes, &Method::GET, "/api/v1/users/some-uuid"));
+        assert!(!scope_allows(&scopes, &Method::GET, "/api/v1/roles"));
+        assert!(!scope_allows(&scopes, &Method::GET, "/api/v1/users-export"));
+        assert!(!scope_allows(&scopes, &Method::GET, "/health"));
+    }
+
+    #[test]
+    fn read_scope_only_allows_safe_methods() {
+        let read = scopes(&["users:read"]);
+        assert!(scope_allows(&read, &Method::HEAD, "/api/v1/users"));
+        for method in [Method::POST, Method::PUT, Method::PATCH, Method::DELETE] {
+            assert!(!scope_allows(&read, &method, "/api/v1/users"));
+        }
+
+        let write = scopes(&["users:write"]);
+        for method in [Method::GET, Method::POST, Method::PUT, Method::DELETE] {
+            assert!(scope_allows(&write, &method, "/api/v1/users"));
+        }
+    }
+
+    #[test]
+    fn unqualified_scope_applies_to_all_modules() {
+        let read = scopes(&["read"]);
+        assert!(scope_allows(&read, &Method::GET, "/api/v1/roles"));
+        assert!(!scope_allows(&read, &Method::DELETE, "/api/v1/roles/some-uuid"));
+
+        let write = scopes(&["write"]);
+        assert!(scope_allows(&write, &Method::DELETE, "/api/v1/roles/some-uuid"));
+        assert!(!scope_allows(&[], &Method::GET, "/api/v1/roles"));
+    }
+
+    #[test]
+    fn credential_and_consent_endpoints_are_forbidden() {
+        for path in [
+            "/api/v1/me/tokens",
+            "/api/v1/me/tokens/some-uuid",
+            "/api/v1/me/password",
+            "/api/v1/me/email",
+            "/api/v1/auth/refresh",
+            "/api/v1/oauth",
+            "/api/v1/oauth/authorizations/some-request",
+            "/api/v1/oauth/consents",
+        ] {
+            assert!(is_forbidden_path(path), "{} should be forbidden", path);
+        }
+        for path in ["/api/v1/me", "/api/v1/me/profile", "/api/v1/oauth-clients", "/api/v1/users"] {
+            assert!(!is_forbidden_path(path), "{} should be allowed", path);
+        }
+    }
+
+    #[test]
+    fn scopes_are_validated() {
+        assert!(validate_scopes(&scopes(&["users:read", "roles:write", "read"])).is_ok());
+        assert!(validate_scopes(&scopes(&["users:admin"])).is_err());
+        assert!(validate_scopes(&scopes(&["unknown:read"])).is_err());
+    }
+}
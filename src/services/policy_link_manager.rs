@@ -22,12 +22,12 @@ impl PolicyLinkManager {
         Self { db, auth_service }
     }
 
-    pub async fn ensure_links_loaded(&self, link_ids: &[PolicyId]) -> Result<(), AppError> {
+    pub async fn ensure_links_loaded(&self, tenant_id: i32, link_ids: &[PolicyId]) -> Result<(), AppError> {
         if link_ids.is_empty() {
             return Ok(());
         }
 
-        let cached_records = self.auth_service.get_template_link_records_from_cache().await?
+        let cached_records = self.auth_service.get_template_link_records_from_cache(tenant_id).await?
             .unwrap_or_default();
 
         let cached_ids: HashSet<_> = cached_records.iter().map(|r| &r.link_uuid).collect();
@@ -41,20 +41,21 @@ impl PolicyLinkManager {
             return Ok(());
         }
 
-        let new_records_from_db = self.load_link_records_from_db(&ids_to_load).await?;
+        let new_records_from_db = self.load_link_records_from_db(tenant_id, &ids_to_load).await?;
         if new_records_from_db.is_empty() {
             return Err(not_found!("数据库中未找到一个或多个所需的策略链接。"));
         }
 
         let all_records = [cached_records, new_records_from_db].concat();
-        self.auth_service.update_template_link_records_in_cache(&all_records).await?;
+        self.auth_service.update_template_link_records_in_cache(tenant_id, &all_records).await?;
 
         Ok(())
     }
 
-    pub async fn create_link(&self, record: TemplateLinkRecord) -> Result<(), AppError> {
+    pub async fn create_link(&self, tenant_id: i32, record: TemplateLinkRecord) -> Result<(), AppError> {
         let new_link = template_links::ActiveModel {
             link_uuid: sea_orm::Set(record.link_uuid.to_string()),
+            tenant_id: sea_orm::Set(tenant_id),
             template_uuid: sea_orm::Set(record.template_uuid.to_string()),
             principal_uid: sea_orm::Set(record.principal_uid.to_string()),
             resource_uid: sea_orm::Set(record.resource_uid.to_string()),
@@ -62,42 +63,44 @@ impl PolicyLinkManager {
         };
         new_link.insert(&self.db).await?;
 
-        let mut cached_records = self.auth_service.get_template_link_records_from_cache().await?
+        let mut cached_records = self.auth_service.get_template_link_records_from_cache(tenant_id).await?
             .unwrap_or_default();
 
         cached_records.retain(|r| r.link_uuid != record.link_uuid);
         cached_records.push(record);
 
-        self.auth_service.update_template_link_records_in_cache(&cached_records).await?;
+        self.auth_service.update_template_link_records_in_cache(tenant_id, &cached_records).await?;
 
         Ok(())
     }
 
-    pub async fn delete_link(&self, link_uuid: &PolicyId) -> Result<(), AppError> {
+    pub async fn delete_link(&self, tenant_id: i32, link_uuid: &PolicyId) -> Result<(), AppError> {
         let res = template_links::Entity::delete_many()
+            .filter(template_links::Column::TenantId.eq(tenant_id))
             .filter(template_links::Column::LinkUuid.eq(link_uuid.to_string()))
             .exec(&self.db).await?;
         if res.rows_affected == 0 {
             return Err(not_found!(format!("未找到可删除的 link_uuid 为“{}”的链接。", link_uuid)));
         }
 
-        let mut cached_records = self.auth_service.get_template_link_records_from_cache().await?
+        let mut cached_records = self.auth_service.get_template_link_records_from_cache(tenant_id).await?
             .unwrap_or_default();
 
         let initial_len = cached_records.len();
         cached_records.retain(|r| &r.link_uuid != link_uuid);
 
         if cached_records.len() < initial_len {
-            self.auth_service.update_template_link_records_in_cache(&cached_records).await?;
+            self.auth_service.update_template_link_records_in_cache(tenant_id, &cached_records).await?;
         }
 
         Ok(())
     }
 
-    async fn load_link_records_from_db(&self, link_ids: &[PolicyId]) -> Result<Vec<TemplateLinkRecord>, AppError> {
+    async fn load_link_records_from_db(&self, tenant_id: i32, link_ids: &[PolicyId]) -> Result<Vec<TemplateLinkRecord>, AppError> {
         let string_ids: Vec<String> = link_ids.iter().map(|id| id.to_string()).collect();
 
         let link_models = template_links::Entity::find()
+            .filter(template_links::Column::TenantId.eq(tenant_id))
             .filter(template_links::Column::LinkUuid.is_in(string_ids))
             .all(&self.db)
             .await?;
@@ -0,0 +1,549 @@
+// 服务账号: 非人类主体, 使用独立的密钥认证, 在 Cedar 中作为 RobotAccount 主体
+use cedar_policy::{Entities, Entity, EntityId, EntityUid, RestrictedExpression, Schema};
+use chrono::{Duration, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::JoinType::InnerJoin;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, RelationTrait, Set, TransactionTrait,
+};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use crate::config::state::AppState;
+use crate::entity::{group_roles, robot_account_groups, robot_account_roles, robot_accounts, roles, user_groups, users};
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::robot_account::{
+    CreateRobotAccountDto, RobotAccountQueryParams, RobotAccountResponse, RobotMemberOf,
+    RotateRobotSecretDto, UpdateRobotAccountDto, ROBOT_TOKEN_PREFIX,
+};
+use crate::services::groups::get_group_entities;
+use crate::services::role::{active_assignment, get_role_entities, get_role_hierarchy_entities};
+use crate::utils::cedar_utils::{
+    entity_type_name, AuthAction, ResourceType, ENTITY_ATTR_NAME, ENTITY_TYPE_GROUP, ENTITY_TYPE_ROBOT_ACCOUNT,
+    ENTITY_TYPE_ROLE, USER_ENTITIES_CACHE_PREFIX,
+};
+use crate::utils::crypto::{generate_secret, hash_token};
+use crate::{conflict, forbidden, not_found, unauthorized};
+
+// 服务账号没有个人资料, 也不能登录
+const ROBOT_FORBIDDEN_PATHS: &[&str] = &["/api/v1/me/", "/api/v1/auth/"];
+
+// 展示的密钥前缀长度, 含 ROBOT_TOKEN_PREFIX
+const DISPLAY_PREFIX_LEN: usize = 14;
+
+// 最后使用时间的更新间隔(秒)
+const LAST_USED_UPDATE_INTERVAL_SECONDS: i64 = 60;
+
+#[derive(Clone)]
+pub struct RobotAccountService {
+    app_state: AppState,
+}
+
+impl RobotAccountService {
+    pub fn new(app_state: AppState) -> Self {
+        Self { app_state }
+    }
+
+    pub async fn list_robots(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        params: RobotAccountQueryParams,
+    ) -> Result<(Vec<RobotAccountResponse>, u64), AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::ViewRobotAccount,
+                ResourceType::RobotAccount(None),
+            )
+            .await?;
+
+        let mut query = robot_accounts::Entity::find()
+            .filter(robot_accounts::Column::TenantId.eq(current_user.tenant_id))
+            .order_by_desc(robot_accounts::Column::RobotId);
+        if let Some(name) = params.name.as_deref().filter(|name| !name.is_empty()) {
+            query = query.filter(robot_accounts::Column::Name.contains(name));
+        }
+        let paginator = query.paginate(&self.app_state.db, params.page_size);
+        let total = paginator.num_items().await?;
+        let page_index = if params.page > 0 { params.page - 1 } else { 0 };
+        let robots = paginator.fetch_page(page_index).await?;
+
+        let mut results = Vec::with_capacity(robots.len());
+        for robot in robots {
+            results.push(self.to_response(robot).await?);
+        }
+        Ok((results, total))
+    }
+
+    pub async fn get_robot(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        robot_uuid: String,
+    ) -> Result<RobotAccountResponse, AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::ViewRobotAccount,
+                ResourceType::RobotAccount(Some(robot_uuid.clone())),
+            )
+            .await?;
+
+        let robot = self.find_robot(current_user.tenant_id, &robot_uuid).await?;
+        self.to_response(robot).await
+    }
+
+    pub async fn create_robot(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        dto: CreateRobotAccountDto,
+    ) -> Result<RobotAccountResponse, AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context.clone(),
+                AuthAction::CreateRobotAccount,
+                ResourceType::RobotAccount(None),
+            )
+            .await?;
+        self.check_membership_changes(&current_user, &context, &HashSet::new(), &dto.roles, &HashSet::new(), &dto.groups)
+            .await?;
+        self.ensure_name_available(current_user.tenant_id, &dto.name, None).await?;
+
+        let created_by = users::Entity::find()
+            .select_only()
+            .column(users::Column::UserId)
+            .filter(users::Column::UserUuid.eq(&current_user.uuid))
+            .into_tuple::<i32>()
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("User {} not found", current_user.uuid))?;
+
+        let secret = format!("{}{}", ROBOT_TOKEN_PREFIX, generate_secret());
+        let now = Utc::now();
+        let txn = self.app_state.db.begin().await?;
+        let robot = robot_accounts::ActiveModel {
+            robot_uuid: Set(uuid::Uuid::new_v4().to_string()),
+            name: Set(dto.name),
+            tenant_id: Set(current_user.tenant_id),
+            description: Set(dto.description),
+            secret_prefix: Set(secret[..DISPLAY_PREFIX_LEN].to_string()),
+            secret_hash: Set(hash_token(&secret)),
+            is_active: Set(dto.is_active),
+            expires_at: Set(dto.expires_in_days.map(|days| now + Duration::days(days as i64))),
+            created_by: Set(created_by),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        replace_memberships(&txn, current_user.tenant_id, robot.robot_id, Some(&dto.roles), Some(&dto.groups)).await?;
+        txn.commit().await?;
+
+        let mut response = self.to_response(robot).await?;
+        response.secret = Some(secret);
+        Ok(response)
+    }
+
+    pub async fn update_robot(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        robot_uuid: String,
+        dto: UpdateRobotAccountDto,
+    ) -> Result<RobotAccountResponse, AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context.clone(),
+                AuthAction::UpdateRobotAccount,
+                ResourceType::RobotAccount(Some(robot_uuid.clone())),
+            )
+            .await?;
+
+        let robot = self.find_robot(current_user.tenant_id, &robot_uuid).await?;
+        let current = self.memberships(robot.robot_id).await?;
+        let current_roles: HashSet<String> = current.0.into_iter().map(|role| role.uuid).collect();
+        let current_groups: HashSet<String> = current.1.into_iter().map(|group| group.uuid).collect();
+        self.check_membership_changes(
+            &current_user,
+            &context,
+            &current_roles,
+            dto.roles.as_deref().unwrap_or(&current_roles.iter().cloned().collect::<Vec<_>>()),
+            &current_groups,
+            dto.groups.as_deref().unwrap_or(&current_groups.iter().cloned().collect::<Vec<_>>()),
+        )
+        .await?;
+        if let Some(name) = &dto.name {
+            self.ensure_name_available(current_user.tenant_id, name, Some(robot.robot_id)).await?;
+        }
+
+        let txn = self.app_state.db.begin().await?;
+        let robot_id = robot.robot_id;
+        let mut robot: robot_accounts::ActiveModel = robot.into();
+        if let Some(name) = dto.name { robot.name = Set(name); }
+        if let Some(description) = dto.description { robot.description = Set(Some(description)); }
+        if let Some(is_active) = dto.is_active { robot.is_active = Set(is_active); }
+        robot.updated_at = Set(Utc::now());
+        let robot = robot.update(&txn).await?;
+        replace_memberships(&txn, current_user.tenant_id, robot_id, dto.roles.as_deref(), dto.groups.as_deref()).await?;
+        txn.commit().await?;
+
+        self.to_response(robot).await
+    }
+
+    /// 重置密钥, 旧密钥立即失效
+    pub async fn rotate_secret(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        robot_uuid: String,
+        dto: RotateRobotSecretDto,
+    ) -> Result<RobotAccountResponse, AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::UpdateRobotAccount,
+                ResourceType::RobotAccount(Some(robot_uuid.clone())),
+            )
+            .await?;
+
+        let robot = self.find_robot(current_user.tenant_id, &robot_uuid).await?;
+        let secret = format!("{}{}", ROBOT_TOKEN_PREFIX, generate_secret());
+        let now = Utc::now();
+        let mut robot: robot_accounts::ActiveModel = robot.into();
+        robot.secret_prefix = Set(secret[..DISPLAY_PREFIX_LEN].to_string());
+        robot.secret_hash = Set(hash_token(&secret));
+        robot.expires_at = Set(dto.expires_in_days.map(|days| now + Duration::days(days as i64)));
+        robot.updated_at = Set(now);
+        let robot = robot.update(&self.app_state.db).await?;
+
+        let mut response = self.to_response(robot).await?;
+        response.secret = Some(secret);
+        Ok(response)
+    }
+
+    pub async fn delete_robot(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        robot_uuid: String,
+    ) -> Result<(), AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::DeleteRobotAccount,
+                ResourceType::RobotAccount(Some(robot_uuid.clone())),
+            )
+            .await?;
+
+        let result = robot_accounts::Entity::delete_many()
+            .filter(robot_accounts::Column::RobotUuid.eq(&robot_uuid))
+            .filter(robot_accounts::Column::TenantId.eq(current_user.tenant_id))
+            .exec(&self.app_state.db)
+            .await?;
+        if result.rows_affected == 0 {
+            return Err(not_found!("Robot account {} not found", robot_uuid));
+        }
+        self.app_state
+            .cache_service
+            .invalidate_user_entities(format!("{}:{}", USER_ENTITIES_CACHE_PREFIX, robot_uuid))
+            .await?;
+        Ok(())
+    }
+
+    // 分配和移除角色、加入和退出用户组, 与用户的授权检查一致
+    async fn check_membership_changes(
+        &self,
+        current_user: &CurrentUser,
+        context: &CedarContext,
+        current_roles: &HashSet<String>,
+        roles: &[String],
+        current_groups: &HashSet<String>,
+        groups: &[String],
+    ) -> Result<(), AppError> {
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
+        let roles: HashSet<String> = roles.iter().cloned().collect();
+        let groups: HashSet<String> = groups.iter().cloned().collect();
+
+        let role_changes = roles
+            .difference(current_roles)
+            .map(|uuid| (uuid, AuthAction::AssignRole))
+            .chain(current_roles.difference(&roles).map(|uuid| (uuid, AuthAction::RevokeRole)));
+        for (role_uuid, action) in role_changes {
+            let role_es = get_role_entities(&self.app_state.db, current_user.tenant_id, &vec![role_uuid.clone()], &schema).await?;
+            if role_es.iter().next().is_none() {
+                return Err(not_found!("Role {} not found", role_uuid));
+            }
+            self.app_state
+                .auth_service
+                .check_permission_with_entities(
+                    &current_user.uuid,
+                    context.clone(),
+                    action,
+                    ResourceType::Role(Some(role_uuid.clone())),
+                    role_es,
+                )
+                .await?;
+        }
+
+        for group_uuid in groups.symmetric_difference(current_groups) {
+            let group_es = get_group_entities(&self.app_state.db, current_user.tenant_id, std::slice::from_ref(group_uuid), &schema).await?;
+            if group_es.iter().next().is_none() {
+                return Err(not_found!("Group {} not found", group_uuid));
+            }
+            self.app_state
+                .auth_service
+                .check_permission_with_entities(
+                    &current_user.uuid,
+                    context.clone(),
+                    AuthAction::UpdateGroup,
+                    ResourceType::Group(Some(group_uuid.clone())),
+                    group_es,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn ensure_name_available(&self, tenant_id: i32, name: &str, exclude_robot_id: Option<i32>) -> Result<(), AppError> {
+        let mut query = robot_accounts::Entity::find()
+            .filter(robot_accounts::Column::TenantId.eq(tenant_id))
+            .filter(robot_accounts::Column::Name.eq(name));
+        if let Some(robot_id) = exclude_robot_id {
+            query = query.filter(robot_accounts::Column::RobotId.ne(robot_id));
+        }
+        if query.count(&self.app_state.db).await? > 0 {
+            return Err(conflict!("Robot account {} already exists", name));
+        }
+        Ok(())
+    }
+
+    async fn find_robot(&self, tenant_id: i32, robot_uuid: &str) -> Result<robot_accounts::Model, AppError> {
+        robot_accounts::Entity::find()
+            .filter(robot_accounts::Column::RobotUuid.eq(robot_uuid))
+            .filter(robot_accounts::Column::TenantId.eq(tenant_id))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("Robot account {} not found", robot_uuid))
+    }
+
+    async fn memberships(&self, robot_id: i32) -> Result<(Vec<RobotMemberOf>, Vec<RobotMemberOf>), AppError> {
+        let roles = roles::Entity::find()
+            .select_only()
+            .column_as(roles::Column::RoleUuid, "uuid")
+            .column_as(roles::Column::RoleName, "name")
+            .join(InnerJoin, roles::Relation::RobotAccountRoles.def())
+            .filter(robot_account_roles::Column::RobotId.eq(robot_id))
+            .into_model::<RobotMemberOf>()
+            .all(&self.app_state.db)
+            .await?;
+        let groups = user_groups::Entity::find()
+            .select_only()
+            .column_as(user_groups::Column::UserGroupUuid, "uuid")
+            .column_as(user_groups::Column::Name, "name")
+            .join(InnerJoin, user_groups::Relation::RobotAccountGroups.def())
+            .filter(robot_account_groups::Column::RobotId.eq(robot_id))
+            .into_model::<RobotMemberOf>()
+            .all(&self.app_state.db)
+            .await?;
+        Ok((roles, groups))
+    }
+
+    async fn to_response(&self, robot: robot_accounts::Model) -> Result<RobotAccountResponse, AppError> {
+        let (roles, groups) = self.memberships(robot.robot_id).await?;
+        let mut response = RobotAccountResponse::from(robot);
+        response.roles = roles;
+        response.groups = groups;
+        Ok(response)
+    }
+}
+
+// 传入 None 时保留原有的角色或用户组
+async fn replace_memberships<C: sea_orm::ConnectionTrait>(
+    db: &C,
+    tenant_id: i32,
+    robot_id: i32,
+    role_uuids: Option<&[String]>,
+    group_uuids: Option<&[String]>,
+) -> Result<(), AppError> {
+    if let Some(role_uuids) = role_uuids {
+        robot_account_roles::Entity::delete_many()
+            .filter(robot_account_roles::Column::RobotId.eq(robot_id))
+            .exec(db)
+            .await?;
+        let role_ids = roles::Entity::find()
+            .select_only()
+            .column(roles::Column::RoleId)
+            .filter(roles::Column::RoleUuid.is_in(role_uuids.to_vec()))
+            .filter(roles::Column::TenantId.eq(tenant_id))
+            .into_tuple::<i32>()
+            .all(db)
+            .await?;
+        if !role_ids.is_empty() {
+            robot_account_roles::Entity::insert_many(role_ids.into_iter().map(|role_id| robot_account_roles::ActiveModel {
+                robot_id: Set(robot_id),
+                role_id: Set(role_id),
+            }))
+            .exec(db)
+            .await?;
+        }
+    }
+    if let Some(group_uuids) = group_uuids {
+        robot_account_groups::Entity::delete_many()
+            .filter(robot_account_groups::Column::RobotId.eq(robot_id))
+            .exec(db)
+            .await?;
+        let group_ids = user_groups::Entity::find()
+            .select_only()
+            .column(user_groups::Column::UserGroupId)
+            .filter(user_groups::Column::UserGroupUuid.is_in(group_uuids.to_vec()))
+            .filter(user_groups::Column::TenantId.eq(tenant_id))
+            .into_tuple::<i32>()
+            .all(db)
+            .await?;
+        if !group_ids.is_empty() {
+            robot_account_groups::Entity::insert_many(group_ids.into_iter().map(|group_id| robot_account_groups::ActiveModel {
+                robot_id: Set(robot_id),
+                group_id: Set(group_id),
+            }))
+            .exec(db)
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// 校验服务账号密钥, 返回以服务账号为主体的 CurrentUser
+pub async fn authenticate_robot(
+    app_state: &AppState,
+    token: &str,
+    path: &str,
+    source_ip: &str,
+) -> Result<CurrentUser, AppError> {
+    let robot = robot_accounts::Entity::find()
+        .filter(robot_accounts::Column::SecretHash.eq(hash_token(token)))
+        .filter(robot_accounts::Column::IsActive.eq(true))
+        .one(&app_state.db)
+        .await?
+        .ok_or(unauthorized!("InvalidToken"))?;
+    let now = Utc::now();
+    if robot.expires_at.is_some_and(|expires_at| expires_at <= now) {
+        return Err(unauthorized!("Token expired"));
+    }
+    if path == "/api/v1/me" || ROBOT_FORBIDDEN_PATHS.iter().any(|prefix| path.starts_with(prefix)) {
+        return Err(forbidden!("Robot accounts cannot be used for this endpoint"));
+    }
+
+    let used_before = now - Duration::seconds(LAST_USED_UPDATE_INTERVAL_SECONDS);
+    robot_accounts::Entity::update_many()
+        .col_expr(robot_accounts::Column::LastUsedAt, Expr::value(now))
+        .col_expr(robot_accounts::Column::LastUsedIp, Expr::value(source_ip))
+        .filter(robot_accounts::Column::RobotId.eq(robot.robot_id))
+        .filter(
+            Condition::any()
+                .add(robot_accounts::Column::LastUsedAt.is_null())
+                .add(robot_accounts::Column::LastUsedAt.lt(used_before)),
+        )
+        .exec(&app_state.db)
+        .await?;
+
+    // 角色和用户组变更的事件只刷新用户的实体缓存, 服务账号每次请求时重新生成
+    let schema = app_state.auth_service.get_schema_copy(robot.tenant_id).await;
+    let entities = get_robot_entities(&app_state.db, &robot, &schema).await?;
+    app_state
+        .cache_service
+        .cache_entities(format!("{}:{}", USER_ENTITIES_CACHE_PREFIX, robot.robot_uuid), entities)
+        .await?;
+
+    Ok(CurrentUser {
+        uuid: robot.robot_uuid,
+        dept_uuid: String::new(),
+        username: robot.name,
+        is_super_admin: false,
+        tenant_id: robot.tenant_id,
+    })
+}
+
+/// 服务账号的主体实体: 父实体为直接分配的角色、所属用户组及用户组的角色
+pub async fn get_robot_entities(
+    db: &DatabaseConnection,
+    robot: &robot_accounts::Model,
+    schema: &Schema,
+) -> Result<Entities, AppError> {
+    let groups = user_groups::Entity::find()
+        .join(InnerJoin, user_groups::Relation::RobotAccountGroups.def())
+        .filter(robot_account_groups::Column::RobotId.eq(robot.robot_id))
+        .all(db)
+        .await?;
+    let group_ids: Vec<i32> = groups.iter().map(|group| group.user_group_id).collect();
+    let mut role_condition = Condition::any().add(
+        roles::Column::RoleId.in_subquery(
+            sea_orm::sea_query::Query::select()
+                .column(robot_account_roles::Column::RoleId)
+                .from(robot_account_roles::Entity)
+                .and_where(robot_account_roles::Column::RobotId.eq(robot.robot_id))
+                .to_owned(),
+        ),
+    );
+    if !group_ids.is_empty() {
+        role_condition = role_condition.add(
+            roles::Column::RoleId.in_subquery(
+                sea_orm::sea_query::Query::select()
+                    .column(group_roles::Column::RoleId)
+                    .from(group_roles::Entity)
+                    .and_where(group_roles::Column::GroupId.is_in(group_ids))
+                    .cond_where(active_assignment(group_roles::Column::ValidFrom, group_roles::Column::ValidUntil))
+                    .to_owned(),
+            ),
+        );
+    }
+    let all_roles = roles::Entity::find().filter(role_condition).all(db).await?;
+
+    let mut entities = Vec::new();
+    let mut parent_uids = HashSet::new();
+    for group in groups {
+        let uid = EntityUid::from_type_name_and_id(
+            entity_type_name(schema, ENTITY_TYPE_GROUP)?,
+            EntityId::from_str(&group.user_group_uuid)?,
+        );
+        let mut attrs = HashMap::new();
+        attrs.insert(ENTITY_ATTR_NAME.to_string(), RestrictedExpression::new_string(group.name));
+        entities.push(Entity::new(uid.clone(), attrs, HashSet::new())?);
+        parent_uids.insert(uid);
+    }
+    // 角色实体带有继承的祖先角色
+    for role in &all_roles {
+        parent_uids.insert(EntityUid::from_type_name_and_id(
+            entity_type_name(schema, ENTITY_TYPE_ROLE)?,
+            EntityId::from_str(&role.role_uuid)?,
+        ));
+    }
+    entities.extend(get_role_hierarchy_entities(db, robot.tenant_id, all_roles, schema).await?);
+
+    let robot_uid = EntityUid::from_type_name_and_id(
+        entity_type_name(schema, ENTITY_TYPE_ROBOT_ACCOUNT)?,
+        EntityId::from_str(&robot.robot_uuid)?,
+    );
+    let mut attrs = HashMap::new();
+    attrs.insert(ENTITY_ATTR_NAME.to_string(), RestrictedExpression::new_string(robot.name.clone()));
+    entities.push(Entity::new(robot_uid, attrs, parent_uids)?);
+
+    Ok(Entities::from_entities(entities, Some(schema))?)
+}
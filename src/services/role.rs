@@ -1,20 +1,23 @@
 // 角色管理路由
 
 use crate::config::state::AppState;
-use crate::entity::{group_roles, roles, user_group_members, user_roles, users};
+use crate::entity::{group_roles, role_parents, roles, tenants, user_group_members, user_roles, users};
 use crate::errors::app_error::AppError;
 use crate::schemas::auth::CurrentUser;
 use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::event::DomainEvent;
 use crate::schemas::role::{
     CreateRoleDto, QueryParams, RoleFieldResponse, RoleResponse,
     UpdateRoleDto,
 };
-use crate::utils::cedar_utils::{entities2json, AuthAction, ResourceType, ENTITY_TYPE_ROLE, ENTITY_ATTR_NAME};
+use crate::services::event_bus::publish_event;
+use crate::utils::cedar_utils::{entities2json, entity_type_name, AuthAction, ResourceType, ENTITY_TYPE_ROLE, ENTITY_ATTR_NAME};
 use crate::{bad_request, conflict, not_found};
-use cedar_policy::{Entities, Entity, EntityId, EntityTypeName, EntityUid, RestrictedExpression, Schema};
-use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QuerySelect, QueryTrait, Select, Set, TransactionTrait};
+use cedar_policy::{Entities, Entity, EntityId, EntityUid, RestrictedExpression, Schema};
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait, PaginatorTrait, JoinType, QueryFilter, QuerySelect, QueryTrait, RelationTrait, Select, Set, TransactionTrait};
 use serde_json::Value;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::str::FromStr;
 use tracing::debug;
 use uuid::Uuid;
@@ -64,7 +67,8 @@ impl RoleService {
             });
 
         // 构建基础查询
-        let mut select = roles::Entity::find();
+        let mut select = roles::Entity::find()
+            .filter(roles::Column::TenantId.eq(current_user.tenant_id));
 
         // 应用通用过滤条件
         if let Some(role_name) = &params.name {
@@ -107,8 +111,8 @@ impl RoleService {
                           context: CedarContext,
                           role_uuid: String) -> Result<RoleResponse, AppError> {
 
-        let schema = self.app_state.auth_service.get_schema_copy().await;
-        let es = get_role_entities(&self.app_state.db, &vec![role_uuid.clone()], &schema).await?;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
+        let es = get_role_entities(&self.app_state.db, current_user.tenant_id, &vec![role_uuid.clone()], &schema).await?;
 
         self.app_state
             .auth_service
@@ -121,16 +125,18 @@ impl RoleService {
             )
             .await?;
 
-        let role = roles::Entity::find()
+        let mut role = roles::Entity::find()
             .column_as(roles::Column::RoleUuid, "id")
             .column_as(roles::Column::RoleName, "name")
             .column(roles::Column::Description)
             .column(roles::Column::CreatedAt)
-            .filter(roles::Column::RoleUuid.eq(role_uuid))
+            .filter(roles::Column::RoleUuid.eq(&role_uuid))
+            .filter(roles::Column::TenantId.eq(current_user.tenant_id))
             .into_model::<RoleResponse>()
             .one(&self.app_state.db)
             .await?
             .ok_or(not_found!("Role Not Found".to_string()))?;
+        role.parent_roles = Some(get_parent_role_uuids(&self.app_state.db, current_user.tenant_id, &role_uuid).await?);
         Ok(role)
     }
 
@@ -143,13 +149,14 @@ impl RoleService {
             .auth_service
             .check_permission(
                 &current_user.uuid,
-                context,
+                context.clone(),
                 AuthAction::CreateRole,
                 ResourceType::Role(None),
             )
             .await?;
 
         if roles::Entity::find()
+            .filter(roles::Column::TenantId.eq(current_user.tenant_id))
             .filter(roles::Column::RoleName.eq(&dto.name))
             .one(&self.app_state.db)
             .await?
@@ -158,15 +165,26 @@ impl RoleService {
             return Err(conflict!("Role already exists".to_string()));
         }
 
+        let parents = self
+            .resolve_parent_roles(&current_user, context, None, dto.parent_roles)
+            .await?;
+
+        let txn = self.app_state.db.begin().await?;
         let role = roles::ActiveModel {
             role_uuid: Set(Uuid::new_v4().to_string()),
+            tenant_id: Set(current_user.tenant_id),
             role_name: Set(dto.name),
             description: Set(Some(dto.description)),
             ..Default::default()
         };
 
-        let role = role.insert(&self.app_state.db).await?;
-        Ok(RoleResponse::from(role))
+        let role = role.insert(&txn).await?;
+        save_parent_roles(&txn, role.role_id, &parents).await?;
+        txn.commit().await?;
+
+        let mut response = RoleResponse::from(role);
+        response.parent_roles = Some(parents.into_iter().map(|parent| parent.role_uuid).collect());
+        Ok(response)
     }
 
     pub async fn update_role(&self,
@@ -175,13 +193,13 @@ impl RoleService {
                              role_uuid: String,
                              dto: UpdateRoleDto) -> Result<RoleResponse, AppError> {
 
-        let schema = self.app_state.auth_service.get_schema_copy().await;
-        let es = get_role_entities(&self.app_state.db, &vec![role_uuid.clone()], &schema).await?;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
+        let es = get_role_entities(&self.app_state.db, current_user.tenant_id, &vec![role_uuid.clone()], &schema).await?;
         self.app_state
             .auth_service
             .check_permission_with_entities(
                 &current_user.uuid,
-                context,
+                context.clone(),
                 AuthAction::UpdateRole,
                 ResourceType::Role(Some(role_uuid.clone())),
                 es
@@ -189,12 +207,21 @@ impl RoleService {
             .await?;
 
 
-        let mut role: roles::ActiveModel = roles::Entity::find()
+        let role_model = roles::Entity::find()
             .filter(roles::Column::RoleUuid.eq(&role_uuid))
+            .filter(roles::Column::TenantId.eq(current_user.tenant_id))
             .one(&self.app_state.db)
             .await?
-            .ok_or(not_found!("Role Not Found".to_string()))?
-            .into();
+            .ok_or(not_found!("Role Not Found".to_string()))?;
+        let parents = match dto.parent_roles {
+            Some(parent_roles) => Some(
+                self.resolve_parent_roles(&current_user, context, Some(&role_model), parent_roles)
+                    .await?,
+            ),
+            None => None,
+        };
+        let (role_id, role_name) = (role_model.role_id, role_model.role_name.clone());
+        let mut role: roles::ActiveModel = role_model.into();
 
         if let Some(name) = dto.name {
             role.role_name = Set(name);
@@ -204,8 +231,34 @@ impl RoleService {
             role.description = Set(Some(description));
         }
         
-        let role = role.update(&self.app_state.db).await?;
-        Ok(RoleResponse::from(role))
+        let txn = self.app_state.db.begin().await?;
+        if let Some(parents) = &parents {
+            // 锁定租户, 同一租户的继承关系修改串行执行, 避免并发修改在检查之后形成环
+            tenants::Entity::find_by_id(current_user.tenant_id)
+                .lock_exclusive()
+                .one(&txn)
+                .await?;
+            let parent_map = get_role_parent_map(&txn, current_user.tenant_id).await?;
+            ensure_no_cycle(&parent_map, role_id, parents, &role_name)?;
+        }
+        let role = role.update(&txn).await?;
+        if let Some(parents) = &parents {
+            role_parents::Entity::delete_many()
+                .filter(role_parents::Column::RoleId.eq(role.role_id))
+                .exec(&txn)
+                .await?;
+            save_parent_roles(&txn, role.role_id, parents).await?;
+        }
+        txn.commit().await?;
+
+        // 继承关系变化影响持有该角色及其子角色的用户
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::RoleUpdated { role_uuid: role_uuid.clone() }).await;
+        let mut response = RoleResponse::from(role);
+        response.parent_roles = Some(match parents {
+            Some(parents) => parents.into_iter().map(|parent| parent.role_uuid).collect(),
+            None => get_parent_role_uuids(&self.app_state.db, current_user.tenant_id, &role_uuid).await?,
+        });
+        Ok(response)
     }
 
     pub async fn delete_role(&self,
@@ -213,8 +266,8 @@ impl RoleService {
                              context: CedarContext,
                              role_uuid: String) -> Result<(), AppError> {
 
-        let schema = self.app_state.auth_service.get_schema_copy().await;
-        let es = get_role_entities(&self.app_state.db, &vec![role_uuid.clone()], &schema).await?;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
+        let es = get_role_entities(&self.app_state.db, current_user.tenant_id, &vec![role_uuid.clone()], &schema).await?;
         self.app_state
             .auth_service
             .check_permission_with_entities(
@@ -226,11 +279,238 @@ impl RoleService {
             )
             .await?;
 
-        roles::Entity::delete_many()
-            .filter(roles::Column::RoleUuid.eq(role_uuid)).exec(&self.app_state.db).await?;
+        let role = roles::Entity::find()
+            .filter(roles::Column::RoleUuid.eq(&role_uuid))
+            .filter(roles::Column::TenantId.eq(current_user.tenant_id))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("Role Not Found".to_string()))?;
+        let user_uuids = get_role_user_uuids(&self.app_state.db, &role_uuid).await?;
+        roles::Entity::delete_by_id(role.role_id).exec(&self.app_state.db).await?;
 
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::RoleDeleted { role_uuid, user_uuids }).await;
         Ok(())
     }
+
+    // 校验父角色: 必须属于当前租户, 且操作者对每个父角色都有分配权限(是否形成环在事务中检查),
+    // 否则可以通过继承把自己无权分配的角色权限授予他人
+    async fn resolve_parent_roles(
+        &self,
+        current_user: &CurrentUser,
+        context: CedarContext,
+        role: Option<&roles::Model>,
+        parent_uuids: Vec<String>,
+    ) -> Result<Vec<roles::Model>, AppError> {
+        let parent_uuids: Vec<String> = parent_uuids
+            .into_iter()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        if parent_uuids.is_empty() {
+            return Ok(Vec::new());
+        }
+        if let Some(role) = role
+            && parent_uuids.contains(&role.role_uuid)
+        {
+            return Err(bad_request!("Role {} cannot inherit from itself", role.role_name));
+        }
+
+        let parents = roles::Entity::find()
+            .filter(roles::Column::RoleUuid.is_in(parent_uuids.clone()))
+            .filter(roles::Column::TenantId.eq(current_user.tenant_id))
+            .all(&self.app_state.db)
+            .await?;
+        if parents.len() != parent_uuids.len() {
+            return Err(not_found!("Parent role not found"));
+        }
+
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
+        let parent_es = get_role_entities(&self.app_state.db, current_user.tenant_id, &parent_uuids, &schema).await?;
+        for parent_uuid in &parent_uuids {
+            self.app_state
+                .auth_service
+                .check_permission_with_entities(
+                    &current_user.uuid,
+                    context.clone(),
+                    AuthAction::AssignRole,
+                    ResourceType::Role(Some(parent_uuid.clone())),
+                    parent_es.clone(),
+                )
+                .await?;
+        }
+
+        Ok(parents)
+    }
+}
+
+// 任一父角色已经继承自该角色时, 再继承该父角色会形成环
+fn ensure_no_cycle(
+    parent_map: &HashMap<i32, Vec<i32>>,
+    role_id: i32,
+    parents: &[roles::Model],
+    role_name: &str,
+) -> Result<(), AppError> {
+    match parents
+        .iter()
+        .find(|parent| role_ancestor_ids(parent_map, parent.role_id).contains(&role_id))
+    {
+        Some(parent) => Err(bad_request!(
+            "Role {} already inherits from {}, inheriting it back would create a cycle",
+            parent.role_name,
+            role_name
+        )),
+        None => Ok(()),
+    }
+}
+
+async fn save_parent_roles<C: ConnectionTrait>(db: &C, role_id: i32, parents: &[roles::Model]) -> Result<(), AppError> {
+    if parents.is_empty() {
+        return Ok(());
+    }
+    let now = Utc::now();
+    role_parents::Entity::insert_many(parents.iter().map(|parent| role_parents::ActiveModel {
+        role_id: Set(role_id),
+        parent_role_id: Set(parent.role_id),
+        created_at: Set(now),
+    }))
+    .exec(db)
+    .await?;
+    Ok(())
+}
+
+async fn get_parent_role_uuids(db: &DatabaseConnection, tenant_id: i32, role_uuid: &str) -> Result<Vec<String>, AppError> {
+    let parent_uuids = roles::Entity::find()
+        .select_only()
+        .column(roles::Column::RoleUuid)
+        .join(JoinType::InnerJoin, role_parents::Relation::ParentRoles.def().rev())
+        .filter(
+            role_parents::Column::RoleId.in_subquery(
+                roles::Entity::find()
+                    .select_only()
+                    .column(roles::Column::RoleId)
+                    .filter(roles::Column::RoleUuid.eq(role_uuid))
+                    .filter(roles::Column::TenantId.eq(tenant_id))
+                    .into_query(),
+            ),
+        )
+        .into_tuple::<String>()
+        .all(db)
+        .await?;
+    Ok(parent_uuids)
+}
+
+/// 租户内全部角色的直接父角色: role_id -> 父角色 role_id
+pub async fn get_role_parent_map<C: ConnectionTrait>(db: &C, tenant_id: i32) -> Result<HashMap<i32, Vec<i32>>, AppError> {
+    let edges = role_parents::Entity::find()
+        .select_only()
+        .columns([role_parents::Column::RoleId, role_parents::Column::ParentRoleId])
+        .filter(
+            role_parents::Column::RoleId.in_subquery(
+                roles::Entity::find()
+                    .select_only()
+                    .column(roles::Column::RoleId)
+                    .filter(roles::Column::TenantId.eq(tenant_id))
+                    .into_query(),
+            ),
+        )
+        .into_tuple::<(i32, i32)>()
+        .all(db)
+        .await?;
+    let mut parent_map: HashMap<i32, Vec<i32>> = HashMap::new();
+    for (role_id, parent_role_id) in edges {
+        parent_map.entry(role_id).or_default().push(parent_role_id);
+    }
+    Ok(parent_map)
+}
+
+/// 角色的全部祖先角色(不含自身), 近的在前
+pub fn role_ancestor_ids(parent_map: &HashMap<i32, Vec<i32>>, role_id: i32) -> Vec<i32> {
+    let mut ancestors = Vec::new();
+    let mut visited = HashSet::from([role_id]);
+    let mut queue = VecDeque::from([role_id]);
+    while let Some(current) = queue.pop_front() {
+        for &parent_id in parent_map.get(&current).into_iter().flatten() {
+            if visited.insert(parent_id) {
+                ancestors.push(parent_id);
+                queue.push_back(parent_id);
+            }
+        }
+    }
+    ancestors
+}
+
+/// 通过继承获得的角色(不含已直接拥有的), 以及经由哪个已拥有的角色继承
+pub async fn get_inherited_roles(
+    db: &DatabaseConnection,
+    parent_map: &HashMap<i32, Vec<i32>>,
+    granted_roles: &[roles::Model],
+) -> Result<Vec<(roles::Model, String)>, AppError> {
+    let mut inherited_from = inherited_role_sources(parent_map, granted_roles);
+    if inherited_from.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let inherited = roles::Entity::find()
+        .filter(roles::Column::RoleId.is_in(inherited_from.keys().copied().collect::<Vec<_>>()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|role| {
+            let from = inherited_from.remove(&role.role_id).unwrap_or_default();
+            (role, from)
+        })
+        .collect();
+    Ok(inherited)
+}
+
+// 继承得到的角色ID -> 经由的已拥有角色名称, 按已拥有角色的顺序取第一个
+fn inherited_role_sources(parent_map: &HashMap<i32, Vec<i32>>, granted_roles: &[roles::Model]) -> HashMap<i32, String> {
+    let granted_ids: HashSet<i32> = granted_roles.iter().map(|role| role.role_id).collect();
+    let mut inherited_from = HashMap::new();
+    for role in granted_roles {
+        for ancestor_id in role_ancestor_ids(parent_map, role.role_id) {
+            if !granted_ids.contains(&ancestor_id) {
+                inherited_from.entry(ancestor_id).or_insert_with(|| role.role_name.clone());
+            }
+        }
+    }
+    inherited_from
+}
+
+/// 角色及其全部祖先角色的实体, 每个角色以直接父角色为父实体, Cedar 据此推导 `Role in Role` 的传递关系
+pub async fn get_role_hierarchy_entities(
+    db: &DatabaseConnection,
+    tenant_id: i32,
+    roles: Vec<roles::Model>,
+    schema: &Schema,
+) -> Result<Vec<Entity>, AppError> {
+    let parent_map = get_role_parent_map(db, tenant_id).await?;
+    let mut all_roles = roles;
+    let inherited = get_inherited_roles(db, &parent_map, &all_roles).await?;
+    all_roles.extend(inherited.into_iter().map(|(role, _)| role));
+
+    let role_uuids: HashMap<i32, String> = all_roles
+        .iter()
+        .map(|role| (role.role_id, role.role_uuid.clone()))
+        .collect();
+    let role_type_name = entity_type_name(schema, ENTITY_TYPE_ROLE)?;
+    let mut entities = Vec::new();
+    for role in all_roles {
+        let role_e_uid = EntityUid::from_type_name_and_id(role_type_name.clone(), EntityId::from_str(&role.role_uuid)?);
+
+        let mut attrs = HashMap::new();
+        let name_expr = RestrictedExpression::new_string(role.role_name);
+        attrs.insert(ENTITY_ATTR_NAME.to_string(), name_expr);
+
+        let mut parents = HashSet::new();
+        for parent_id in parent_map.get(&role.role_id).into_iter().flatten() {
+            if let Some(parent_uuid) = role_uuids.get(parent_id) {
+                parents.insert(EntityUid::from_type_name_and_id(role_type_name.clone(), EntityId::from_str(parent_uuid)?));
+            }
+        }
+        entities.push(Entity::new(role_e_uid, attrs, parents)?);
+    }
+    Ok(entities)
 }
 
 
@@ -246,7 +526,8 @@ pub async fn get_role_models_by_user_uuid(db: &DatabaseConnection, user_uuid: Us
     let direct_role_ids_query = user_roles::Entity::find()
         .select_only() // 只选择特定列
         .column(user_roles::Column::RoleId) // 我们只需要 role_id
-        .filter(user_roles::Column::UserId.in_subquery(user_id_subquery.clone()));
+        .filter(user_roles::Column::UserId.in_subquery(user_id_subquery.clone()))
+        .filter(active_assignment(user_roles::Column::ValidFrom, user_roles::Column::ValidUntil));
 
     // --- 子查询 2: 获取通过用户组继承的角色 ID ---
     // 首先，找到该用户所属的所有 group_id
@@ -259,7 +540,8 @@ pub async fn get_role_models_by_user_uuid(db: &DatabaseConnection, user_uuid: Us
     let group_role_ids_query = group_roles::Entity::find()
         .select_only()
         .column(group_roles::Column::RoleId)
-        .filter(group_roles::Column::GroupId.in_subquery(group_ids_query.into_query()));
+        .filter(group_roles::Column::GroupId.in_subquery(group_ids_query.into_query()))
+        .filter(active_assignment(group_roles::Column::ValidFrom, group_roles::Column::ValidUntil));
 
     // --- 主查询: 获取所有符合条件的角色信息 ---
     // 使用 Condition::any() (即 OR) 来合并两个子查询的结果
@@ -277,27 +559,181 @@ pub async fn get_role_models_by_user_uuid(db: &DatabaseConnection, user_uuid: Us
 }
 
 
-pub async fn get_role_entities(db: &DatabaseConnection, role_ids: &Vec<String>, schema: &Schema) -> Result<Entities, AppError> {
-    let roles = roles::Entity::find()
-        .filter(roles::Column::RoleUuid.is_in(role_ids.to_vec()))
+// 拥有该角色的用户(直接分配、通过用户组或通过子角色继承)
+pub async fn get_role_user_uuids(db: &DatabaseConnection, role_uuid: &str) -> Result<Vec<UserUUID>, AppError> {
+    let Some(role_id) = roles::Entity::find()
+        .select_only()
+        .column(roles::Column::RoleId)
+        .filter(roles::Column::RoleUuid.eq(role_uuid))
+        .into_tuple::<i32>()
+        .one(db)
+        .await?
+    else {
+        return Ok(Vec::new());
+    };
+    let role_ids = get_role_descendant_ids(db, role_id).await?;
+
+    let direct_user_ids_query = user_roles::Entity::find()
+        .select_only()
+        .column(user_roles::Column::UserId)
+        .filter(user_roles::Column::RoleId.is_in(role_ids.clone()));
+
+    let group_ids_query = group_roles::Entity::find()
+        .select_only()
+        .column(group_roles::Column::GroupId)
+        .filter(group_roles::Column::RoleId.is_in(role_ids));
+
+    let group_user_ids_query = user_group_members::Entity::find()
+        .select_only()
+        .column(user_group_members::Column::UserId)
+        .filter(user_group_members::Column::GroupId.in_subquery(group_ids_query.into_query()));
+
+    let user_uuids = users::Entity::find()
+        .select_only()
+        .column(users::Column::UserUuid)
+        .filter(
+            Condition::any()
+                .add(users::Column::UserId.in_subquery(direct_user_ids_query.into_query()))
+                .add(users::Column::UserId.in_subquery(group_user_ids_query.into_query())),
+        )
+        .into_tuple::<String>()
         .all(db)
         .await?;
-    let mut entities = HashSet::new();
-    for role in roles {
-        let role_eid = EntityId::from_str(&role.role_uuid.to_string())?;
-        let role_typename = EntityTypeName::from_str(ENTITY_TYPE_ROLE)?;
-        let role_e_uid = EntityUid::from_type_name_and_id(role_typename, role_eid);
+    Ok(user_uuids)
+}
 
-        let mut attrs = HashMap::new();
-        let name_expr = RestrictedExpression::new_string(role.role_name);
-        attrs.insert(ENTITY_ATTR_NAME.to_string(), name_expr);
+/// 处于有效期内的角色分配: 生效时间为空或已到, 失效时间为空或未到
+pub fn active_assignment<C: ColumnTrait>(valid_from: C, valid_until: C) -> Condition {
+    let now = Utc::now();
+    Condition::all()
+        .add(Condition::any().add(valid_from.is_null()).add(valid_from.lte(now)))
+        .add(Condition::any().add(valid_until.is_null()).add(valid_until.gt(now)))
+}
 
-        let parents = HashSet::new();
-        let role_entity = Entity::new(role_e_uid, attrs, parents)?;
-        entities.insert(role_entity);
+/// 校验限时分配的有效期, 返回分配后是否立即生效
+pub fn validate_assignment_period(
+    valid_from: Option<DateTime<Utc>>,
+    valid_until: Option<DateTime<Utc>>,
+) -> Result<bool, AppError> {
+    let now = Utc::now();
+    if let Some(valid_until) = valid_until {
+        if valid_until <= now {
+            return Err(bad_request!("valid_until must be in the future"));
+        }
+        if let Some(valid_from) = valid_from
+            && valid_from >= valid_until
+        {
+            return Err(bad_request!("valid_from must be earlier than valid_until"));
+        }
+    }
+    Ok(valid_from.is_none_or(|valid_from| valid_from <= now))
+}
+
+// 角色自身及其全部子孙角色
+async fn get_role_descendant_ids(db: &DatabaseConnection, role_id: i32) -> Result<Vec<i32>, AppError> {
+    let mut role_ids = vec![role_id];
+    let mut visited = HashSet::from([role_id]);
+    let mut frontier = vec![role_id];
+    while !frontier.is_empty() {
+        let children = role_parents::Entity::find()
+            .select_only()
+            .column(role_parents::Column::RoleId)
+            .filter(role_parents::Column::ParentRoleId.is_in(frontier))
+            .into_tuple::<i32>()
+            .all(db)
+            .await?;
+        frontier = children.into_iter().filter(|id| visited.insert(*id)).collect();
+        role_ids.extend(&frontier);
     }
+    Ok(role_ids)
+}
+
+/// 只包含属于该租户的角色, 其他租户的角色视为不存在; 同时包含其祖先角色以体现继承关系
+pub async fn get_role_entities(db: &DatabaseConnection, tenant_id: i32, role_ids: &Vec<String>, schema: &Schema) -> Result<Entities, AppError> {
+    let roles = roles::Entity::find()
+        .filter(roles::Column::TenantId.eq(tenant_id))
+        .filter(roles::Column::RoleUuid.is_in(role_ids.to_vec()))
+        .all(db)
+        .await?;
+    let entities = get_role_hierarchy_entities(db, tenant_id, roles, schema).await?;
     let entities = Entities::from_entities(entities, Some(&schema))?;
     let entities_json = entities2json(&entities)?;
     debug!("Role: {:?}; Entities Json: {}", role_ids, entities_json);
     Ok(entities)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(role_id: i32) -> roles::Model {
+        roles::Model {
+            role_id,
+            role_uuid: format!("role-{}", role_id),
+            tenant_id: 1,
+            role_name: format!("R{}", role_id),
+            description: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn parent_map(edges: &[(i32, i32)]) -> HashMap<i32, Vec<i32>> {
+        let mut map: HashMap<i32, Vec<i32>> = HashMap::new();
+        for &(role_id, parent_id) in edges {
+            map.entry(role_id).or_default().push(parent_id);
+        }
+        map
+    }
+
+    #[test]
+    fn ancestors_exclude_self_even_with_self_loop() {
+        let map = parent_map(&[(1, 1), (1, 2)]);
+        assert_eq!(role_ancestor_ids(&map, 1), vec![2]);
+        assert!(role_ancestor_ids(&map, 3).is_empty());
+    }
+
+    #[test]
+    fn ancestors_terminate_on_indirect_cycle() {
+        // 1 -> 2 -> 3 -> 1
+        let map = parent_map(&[(1, 2), (2, 3), (3, 1)]);
+        assert_eq!(role_ancestor_ids(&map, 1), vec![2, 3]);
+        assert_eq!(role_ancestor_ids(&map, 2), vec![3, 1]);
+    }
+
+    #[test]
+    fn ancestors_visit_diamond_once_nearest_first() {
+        // 1 继承 2 和 3, 2 和 3 都继承 4, 4 继承 5
+        let map = parent_map(&[(1, 2), (1, 3), (2, 4), (3, 4), (4, 5)]);
+        assert_eq!(role_ancestor_ids(&map, 1), vec![2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn rejects_parent_that_already_inherits_the_role() {
+        // 1 -> 2 -> 3, 让 3 继承 1 会形成环
+        let map = parent_map(&[(1, 2), (2, 3)]);
+        assert!(ensure_no_cycle(&map, 3, &[role(1)], "R3").is_err());
+        assert!(ensure_no_cycle(&map, 3, &[role(4), role(2)], "R3").is_err());
+        // 菱形不是环
+        let map = parent_map(&[(1, 2), (1, 3), (2, 4)]);
+        assert!(ensure_no_cycle(&map, 3, &[role(4)], "R3").is_ok());
+        assert!(ensure_no_cycle(&map, 5, &[role(1)], "R5").is_ok());
+    }
+
+    #[test]
+    fn inherited_roles_skip_granted_and_keep_first_source() {
+        let map = parent_map(&[(1, 2), (1, 3), (2, 4), (3, 4), (5, 4)]);
+        let sources = inherited_role_sources(&map, &[role(1), role(5), role(3)]);
+        // 3 已直接拥有, 不算继承; 4 经由第一个已拥有的角色 R1 继承
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources.get(&2).map(String::as_str), Some("R1"));
+        assert_eq!(sources.get(&4).map(String::as_str), Some("R1"));
+        assert!(!sources.contains_key(&3));
+    }
+
+    #[test]
+    fn inherited_roles_ignore_cycles_back_to_granted_role() {
+        let map = parent_map(&[(1, 1), (1, 2), (2, 1)]);
+        let sources = inherited_role_sources(&map, &[role(1)]);
+        assert_eq!(sources.keys().copied().collect::<Vec<_>>(), vec![2]);
+    }
+}
@@ -0,0 +1,697 @@
+// 角色申请与限时授权: 用户申请角色, 由 Cedar 授权的审批人批准后按申请的有效期分配;
+// 后台任务负责激活到达生效时间的分配、撤销到期的分配, 并通知用户
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set, TransactionTrait,
+};
+use std::collections::HashMap;
+use tokio::time::{sleep, Duration};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::config::state::AppState;
+use crate::entity::{group_roles, role_requests, roles, user_groups, user_roles, users};
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::event::DomainEvent;
+use crate::schemas::notification::{
+    NewNotification, NOTIFICATION_CATEGORY_ACCESS, NOTIFICATION_INFO, NOTIFICATION_WARNING,
+};
+use crate::schemas::role_request::{
+    ApproveRoleRequestDto, CreateRoleRequestDto, RejectRoleRequestDto, RoleRequestQueryParams,
+    RoleRequestResponse, ROLE_REQUEST_APPROVED, ROLE_REQUEST_CANCELLED, ROLE_REQUEST_EXPIRED,
+    ROLE_REQUEST_PENDING, ROLE_REQUEST_REJECTED,
+};
+use crate::services::event_bus::publish_event;
+use crate::services::notification::notify_user;
+use crate::services::role::{active_assignment, get_role_entities, validate_assignment_period};
+use crate::utils::cedar_utils::{AuthAction, ResourceType};
+use crate::{bad_request, conflict, forbidden, not_found};
+
+const ROLE_ASSIGNMENT_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Clone)]
+pub struct RoleRequestService {
+    app_state: AppState,
+}
+
+impl RoleRequestService {
+    pub fn new(app_state: AppState) -> Self {
+        Self { app_state }
+    }
+
+    pub async fn list_requests(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        params: RoleRequestQueryParams,
+    ) -> Result<(Vec<RoleRequestResponse>, u64), AppError> {
+        let mut query = role_requests::Entity::find()
+            .filter(role_requests::Column::TenantId.eq(current_user.tenant_id))
+            .order_by_desc(role_requests::Column::Id);
+        if params.mine {
+            let requester = self.requester(&current_user).await?;
+            query = query.filter(role_requests::Column::RequesterId.eq(requester.user_id));
+        } else {
+            let role_ids = self.approvable_role_ids(&current_user, context).await?;
+            query = query.filter(role_requests::Column::RoleId.is_in(role_ids));
+        }
+        if let Some(status) = params.status.as_deref().filter(|status| !status.is_empty()) {
+            query = query.filter(role_requests::Column::Status.eq(status));
+        }
+
+        let paginator = query.paginate(&self.app_state.db, params.page_size);
+        let total = paginator.num_items().await?;
+        let page_index = if params.page > 0 { params.page - 1 } else { 0 };
+        let requests = paginator.fetch_page(page_index).await?;
+        Ok((self.to_responses(requests).await?, total))
+    }
+
+    pub async fn get_request(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        request_uuid: String,
+    ) -> Result<RoleRequestResponse, AppError> {
+        let request = self.find_request(&current_user, &request_uuid).await?;
+        let is_requester = users::Entity::find_by_id(request.requester_id)
+            .one(&self.app_state.db)
+            .await?
+            .is_some_and(|requester| requester.user_uuid == current_user.uuid);
+        if !is_requester {
+            self.check_approver(&current_user, context, request.role_id).await?;
+        }
+        self.to_response(request).await
+    }
+
+    pub async fn create_request(
+        &self,
+        current_user: CurrentUser,
+        dto: CreateRoleRequestDto,
+    ) -> Result<RoleRequestResponse, AppError> {
+        let requester = self.requester(&current_user).await?;
+        validate_assignment_period(dto.valid_from, dto.valid_until)?;
+        let role = roles::Entity::find()
+            .filter(roles::Column::RoleUuid.eq(&dto.role_uuid))
+            .filter(roles::Column::TenantId.eq(current_user.tenant_id))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("Role {} not found", dto.role_uuid))?;
+
+        let has_permanent_role = user_roles::Entity::find()
+            .filter(user_roles::Column::UserId.eq(requester.user_id))
+            .filter(user_roles::Column::RoleId.eq(role.role_id))
+            .filter(user_roles::Column::ValidUntil.is_null())
+            .filter(active_assignment(user_roles::Column::ValidFrom, user_roles::Column::ValidUntil))
+            .count(&self.app_state.db)
+            .await?
+            > 0;
+        if has_permanent_role {
+            return Err(conflict!("You already have role {}", role.role_name));
+        }
+        let pending = role_requests::Entity::find()
+            .filter(role_requests::Column::RequesterId.eq(requester.user_id))
+            .filter(role_requests::Column::RoleId.eq(role.role_id))
+            .filter(role_requests::Column::Status.eq(ROLE_REQUEST_PENDING))
+            .count(&self.app_state.db)
+            .await?;
+        if pending > 0 {
+            return Err(conflict!("A pending request for role {} already exists", role.role_name));
+        }
+
+        let now = Utc::now();
+        let request = role_requests::ActiveModel {
+            request_uuid: Set(Uuid::new_v4().to_string()),
+            tenant_id: Set(current_user.tenant_id),
+            requester_id: Set(requester.user_id),
+            role_id: Set(role.role_id),
+            reason: Set(dto.reason),
+            valid_from: Set(dto.valid_from),
+            valid_until: Set(dto.valid_until),
+            status: Set(ROLE_REQUEST_PENDING.to_string()),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&self.app_state.db)
+        .await?;
+        self.to_response(request).await
+    }
+
+    pub async fn approve_request(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        request_uuid: String,
+        dto: ApproveRoleRequestDto,
+    ) -> Result<RoleRequestResponse, AppError> {
+        let request = self.find_pending_request(&current_user, &request_uuid).await?;
+        let approver = self.approver(&current_user, context, &request).await?;
+
+        // 审批人只能缩短有效期
+        let valid_until = match (dto.valid_until, request.valid_until) {
+            (Some(approved), Some(requested)) if approved > requested => {
+                return Err(bad_request!("valid_until must not be later than the requested {}", requested));
+            }
+            (Some(approved), _) => Some(approved),
+            (None, requested) => requested,
+        };
+        let active = validate_assignment_period(request.valid_from, valid_until)?;
+
+        let now = Utc::now();
+        let txn = self.app_state.db.begin().await?;
+        // 只有状态仍为 pending 时才更新, 避免并发审批重复授权
+        let claimed = role_requests::Entity::update_many()
+            .col_expr(role_requests::Column::Status, Expr::value(ROLE_REQUEST_APPROVED))
+            .col_expr(role_requests::Column::ValidUntil, Expr::value(valid_until))
+            .col_expr(role_requests::Column::ApproverId, Expr::value(approver.user_id))
+            .col_expr(role_requests::Column::DecisionComment, Expr::value(dto.comment))
+            .col_expr(role_requests::Column::DecidedAt, Expr::value(now))
+            .col_expr(role_requests::Column::UpdatedAt, Expr::value(now))
+            .filter(role_requests::Column::Id.eq(request.id))
+            .filter(role_requests::Column::Status.eq(ROLE_REQUEST_PENDING))
+            .exec(&txn)
+            .await?
+            .rows_affected;
+        if claimed == 0 {
+            return Err(conflict!("Role request {} has already been decided", request_uuid));
+        }
+
+        // 锁定该用户该角色已有的分配, 避免与并发的分配操作交错
+        let existing = user_roles::Entity::find()
+            .filter(user_roles::Column::UserId.eq(request.requester_id))
+            .filter(user_roles::Column::RoleId.eq(request.role_id))
+            .lock_exclusive()
+            .one(&txn)
+            .await?;
+        let mut replaced_expired = false;
+        if let Some(existing) = existing {
+            let existing_active = existing.valid_from.is_none_or(|valid_from| valid_from <= now)
+                && existing.valid_until.is_none_or(|valid_until| valid_until > now);
+            let existing_scheduled = existing.valid_from.is_some_and(|valid_from| valid_from > now);
+            // 已有生效中的永久分配时不能用限时分配覆盖
+            if existing_active && existing.valid_until.is_none() {
+                return Err(conflict!("Requester already has a permanent assignment of this role"));
+            }
+            // 单独安排的未来分配不能被静默删除
+            if existing_scheduled {
+                return Err(conflict!(
+                    "Requester already has an assignment of this role scheduled from {}",
+                    existing.valid_from.unwrap_or(now)
+                ));
+            }
+            // 未来生效的申请不能提前撤销正在生效的分配
+            if existing_active && !active {
+                return Err(conflict!("Requester already has an active assignment of this role"));
+            }
+            // 剩余情况是以新的有效期续期生效中的限时分配, 或替换已到期尚未清理的分配
+            replaced_expired = !existing_active;
+            user_roles::Entity::delete_many()
+                .filter(user_roles::Column::UserId.eq(request.requester_id))
+                .filter(user_roles::Column::RoleId.eq(request.role_id))
+                .exec(&txn)
+                .await?;
+        }
+        user_roles::Entity::insert(user_roles::ActiveModel {
+            user_id: Set(request.requester_id),
+            role_id: Set(request.role_id),
+            valid_from: Set(request.valid_from),
+            valid_until: Set(valid_until),
+            activated_at: Set(active.then_some(now)),
+            ..Default::default()
+        })
+        .exec(&txn)
+        .await?;
+        txn.commit().await?;
+
+        let request = self.find_request(&current_user, &request_uuid).await?;
+        let response = self.to_response(request.clone()).await?;
+        // 替换掉的已到期分配不会再由后台任务撤销, 新分配尚未生效时需要在这里补发撤销事件
+        if replaced_expired && !active {
+            publish_event(&self.app_state, Some(&current_user), DomainEvent::RoleRevoked {
+                user_uuid: response.requester_uuid.clone(),
+                role_uuid: response.role_uuid.clone(),
+            })
+            .await;
+        }
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::RoleAssigned {
+            user_uuid: response.requester_uuid.clone(),
+            role_uuid: response.role_uuid.clone(),
+        })
+        .await;
+
+        let content = match (active, request.valid_from) {
+            (false, Some(valid_from)) => format!("Effective from {}", valid_from),
+            _ => validity_text(valid_until),
+        };
+        self.notify_requester(&request, NewNotification {
+            category: NOTIFICATION_CATEGORY_ACCESS.to_string(),
+            level: NOTIFICATION_INFO.to_string(),
+            title: format!("Your request for role {} was approved", response.role_name),
+            content: Some(content),
+            link: None,
+        })
+        .await;
+        Ok(response)
+    }
+
+    pub async fn reject_request(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        request_uuid: String,
+        dto: RejectRoleRequestDto,
+    ) -> Result<RoleRequestResponse, AppError> {
+        let request = self.find_pending_request(&current_user, &request_uuid).await?;
+        let approver = self.approver(&current_user, context, &request).await?;
+
+        let now = Utc::now();
+        // 与审批一样只在状态仍为 pending 时更新, 避免覆盖并发审批的结果
+        let claimed = role_requests::Entity::update_many()
+            .col_expr(role_requests::Column::Status, Expr::value(ROLE_REQUEST_REJECTED))
+            .col_expr(role_requests::Column::ApproverId, Expr::value(approver.user_id))
+            .col_expr(role_requests::Column::DecisionComment, Expr::value(dto.comment.clone()))
+            .col_expr(role_requests::Column::DecidedAt, Expr::value(now))
+            .col_expr(role_requests::Column::UpdatedAt, Expr::value(now))
+            .filter(role_requests::Column::Id.eq(request.id))
+            .filter(role_requests::Column::Status.eq(ROLE_REQUEST_PENDING))
+            .exec(&self.app_state.db)
+            .await?
+            .rows_affected;
+        if claimed == 0 {
+            return Err(conflict!("Role request {} has already been decided", request_uuid));
+        }
+
+        let request = self.find_request(&current_user, &request_uuid).await?;
+        let response = self.to_response(request.clone()).await?;
+        self.notify_requester(&request, NewNotification {
+            category: NOTIFICATION_CATEGORY_ACCESS.to_string(),
+            level: NOTIFICATION_WARNING.to_string(),
+            title: format!("Your request for role {} was rejected", response.role_name),
+            content: dto.comment,
+            link: None,
+        })
+        .await;
+        Ok(response)
+    }
+
+    pub async fn cancel_request(
+        &self,
+        current_user: CurrentUser,
+        request_uuid: String,
+    ) -> Result<(), AppError> {
+        let requester = self.requester(&current_user).await?;
+        let request = self.find_pending_request(&current_user, &request_uuid).await?;
+        if request.requester_id != requester.user_id {
+            return Err(forbidden!("Only the requester can cancel this request"));
+        }
+
+        let claimed = role_requests::Entity::update_many()
+            .col_expr(role_requests::Column::Status, Expr::value(ROLE_REQUEST_CANCELLED))
+            .col_expr(role_requests::Column::UpdatedAt, Expr::value(Utc::now()))
+            .filter(role_requests::Column::Id.eq(request.id))
+            .filter(role_requests::Column::Status.eq(ROLE_REQUEST_PENDING))
+            .exec(&self.app_state.db)
+            .await?
+            .rows_affected;
+        if claimed == 0 {
+            return Err(conflict!("Role request {} has already been decided", request_uuid));
+        }
+        Ok(())
+    }
+
+    // 只有用户可以申请和审批, 服务账号不参与审批流程
+    async fn requester(&self, current_user: &CurrentUser) -> Result<users::Model, AppError> {
+        users::Entity::find()
+            .filter(users::Column::UserUuid.eq(&current_user.uuid))
+            .filter(users::Column::TenantId.eq(current_user.tenant_id))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(forbidden!("Only users can request or approve roles"))
+    }
+
+    // 审批人需要对申请的角色有 ApproveRoleRequest 权限, 且不能审批自己的申请
+    async fn approver(
+        &self,
+        current_user: &CurrentUser,
+        context: CedarContext,
+        request: &role_requests::Model,
+    ) -> Result<users::Model, AppError> {
+        let approver = self.requester(current_user).await?;
+        if approver.user_id == request.requester_id {
+            return Err(forbidden!("You cannot approve or reject your own request"));
+        }
+        self.check_approver(current_user, context, request.role_id).await?;
+        Ok(approver)
+    }
+
+    async fn check_approver(
+        &self,
+        current_user: &CurrentUser,
+        context: CedarContext,
+        role_id: i32,
+    ) -> Result<(), AppError> {
+        let role = roles::Entity::find_by_id(role_id)
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("Role not found"))?;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
+        let role_es = get_role_entities(&self.app_state.db, current_user.tenant_id, &vec![role.role_uuid.clone()], &schema).await?;
+        self.app_state
+            .auth_service
+            .check_permission_with_entities(
+                &current_user.uuid,
+                context,
+                AuthAction::ApproveRoleRequest,
+                ResourceType::Role(Some(role.role_uuid)),
+                role_es,
+            )
+            .await?;
+        Ok(())
+    }
+
+    // 当前用户有权审批的角色
+    async fn approvable_role_ids(
+        &self,
+        current_user: &CurrentUser,
+        context: CedarContext,
+    ) -> Result<Vec<i32>, AppError> {
+        let role_ids = roles::Entity::find()
+            .select_only()
+            .column(roles::Column::RoleId)
+            .filter(roles::Column::TenantId.eq(current_user.tenant_id))
+            .into_tuple::<i32>()
+            .all(&self.app_state.db)
+            .await?;
+        let mut approvable = Vec::new();
+        for role_id in role_ids {
+            if self.check_approver(current_user, context.clone(), role_id).await.is_ok() {
+                approvable.push(role_id);
+            }
+        }
+        Ok(approvable)
+    }
+
+    async fn find_request(
+        &self,
+        current_user: &CurrentUser,
+        request_uuid: &str,
+    ) -> Result<role_requests::Model, AppError> {
+        role_requests::Entity::find()
+            .filter(role_requests::Column::RequestUuid.eq(request_uuid))
+            .filter(role_requests::Column::TenantId.eq(current_user.tenant_id))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("Role request {} not found", request_uuid))
+    }
+
+    async fn find_pending_request(
+        &self,
+        current_user: &CurrentUser,
+        request_uuid: &str,
+    ) -> Result<role_requests::Model, AppError> {
+        let request = self.find_request(current_user, request_uuid).await?;
+        if request.status != ROLE_REQUEST_PENDING {
+            return Err(conflict!("Role request {} is already {}", request_uuid, request.status));
+        }
+        Ok(request)
+    }
+
+    // 通知失败不影响审批结果
+    async fn notify_requester(&self, request: &role_requests::Model, notification: NewNotification) {
+        let result = match users::Entity::find_by_id(request.requester_id).one(&self.app_state.db).await {
+            Ok(Some(requester)) => {
+                notify_user(&self.app_state, requester.user_id, &requester.user_uuid, notification)
+                    .await
+                    .map(|_| ())
+            }
+            Ok(None) => Ok(()),
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = result {
+            warn!("通知角色申请人失败: {:?}", e);
+        }
+    }
+
+    async fn to_response(&self, request: role_requests::Model) -> Result<RoleRequestResponse, AppError> {
+        self.to_responses(vec![request])
+            .await?
+            .pop()
+            .ok_or(not_found!("Role request not found"))
+    }
+
+    async fn to_responses(
+        &self,
+        requests: Vec<role_requests::Model>,
+    ) -> Result<Vec<RoleRequestResponse>, AppError> {
+        let user_ids: Vec<i32> = requests
+            .iter()
+            .flat_map(|request| std::iter::once(request.requester_id).chain(request.approver_id))
+            .collect();
+        let role_ids: Vec<i32> = requests.iter().map(|request| request.role_id).collect();
+        let users: HashMap<i32, (String, String)> = users::Entity::find()
+            .select_only()
+            .columns([users::Column::UserId, users::Column::UserUuid, users::Column::Username])
+            .filter(users::Column::UserId.is_in(user_ids))
+            .into_tuple::<(i32, String, String)>()
+            .all(&self.app_state.db)
+            .await?
+            .into_iter()
+            .map(|(user_id, user_uuid, username)| (user_id, (user_uuid, username)))
+            .collect();
+        let roles: HashMap<i32, (String, String)> = roles::Entity::find()
+            .select_only()
+            .columns([roles::Column::RoleId, roles::Column::RoleUuid, roles::Column::RoleName])
+            .filter(roles::Column::RoleId.is_in(role_ids))
+            .into_tuple::<(i32, String, String)>()
+            .all(&self.app_state.db)
+            .await?
+            .into_iter()
+            .map(|(role_id, role_uuid, role_name)| (role_id, (role_uuid, role_name)))
+            .collect();
+
+        let responses = requests
+            .into_iter()
+            .map(|request| {
+                let (requester_uuid, requester_name) = users.get(&request.requester_id).cloned().unwrap_or_default();
+                let (role_uuid, role_name) = roles.get(&request.role_id).cloned().unwrap_or_default();
+                RoleRequestResponse {
+                    uuid: request.request_uuid,
+                    requester_uuid,
+                    requester_name,
+                    role_uuid,
+                    role_name,
+                    reason: request.reason,
+                    valid_from: request.valid_from,
+                    valid_until: request.valid_until,
+                    status: request.status,
+                    approver_name: request
+                        .approver_id
+                        .and_then(|approver_id| users.get(&approver_id))
+                        .map(|(_, username)| username.clone()),
+                    decision_comment: request.decision_comment,
+                    decided_at: request.decided_at,
+                    created_at: request.created_at,
+                }
+            })
+            .collect();
+        Ok(responses)
+    }
+}
+
+fn validity_text(valid_until: Option<DateTime<Utc>>) -> String {
+    match valid_until {
+        Some(valid_until) => format!("Valid until {}", valid_until),
+        None => "Permanent".to_string(),
+    }
+}
+
+// 后台任务：激活到达生效时间的角色分配, 撤销到期的角色分配
+pub async fn run_role_assignment_scheduler(state: AppState) {
+    loop {
+        if let Err(e) = process_role_assignments(&state).await {
+            error!("处理限时角色分配失败: {}", e);
+        }
+        sleep(ROLE_ASSIGNMENT_POLL_INTERVAL).await;
+    }
+}
+
+async fn process_role_assignments(state: &AppState) -> Result<(), AppError> {
+    activate_user_roles(state).await?;
+    expire_user_roles(state).await?;
+    activate_group_roles(state).await?;
+    expire_group_roles(state).await?;
+    expire_pending_requests(state).await?;
+    Ok(())
+}
+
+// 多节点部署时, 更新或删除成功的节点负责发布事件和通知, 不会重复处理
+async fn activate_user_roles(state: &AppState) -> Result<(), AppError> {
+    let now = Utc::now();
+    let due = user_roles::Entity::find()
+        .filter(user_roles::Column::ActivatedAt.is_null())
+        .filter(active_assignment(user_roles::Column::ValidFrom, user_roles::Column::ValidUntil))
+        .all(&state.db)
+        .await?;
+    for assignment in due {
+        let claimed = user_roles::Entity::update_many()
+            .col_expr(user_roles::Column::ActivatedAt, Expr::value(now))
+            .filter(user_roles::Column::UserId.eq(assignment.user_id))
+            .filter(user_roles::Column::RoleId.eq(assignment.role_id))
+            .filter(user_roles::Column::ActivatedAt.is_null())
+            .exec(&state.db)
+            .await?
+            .rows_affected;
+        if claimed == 0 {
+            continue;
+        }
+        let Some((user, role)) = assignment_parties(state, assignment.user_id, assignment.role_id).await? else {
+            continue;
+        };
+        info!("用户 {} 的角色 {} 已生效", user.username, role.role_name);
+        publish_event(state, None, DomainEvent::RoleAssigned {
+            user_uuid: user.user_uuid.clone(),
+            role_uuid: role.role_uuid.clone(),
+        })
+        .await;
+        let notification = NewNotification {
+            category: NOTIFICATION_CATEGORY_ACCESS.to_string(),
+            level: NOTIFICATION_INFO.to_string(),
+            title: format!("Role {} is now active", role.role_name),
+            content: Some(validity_text(assignment.valid_until)),
+            link: None,
+        };
+        if let Err(e) = notify_user(state, user.user_id, &user.user_uuid, notification).await {
+            warn!("通知用户 {} 角色生效失败: {:?}", user.username, e);
+        }
+    }
+    Ok(())
+}
+
+async fn expire_user_roles(state: &AppState) -> Result<(), AppError> {
+    let now = Utc::now();
+    let expired = user_roles::Entity::find()
+        .filter(user_roles::Column::ValidUntil.lte(now))
+        .all(&state.db)
+        .await?;
+    for assignment in expired {
+        let deleted = user_roles::Entity::delete_many()
+            .filter(user_roles::Column::UserId.eq(assignment.user_id))
+            .filter(user_roles::Column::RoleId.eq(assignment.role_id))
+            .filter(user_roles::Column::ValidUntil.lte(now))
+            .exec(&state.db)
+            .await?
+            .rows_affected;
+        if deleted == 0 {
+            continue;
+        }
+        let Some((user, role)) = assignment_parties(state, assignment.user_id, assignment.role_id).await? else {
+            continue;
+        };
+        info!("用户 {} 的角色 {} 已到期撤销", user.username, role.role_name);
+        publish_event(state, None, DomainEvent::RoleRevoked {
+            user_uuid: user.user_uuid.clone(),
+            role_uuid: role.role_uuid.clone(),
+        })
+        .await;
+        let notification = NewNotification {
+            category: NOTIFICATION_CATEGORY_ACCESS.to_string(),
+            level: NOTIFICATION_WARNING.to_string(),
+            title: format!("Role {} has expired", role.role_name),
+            content: None,
+            link: None,
+        };
+        if let Err(e) = notify_user(state, user.user_id, &user.user_uuid, notification).await {
+            warn!("通知用户 {} 角色到期失败: {:?}", user.username, e);
+        }
+    }
+    Ok(())
+}
+
+// 用户组的角色变化通过领域事件向组成员推送权限变更
+async fn activate_group_roles(state: &AppState) -> Result<(), AppError> {
+    let now = Utc::now();
+    let due = group_roles::Entity::find()
+        .filter(group_roles::Column::ActivatedAt.is_null())
+        .filter(active_assignment(group_roles::Column::ValidFrom, group_roles::Column::ValidUntil))
+        .all(&state.db)
+        .await?;
+    for assignment in due {
+        let claimed = group_roles::Entity::update_many()
+            .col_expr(group_roles::Column::ActivatedAt, Expr::value(now))
+            .filter(group_roles::Column::GroupId.eq(assignment.group_id))
+            .filter(group_roles::Column::RoleId.eq(assignment.role_id))
+            .filter(group_roles::Column::ActivatedAt.is_null())
+            .exec(&state.db)
+            .await?
+            .rows_affected;
+        if claimed == 0 {
+            continue;
+        }
+        if let Some((group_uuid, role_uuid)) = group_assignment_uuids(state, &assignment).await? {
+            publish_event(state, None, DomainEvent::GroupRoleAssigned { group_uuid, role_uuid }).await;
+        }
+    }
+    Ok(())
+}
+
+async fn expire_group_roles(state: &AppState) -> Result<(), AppError> {
+    let now = Utc::now();
+    let expired = group_roles::Entity::find()
+        .filter(group_roles::Column::ValidUntil.lte(now))
+        .all(&state.db)
+        .await?;
+    for assignment in expired {
+        let deleted = group_roles::Entity::delete_many()
+            .filter(group_roles::Column::GroupId.eq(assignment.group_id))
+            .filter(group_roles::Column::RoleId.eq(assignment.role_id))
+            .filter(group_roles::Column::ValidUntil.lte(now))
+            .exec(&state.db)
+            .await?
+            .rows_affected;
+        if deleted == 0 {
+            continue;
+        }
+        if let Some((group_uuid, role_uuid)) = group_assignment_uuids(state, &assignment).await? {
+            publish_event(state, None, DomainEvent::GroupRoleRevoked { group_uuid, role_uuid }).await;
+        }
+    }
+    Ok(())
+}
+
+// 申请的失效时间已过仍未审批的申请不再有意义
+async fn expire_pending_requests(state: &AppState) -> Result<(), AppError> {
+    let now = Utc::now();
+    role_requests::Entity::update_many()
+        .col_expr(role_requests::Column::Status, Expr::value(ROLE_REQUEST_EXPIRED))
+        .col_expr(role_requests::Column::UpdatedAt, Expr::value(now))
+        .filter(role_requests::Column::Status.eq(ROLE_REQUEST_PENDING))
+        .filter(role_requests::Column::ValidUntil.lte(now))
+        .exec(&state.db)
+        .await?;
+    Ok(())
+}
+
+async fn assignment_parties(
+    state: &AppState,
+    user_id: i32,
+    role_id: i32,
+) -> Result<Option<(users::Model, roles::Model)>, AppError> {
+    let user = users::Entity::find_by_id(user_id).one(&state.db).await?;
+    let role = roles::Entity::find_by_id(role_id).one(&state.db).await?;
+    Ok(user.zip(role))
+}
+
+async fn group_assignment_uuids(
+    state: &AppState,
+    assignment: &group_roles::Model,
+) -> Result<Option<(String, String)>, AppError> {
+    let group = user_groups::Entity::find_by_id(assignment.group_id).one(&state.db).await?;
+    let role = roles::Entity::find_by_id(assignment.role_id).one(&state.db).await?;
+    Ok(group
+        .zip(role)
+        .map(|(group, role)| (group.user_group_uuid, role.role_uuid)))
+}
@@ -0,0 +1,1003 @@
+// SCIM 2.0 用户同步: 身份提供方通过 /scim/v2 创建、更新和停用用户, 维护用户组成员
+// 用户和用户组直接映射到 users、user_groups、user_group_members 表, 变更后与 UserService、GroupService 一样发布领域事件
+// SCIM 使用全局令牌认证, 只同步默认租户
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait, JoinType,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait, Set, TransactionTrait,
+};
+use serde_json::{json, Value as JsonValue};
+use std::collections::{HashMap, HashSet};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::config::state::AppState;
+use crate::entity::{departments, group_roles, user_group_members, user_groups, users};
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::event::DomainEvent;
+use crate::schemas::tenant::DEFAULT_TENANT_ID;
+use crate::schemas::scim::{
+    ScimEnterpriseUser, ScimGroup, ScimListParams, ScimListResponse, ScimMeta, ScimMultiValue,
+    ScimName, ScimPatchRequest, ScimReference, ScimUser, SCIM_ENTERPRISE_USER_SCHEMA,
+    SCIM_GROUP_SCHEMA, SCIM_SERVICE_PROVIDER_CONFIG_SCHEMA, SCIM_USER_SCHEMA,
+};
+use crate::services::event_bus::publish_event;
+use crate::services::password::{hash_new_password, record_password_history};
+use crate::utils::crypto::{generate_secret, hash_password};
+use crate::utils::scim_filter::{parse_filter, to_condition, FilterValue, ScimFilter};
+use crate::{bad_request, conflict, not_found};
+
+const SCIM_BASE_PATH: &str = "/api/scim/v2";
+const SCIM_CORE_USER_PREFIX: &str = "urn:ietf:params:scim:schemas:core:2.0:user:";
+const SCIM_CORE_GROUP_PREFIX: &str = "urn:ietf:params:scim:schemas:core:2.0:group:";
+
+// 用户属性变更, 由 PUT 的完整资源或 PATCH 的操作列表得到
+#[derive(Default)]
+struct UserChanges {
+    username: Option<String>,
+    alias: Option<Option<String>>,
+    email: Option<String>,
+    phone: Option<Option<String>>,
+    active: Option<bool>,
+    password: Option<String>,
+    department: Option<String>,
+}
+
+// 用户组成员变更, 按操作顺序执行
+#[derive(Debug)]
+enum MemberOp {
+    Set(Vec<String>),
+    Add(Vec<String>),
+    Remove(Vec<String>),
+}
+
+#[derive(Clone)]
+pub struct ScimService {
+    app_state: AppState,
+}
+
+impl ScimService {
+    pub fn new(app_state: AppState) -> Self {
+        Self { app_state }
+    }
+
+    fn actor() -> CurrentUser {
+        CurrentUser::system("scim", "SCIM")
+    }
+
+    fn location(&self, resource: &str, id: &str) -> Option<String> {
+        self.app_state
+            .config
+            .server
+            .public_link(&format!("{}/{}/{}", SCIM_BASE_PATH, resource, id))
+            .ok()
+            .map(|url| url.to_string())
+    }
+
+    fn page(&self, params: &ScimListParams) -> (u64, u64) {
+        let max_results = self.app_state.config.scim.max_results;
+        let start_index = params.start_index.unwrap_or(1).max(1);
+        let count = params.count.unwrap_or(max_results).min(max_results);
+        (start_index, count)
+    }
+
+    pub fn service_provider_config(&self) -> JsonValue {
+        json!({
+            "schemas": [SCIM_SERVICE_PROVIDER_CONFIG_SCHEMA],
+            "patch": { "supported": true },
+            "bulk": { "supported": false, "maxOperations": 0, "maxPayloadSize": 0 },
+            "filter": { "supported": true, "maxResults": self.app_state.config.scim.max_results },
+            "changePassword": { "supported": true },
+            "sort": { "supported": false },
+            "etag": { "supported": false },
+            "authenticationSchemes": [{
+                "type": "oauthbearertoken",
+                "name": "OAuth Bearer Token",
+                "description": "Authentication scheme using the OAuth Bearer Token Standard",
+                "primary": true
+            }],
+        })
+    }
+
+    // ---------- Users ----------
+
+    pub async fn list_users(&self, params: ScimListParams) -> Result<ScimListResponse<ScimUser>, AppError> {
+        let condition = match &params.filter {
+            Some(filter) => to_condition(&parse_filter(filter)?, &user_column)?,
+            None => Condition::all(),
+        };
+        let (start_index, count) = self.page(&params);
+
+        let condition = Condition::all()
+            .add(condition)
+            .add(users::Column::TenantId.eq(DEFAULT_TENANT_ID));
+        let total = users::Entity::find()
+            .filter(condition.clone())
+            .count(&self.app_state.db)
+            .await?;
+        let users = users::Entity::find()
+            .filter(condition)
+            .order_by_asc(users::Column::UserId)
+            .offset(start_index - 1)
+            .limit(count)
+            .all(&self.app_state.db)
+            .await?;
+
+        let resources = self.to_scim_users(users).await?;
+        Ok(ScimListResponse::new(resources, total, start_index))
+    }
+
+    pub async fn get_user(&self, user_uuid: &str) -> Result<ScimUser, AppError> {
+        let user = find_user(&self.app_state.db, user_uuid).await?;
+        let mut resources = self.to_scim_users(vec![user]).await?;
+        Ok(resources.remove(0))
+    }
+
+    pub async fn create_user(&self, resource: ScimUser) -> Result<ScimUser, AppError> {
+        let username = resource.user_name.trim().to_string();
+        if username.is_empty() {
+            return Err(bad_request!("userName is required"));
+        }
+        // 未提供邮箱时, userName 为邮箱格式则作为邮箱
+        let email = primary_value(&resource.emails)
+            .or_else(|| username.contains('@').then(|| username.clone()))
+            .ok_or(bad_request!("emails is required"))?;
+
+        let txn = self.app_state.db.begin().await?;
+        let exists = users::Entity::find()
+            .filter(
+                Condition::any()
+                    .add(users::Column::Username.eq(&username))
+                    .add(users::Column::Email.eq(&email)),
+            )
+            .filter(users::Column::TenantId.eq(DEFAULT_TENANT_ID))
+            .one(&txn)
+            .await?;
+        if exists.is_some() {
+            return Err(conflict!("User with userName {} or email {} already exists", username, email));
+        }
+
+        let dept_id = match resource.enterprise.as_ref().and_then(|e| e.department.clone()) {
+            Some(name) => find_dept_by_name(&txn, &name).await?,
+            None => self.default_dept_id(&txn).await?,
+        };
+
+        // 未提供密码时设置随机密码, 用户只能通过单点登录或重置密码登录
+        let policy = &self.app_state.config.security.password;
+        let hashed_password = match &resource.password {
+            Some(password) => hash_new_password(&txn, policy, None, &username, password).await?,
+            None => hash_password(&generate_secret())?,
+        };
+
+        let user = users::ActiveModel {
+            user_uuid: Set(Uuid::new_v4().to_string()),
+            username: Set(username),
+            email: Set(email),
+            password: Set(hashed_password.clone()),
+            dept_id: Set(dept_id),
+            tenant_id: Set(DEFAULT_TENANT_ID),
+            alias: Set(display_name(&resource)),
+            phone: Set(primary_value(&resource.phone_numbers)),
+            is_active: Set(resource.active),
+            password_changed_at: Set(Some(chrono::Local::now().naive_local())),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        if resource.password.is_some() {
+            record_password_history(&txn, policy, user.user_id, &hashed_password).await?;
+        }
+        txn.commit().await?;
+
+        publish_event(&self.app_state, Some(&Self::actor()), DomainEvent::UserCreated {
+            user_uuid: user.user_uuid.clone(),
+            username: user.username.clone(),
+        }).await;
+        self.get_user(&user.user_uuid).await
+    }
+
+    pub async fn replace_user(&self, user_uuid: &str, resource: ScimUser) -> Result<ScimUser, AppError> {
+        let changes = UserChanges {
+            username: Some(resource.user_name.trim().to_string()),
+            alias: Some(display_name(&resource)),
+            email: primary_value(&resource.emails),
+            phone: Some(primary_value(&resource.phone_numbers)),
+            active: Some(resource.active),
+            password: resource.password,
+            department: resource.enterprise.and_then(|e| e.department),
+        };
+        self.apply_user_changes(user_uuid, changes).await?;
+        self.get_user(user_uuid).await
+    }
+
+    pub async fn patch_user(&self, user_uuid: &str, patch: ScimPatchRequest) -> Result<ScimUser, AppError> {
+        let mut changes = UserChanges::default();
+        for operation in patch.operations {
+            match (operation.op.to_lowercase().as_str(), operation.path) {
+                ("add" | "replace", Some(path)) => {
+                    set_user_attr(&mut changes, &path, operation.value.unwrap_or(JsonValue::Null))?
+                }
+                ("add" | "replace", None) => match operation.value {
+                    Some(JsonValue::Object(values)) => {
+                        for (path, value) in values {
+                            set_user_attr(&mut changes, &path, value)?;
+                        }
+                    }
+                    _ => return Err(bad_request!("Patch value must be an object when path is omitted")),
+                },
+                ("remove", Some(path)) => remove_user_attr(&mut changes, &path)?,
+                ("remove", None) => return Err(bad_request!("Patch remove requires a path")),
+                (op, _) => return Err(bad_request!("Unsupported patch op {}", op)),
+            }
+        }
+        self.apply_user_changes(user_uuid, changes).await?;
+        self.get_user(user_uuid).await
+    }
+
+    /// 与 UserService 一致, 用户不删除只是禁用
+    pub async fn delete_user(&self, user_uuid: &str) -> Result<(), AppError> {
+        let user = find_user(&self.app_state.db, user_uuid).await?;
+        let mut user: users::ActiveModel = user.into();
+        user.is_active = Set(false);
+        user.updated_at = Set(Utc::now().naive_utc());
+        user.update(&self.app_state.db).await?;
+
+        publish_event(&self.app_state, Some(&Self::actor()), DomainEvent::UserDisabled {
+            user_uuid: user_uuid.to_string(),
+        }).await;
+        Ok(())
+    }
+
+    async fn apply_user_changes(&self, user_uuid: &str, changes: UserChanges) -> Result<(), AppError> {
+        let txn = self.app_state.db.begin().await?;
+        let user = find_user(&txn, user_uuid).await?;
+        let original_dept_id = user.dept_id;
+        let was_active = user.is_active;
+
+        if let Some(username) = &changes.username {
+            if username.is_empty() {
+                return Err(bad_request!("userName is required"));
+            }
+            ensure_unique(&txn, users::Column::Username, username, user.user_id).await?;
+        }
+        if let Some(email) = &changes.email {
+            ensure_unique(&txn, users::Column::Email, email, user.user_id).await?;
+        }
+        let dept_id = match &changes.department {
+            Some(name) => Some(find_dept_by_name(&txn, name).await?),
+            None => None,
+        };
+
+        let policy = &self.app_state.config.security.password;
+        let new_password = match &changes.password {
+            Some(password) => {
+                let username = changes.username.as_deref().unwrap_or(&user.username);
+                Some(hash_new_password(&txn, policy, Some(&user), username, password).await?)
+            }
+            None => None,
+        };
+
+        let user_id = user.user_id;
+        let mut user: users::ActiveModel = user.into();
+        if let Some(username) = changes.username { user.username = Set(username); }
+        if let Some(alias) = changes.alias { user.alias = Set(alias); }
+        if let Some(email) = changes.email { user.email = Set(email); }
+        if let Some(phone) = changes.phone { user.phone = Set(phone); }
+        if let Some(active) = changes.active { user.is_active = Set(active); }
+        if let Some(dept_id) = dept_id { user.dept_id = Set(dept_id); }
+        if let Some(password) = &new_password {
+            user.password = Set(password.clone());
+            user.password_changed_at = Set(Some(chrono::Local::now().naive_local()));
+        }
+        user.updated_at = Set(Utc::now().naive_utc());
+        let user = user.update(&txn).await?;
+        if let Some(password) = &new_password {
+            record_password_history(&txn, policy, user_id, password).await?;
+        }
+
+        let from_dept_uuid = match dept_id {
+            Some(dept_id) if dept_id != original_dept_id => Some(dept_uuid(&txn, original_dept_id).await?),
+            _ => None,
+        };
+        let to_dept_uuid = match &from_dept_uuid {
+            Some(_) => Some(dept_uuid(&txn, user.dept_id).await?),
+            None => None,
+        };
+        txn.commit().await?;
+
+        let actor = Self::actor();
+        let user_uuid = user.user_uuid.clone();
+        if was_active && !user.is_active {
+            publish_event(&self.app_state, Some(&actor), DomainEvent::UserDisabled { user_uuid }).await;
+        } else {
+            publish_event(&self.app_state, Some(&actor), DomainEvent::UserUpdated { user_uuid }).await;
+        }
+        if let (Some(from_dept_uuid), Some(to_dept_uuid)) = (from_dept_uuid, to_dept_uuid) {
+            publish_event(&self.app_state, Some(&actor), DomainEvent::UserDepartmentChanged {
+                user_uuid: user.user_uuid,
+                from_dept_uuid,
+                to_dept_uuid,
+            }).await;
+        }
+        Ok(())
+    }
+
+    async fn default_dept_id<C: ConnectionTrait>(&self, db: &C) -> Result<i32, AppError> {
+        let default_dept_uuid = &self.app_state.config.scim.default_dept_uuid;
+        let query = departments::Entity::find()
+            .select_only()
+            .column(departments::Column::DeptId)
+            .filter(departments::Column::TenantId.eq(DEFAULT_TENANT_ID));
+        let query = if default_dept_uuid.is_empty() {
+            query.filter(departments::Column::ParentId.eq(0))
+        } else {
+            query.filter(departments::Column::DeptUuid.eq(default_dept_uuid))
+        };
+        query
+            .into_tuple::<i32>()
+            .one(db)
+            .await?
+            .ok_or(not_found!("Default department not found"))
+    }
+
+    async fn to_scim_users(&self, users: Vec<users::Model>) -> Result<Vec<ScimUser>, AppError> {
+        let user_ids: Vec<i32> = users.iter().map(|u| u.user_id).collect();
+        let mut groups: HashMap<i32, Vec<ScimReference>> = HashMap::new();
+        let memberships = user_group_members::Entity::find()
+            .select_only()
+            .column(user_group_members::Column::UserId)
+            .column(user_groups::Column::UserGroupUuid)
+            .column(user_groups::Column::Name)
+            .join(JoinType::InnerJoin, user_group_members::Relation::UserGroups.def())
+            .filter(user_group_members::Column::UserId.is_in(user_ids))
+            .into_tuple::<(i32, String, String)>()
+            .all(&self.app_state.db)
+            .await?;
+        for (user_id, group_uuid, name) in memberships {
+            groups.entry(user_id).or_default().push(ScimReference {
+                reference: self.location("Groups", &group_uuid),
+                value: group_uuid,
+                display: Some(name),
+            });
+        }
+
+        let dept_ids: HashSet<i32> = users.iter().map(|u| u.dept_id).collect();
+        let dept_names: HashMap<i32, String> = departments::Entity::find()
+            .select_only()
+            .columns([departments::Column::DeptId, departments::Column::Name])
+            .filter(departments::Column::DeptId.is_in(dept_ids))
+            .into_tuple::<(i32, String)>()
+            .all(&self.app_state.db)
+            .await?
+            .into_iter()
+            .collect();
+
+        let resources = users
+            .into_iter()
+            .map(|user| ScimUser {
+                schemas: vec![SCIM_USER_SCHEMA.to_string(), SCIM_ENTERPRISE_USER_SCHEMA.to_string()],
+                meta: Some(ScimMeta {
+                    resource_type: "User".to_string(),
+                    created: user.created_at.and_utc(),
+                    last_modified: user.updated_at.and_utc(),
+                    location: self.location("Users", &user.user_uuid),
+                }),
+                id: Some(user.user_uuid),
+                external_id: None,
+                name: user.alias.clone().map(|alias| ScimName {
+                    formatted: Some(alias),
+                    ..Default::default()
+                }),
+                display_name: user.alias,
+                emails: vec![ScimMultiValue {
+                    value: user.email,
+                    kind: Some("work".to_string()),
+                    primary: Some(true),
+                }],
+                phone_numbers: user
+                    .phone
+                    .map(|phone| ScimMultiValue {
+                        value: phone,
+                        kind: Some("work".to_string()),
+                        primary: Some(true),
+                    })
+                    .into_iter()
+                    .collect(),
+                active: user.is_active,
+                password: None,
+                groups: groups.remove(&user.user_id).unwrap_or_default(),
+                enterprise: Some(ScimEnterpriseUser {
+                    department: dept_names.get(&user.dept_id).cloned(),
+                }),
+                user_name: user.username,
+            })
+            .collect();
+        Ok(resources)
+    }
+
+    // ---------- Groups ----------
+
+    pub async fn list_groups(&self, params: ScimListParams) -> Result<ScimListResponse<ScimGroup>, AppError> {
+        let condition = match &params.filter {
+            Some(filter) => to_condition(&parse_filter(filter)?, &group_column)?,
+            None => Condition::all(),
+        };
+        let (start_index, count) = self.page(&params);
+        let with_members = !params
+            .excluded_attributes
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .any(|attr| attr.trim().eq_ignore_ascii_case("members"));
+
+        let condition = Condition::all()
+            .add(condition)
+            .add(user_groups::Column::TenantId.eq(DEFAULT_TENANT_ID));
+        let total = user_groups::Entity::find()
+            .filter(condition.clone())
+            .count(&self.app_state.db)
+            .await?;
+        let groups = user_groups::Entity::find()
+            .filter(condition)
+            .order_by_asc(user_groups::Column::UserGroupId)
+            .offset(start_index - 1)
+            .limit(count)
+            .all(&self.app_state.db)
+            .await?;
+
+        let resources = self.to_scim_groups(groups, with_members).await?;
+        Ok(ScimListResponse::new(resources, total, start_index))
+    }
+
+    pub async fn get_group(&self, group_uuid: &str) -> Result<ScimGroup, AppError> {
+        let group = find_group(&self.app_state.db, group_uuid).await?;
+        let mut resources = self.to_scim_groups(vec![group], true).await?;
+        Ok(resources.remove(0))
+    }
+
+    pub async fn create_group(&self, resource: ScimGroup) -> Result<ScimGroup, AppError> {
+        let name = resource.display_name.trim().to_string();
+        if name.is_empty() {
+            return Err(bad_request!("displayName is required"));
+        }
+
+        let txn = self.app_state.db.begin().await?;
+        if user_groups::Entity::find()
+            .filter(user_groups::Column::Name.eq(&name))
+            .filter(user_groups::Column::TenantId.eq(DEFAULT_TENANT_ID))
+            .one(&txn)
+            .await?
+            .is_some()
+        {
+            return Err(conflict!("Group {} already exists", name));
+        }
+
+        let group = user_groups::ActiveModel {
+            user_group_uuid: Set(Uuid::new_v4().to_string()),
+            name: Set(name),
+            tenant_id: Set(DEFAULT_TENANT_ID),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        let member_uuids: Vec<String> = resource.members.into_iter().map(|m| m.value).collect();
+        let members = find_user_ids(&txn, &member_uuids).await?;
+        if !members.is_empty() {
+            user_group_members::Entity::insert_many(members.values().map(|&user_id| {
+                user_group_members::ActiveModel {
+                    group_id: Set(group.user_group_id),
+                    user_id: Set(user_id),
+                    ..Default::default()
+                }
+            }))
+            .exec(&txn)
+            .await?;
+        }
+        txn.commit().await?;
+
+        if !members.is_empty() {
+            publish_event(&self.app_state, Some(&Self::actor()), DomainEvent::GroupMembersChanged {
+                group_uuid: group.user_group_uuid.clone(),
+                user_uuids: members.into_keys().collect(),
+            }).await;
+        }
+        self.get_group(&group.user_group_uuid).await
+    }
+
+    pub async fn replace_group(&self, group_uuid: &str, resource: ScimGroup) -> Result<ScimGroup, AppError> {
+        let members = resource.members.into_iter().map(|m| m.value).collect();
+        self.apply_group_changes(group_uuid, Some(resource.display_name.trim().to_string()), vec![MemberOp::Set(members)])
+            .await?;
+        self.get_group(group_uuid).await
+    }
+
+    pub async fn patch_group(&self, group_uuid: &str, patch: ScimPatchRequest) -> Result<ScimGroup, AppError> {
+        let mut name = None;
+        let mut member_ops = Vec::new();
+        for operation in patch.operations {
+            let op = operation.op.to_lowercase();
+            match (op.as_str(), operation.path) {
+                ("add" | "replace", None) => match operation.value {
+                    Some(JsonValue::Object(values)) => {
+                        for (path, value) in values {
+                            set_group_attr(&op, &path, value, &mut name, &mut member_ops)?;
+                        }
+                    }
+                    _ => return Err(bad_request!("Patch value must be an object when path is omitted")),
+                },
+                ("add" | "replace", Some(path)) => {
+                    let value = operation.value.unwrap_or(JsonValue::Null);
+                    set_group_attr(&op, &path, value, &mut name, &mut member_ops)?;
+                }
+                ("remove", Some(path)) => {
+                    let path = path.trim();
+                    // members[value eq "..."] 删除指定成员
+                    if let Some(filter) = path
+                        .strip_prefix("members[")
+                        .and_then(|rest| rest.strip_suffix(']'))
+                    {
+                        member_ops.push(MemberOp::Remove(member_filter_values(filter)?));
+                    } else if normalize_path(path, SCIM_CORE_GROUP_PREFIX) == "members" {
+                        // 未指定成员时删除全部成员
+                        match operation.value {
+                            Some(value) => member_ops.push(MemberOp::Remove(member_values(value)?)),
+                            None => member_ops.push(MemberOp::Set(Vec::new())),
+                        }
+                    } else {
+                        return Err(bad_request!("Attribute {} cannot be removed", path));
+                    }
+                }
+                ("remove", None) => return Err(bad_request!("Patch remove requires a path")),
+                (op, _) => return Err(bad_request!("Unsupported patch op {}", op)),
+            }
+        }
+        self.apply_group_changes(group_uuid, name, member_ops).await?;
+        self.get_group(group_uuid).await
+    }
+
+    pub async fn delete_group(&self, group_uuid: &str) -> Result<(), AppError> {
+        let txn = self.app_state.db.begin().await?;
+        let group = find_group(&txn, group_uuid).await?;
+        let members = group_member_uuids(&txn, group.user_group_id).await?;
+
+        user_group_members::Entity::delete_many()
+            .filter(user_group_members::Column::GroupId.eq(group.user_group_id))
+            .exec(&txn)
+            .await?;
+        group_roles::Entity::delete_many()
+            .filter(group_roles::Column::GroupId.eq(group.user_group_id))
+            .exec(&txn)
+            .await?;
+        user_groups::Entity::delete_many()
+            .filter(user_groups::Column::UserGroupId.eq(group.user_group_id))
+            .exec(&txn)
+            .await?;
+        txn.commit().await?;
+
+        let actor = Self::actor();
+        if !members.is_empty() {
+            publish_event(&self.app_state, Some(&actor), DomainEvent::GroupMembersChanged {
+                group_uuid: group_uuid.to_string(),
+                user_uuids: members.into_values().collect(),
+            }).await;
+        }
+        publish_event(&self.app_state, Some(&actor), DomainEvent::GroupDeleted {
+            group_uuid: group_uuid.to_string(),
+        }).await;
+        Ok(())
+    }
+
+    async fn apply_group_changes(
+        &self,
+        group_uuid: &str,
+        name: Option<String>,
+        member_ops: Vec<MemberOp>,
+    ) -> Result<(), AppError> {
+        let txn = self.app_state.db.begin().await?;
+        let group = find_group(&txn, group_uuid).await?;
+        let group_id = group.user_group_id;
+
+        let renamed = matches!(&name, Some(name) if *name != group.name);
+        if let Some(name) = name.filter(|_| renamed) {
+            if name.is_empty() {
+                return Err(bad_request!("displayName is required"));
+            }
+            if user_groups::Entity::find()
+                .filter(user_groups::Column::Name.eq(&name))
+                .filter(user_groups::Column::TenantId.eq(DEFAULT_TENANT_ID))
+                .filter(user_groups::Column::UserGroupId.ne(group_id))
+                .one(&txn)
+                .await?
+                .is_some()
+            {
+                return Err(conflict!("Group {} already exists", name));
+            }
+            let mut group: user_groups::ActiveModel = group.into();
+            group.name = Set(name);
+            group.updated_at = Set(Utc::now());
+            group.update(&txn).await?;
+        }
+
+        // user_id -> user_uuid
+        let current = group_member_uuids(&txn, group_id).await?;
+        let mut target: HashSet<String> = current.values().cloned().collect();
+        for op in member_ops {
+            match op {
+                MemberOp::Set(uuids) => target = uuids.into_iter().collect(),
+                MemberOp::Add(uuids) => target.extend(uuids),
+                MemberOp::Remove(uuids) => {
+                    for uuid in uuids {
+                        target.remove(&uuid);
+                    }
+                }
+            }
+        }
+        let target_ids = find_user_ids(&txn, &target.into_iter().collect::<Vec<_>>()).await?;
+
+        let added: Vec<(String, i32)> = target_ids
+            .iter()
+            .filter(|(_, user_id)| !current.contains_key(user_id))
+            .map(|(uuid, user_id)| (uuid.clone(), *user_id))
+            .collect();
+        let removed: Vec<(i32, String)> = current
+            .into_iter()
+            .filter(|(_, uuid)| !target_ids.contains_key(uuid))
+            .collect();
+
+        if !removed.is_empty() {
+            user_group_members::Entity::delete_many()
+                .filter(user_group_members::Column::GroupId.eq(group_id))
+                .filter(user_group_members::Column::UserId.is_in(removed.iter().map(|(id, _)| *id)))
+                .exec(&txn)
+                .await?;
+        }
+        if !added.is_empty() {
+            user_group_members::Entity::insert_many(added.iter().map(|(_, user_id)| {
+                user_group_members::ActiveModel {
+                    group_id: Set(group_id),
+                    user_id: Set(*user_id),
+                    ..Default::default()
+                }
+            }))
+            .exec(&txn)
+            .await?;
+        }
+        txn.commit().await?;
+
+        let actor = Self::actor();
+        if renamed {
+            publish_event(&self.app_state, Some(&actor), DomainEvent::GroupUpdated {
+                group_uuid: group_uuid.to_string(),
+            }).await;
+        }
+        let changed: Vec<String> = removed
+            .into_iter()
+            .map(|(_, uuid)| uuid)
+            .chain(added.into_iter().map(|(uuid, _)| uuid))
+            .collect();
+        if !changed.is_empty() {
+            publish_event(&self.app_state, Some(&actor), DomainEvent::GroupMembersChanged {
+                group_uuid: group_uuid.to_string(),
+                user_uuids: changed,
+            }).await;
+        }
+        Ok(())
+    }
+
+    async fn to_scim_groups(&self, groups: Vec<user_groups::Model>, with_members: bool) -> Result<Vec<ScimGroup>, AppError> {
+        let mut members: HashMap<i32, Vec<ScimReference>> = HashMap::new();
+        if with_members {
+            let group_ids: Vec<i32> = groups.iter().map(|g| g.user_group_id).collect();
+            let rows = user_group_members::Entity::find()
+                .select_only()
+                .column(user_group_members::Column::GroupId)
+                .column(users::Column::UserUuid)
+                .column(users::Column::Username)
+                .join(JoinType::InnerJoin, user_group_members::Relation::Users.def())
+                .filter(user_group_members::Column::GroupId.is_in(group_ids))
+                .into_tuple::<(i32, String, String)>()
+                .all(&self.app_state.db)
+                .await?;
+            for (group_id, user_uuid, username) in rows {
+                members.entry(group_id).or_default().push(ScimReference {
+                    reference: self.location("Users", &user_uuid),
+                    value: user_uuid,
+                    display: Some(username),
+                });
+            }
+        }
+
+        let resources = groups
+            .into_iter()
+            .map(|group| ScimGroup {
+                schemas: vec![SCIM_GROUP_SCHEMA.to_string()],
+                meta: Some(ScimMeta {
+                    resource_type: "Group".to_string(),
+                    created: group.created_at,
+                    last_modified: group.updated_at,
+                    location: self.location("Groups", &group.user_group_uuid),
+                }),
+                id: Some(group.user_group_uuid),
+                external_id: None,
+                display_name: group.name,
+                members: members.remove(&group.user_group_id).unwrap_or_default(),
+            })
+            .collect();
+        Ok(resources)
+    }
+}
+
+fn user_column(attr: &str) -> Option<users::Column> {
+    match attr.strip_prefix(SCIM_CORE_USER_PREFIX).unwrap_or(attr) {
+        "id" => Some(users::Column::UserUuid),
+        "username" => Some(users::Column::Username),
+        "displayname" | "name.formatted" => Some(users::Column::Alias),
+        "emails" | "emails.value" => Some(users::Column::Email),
+        "phonenumbers" | "phonenumbers.value" => Some(users::Column::Phone),
+        "active" => Some(users::Column::IsActive),
+        _ => None,
+    }
+}
+
+fn group_column(attr: &str) -> Option<user_groups::Column> {
+    match attr.strip_prefix(SCIM_CORE_GROUP_PREFIX).unwrap_or(attr) {
+        "id" => Some(user_groups::Column::UserGroupUuid),
+        "displayname" => Some(user_groups::Column::Name),
+        _ => None,
+    }
+}
+
+// 属性路径统一为小写并去掉核心 Schema 前缀和值过滤, 如 emails[type eq "work"].value -> emails.value
+fn normalize_path(path: &str, core_prefix: &str) -> String {
+    let path = path.trim().to_lowercase();
+    let path = path.strip_prefix(core_prefix).unwrap_or(&path);
+    match (path.find('['), path.find(']')) {
+        (Some(start), Some(end)) if start < end => format!("{}{}", &path[..start], &path[end + 1..]),
+        _ => path.to_string(),
+    }
+}
+
+fn set_user_attr(changes: &mut UserChanges, path: &str, value: JsonValue) -> Result<(), AppError> {
+    let attr = normalize_path(path, SCIM_CORE_USER_PREFIX);
+    let enterprise = SCIM_ENTERPRISE_USER_SCHEMA.to_lowercase();
+    match attr.as_str() {
+        "username" => changes.username = Some(json_string(&value, path)?.trim().to_string()),
+        "displayname" | "name.formatted" => changes.alias = Some(json_optional_string(&value)),
+        "name" => {
+            let name: ScimName = serde_json::from_value(value)?;
+            changes.alias = Some(format_name(&name));
+        }
+        "emails" => {
+            let emails: Vec<ScimMultiValue> = serde_json::from_value(value)?;
+            changes.email = primary_value(&emails);
+        }
+        "emails.value" => changes.email = Some(json_string(&value, path)?),
+        "phonenumbers" => {
+            let phones: Vec<ScimMultiValue> = serde_json::from_value(value)?;
+            changes.phone = Some(primary_value(&phones));
+        }
+        "phonenumbers.value" => changes.phone = Some(json_optional_string(&value)),
+        "active" => changes.active = Some(json_bool(&value, path)?),
+        "password" => changes.password = Some(json_string(&value, path)?),
+        attr if attr == enterprise => {
+            let extension: ScimEnterpriseUser = serde_json::from_value(value)?;
+            if extension.department.is_some() {
+                changes.department = extension.department;
+            }
+        }
+        attr if attr == format!("{}:department", enterprise) => {
+            changes.department = Some(json_string(&value, path)?);
+        }
+        // 不保存的属性(externalId、title 等)直接忽略
+        _ => debug!("SCIM ignore user attribute {}", path),
+    }
+    Ok(())
+}
+
+fn remove_user_attr(changes: &mut UserChanges, path: &str) -> Result<(), AppError> {
+    match normalize_path(path, SCIM_CORE_USER_PREFIX).as_str() {
+        "displayname" | "name" | "name.formatted" => changes.alias = Some(None),
+        "phonenumbers" | "phonenumbers.value" => changes.phone = Some(None),
+        "username" | "emails" | "emails.value" | "active" | "password" => {
+            return Err(bad_request!("Attribute {} cannot be removed", path));
+        }
+        _ => debug!("SCIM ignore user attribute {}", path),
+    }
+    Ok(())
+}
+
+fn set_group_attr(
+    op: &str,
+    path: &str,
+    value: JsonValue,
+    name: &mut Option<String>,
+    member_ops: &mut Vec<MemberOp>,
+) -> Result<(), AppError> {
+    match normalize_path(path, SCIM_CORE_GROUP_PREFIX).as_str() {
+        "displayname" => *name = Some(json_string(&value, path)?.trim().to_string()),
+        "members" if op == "add" => member_ops.push(MemberOp::Add(member_values(value)?)),
+        "members" => member_ops.push(MemberOp::Set(member_values(value)?)),
+        _ => debug!("SCIM ignore group attribute {}", path),
+    }
+    Ok(())
+}
+
+fn member_values(value: JsonValue) -> Result<Vec<String>, AppError> {
+    let members: Vec<ScimReference> = match value {
+        JsonValue::Array(_) => serde_json::from_value(value)?,
+        JsonValue::Null => Vec::new(),
+        value => vec![serde_json::from_value(value)?],
+    };
+    Ok(members.into_iter().map(|m| m.value).collect())
+}
+
+// 解析 value eq "..." (可用 or 连接) 中的成员ID
+fn member_filter_values(filter: &str) -> Result<Vec<String>, AppError> {
+    fn collect(filter: &ScimFilter, values: &mut Vec<String>) -> Result<(), AppError> {
+        match filter {
+            ScimFilter::Compare { attr, op, value: FilterValue::Str(value) }
+                if attr.eq_ignore_ascii_case("value") && op == "eq" =>
+            {
+                values.push(value.clone());
+                Ok(())
+            }
+            ScimFilter::Or(left, right) => {
+                collect(left, values)?;
+                collect(right, values)
+            }
+            _ => Err(bad_request!("Invalid filter: unsupported member filter")),
+        }
+    }
+    let mut values = Vec::new();
+    collect(&parse_filter(filter)?, &mut values)?;
+    Ok(values)
+}
+
+fn json_string(value: &JsonValue, path: &str) -> Result<String, AppError> {
+    value
+        .as_str()
+        .map(|s| s.to_string())
+        .ok_or(bad_request!("Attribute {} must be a string", path))
+}
+
+fn json_optional_string(value: &JsonValue) -> Option<String> {
+    value.as_str().map(|s| s.to_string()).filter(|s| !s.is_empty())
+}
+
+// 部分身份提供方(如 Azure AD)以字符串 "True"/"False" 传递布尔值
+fn json_bool(value: &JsonValue, path: &str) -> Result<bool, AppError> {
+    match value {
+        JsonValue::Bool(b) => Ok(*b),
+        JsonValue::String(s) if s.eq_ignore_ascii_case("true") => Ok(true),
+        JsonValue::String(s) if s.eq_ignore_ascii_case("false") => Ok(false),
+        _ => Err(bad_request!("Attribute {} must be a boolean", path)),
+    }
+}
+
+fn primary_value(values: &[ScimMultiValue]) -> Option<String> {
+    values
+        .iter()
+        .find(|v| v.primary == Some(true))
+        .or_else(|| values.first())
+        .map(|v| v.value.clone())
+}
+
+fn format_name(name: &ScimName) -> Option<String> {
+    name.formatted.clone().filter(|s| !s.is_empty()).or_else(|| {
+        let parts: Vec<&str> = [name.given_name.as_deref(), name.family_name.as_deref()]
+            .into_iter()
+            .flatten()
+            .filter(|s| !s.is_empty())
+            .collect();
+        (!parts.is_empty()).then(|| parts.join(" "))
+    })
+}
+
+// 用户别名取 displayName, 其次为 name
+fn display_name(resource: &ScimUser) -> Option<String> {
+    resource
+        .display_name
+        .clone()
+        .filter(|s| !s.is_empty())
+        .or_else(|| resource.name.as_ref().and_then(format_name))
+}
+
+async fn find_user<C: ConnectionTrait>(db: &C, user_uuid: &str) -> Result<users::Model, AppError> {
+    users::Entity::find()
+        .filter(users::Column::UserUuid.eq(user_uuid))
+        .filter(users::Column::TenantId.eq(DEFAULT_TENANT_ID))
+        .one(db)
+        .await?
+        .ok_or(not_found!("User {} not found", user_uuid))
+}
+
+async fn find_group<C: ConnectionTrait>(db: &C, group_uuid: &str) -> Result<user_groups::Model, AppError> {
+    user_groups::Entity::find()
+        .filter(user_groups::Column::UserGroupUuid.eq(group_uuid))
+        .filter(user_groups::Column::TenantId.eq(DEFAULT_TENANT_ID))
+        .one(db)
+        .await?
+        .ok_or(not_found!("Group {} not found", group_uuid))
+}
+
+async fn ensure_unique<C: ConnectionTrait>(
+    db: &C,
+    column: users::Column,
+    value: &str,
+    user_id: i32,
+) -> Result<(), AppError> {
+    let exists = users::Entity::find()
+        .filter(column.eq(value))
+        .filter(users::Column::TenantId.eq(DEFAULT_TENANT_ID))
+        .filter(users::Column::UserId.ne(user_id))
+        .one(db)
+        .await?;
+    if exists.is_some() {
+        return Err(conflict!("{} already exists", value));
+    }
+    Ok(())
+}
+
+async fn find_dept_by_name<C: ConnectionTrait>(db: &C, name: &str) -> Result<i32, AppError> {
+    departments::Entity::find()
+        .select_only()
+        .column(departments::Column::DeptId)
+        .filter(departments::Column::Name.eq(name))
+        .filter(departments::Column::TenantId.eq(DEFAULT_TENANT_ID))
+        .filter(departments::Column::IsDeleted.eq(false))
+        .into_tuple::<i32>()
+        .one(db)
+        .await?
+        .ok_or(bad_request!("Department {} not found", name))
+}
+
+async fn dept_uuid<C: ConnectionTrait>(db: &C, dept_id: i32) -> Result<String, AppError> {
+    departments::Entity::find()
+        .select_only()
+        .column(departments::Column::DeptUuid)
+        .filter(departments::Column::DeptId.eq(dept_id))
+        .into_tuple::<String>()
+        .one(db)
+        .await?
+        .ok_or(not_found!("Department not found"))
+}
+
+/// 用户组成员, user_id -> user_uuid
+async fn group_member_uuids<C: ConnectionTrait>(db: &C, group_id: i32) -> Result<HashMap<i32, String>, AppError> {
+    let members = users::Entity::find()
+        .select_only()
+        .column(users::Column::UserId)
+        .column(users::Column::UserUuid)
+        .join(JoinType::InnerJoin, users::Relation::UserGroupMembers.def())
+        .filter(user_group_members::Column::GroupId.eq(group_id))
+        .into_tuple::<(i32, String)>()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect();
+    Ok(members)
+}
+
+/// 按 user_uuid 查找用户ID, 有不存在的用户时报错
+async fn find_user_ids<C: ConnectionTrait>(db: &C, user_uuids: &[String]) -> Result<HashMap<String, i32>, AppError> {
+    if user_uuids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let users: HashMap<String, i32> = users::Entity::find()
+        .select_only()
+        .column(users::Column::UserUuid)
+        .column(users::Column::UserId)
+        .filter(users::Column::UserUuid.is_in(user_uuids.iter().cloned()))
+        .filter(users::Column::TenantId.eq(DEFAULT_TENANT_ID))
+        .into_tuple::<(String, i32)>()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect();
+    if let Some(missing) = user_uuids.iter().find(|uuid| !users.contains_key(*uuid)) {
+        return Err(bad_request!("User {} not found", missing));
+    }
+    Ok(users)
+}
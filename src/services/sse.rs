@@ -1,35 +1,57 @@
 // 一个全局SSE推送服务
 
-use std::sync::{Arc, Mutex};
+use std::collections::{HashMap, VecDeque};
 use axum::{
     response::{
         sse::{Event, KeepAlive, Sse},
     },
 };
-use redis::AsyncCommands;
 use tokio::sync::mpsc;
-use tokio_stream::{Stream, StreamExt, wrappers::ReceiverStream};
+use tokio_stream::{Stream, StreamExt};
 use crate::errors::app_error::AppError;
 use crate::schemas::auth::CurrentUser;
-use crate::config::state::{AppState, SSESenders};
-use crate::utils::sse::redis_offline_key;
+use crate::config::state::AppState;
+use crate::utils::sse::{
+    latest_event_id, read_event_range, register_presence, remove_presence, replay_messages, replay_offline_messages,
+    SseMessage,
+};
 
 
-// 负责在 Stream 被 Drop 时，自动从在线用户列表中移除 sender
+// 负责在 Stream 被 Drop 时，自动从在线用户列表中移除该连接的 sender
 struct SseStreamCleanup {
     user_uuid: String,
-    sse_senders: SSESenders,
+    connection_id: String,
+    app_state: AppState,
 }
 
 impl Drop for SseStreamCleanup {
     fn drop(&mut self) {
         let user_uuid = self.user_uuid.clone();
-        let sse_senders = self.sse_senders.clone();
+        let connection_id = self.connection_id.clone();
+        let app_state = self.app_state.clone();
 
         // 不能在 drop 中直接 .await，所以需要生成一个新任务来执行异步的清理操作
         tokio::spawn(async move {
-            sse_senders.lock().await.remove(&user_uuid);
-            tracing::debug!("用户 {} 的 SSE 连接已关闭，sender 已被自动清理。", user_uuid);
+            // 同一用户的其他连接(如其他浏览器标签页)不受影响
+            let last_connection = {
+                let mut senders = app_state.sse_senders.lock().await;
+                match senders.get_mut(&user_uuid) {
+                    Some(connections) => {
+                        connections.remove(&connection_id);
+                        if connections.is_empty() {
+                            senders.remove(&user_uuid);
+                            true
+                        } else {
+                            false
+                        }
+                    }
+                    None => true,
+                }
+            };
+            if last_connection && let Err(e) = remove_presence(&app_state, &user_uuid).await {
+                tracing::warn!("移除用户 {} 的在线状态失败: {}", user_uuid, e);
+            }
+            tracing::debug!("用户 {} 的 SSE 连接 {} 已关闭，sender 已被自动清理。", user_uuid, connection_id);
         });
     }
 }
@@ -45,87 +67,64 @@ impl SSEService {
         Self { app_state }
     }
 
-    async fn send_offline_messages(
-        state_clone: AppState,
-        user_uuid: String,
-        tx: mpsc::Sender<String>,
-    ){
-        // 1. 获取 Redis 连接
-        let mut conn = match state_clone.redis.get_multiplexed_async_connection().await {
-            Ok(conn) => conn,
-            Err(e) => {
-                tracing::error!("[用户 {}] 无法获取离线消息的 Redis 连接：{}”", user_uuid, e);
-                return;
-            }
-        };
-
-        let key = redis_offline_key(user_uuid.clone());
-
-        // 2. 使用 LRANGE 获取所有消息
-        // LRANGE key 0 -1  获取列表中的所有元素
-        let messages: Vec<String> = match conn.lrange(&key, 0, -1).await {
-            Ok(messages) => messages,
-            Err(e) => {
-                tracing::error!("[用户 {}] 无法从 Redis 键“{}”进行 LRANGE：{}", user_uuid, key, e);
-                return;
-            }
-        };
-
-        // 如果没有离线消息，就提前结束
-        if messages.is_empty() {
-            return;
-        }
-
-        tracing::debug!("[用户 {}] 在 Redis 中发现 {} 条离线消息。正在发送...", user_uuid, messages.len());
-
-        // 3. 倒序发送消息，以保持原始顺序
-        // 因为 LPUSH 是 "Left Push"，所以最新的消息在列表的最左边（索引0）。
-        // 我们从后往前遍历，就能按时间顺序（旧 -> 新）发送。
-        for msg in messages.iter().rev() {
-            if tx.send(msg.clone()).await.is_err() {
-                // 如果在发送离线消息时客户端就断开了，我们就不再继续发送，
-                // 也不删除 Redis 中的消息，等待用户下次上线。
-                tracing::error!("[用户 {}] 客户端在发送离线消息时断开连接。正在中止。", user_uuid.clone());
-                return; // 退出任务
-            }
-        }
-
-        // 4. 所有消息都成功发送后，删除 Redis 中的键
-        tracing::debug!("[用户 {}] 所有离线消息已发送。正在删除 Redis 键“{}”。", user_uuid, key);
-
-        let _: () = conn.del(&key).await.unwrap_or_else(|e| {
-            tracing::error!("[用户 {}] 无法删除 Redis 键“{}”：{} . 可能会导致重复。", user_uuid, key, e);
-        });
-
-    }
-
+    /// 建立 SSE 连接, 带 Last-Event-ID 时补发之后的事件, 否则补发离线消息
     pub async fn global_message(
         &self,
         current_user: CurrentUser,
+        last_event_id: Option<u64>,
     )-> Sse<impl Stream<Item = Result<Event, AppError>> + use<>> {
-        // 创建一个新的 MPSC 通道，用于此用户的 SSE 事件
+        // 创建一个新的 MPSC 通道，用于此连接的 SSE 事件
         let user_uuid = current_user.uuid;
-        let (tx, rx) = mpsc::channel(100); // 缓冲区大小为100
-        let state_clone = self.app_state.clone();
-        let tx_clone = tx.clone();
-
-        // -----------------发送离线消息开始 -----------------
-        tokio::spawn(Self::send_offline_messages(state_clone, user_uuid.clone(), tx_clone));
-
-        // -----------------发送离线消息结束 -----------------
-
-        // 将发送端 (tx) 存储到共享状态中，与 user_id 关联
-        // 需要一个 `lock` 来安全地修改 HashMap
-        self.app_state.sse_senders.lock().await.insert(user_uuid.clone(), tx.clone());
-        tracing::debug!("为用户 {} 建立了 SSE 连接", user_uuid.clone());
+        let connection_id = uuid::Uuid::new_v4().to_string();
+        let (tx, rx) = mpsc::channel::<SseMessage>(100); // 缓冲区大小为100
+
+        // 先登记连接再读取补发消息, 期间产生的新消息不会丢失, 重复的由下面按 id 过滤
+        self.app_state
+            .sse_senders
+            .lock()
+            .await
+            .entry(user_uuid.clone())
+            .or_insert_with(HashMap::new)
+            .insert(connection_id.clone(), tx);
+        // 登记在线状态, 其他节点据此把消息转发到本节点
+        if let Err(e) = register_presence(&self.app_state, &user_uuid).await {
+            tracing::warn!("登记用户 {} 的在线状态失败: {}", user_uuid, e);
+        }
+        tracing::debug!("为用户 {} 建立了 SSE 连接 {}", user_uuid, connection_id);
+
+        // 不带 Last-Event-ID 时从当前最新的 id 开始, 之前的消息只通过离线补发送达
+        let start_id = match last_event_id {
+            Some(last_event_id) => last_event_id,
+            None => latest_event_id(&self.app_state, &user_uuid).await.unwrap_or_else(|e| {
+                tracing::error!("[用户 {}] 读取最新消息 id 失败：{}", user_uuid, e);
+                0
+            }),
+        };
+        let backlog = match last_event_id {
+            Some(last_event_id) => replay_messages(&self.app_state, &user_uuid, last_event_id).await,
+            None => replay_offline_messages(&self.app_state, &user_uuid).await,
+        }
+        .unwrap_or_else(|e| {
+            tracing::error!("[用户 {}] 读取待补发消息失败：{}", user_uuid, e);
+            Vec::new()
+        });
+        let delivered_up_to = backlog.iter().map(|m| m.id).max().unwrap_or(0).max(start_id);
 
         let cleanup_guard = SseStreamCleanup {
-            user_uuid,
-            sse_senders: self.app_state.sse_senders.clone(),
+            user_uuid: user_uuid.clone(),
+            connection_id,
+            app_state: self.app_state.clone(),
         };
 
-        let stream = ReceiverStream::new(rx)
-            .map(|data| Ok(Event::default().data(data)))
+        let live = ordered_stream(self.app_state.clone(), user_uuid, rx, delivered_up_to);
+        let stream = tokio_stream::iter(backlog)
+            .chain(live)
+            .map(|message| {
+                Ok(Event::default()
+                    .id(message.id.to_string())
+                    .event(message.event)
+                    .data(message.data))
+            })
             //  使用 `map` 或者 `inspect` 来确保 cleanup_guard 的生命周期和流绑定
             .map(move |res| {
                 // 这个闭包借用了 cleanup_guard，只要流还存活，它就存活
@@ -136,4 +135,40 @@ impl SSEService {
 
         Sse::new(stream).keep_alive(KeepAlive::default())
     }
-}
\ No newline at end of file
+}
+
+// 按 id 顺序输出连接收到的消息: 跳过已发送的, 发现 id 不连续时先从事件日志补齐中间的消息
+// 推送时 id 和事件日志原子写入, 收到某条消息时比它小的消息一定已在日志中
+fn ordered_stream(
+    app_state: AppState,
+    user_uuid: String,
+    rx: mpsc::Receiver<SseMessage>,
+    delivered_up_to: u64,
+) -> impl Stream<Item = SseMessage> {
+    futures_util::stream::unfold(
+        (rx, delivered_up_to, VecDeque::new()),
+        move |(mut rx, mut delivered_up_to, mut pending)| {
+            let app_state = app_state.clone();
+            let user_uuid = user_uuid.clone();
+            async move {
+                loop {
+                    if let Some(message) = pending.pop_front() {
+                        return Some((message, (rx, delivered_up_to, pending)));
+                    }
+                    let message = rx.recv().await?;
+                    if message.id <= delivered_up_to {
+                        continue;
+                    }
+                    if message.id > delivered_up_to + 1 {
+                        match read_event_range(&app_state, &user_uuid, delivered_up_to, message.id - 1).await {
+                            Ok(missing) => pending.extend(missing),
+                            Err(e) => tracing::error!("[用户 {}] 补齐乱序消息失败：{}", user_uuid, e),
+                        }
+                    }
+                    delivered_up_to = message.id;
+                    pending.push_back(message);
+                }
+            }
+        },
+    )
+}
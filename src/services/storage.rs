@@ -0,0 +1,197 @@
+// 文件存储: 本地文件系统或 S3 兼容存储
+use std::io::Cursor;
+use std::time::Duration;
+use axum::http::Method;
+use bytes::Bytes;
+use hmac::{Hmac, Mac};
+use image::{imageops::FilterType, ImageFormat};
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::local::LocalFileSystem;
+use object_store::path::Path as ObjectPath;
+use object_store::signer::Signer;
+use object_store::{ObjectStore, PutPayload};
+use sha2::Sha256;
+use tracing::info;
+use crate::bad_request;
+use crate::config::auth::JWT_SECRET;
+use crate::config::storage::{StorageBackend, StorageConfig};
+use crate::errors::app_error::AppError;
+
+// 上传头像在存储中的路径前缀
+pub const AVATAR_PREFIX: &str = "avatars/";
+
+/// 用户自己上传的头像所在路径, 只允许签名或删除该路径下的文件
+pub fn avatar_prefix(owner_uuid: &str) -> String {
+    format!("{}{}/", AVATAR_PREFIX, owner_uuid)
+}
+
+// 本地存储的下载接口, 通过签名参数校验
+const LOCAL_DOWNLOAD_PATH: &str = "/api/v1/files";
+
+enum Store {
+    Local(LocalFileSystem),
+    S3(AmazonS3),
+}
+
+pub struct StorageService {
+    store: Store,
+    config: StorageConfig,
+}
+
+impl StorageService {
+    pub fn new(config: &StorageConfig) -> Result<Self, AppError> {
+        let store = match config.backend {
+            StorageBackend::Local => {
+                std::fs::create_dir_all(&config.local.root)
+                    .map_err(|e| AppError::internal_server_error(anyhow::anyhow!(e)))?;
+                info!("使用本地文件存储: {}", config.local.root);
+                Store::Local(LocalFileSystem::new_with_prefix(&config.local.root)?)
+            }
+            StorageBackend::S3 => {
+                let mut builder = AmazonS3Builder::new()
+                    .with_bucket_name(&config.s3.bucket)
+                    .with_region(&config.s3.region)
+                    .with_access_key_id(&config.s3.access_key_id)
+                    .with_secret_access_key(&config.s3.secret_access_key)
+                    .with_allow_http(config.s3.allow_http);
+                if let Some(endpoint) = &config.s3.endpoint {
+                    builder = builder.with_endpoint(endpoint);
+                }
+                info!("使用S3存储: {}", config.s3.bucket);
+                Store::S3(builder.build()?)
+            }
+        };
+
+        Ok(Self {
+            store,
+            config: config.clone(),
+        })
+    }
+
+    fn object_store(&self) -> &dyn ObjectStore {
+        match &self.store {
+            Store::Local(store) => store,
+            Store::S3(store) => store,
+        }
+    }
+
+    pub async fn put(&self, key: &str, data: Bytes) -> Result<(), AppError> {
+        self.object_store()
+            .put(&ObjectPath::from(key), PutPayload::from(data))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Bytes, AppError> {
+        let result = self.object_store().get(&ObjectPath::from(key)).await?;
+        Ok(result.bytes().await?)
+    }
+
+    /// 删除文件, 文件不存在时忽略
+    pub async fn delete(&self, key: &str) -> Result<(), AppError> {
+        match self.object_store().delete(&ObjectPath::from(key)).await {
+            Ok(_) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 上传的头像保存的是存储路径, 返回时转换为签名链接; 外部头像地址原样返回,
+    /// 指向其他用户头像的路径不签名
+    pub async fn avatar_url(&self, owner_uuid: &str, avatar: Option<String>) -> Result<Option<String>, AppError> {
+        match avatar {
+            Some(key) if key.starts_with(&avatar_prefix(owner_uuid)) => Ok(Some(self.signed_url(&key).await?)),
+            Some(key) if key.starts_with(AVATAR_PREFIX) => Ok(None),
+            other => Ok(other),
+        }
+    }
+
+    /// 生成带有效期的下载链接
+    pub async fn signed_url(&self, key: &str) -> Result<String, AppError> {
+        let expires_in = self.config.signed_url_expiration;
+        match &self.store {
+            Store::S3(store) => {
+                let url = store
+                    .signed_url(Method::GET, &ObjectPath::from(key), Duration::from_secs(expires_in))
+                    .await?;
+                Ok(url.to_string())
+            }
+            Store::Local(_) => {
+                let expires = chrono::Utc::now().timestamp() + expires_in as i64;
+                Ok(format!(
+                    "{}/{}?expires={}&signature={}",
+                    LOCAL_DOWNLOAD_PATH,
+                    key,
+                    expires,
+                    sign(key, expires)
+                ))
+            }
+        }
+    }
+
+    /// 校验本地下载链接的签名
+    pub fn verify_signature(&self, key: &str, expires: i64, signature: &str) -> bool {
+        if expires < chrono::Utc::now().timestamp() {
+            return false;
+        }
+        let Ok(signature) = hex::decode(signature) else {
+            return false;
+        };
+        let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(JWT_SECRET.as_bytes()) else {
+            return false;
+        };
+        mac.update(format!("{}:{}", key, expires).as_bytes());
+        mac.verify_slice(&signature).is_ok()
+    }
+
+    /// 校验上传文件的大小和类型, 类型以文件内容识别结果为准
+    pub fn validate_image(&self, content_type: Option<&str>, data: &[u8]) -> Result<ImageFormat, AppError> {
+        if data.is_empty() {
+            return Err(bad_request!("Empty file"));
+        }
+        if data.len() > self.config.max_upload_bytes {
+            return Err(bad_request!(
+                "File exceeds the maximum size of {} bytes",
+                self.config.max_upload_bytes
+            ));
+        }
+
+        let format = image::guess_format(data).map_err(|_| bad_request!("Unsupported file type"))?;
+        let mime = format.to_mime_type();
+        let declared_matches = content_type.is_none_or(|ct| ct == mime);
+        if !declared_matches || !self.config.allowed_content_types.iter().any(|t| t == mime) {
+            return Err(bad_request!("Unsupported file type: {}", mime));
+        }
+        Ok(format)
+    }
+
+    pub fn avatar_size(&self) -> u32 {
+        self.config.avatar_size
+    }
+
+    pub fn thumbnail_size(&self) -> u32 {
+        self.config.thumbnail_size
+    }
+
+    pub fn max_upload_bytes(&self) -> usize {
+        self.config.max_upload_bytes
+    }
+}
+
+fn sign(key: &str, expires: i64) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(JWT_SECRET.as_bytes())
+        .expect("HMAC can take key of any size");
+    mac.update(format!("{}:{}", key, expires).as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// 将图片裁剪缩放为正方形并编码为PNG, 图片处理较耗CPU, 放到阻塞线程中执行
+pub async fn resize_square(data: Bytes, size: u32) -> Result<Bytes, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let image = image::load_from_memory(&data)?;
+        let resized = image.resize_to_fill(size, size, FilterType::Lanczos3);
+        let mut buffer = Cursor::new(Vec::new());
+        resized.write_to(&mut buffer, ImageFormat::Png)?;
+        Ok::<Bytes, AppError>(Bytes::from(buffer.into_inner()))
+    })
+    .await?
+}
@@ -0,0 +1,358 @@
+// 受管系统登记: 系统信息、按 JSON Schema 校验的配置, 授权以 System 实体为资源
+use cedar_policy::{Entities, Entity, EntityId, EntityUid, RestrictedExpression, Schema};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, Set,
+};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use crate::config::state::AppState;
+use crate::entity::{cluster_config, systems};
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::system::{
+    CreateSystemDto, SystemConfigResponse, SystemQueryParams, SystemResponse, UpdateSystemDto,
+    UpsertSystemConfigDto,
+};
+use crate::utils::cedar_utils::{
+    entity_type_name, AuthAction, ResourceType, ENTITY_ATTR_CODE, ENTITY_ATTR_NAME, ENTITY_TYPE_SYSTEM,
+};
+use crate::{bad_request, conflict, not_found};
+
+#[derive(Clone)]
+pub struct SystemService {
+    app_state: AppState,
+}
+
+impl SystemService {
+    pub fn new(app_state: AppState) -> Self {
+        Self { app_state }
+    }
+
+    pub async fn list_systems(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        params: SystemQueryParams,
+    ) -> Result<(Vec<SystemResponse>, u64), AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::ViewSystem,
+                ResourceType::System(None),
+            )
+            .await?;
+
+        let mut query = systems::Entity::find()
+            .filter(systems::Column::TenantId.eq(current_user.tenant_id))
+            .order_by_desc(systems::Column::SystemId);
+        if let Some(keyword) = params.keyword.as_deref().filter(|keyword| !keyword.is_empty()) {
+            query = query.filter(
+                Condition::any()
+                    .add(systems::Column::Name.contains(keyword))
+                    .add(systems::Column::Code.contains(keyword)),
+            );
+        }
+        let paginator = query.paginate(&self.app_state.db, params.page_size);
+        let total = paginator.num_items().await?;
+        let page_index = if params.page > 0 { params.page - 1 } else { 0 };
+        let results = paginator
+            .fetch_page(page_index)
+            .await?
+            .into_iter()
+            .map(SystemResponse::from)
+            .collect();
+
+        Ok((results, total))
+    }
+
+    pub async fn get_system(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        system_uuid: String,
+    ) -> Result<SystemResponse, AppError> {
+        let system = self
+            .authorized_system(&current_user, context, AuthAction::ViewSystem, &system_uuid)
+            .await?;
+        Ok(SystemResponse::from(system))
+    }
+
+    pub async fn create_system(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        dto: CreateSystemDto,
+    ) -> Result<SystemResponse, AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::CreateSystem,
+                ResourceType::System(None),
+            )
+            .await?;
+        if !dto
+            .code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(bad_request!("System code may only contain letters, digits, '-' and '_'"));
+        }
+        if let Some(config_schema) = &dto.config_schema {
+            compile_config_schema(config_schema)?;
+        }
+        let exists = systems::Entity::find()
+            .filter(systems::Column::Code.eq(&dto.code))
+            .filter(systems::Column::TenantId.eq(current_user.tenant_id))
+            .count(&self.app_state.db)
+            .await?;
+        if exists > 0 {
+            return Err(conflict!("System {} already exists", dto.code));
+        }
+
+        let now = Utc::now();
+        let system = systems::ActiveModel {
+            system_uuid: Set(uuid::Uuid::new_v4().to_string()),
+            tenant_id: Set(current_user.tenant_id),
+            code: Set(dto.code),
+            name: Set(dto.name),
+            description: Set(dto.description),
+            base_url: Set(dto.base_url),
+            config_schema: Set(dto.config_schema),
+            is_active: Set(dto.is_active),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&self.app_state.db)
+        .await?;
+
+        Ok(SystemResponse::from(system))
+    }
+
+    pub async fn update_system(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        system_uuid: String,
+        dto: UpdateSystemDto,
+    ) -> Result<SystemResponse, AppError> {
+        let system = self
+            .authorized_system(&current_user, context, AuthAction::UpdateSystem, &system_uuid)
+            .await?;
+
+        // 新的 Schema 必须兼容已保存的全部配置
+        if let Some(config_schema) = &dto.config_schema {
+            let validator = compile_config_schema(config_schema)?;
+            let configs = cluster_config::Entity::find()
+                .filter(cluster_config::Column::SystemId.eq(system.system_id))
+                .all(&self.app_state.db)
+                .await?;
+            for config in configs {
+                validate_config(&validator, &config.config)
+                    .map_err(|e| bad_request!("Config {} does not match the new schema: {}", config.name, e))?;
+            }
+        }
+
+        let mut system: systems::ActiveModel = system.into();
+        if let Some(name) = dto.name { system.name = Set(name); }
+        if let Some(description) = dto.description { system.description = Set(Some(description)); }
+        if let Some(base_url) = dto.base_url { system.base_url = Set(Some(base_url)); }
+        if let Some(config_schema) = dto.config_schema { system.config_schema = Set(Some(config_schema)); }
+        if let Some(is_active) = dto.is_active { system.is_active = Set(is_active); }
+        system.updated_at = Set(Utc::now());
+
+        let system = system.update(&self.app_state.db).await?;
+        Ok(SystemResponse::from(system))
+    }
+
+    pub async fn delete_system(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        system_uuid: String,
+    ) -> Result<(), AppError> {
+        let system = self
+            .authorized_system(&current_user, context, AuthAction::DeleteSystem, &system_uuid)
+            .await?;
+        // 配置随系统级联删除
+        systems::Entity::delete_by_id(system.system_id)
+            .exec(&self.app_state.db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn list_configs(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        system_uuid: String,
+    ) -> Result<Vec<SystemConfigResponse>, AppError> {
+        let system = self
+            .authorized_system(&current_user, context, AuthAction::ViewSystemConfig, &system_uuid)
+            .await?;
+        let configs = cluster_config::Entity::find()
+            .filter(cluster_config::Column::SystemId.eq(system.system_id))
+            .order_by_asc(cluster_config::Column::Name)
+            .all(&self.app_state.db)
+            .await?;
+        Ok(configs.into_iter().map(SystemConfigResponse::from).collect())
+    }
+
+    pub async fn get_config(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        system_uuid: String,
+        name: String,
+    ) -> Result<SystemConfigResponse, AppError> {
+        let system = self
+            .authorized_system(&current_user, context, AuthAction::ViewSystemConfig, &system_uuid)
+            .await?;
+        let config = self
+            .find_config(system.system_id, &name)
+            .await?
+            .ok_or(not_found!("Config {} not found", name))?;
+        Ok(SystemConfigResponse::from(config))
+    }
+
+    /// 按名称创建或覆盖配置
+    pub async fn upsert_config(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        system_uuid: String,
+        name: String,
+        dto: UpsertSystemConfigDto,
+    ) -> Result<SystemConfigResponse, AppError> {
+        let system = self
+            .authorized_system(&current_user, context, AuthAction::UpdateSystemConfig, &system_uuid)
+            .await?;
+        if name.is_empty() || name.len() > 100 {
+            return Err(bad_request!("Config name must be 1 to 100 characters"));
+        }
+        if let Some(config_schema) = &system.config_schema {
+            let validator = compile_config_schema(config_schema)?;
+            validate_config(&validator, &dto.config)
+                .map_err(|e| bad_request!("Config does not match the system schema: {}", e))?;
+        }
+
+        let now = Utc::now().naive_utc();
+        let config = match self.find_config(system.system_id, &name).await? {
+            Some(config) => {
+                let mut config: cluster_config::ActiveModel = config.into();
+                config.config = Set(dto.config);
+                config.updated_at = Set(now);
+                config.update(&self.app_state.db).await?
+            }
+            None => {
+                cluster_config::ActiveModel {
+                    system_id: Set(system.system_id),
+                    name: Set(name),
+                    config: Set(dto.config),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                    ..Default::default()
+                }
+                .insert(&self.app_state.db)
+                .await?
+            }
+        };
+        Ok(SystemConfigResponse::from(config))
+    }
+
+    pub async fn delete_config(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        system_uuid: String,
+        name: String,
+    ) -> Result<(), AppError> {
+        let system = self
+            .authorized_system(&current_user, context, AuthAction::UpdateSystemConfig, &system_uuid)
+            .await?;
+        let result = cluster_config::Entity::delete_many()
+            .filter(cluster_config::Column::SystemId.eq(system.system_id))
+            .filter(cluster_config::Column::Name.eq(&name))
+            .exec(&self.app_state.db)
+            .await?;
+        if result.rows_affected == 0 {
+            return Err(not_found!("Config {} not found", name));
+        }
+        Ok(())
+    }
+
+    // 带上 System 实体做授权, 策略可以按 resource.code 区分系统
+    async fn authorized_system(
+        &self,
+        current_user: &CurrentUser,
+        context: CedarContext,
+        action: AuthAction,
+        system_uuid: &str,
+    ) -> Result<systems::Model, AppError> {
+        let system = systems::Entity::find()
+            .filter(systems::Column::SystemUuid.eq(system_uuid))
+            .filter(systems::Column::TenantId.eq(current_user.tenant_id))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("System {} not found", system_uuid))?;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
+        let system_es = get_system_entities(std::slice::from_ref(&system), &schema)?;
+        self.app_state
+            .auth_service
+            .check_permission_with_entities(
+                &current_user.uuid,
+                context,
+                action,
+                ResourceType::System(Some(system.system_uuid.clone())),
+                system_es,
+            )
+            .await?;
+        Ok(system)
+    }
+
+    async fn find_config(&self, system_id: u32, name: &str) -> Result<Option<cluster_config::Model>, AppError> {
+        Ok(cluster_config::Entity::find()
+            .filter(cluster_config::Column::SystemId.eq(system_id))
+            .filter(cluster_config::Column::Name.eq(name))
+            .one(&self.app_state.db)
+            .await?)
+    }
+}
+
+pub fn get_system_entities(systems: &[systems::Model], schema: &Schema) -> Result<Entities, AppError> {
+    let system_type_name = entity_type_name(schema, ENTITY_TYPE_SYSTEM)?;
+    let mut entities = Vec::new();
+    for system in systems {
+        let system_uid = EntityUid::from_type_name_and_id(
+            system_type_name.clone(),
+            EntityId::from_str(&system.system_uuid)?,
+        );
+        let mut attrs = HashMap::new();
+        attrs.insert(ENTITY_ATTR_NAME.to_string(), RestrictedExpression::new_string(system.name.clone()));
+        attrs.insert(ENTITY_ATTR_CODE.to_string(), RestrictedExpression::new_string(system.code.clone()));
+        entities.push(Entity::new(system_uid, attrs, HashSet::new())?);
+    }
+    Ok(Entities::from_entities(entities, Some(schema))?)
+}
+
+fn compile_config_schema(config_schema: &Value) -> Result<jsonschema::Validator, AppError> {
+    jsonschema::validator_for(config_schema).map_err(|e| bad_request!("Invalid config schema: {}", e))
+}
+
+// 汇总全部校验错误, 便于一次性修正
+fn validate_config(validator: &jsonschema::Validator, config: &Value) -> Result<(), String> {
+    let errors: Vec<String> = validator
+        .iter_errors(config)
+        .map(|e| format!("{} at '{}'", e, e.instance_path))
+        .collect();
+    if errors.is_empty() { Ok(()) } else { Err(errors.join("; ")) }
+}
@@ -0,0 +1,339 @@
+// 租户管理: 仅平台 (默认租户) 可用, 新租户拥有独立的 Cedar 命名空间、Schema、策略和管理员
+use cedar_policy::{Entities, Entity, EntityId, EntityUid, RestrictedExpression, Schema};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, Set, TransactionTrait,
+};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::config::state::AppState;
+use crate::entity::{cedar_policy_set, cedar_schema, departments, roles, tenants, user_roles, users};
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::event::DomainEvent;
+use crate::schemas::tenant::{
+    CreateTenantDto, TenantQueryParams, TenantResponse, UpdateTenantDto, DEFAULT_TENANT_ID,
+};
+use crate::services::cedar_policy::CedarPolicyService;
+use crate::services::event_bus::publish_event;
+use crate::services::password::{hash_new_password, record_password_history};
+use crate::utils::cedar_utils::{
+    entity_type_name, AuthAction, ResourceType, ENTITY_ATTR_CODE, ENTITY_ATTR_NAME, ENTITY_TYPE_TENANT,
+};
+use crate::{bad_request, conflict, forbidden, not_found};
+
+#[derive(Clone)]
+pub struct TenantService {
+    app_state: AppState,
+}
+
+impl TenantService {
+    pub fn new(app_state: AppState) -> Self {
+        Self { app_state }
+    }
+
+    pub async fn list_tenants(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        params: TenantQueryParams,
+    ) -> Result<(Vec<TenantResponse>, u64), AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::ViewTenant,
+                ResourceType::Tenant(None),
+            )
+            .await?;
+
+        let mut query = tenants::Entity::find().order_by_asc(tenants::Column::TenantId);
+        if let Some(keyword) = params.keyword.as_deref().filter(|keyword| !keyword.is_empty()) {
+            query = query.filter(
+                Condition::any()
+                    .add(tenants::Column::Name.contains(keyword))
+                    .add(tenants::Column::Code.contains(keyword)),
+            );
+        }
+        let paginator = query.paginate(&self.app_state.db, params.page_size);
+        let total = paginator.num_items().await?;
+        let page_index = if params.page > 0 { params.page - 1 } else { 0 };
+        let results = paginator
+            .fetch_page(page_index)
+            .await?
+            .into_iter()
+            .map(TenantResponse::from)
+            .collect();
+
+        Ok((results, total))
+    }
+
+    pub async fn get_tenant(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        tenant_uuid: String,
+    ) -> Result<TenantResponse, AppError> {
+        let tenant = self
+            .authorized_tenant(&current_user, context, AuthAction::ViewTenant, &tenant_uuid)
+            .await?;
+        Ok(TenantResponse::from(tenant))
+    }
+
+    pub async fn create_tenant(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        dto: CreateTenantDto,
+    ) -> Result<TenantResponse, AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::CreateTenant,
+                ResourceType::Tenant(None),
+            )
+            .await?;
+        if !dto
+            .code
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+            || dto.code.starts_with('-')
+            || dto.code.ends_with('-')
+        {
+            return Err(bad_request!(
+                "Tenant code may only contain lowercase letters, digits and '-', and must not start or end with '-'"
+            ));
+        }
+        if !dto.namespace.starts_with(|c: char| c.is_ascii_alphabetic())
+            || !dto.namespace.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(bad_request!(
+                "Tenant namespace must start with a letter and may only contain letters, digits and '_'"
+            ));
+        }
+        let exists = tenants::Entity::find()
+            .filter(
+                Condition::any()
+                    .add(tenants::Column::Code.eq(&dto.code))
+                    .add(tenants::Column::Namespace.eq(&dto.namespace)),
+            )
+            .count(&self.app_state.db)
+            .await?;
+        if exists > 0 {
+            return Err(conflict!("Tenant code {} or namespace {} already exists", dto.code, dto.namespace));
+        }
+
+        // 以默认租户的 Schema 为模板, 包裹在新租户的命名空间中
+        let base_schema = cedar_schema::Entity::find()
+            .filter(cedar_schema::Column::TenantId.eq(DEFAULT_TENANT_ID))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("Default tenant schema not found"))?;
+        let tenant_schema = format!("namespace {} {{\n{}\n}}\n", dto.namespace, base_schema.schema);
+        let (_, _) = Schema::from_cedarschema_str(&tenant_schema)
+            .map_err(|e| bad_request!("Failed to build tenant schema: {}", e))?;
+
+        let now = Utc::now();
+        let txn = self.app_state.db.begin().await?;
+        let tenant = tenants::ActiveModel {
+            tenant_uuid: Set(Uuid::new_v4().to_string()),
+            code: Set(dto.code),
+            namespace: Set(Some(dto.namespace.clone())),
+            name: Set(dto.name.clone()),
+            description: Set(dto.description),
+            is_active: Set(true),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        cedar_schema::ActiveModel {
+            schema_uuid: Set(Uuid::new_v4().to_string()),
+            tenant_id: Set(tenant.tenant_id),
+            schema: Set(tenant_schema),
+            description: Set(base_schema.description),
+            is_active: Set(true),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        let role = roles::ActiveModel {
+            role_uuid: Set(Uuid::new_v4().to_string()),
+            tenant_id: Set(tenant.tenant_id),
+            role_name: Set("SuperAdmin".to_string()),
+            description: Set(Some("租户超级管理员".to_string())),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        let dept = departments::ActiveModel {
+            dept_uuid: Set(Uuid::new_v4().to_string()),
+            tenant_id: Set(tenant.tenant_id),
+            name: Set(dto.name),
+            parent_id: Set(0),
+            order: Set(0),
+            is_deleted: Set(false),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        let policy = &self.app_state.config.security.password;
+        let hashed_password =
+            hash_new_password(&txn, policy, None, &dto.admin_username, &dto.admin_password).await?;
+        let admin = users::ActiveModel {
+            user_uuid: Set(Uuid::new_v4().to_string()),
+            username: Set(dto.admin_username),
+            email: Set(dto.admin_email),
+            password: Set(hashed_password.clone()),
+            dept_id: Set(dept.dept_id),
+            tenant_id: Set(tenant.tenant_id),
+            is_active: Set(true),
+            password_changed_at: Set(Some(chrono::Local::now().naive_local())),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        record_password_history(&txn, policy, admin.user_id, &hashed_password).await?;
+
+        user_roles::Entity::insert(user_roles::ActiveModel {
+            user_id: Set(admin.user_id),
+            role_id: Set(role.role_id),
+            ..Default::default()
+        })
+        .exec(&txn)
+        .await?;
+
+        let annotation = "租户管理员可以执行所有操作".to_string();
+        let policy_text = format!(
+            "@annotation(\"{}\")\npermit (\n    principal in {}::Role::\"{}\",\n    action,\n    resource\n);",
+            annotation, dto.namespace, role.role_uuid
+        );
+        cedar_policy_set::ActiveModel {
+            annotation: Set(annotation.clone()),
+            policy_hash: Set(CedarPolicyService::hash_policy_content(&policy_text)?),
+            policy_text: Set(policy_text),
+            effect: Set("permit".to_string()),
+            policy_type: Set("STATIC".to_string()),
+            policy_uuid: Set(Uuid::new_v4().to_string()),
+            is_active: Set(true),
+            description: Set(annotation),
+            created_by: Set(admin.user_id),
+            tenant_id: Set(tenant.tenant_id),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        txn.commit().await?;
+
+        // 各节点重新加载租户列表和 Schema
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::SchemaChanged).await;
+        Ok(TenantResponse::from(tenant))
+    }
+
+    pub async fn update_tenant(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        tenant_uuid: String,
+        dto: UpdateTenantDto,
+    ) -> Result<TenantResponse, AppError> {
+        let tenant = self
+            .authorized_tenant(&current_user, context, AuthAction::UpdateTenant, &tenant_uuid)
+            .await?;
+        if tenant.tenant_id == DEFAULT_TENANT_ID && dto.is_active == Some(false) {
+            return Err(forbidden!("The default tenant cannot be deactivated"));
+        }
+        let active_changed = dto.is_active.is_some_and(|is_active| is_active != tenant.is_active);
+
+        let mut tenant: tenants::ActiveModel = tenant.into();
+        if let Some(name) = dto.name { tenant.name = Set(name); }
+        if let Some(description) = dto.description { tenant.description = Set(Some(description)); }
+        if let Some(is_active) = dto.is_active { tenant.is_active = Set(is_active); }
+        tenant.updated_at = Set(Utc::now());
+
+        let tenant = tenant.update(&self.app_state.db).await?;
+        if active_changed {
+            publish_event(&self.app_state, Some(&current_user), DomainEvent::SchemaChanged).await;
+        }
+        Ok(TenantResponse::from(tenant))
+    }
+
+    pub async fn delete_tenant(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        tenant_uuid: String,
+    ) -> Result<(), AppError> {
+        let tenant = self
+            .authorized_tenant(&current_user, context, AuthAction::DeleteTenant, &tenant_uuid)
+            .await?;
+        if tenant.tenant_id == DEFAULT_TENANT_ID {
+            return Err(forbidden!("The default tenant cannot be deleted"));
+        }
+        // 租户下的用户、角色、策略等随租户级联删除
+        tenants::Entity::delete_by_id(tenant.tenant_id)
+            .exec(&self.app_state.db)
+            .await?;
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::SchemaChanged).await;
+        Ok(())
+    }
+
+    async fn authorized_tenant(
+        &self,
+        current_user: &CurrentUser,
+        context: CedarContext,
+        action: AuthAction,
+        tenant_uuid: &str,
+    ) -> Result<tenants::Model, AppError> {
+        let tenant = tenants::Entity::find()
+            .filter(tenants::Column::TenantUuid.eq(tenant_uuid))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("Tenant {} not found", tenant_uuid))?;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
+        let tenant_es = get_tenant_entities(std::slice::from_ref(&tenant), &schema)?;
+        self.app_state
+            .auth_service
+            .check_permission_with_entities(
+                &current_user.uuid,
+                context,
+                action,
+                ResourceType::Tenant(Some(tenant.tenant_uuid.clone())),
+                tenant_es,
+            )
+            .await?;
+        Ok(tenant)
+    }
+}
+
+pub fn get_tenant_entities(tenants: &[tenants::Model], schema: &Schema) -> Result<Entities, AppError> {
+    let mut entities = Vec::new();
+    for tenant in tenants {
+        let tenant_uid = EntityUid::from_type_name_and_id(
+            entity_type_name(schema, ENTITY_TYPE_TENANT)?,
+            EntityId::from_str(&tenant.tenant_uuid)?,
+        );
+        let mut attrs = HashMap::new();
+        attrs.insert(ENTITY_ATTR_NAME.to_string(), RestrictedExpression::new_string(tenant.name.clone()));
+        attrs.insert(ENTITY_ATTR_CODE.to_string(), RestrictedExpression::new_string(tenant.code.clone()));
+        entities.push(Entity::new(tenant_uid, attrs, HashSet::new())?);
+    }
+    Ok(Entities::from_entities(entities, Some(schema))?)
+}
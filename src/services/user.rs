@@ -5,24 +5,29 @@ use crate::entity::{
 use crate::errors::app_error::AppError;
 use crate::schemas::auth::CurrentUser;
 use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::event::DomainEvent;
 use crate::schemas::user::{
     AssignRoleDto, CreateUserDto, DeptResponse, DirectRole, GroupResponse, GroupRole, QueryParams,
     UpdateUserDto, UserResponse, UserRoleInfo, UserUUID,
 };
+use crate::services::event_bus::publish_event;
 use crate::services::department::{
     DepartmentService, find_descendants_entities, get_dept_entities,
 };
 use crate::services::groups::{GroupService, get_group_entities};
-use crate::services::role::{RoleService, get_role_entities, get_role_models_by_user_uuid};
+use crate::services::login_guard::LoginGuardService;
+use crate::services::password::{hash_new_password, record_password_history};
+use crate::services::role::{
+    RoleService, active_assignment, get_inherited_roles, get_role_entities, get_role_hierarchy_entities,
+    get_role_models_by_user_uuid, get_role_parent_map, validate_assignment_period,
+};
 use crate::utils::cedar_utils::{
     AuthAction, ENTITY_ATTR_NAME, ENTITY_TYPE_GROUP, ENTITY_TYPE_ROLE, ENTITY_TYPE_USER,
-    ResourceType, entities2json,
+    ResourceType, USER_ENTITIES_CACHE_PREFIX, entities2json, entity_type_name,
 };
-use crate::utils::crypto::hash_password;
 use crate::{bad_request, conflict, not_found};
-use cedar_policy::{
-    Entities, Entity, EntityId, EntityTypeName, EntityUid, RestrictedExpression, Schema,
-};
+use cedar_policy::{Entities, Entity, EntityId, EntityUid, RestrictedExpression, Schema};
+use chrono::{DateTime, Utc};
 use sea_orm::JoinType::InnerJoin;
 use sea_orm::sea_query::Query;
 use sea_orm::{
@@ -38,6 +43,7 @@ use uuid::Uuid;
 
 const ROLE_SOURCE_DIRECT: &str = "direct";
 const ROLE_SOURCE_GROUP: &str = "group";
+const ROLE_SOURCE_INHERITED: &str = "inherited";
 
 #[derive(Clone)]
 pub struct UserService {
@@ -50,13 +56,20 @@ impl UserService {
             app_state: app_state.clone(),
         }
     }
+
+    async fn user_response(&self, user: users::Model) -> Result<UserResponse, AppError> {
+        let mut response = UserResponse::from(user);
+        response.avatar = self.app_state.storage_service.avatar_url(&response.uuid, response.avatar.take()).await?;
+        Ok(response)
+    }
     // 获取用户实体信息
     pub async fn get_user_role_models(&self, user_id: i32) -> Result<Vec<roles::Model>, AppError> {
         // --- 子查询 1: 获取直接分配给用户的角色 ID ---
         let direct_role_ids_query = user_roles::Entity::find()
             .select_only() // 只选择特定列
             .column(user_roles::Column::RoleId) // 我们只需要 role_id
-            .filter(user_roles::Column::UserId.eq(user_id));
+            .filter(user_roles::Column::UserId.eq(user_id))
+            .filter(active_assignment(user_roles::Column::ValidFrom, user_roles::Column::ValidUntil));
 
         // --- 子查询 2: 获取通过用户组继承的角色 ID ---
         // 首先，找到该用户所属的所有 group_id
@@ -69,7 +82,8 @@ impl UserService {
         let group_role_ids_query = group_roles::Entity::find()
             .select_only()
             .column(group_roles::Column::RoleId)
-            .filter(group_roles::Column::GroupId.in_subquery(group_ids_query.into_query()));
+            .filter(group_roles::Column::GroupId.in_subquery(group_ids_query.into_query()))
+            .filter(active_assignment(group_roles::Column::ValidFrom, group_roles::Column::ValidUntil));
 
         // --- 主查询: 获取所有符合条件的角色信息 ---
         // 使用 Condition::any() (即 OR) 来合并两个子查询的结果
@@ -127,7 +141,9 @@ impl UserService {
                 .collect()
             });
 
-        let mut query = users::Entity::find().filter(users::Column::IsActive.eq(true));
+        let mut query = users::Entity::find()
+            .filter(users::Column::TenantId.eq(current_user.tenant_id))
+            .filter(users::Column::IsActive.eq(true));
 
         // 应用其他过滤条件
         if let Some(username) = params.username {
@@ -141,6 +157,7 @@ impl UserService {
                 .select_only()
                 .column(departments::Column::DeptId)
                 .filter(departments::Column::DeptUuid.eq(dept_uuid))
+                .filter(departments::Column::TenantId.eq(current_user.tenant_id))
                 .into_tuple::<i32>()
                 .one(&self.app_state.db)
                 .await?
@@ -265,6 +282,15 @@ impl UserService {
                     }
                 }
 
+                // 上传的头像转换为签名链接
+                let owner_uuid = user_obj.get("uuid").and_then(|u| u.as_str()).unwrap_or_default().to_string();
+                if let Some(avatar) = user_obj.get_mut("avatar")
+                    && let Some(key) = avatar.as_str()
+                {
+                    let url = self.app_state.storage_service.avatar_url(&owner_uuid, Some(key.to_string())).await?;
+                    *avatar = json!(url);
+                }
+
                 // 坑爹的MYSQL, BOOL要单独处理
                 if requested_fields.contains("is_active") {
                     if let Some(is_active_val) = user_obj.get_mut("is_active") {
@@ -286,8 +312,8 @@ impl UserService {
         context: CedarContext,
         user_uuid: String,
     ) -> Result<UserResponse, AppError> {
-        let schema = self.app_state.auth_service.get_schema_copy().await;
-        let es = get_user_entities(&self.app_state.db, user_uuid.clone(), &schema).await?;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
+        let es = get_user_entities(&self.app_state.db, current_user.tenant_id, user_uuid.clone(), &schema).await?;
         self.app_state
             .auth_service
             .check_permission_with_entities(
@@ -302,6 +328,7 @@ impl UserService {
         // 查询用户及其部门信息
         let user_with_dept = users::Entity::find()
             .filter(users::Column::UserUuid.eq(user_uuid))
+            .filter(users::Column::TenantId.eq(current_user.tenant_id))
             .find_also_related(departments::Entity)
             .one(&self.app_state.db)
             .await?;
@@ -314,6 +341,7 @@ impl UserService {
                 .all(&self.app_state.db)
                 .await?;
 
+            let avatar = self.app_state.storage_service.avatar_url(&user.user_uuid, user.avatar).await?;
             let user_response = UserResponse {
                 uuid: user.user_uuid,
                 username: user.username,
@@ -332,7 +360,7 @@ impl UserService {
                         name: g.name,
                     })
                     .collect(),
-                avatar: user.avatar,
+                avatar,
                 last_login: user.last_login,
             };
 
@@ -348,9 +376,9 @@ impl UserService {
         context: CedarContext,
         dto: CreateUserDto,
     ) -> Result<UserResponse, AppError> {
-        let schema = self.app_state.auth_service.get_schema_copy().await;
-        let dept_es = get_dept_entities(&self.app_state.db, &dto.dept, &schema).await?;
-        let group_es = get_group_entities(&self.app_state.db, &dto.groups, &schema).await?;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
+        let dept_es = get_dept_entities(&self.app_state.db, current_user.tenant_id, &dto.dept, &schema).await?;
+        let group_es = get_group_entities(&self.app_state.db, current_user.tenant_id, &dto.groups, &schema).await?;
         let merged_es = dept_es.add_entities(group_es.clone(), Some(&schema))?;
         self.app_state
             .auth_service
@@ -379,9 +407,8 @@ impl UserService {
         let txn = self.app_state.db.begin().await?;
 
         if users::Entity::find()
-            .filter(
-                users::Column::Email.eq(&dto.email)
-            )
+            .filter(users::Column::Email.eq(&dto.email))
+            .filter(users::Column::TenantId.eq(current_user.tenant_id))
             .one(&txn)
             .await?
             .is_some()
@@ -389,12 +416,15 @@ impl UserService {
             return Err(conflict!("Username or email already exists".to_string(),));
         }
 
-        let hashed_password = hash_password(&dto.password)?;
+        let policy = &self.app_state.config.security.password;
+        let hashed_password =
+            hash_new_password(&txn, policy, None, &dto.username, &dto.password).await?;
 
         let dept_id = departments::Entity::find()
             .select_only()
             .column(departments::Column::DeptId)
             .filter(departments::Column::DeptUuid.eq(dto.dept))
+            .filter(departments::Column::TenantId.eq(current_user.tenant_id))
             .into_tuple::<i32>()
             .one(&txn)
             .await?
@@ -404,20 +434,24 @@ impl UserService {
             user_uuid: Set(Uuid::new_v4().to_string()),
             username: Set(dto.username),
             email: Set(dto.email),
-            password: Set(hashed_password),
+            password: Set(hashed_password.clone()),
             dept_id: Set(dept_id),
+            tenant_id: Set(current_user.tenant_id),
             alias: Set(dto.alias),
             phone: Set(dto.phone),
             is_active: Set(dto.is_active),
+            password_changed_at: Set(Some(chrono::Local::now().naive_local())),
             ..Default::default()
         };
 
         let user = user.insert(&txn).await?;
+        record_password_history(&txn, policy, user.user_id, &hashed_password).await?;
 
         let user_group_ids = user_groups::Entity::find()
             .select_only()
             .column(user_groups::Column::UserGroupId)
             .filter(user_groups::Column::UserGroupUuid.is_in(dto.groups))
+            .filter(user_groups::Column::TenantId.eq(current_user.tenant_id))
             .into_tuple::<i32>()
             .all(&txn)
             .await?;
@@ -436,7 +470,11 @@ impl UserService {
 
         txn.commit().await?;
 
-        Ok(UserResponse::from(user))
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::UserCreated {
+            user_uuid: user.user_uuid.clone(),
+            username: user.username.clone(),
+        }).await;
+        self.user_response(user).await
     }
 
     pub async fn update_user(
@@ -448,21 +486,22 @@ impl UserService {
     ) -> Result<UserResponse, AppError> {
 
 
-        let schema = self.app_state.auth_service.get_schema_copy().await;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
 
         let mut entities = Entities::empty();
-        let user_es = get_user_entities(&self.app_state.db, user_uuid.clone(), &schema).await?;
+        let user_es = get_user_entities(&self.app_state.db, current_user.tenant_id, user_uuid.clone(), &schema).await?;
         entities = entities.add_entities(user_es, Some(&schema))?;
 
         let mut target_dept_id: Option<i32> = None;
         if let Some(dept_uuid) = dto.dept.clone() {
-            let dept_es = get_dept_entities(&self.app_state.db, &dept_uuid, &schema).await?;
+            let dept_es = get_dept_entities(&self.app_state.db, current_user.tenant_id, &dept_uuid, &schema).await?;
             entities = entities.add_entities(dept_es, Some(&schema))?;
 
             let dept_id = departments::Entity::find()
                 .select_only()
                 .column(departments::Column::DeptId)
                 .filter(departments::Column::DeptUuid.eq(dept_uuid))
+                .filter(departments::Column::TenantId.eq(current_user.tenant_id))
                 .into_tuple::<i32>()
                 .one(&self.app_state.db)
                 .await?
@@ -476,13 +515,14 @@ impl UserService {
             if group_uuids.is_empty() {
                 target_group_ids = Some(vec![]);
             } else {
-                let group_es = get_group_entities(&self.app_state.db, group_uuids, &schema).await?;
+                let group_es = get_group_entities(&self.app_state.db, current_user.tenant_id, group_uuids, &schema).await?;
                 entities = entities.add_entities(group_es, Some(&schema))?;
 
                 let group_ids = user_groups::Entity::find()
                     .select_only()
                     .column(user_groups::Column::UserGroupId)
                     .filter(user_groups::Column::UserGroupUuid.is_in(group_uuids))
+                    .filter(user_groups::Column::TenantId.eq(current_user.tenant_id))
                     .into_tuple::<i32>()
                     .all(&self.app_state.db)
                     .await?;
@@ -530,6 +570,7 @@ impl UserService {
 
         let mut user: users::ActiveModel = users::Entity::find()
             .filter(users::Column::UserUuid.eq(&user_uuid))
+            .filter(users::Column::TenantId.eq(current_user.tenant_id))
             .one(&txn)
             .await?
             .ok_or_else(|| not_found!("User not found"))?
@@ -541,6 +582,7 @@ impl UserService {
         if let Some(phone) = dto.phone { user.phone = Set(Some(phone)); }
         if let Some(is_active) = dto.is_active { user.is_active = Set(is_active); }
 
+        let original_dept_id = *user.dept_id.as_ref();
         if let Some(dept_id) = target_dept_id {
             user.dept_id = Set(dept_id);
         }
@@ -566,9 +608,27 @@ impl UserService {
             }
         }
 
+        let from_dept_uuid = match target_dept_id {
+            Some(dept_id) if dept_id != original_dept_id => departments::Entity::find_by_id(original_dept_id)
+                .one(&txn)
+                .await?
+                .map(|dept| dept.dept_uuid),
+            _ => None,
+        };
+
         txn.commit().await?;
 
-        Ok(UserResponse::from(user))
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::UserUpdated {
+            user_uuid: user_uuid.clone(),
+        }).await;
+        if let (Some(from_dept_uuid), Some(to_dept_uuid)) = (from_dept_uuid, dto.dept) {
+            publish_event(&self.app_state, Some(&current_user), DomainEvent::UserDepartmentChanged {
+                user_uuid,
+                from_dept_uuid,
+                to_dept_uuid,
+            }).await;
+        }
+        self.user_response(user).await
     }
 
     pub async fn delete_user(
@@ -577,8 +637,8 @@ impl UserService {
         context: CedarContext,
         user_uuid: String,
     ) -> Result<(), AppError> {
-        let schema = self.app_state.auth_service.get_schema_copy().await;
-        let user_es = get_user_entities(&self.app_state.db, user_uuid.clone(), &schema).await?;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
+        let user_es = get_user_entities(&self.app_state.db, current_user.tenant_id, user_uuid.clone(), &schema).await?;
         self.app_state
             .auth_service
             .check_permission_with_entities(
@@ -592,7 +652,8 @@ impl UserService {
 
         // 用户不删除 只是禁用
         users::Entity::update_many()
-            .filter(users::Column::UserUuid.eq(user_uuid))
+            .filter(users::Column::UserUuid.eq(&user_uuid))
+            .filter(users::Column::TenantId.eq(current_user.tenant_id))
             .set(users::ActiveModel {
                 is_active: Set(false),
                 ..Default::default()
@@ -600,9 +661,46 @@ impl UserService {
             .exec(&self.app_state.db)
             .await?;
 
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::UserDisabled { user_uuid }).await;
+
         Ok(())
     }
 
+    /// 解除登录失败导致的锁定
+    pub async fn unlock_user(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        user_uuid: String,
+    ) -> Result<(), AppError> {
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
+        let user_es = get_user_entities(&self.app_state.db, current_user.tenant_id, user_uuid.clone(), &schema).await?;
+        self.app_state
+            .auth_service
+            .check_permission_with_entities(
+                &current_user.uuid,
+                context,
+                AuthAction::UpdateUser,
+                ResourceType::User(Some(user_uuid.clone())),
+                user_es,
+            )
+            .await?;
+
+        let username = users::Entity::find()
+            .select_only()
+            .column(users::Column::Username)
+            .filter(users::Column::UserUuid.eq(user_uuid))
+            .filter(users::Column::TenantId.eq(current_user.tenant_id))
+            .into_tuple::<String>()
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("User not found"))?;
+
+        LoginGuardService::new(self.app_state.clone(), current_user.tenant_id)
+            .unlock(&username, &current_user.username)
+            .await
+    }
+
     pub async fn user_roles(
         &self,
         current_user: CurrentUser,
@@ -625,6 +723,7 @@ impl UserService {
             .select_only()
             .column(users::Column::UserId)
             .filter(users::Column::UserUuid.eq(&user_uuid))
+            .filter(users::Column::TenantId.eq(current_user.tenant_id))
             .into_tuple::<i32>()
             .one(&self.app_state.db)
             .await?
@@ -639,6 +738,8 @@ impl UserService {
                 role_name: direct_role.role_name,
                 source: ROLE_SOURCE_DIRECT.to_string(),
                 group_name: None,
+                inherited_from: None,
+                valid_until: direct_role.valid_until,
             });
         }
 
@@ -650,6 +751,23 @@ impl UserService {
                 role_name: group_role.role_name,
                 source: ROLE_SOURCE_GROUP.to_string(),
                 group_name: Some(group_role.group_name),
+                inherited_from: None,
+                valid_until: group_role.valid_until,
+            });
+        }
+
+        // 获取通过角色继承获得的角色
+        let granted_roles = self.get_user_role_models(user_id).await?;
+        let parent_map = get_role_parent_map(&self.app_state.db, current_user.tenant_id).await?;
+        let inherited_roles = get_inherited_roles(&self.app_state.db, &parent_map, &granted_roles).await?;
+        for (inherited_role, inherited_from) in inherited_roles {
+            all_roles.push(UserRoleInfo {
+                uuid: Some(inherited_role.role_uuid),
+                role_name: inherited_role.role_name,
+                source: ROLE_SOURCE_INHERITED.to_string(),
+                group_name: None,
+                inherited_from: Some(inherited_from),
+                valid_until: None,
             });
         }
 
@@ -662,15 +780,18 @@ impl UserService {
             .select_only()
             .column(roles::Column::RoleUuid)
             .column(roles::Column::RoleName)
+            .column(user_roles::Column::ValidUntil)
             .join(JoinType::InnerJoin, roles::Relation::UserRoles.def())
             .filter(user_roles::Column::UserId.eq(user_id))
-            .into_tuple::<(String, String)>()
+            .filter(active_assignment(user_roles::Column::ValidFrom, user_roles::Column::ValidUntil))
+            .into_tuple::<(String, String, Option<DateTime<Utc>>)>()
             .all(&self.app_state.db)
             .await?
             .into_iter()
-            .map(|(uuid, name)| DirectRole {
+            .map(|(uuid, name, valid_until)| DirectRole {
                 uuid,
                 role_name: name,
+                valid_until,
             })
             .collect();
 
@@ -683,20 +804,23 @@ impl UserService {
             .select_only()
             .columns([roles::Column::RoleUuid, roles::Column::RoleName])
             .column_as(user_groups::Column::Name, "group_name")
+            .column(group_roles::Column::ValidUntil)
             .join(InnerJoin, roles::Relation::GroupRoles.def())
             .join(InnerJoin, group_roles::Relation::UserGroups.def())
             .join(InnerJoin, user_groups::Relation::UserGroupMembers.def())
             .filter(user_group_members::Column::UserId.eq(user_id))
-            .into_tuple::<(String, String, String)>()
+            .filter(active_assignment(group_roles::Column::ValidFrom, group_roles::Column::ValidUntil))
+            .into_tuple::<(String, String, String, Option<DateTime<Utc>>)>()
             .all(&self.app_state.db)
             .await?;
 
         let group_roles = roles
             .into_iter()
-            .map(|(role_uuid, role_name, group_name)| GroupRole {
+            .map(|(role_uuid, role_name, group_name, valid_until)| GroupRole {
                 uuid: role_uuid,
                 role_name,
                 group_name,
+                valid_until,
             })
             .collect();
 
@@ -710,12 +834,13 @@ impl UserService {
         user_uuid: String,
         dto: AssignRoleDto,
     ) -> Result<(), AppError> {
-        let schema = self.app_state.auth_service.get_schema_copy().await;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
 
         let target_user_id = users::Entity::find()
             .select_only()
             .column(users::Column::UserId)
             .filter(users::Column::UserUuid.eq(user_uuid.clone()))
+            .filter(users::Column::TenantId.eq(current_user.tenant_id))
             .into_tuple::<i32>()
             .one(&self.app_state.db)
             .await?
@@ -725,13 +850,15 @@ impl UserService {
             .select_only()
             .column(roles::Column::RoleId)
             .filter(roles::Column::RoleUuid.eq(dto.role_uuid.clone()))
+            .filter(roles::Column::TenantId.eq(current_user.tenant_id))
             .into_tuple::<i32>()
             .one(&self.app_state.db)
             .await?
             .ok_or_else(|| not_found!(format!("not found role[{}]", dto.role_uuid)))?;
 
-        let user_es = get_user_entities(&self.app_state.db, user_uuid.clone(), &schema).await?;
-        let role_es = get_role_entities(&self.app_state.db, &vec![dto.role_uuid.clone()], &schema).await?;
+        let active = validate_assignment_period(dto.valid_from, dto.valid_until)?;
+        let user_es = get_user_entities(&self.app_state.db, current_user.tenant_id, user_uuid.clone(), &schema).await?;
+        let role_es = get_role_entities(&self.app_state.db, current_user.tenant_id, &vec![dto.role_uuid.clone()], &schema).await?;
 
         let merged_es = role_es.add_entities(user_es, Some(&schema))?;
 
@@ -741,7 +868,7 @@ impl UserService {
                 &current_user.uuid,
                 context,
                 AuthAction::AssignRole,
-                ResourceType::Role(Some(dto.role_uuid)),
+                ResourceType::Role(Some(dto.role_uuid.clone())),
                 merged_es,
             )
             .await?;
@@ -753,9 +880,13 @@ impl UserService {
             .exec(&txn)
             .await?;
 
+        // 未到生效时间的分配由后台任务在生效时激活
         user_roles::Entity::insert(user_roles::ActiveModel {
             user_id: Set(target_user_id),
             role_id: Set(target_role_id),
+            valid_from: Set(dto.valid_from),
+            valid_until: Set(dto.valid_until),
+            activated_at: Set(active.then(Utc::now)),
             ..Default::default()
         })
         .exec(&txn)
@@ -763,6 +894,10 @@ impl UserService {
 
         txn.commit().await?;
 
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::RoleAssigned {
+            user_uuid,
+            role_uuid: dto.role_uuid,
+        }).await;
         Ok(())
     }
 
@@ -773,10 +908,10 @@ impl UserService {
         user_uuid: UserUUID,
         role_uuid: String,
     ) -> Result<(), AppError> {
-        let schema = self.app_state.auth_service.get_schema_copy().await;
+        let schema = self.app_state.auth_service.get_schema_copy(current_user.tenant_id).await;
         let role_es =
-            get_role_entities(&self.app_state.db, &vec![role_uuid.clone()], &schema).await?;
-        let user_es = get_user_entities(&self.app_state.db, user_uuid.clone(), &schema).await?;
+            get_role_entities(&self.app_state.db, current_user.tenant_id, &vec![role_uuid.clone()], &schema).await?;
+        let user_es = get_user_entities(&self.app_state.db, current_user.tenant_id, user_uuid.clone(), &schema).await?;
         let merged_es = role_es.add_entities(user_es, None)?;
 
         self.app_state
@@ -796,7 +931,8 @@ impl UserService {
                     Query::select()
                         .column(users::Column::UserId)
                         .from(users::Entity)
-                        .and_where(users::Column::UserUuid.eq(user_uuid))
+                        .and_where(users::Column::UserUuid.eq(&user_uuid))
+                        .and_where(users::Column::TenantId.eq(current_user.tenant_id))
                         .to_owned(),
                 ),
             )
@@ -805,29 +941,60 @@ impl UserService {
                     Query::select()
                         .column(roles::Column::RoleId)
                         .from(roles::Entity)
-                        .and_where(roles::Column::RoleUuid.eq(role_uuid))
+                        .and_where(roles::Column::RoleUuid.eq(&role_uuid))
+                        .and_where(roles::Column::TenantId.eq(current_user.tenant_id))
                         .to_owned(),
                 ),
             )
             .exec(&self.app_state.db)
             .await?;
 
+        publish_event(&self.app_state, Some(&current_user), DomainEvent::RoleRevoked {
+            user_uuid,
+            role_uuid,
+        }).await;
         Ok(())
     }
 }
 
+/// 用户实体只在登录时缓存, 不经过登录的认证方式(OAuth2 刷新令牌、个人访问令牌)在授权检查前需先确保已缓存
+pub async fn ensure_user_entities(app_state: &AppState, user_uuid: &str) -> Result<(), AppError> {
+    let cache_key = format!("{}:{}", USER_ENTITIES_CACHE_PREFIX, user_uuid);
+    if app_state.cache_service.get_entities(cache_key.clone()).await?.is_none() {
+        let tenant_id = get_user_tenant_id(&app_state.db, user_uuid).await?;
+        let schema = app_state.auth_service.get_schema_copy(tenant_id).await;
+        let entities = get_user_entities(&app_state.db, tenant_id, user_uuid.to_string(), &schema).await?;
+        app_state.cache_service.cache_entities(cache_key, entities).await?;
+    }
+    Ok(())
+}
+
+/// 用户所属的租户, 用于选择生成实体时使用的 Schema
+pub async fn get_user_tenant_id(db: &DatabaseConnection, user_uuid: &str) -> Result<i32, AppError> {
+    users::Entity::find()
+        .select_only()
+        .column(users::Column::TenantId)
+        .filter(users::Column::UserUuid.eq(user_uuid))
+        .into_tuple::<i32>()
+        .one(db)
+        .await?
+        .ok_or(not_found!("User {} not found", user_uuid))
+}
+
 pub async fn get_user_entities(
     db: &DatabaseConnection,
+    tenant_id: i32,
     user_uuid: UserUUID,
     schema: &Schema,
 ) -> Result<Entities, AppError> {
 
-    // 获取 user_id 和 username
+    // 获取 user_id 和 username, 其他租户的用户视为不存在
     let (user_id, username) = users::Entity::find()
         .select_only()
         .column(users::Column::UserId)
         .column(users::Column::Username)
         .filter(users::Column::UserUuid.eq(user_uuid.clone()))
+        .filter(users::Column::TenantId.eq(tenant_id))
         .into_tuple::<(i32, String)>()
         .one(db)
         .await?
@@ -860,7 +1027,7 @@ pub async fn get_user_entities(
     if department.is_some() {
         let department = department.unwrap();
         // 用户所有的子部门
-        let child_dept_entities = find_descendants_entities(db, department.dept_id).await?;
+        let child_dept_entities = find_descendants_entities(db, tenant_id, department.dept_id, schema).await?;
         for child_dept_entity in child_dept_entities {
             let dept_e_uid = child_dept_entity.uid();
             user_parent_uids.insert(dept_e_uid);
@@ -870,7 +1037,7 @@ pub async fn get_user_entities(
 
     for group in groups {
         let group_eid = EntityId::from_str(&group.user_group_uuid)?;
-        let group_type_name = EntityTypeName::from_str(ENTITY_TYPE_GROUP)?;
+        let group_type_name = entity_type_name(schema, ENTITY_TYPE_GROUP)?;
         let group_e_uid = EntityUid::from_type_name_and_id(group_type_name, group_eid);
 
         let mut attrs = HashMap::new();
@@ -883,23 +1050,16 @@ pub async fn get_user_entities(
         user_parent_uids.insert(group_e_uid);
     }
 
-    for role in all_roles {
-        let role_eid = EntityId::from_str(role.role_uuid.to_string().as_str())?;
-        let role_type_name = EntityTypeName::from_str(ENTITY_TYPE_ROLE)?;
-        let role_e_uid = EntityUid::from_type_name_and_id(role_type_name, role_eid);
-
-        let mut attrs = HashMap::new();
-        let name_expr = RestrictedExpression::new_string(role.role_name);
-        attrs.insert(ENTITY_ATTR_NAME.to_string(), name_expr);
-
-        let parents = HashSet::new();
-        let role_entity = Entity::new(role_e_uid.clone(), attrs, parents)?;
-        entities.insert(role_entity);
-        user_parent_uids.insert(role_e_uid);
+    // 用户的父实体只有其拥有的角色, 继承的祖先角色通过角色实体的父关系体现
+    let role_type_name = entity_type_name(schema, ENTITY_TYPE_ROLE)?;
+    for role in &all_roles {
+        let role_eid = EntityId::from_str(role.role_uuid.as_str())?;
+        user_parent_uids.insert(EntityUid::from_type_name_and_id(role_type_name.clone(), role_eid));
     }
+    entities.extend(get_role_hierarchy_entities(db, tenant_id, all_roles, schema).await?);
 
     let user_eid = EntityId::from_str(user_uuid.as_str())?;
-    let user_type_name = EntityTypeName::from_str(ENTITY_TYPE_USER)?;
+    let user_type_name = entity_type_name(schema, ENTITY_TYPE_USER)?;
     let user_e_uid = EntityUid::from_type_name_and_id(user_type_name, user_eid);
     let mut attrs = HashMap::new();
 
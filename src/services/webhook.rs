@@ -0,0 +1,705 @@
+// Webhook: 领域事件按订阅写入投递记录, 由后台任务签名后发送, 失败后按指数退避重试
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use chrono::{DateTime, Duration, Utc};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::redirect::Policy as RedirectPolicy;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+use tracing::{error, info, warn};
+
+use crate::config::state::AppState;
+use crate::config::webhook::WebhookConfig;
+use crate::entity::{users, webhook_deliveries, webhooks};
+use crate::errors::app_error::AppError;
+use crate::schemas::auth::CurrentUser;
+use crate::schemas::cedar_policy::CedarContext;
+use crate::schemas::event::{EventEnvelope, DOMAIN_EVENT_TYPES};
+use crate::schemas::webhook::{
+    CreateWebhookDto, DeliveryQueryParams, UpdateWebhookDto, WebhookDeliveryResponse,
+    WebhookPayload, WebhookQueryParams, WebhookResponse,
+};
+use crate::utils::cedar_utils::{AuthAction, ResourceType};
+use crate::utils::crypto::{decrypt_data, encrypt_data, generate_secret, hmac_sha256_hex};
+use crate::utils::net::{ensure_public_url, PublicOnlyResolver};
+use crate::{bad_request, not_found};
+
+// 投递记录的状态
+pub const DELIVERY_PENDING: &str = "pending";
+pub const DELIVERY_SENDING: &str = "sending";
+pub const DELIVERY_SUCCEEDED: &str = "succeeded";
+pub const DELIVERY_DEAD: &str = "dead";
+
+// 签名密钥加密保存, 投递时解密
+const WEBHOOK_SECRET_PURPOSE: &str = "webhook-secret";
+
+// 请求头: 订阅方用 HMAC-SHA256(secret, "{timestamp}.{body}") 校验签名
+const HEADER_EVENT: &str = "X-Webhook-Event";
+const HEADER_DELIVERY: &str = "X-Webhook-Delivery";
+const HEADER_TIMESTAMP: &str = "X-Webhook-Timestamp";
+const HEADER_SIGNATURE: &str = "X-Webhook-Signature";
+
+// 保存到投递记录中的错误信息长度, 不保存订阅方的响应内容
+const MAX_ERROR_LENGTH: usize = 1000;
+
+#[derive(Clone)]
+pub struct WebhookService {
+    app_state: AppState,
+}
+
+impl WebhookService {
+    pub fn new(app_state: AppState) -> Self {
+        Self { app_state }
+    }
+
+    pub async fn list_webhooks(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        params: WebhookQueryParams,
+    ) -> Result<(Vec<WebhookResponse>, u64), AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::ViewWebhook,
+                ResourceType::Webhook(None),
+            )
+            .await?;
+
+        let paginator = webhooks::Entity::find()
+            .filter(webhooks::Column::TenantId.eq(current_user.tenant_id))
+            .order_by_desc(webhooks::Column::WebhookId)
+            .paginate(&self.app_state.db, params.page_size);
+        let total = paginator.num_items().await?;
+        let page_index = if params.page > 0 { params.page - 1 } else { 0 };
+        let results = paginator
+            .fetch_page(page_index)
+            .await?
+            .into_iter()
+            .map(WebhookResponse::from)
+            .collect();
+
+        Ok((results, total))
+    }
+
+    pub async fn get_webhook(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        webhook_uuid: String,
+    ) -> Result<WebhookResponse, AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::ViewWebhook,
+                ResourceType::Webhook(Some(webhook_uuid.clone())),
+            )
+            .await?;
+
+        Ok(WebhookResponse::from(self.find_webhook(&current_user, &webhook_uuid).await?))
+    }
+
+    pub async fn create_webhook(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        dto: CreateWebhookDto,
+    ) -> Result<WebhookResponse, AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::CreateWebhook,
+                ResourceType::Webhook(None),
+            )
+            .await?;
+        validate_events(&dto.events)?;
+        self.validate_url(&dto.url).await?;
+
+        let created_by = users::Entity::find()
+            .select_only()
+            .column(users::Column::UserId)
+            .filter(users::Column::UserUuid.eq(&current_user.uuid))
+            .into_tuple::<i32>()
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("User {} not found", current_user.uuid))?;
+
+        let secret = dto.secret.unwrap_or_else(generate_secret);
+        let now = Utc::now();
+        let webhook = webhooks::ActiveModel {
+            webhook_uuid: Set(uuid::Uuid::new_v4().to_string()),
+            tenant_id: Set(current_user.tenant_id),
+            name: Set(dto.name),
+            url: Set(dto.url),
+            secret: Set(encrypt_data(WEBHOOK_SECRET_PURPOSE, &secret)?),
+            events: Set(serde_json::to_value(dto.events)?),
+            is_active: Set(dto.is_active),
+            created_by: Set(created_by),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&self.app_state.db)
+        .await?;
+
+        let mut response = WebhookResponse::from(webhook);
+        response.secret = Some(secret);
+        Ok(response)
+    }
+
+    pub async fn update_webhook(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        webhook_uuid: String,
+        dto: UpdateWebhookDto,
+    ) -> Result<WebhookResponse, AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::UpdateWebhook,
+                ResourceType::Webhook(Some(webhook_uuid.clone())),
+            )
+            .await?;
+
+        let mut webhook: webhooks::ActiveModel = self.find_webhook(&current_user, &webhook_uuid).await?.into();
+        if let Some(name) = dto.name { webhook.name = Set(name); }
+        if let Some(url) = dto.url {
+            self.validate_url(&url).await?;
+            webhook.url = Set(url);
+        }
+        if let Some(secret) = dto.secret { webhook.secret = Set(encrypt_data(WEBHOOK_SECRET_PURPOSE, &secret)?); }
+        if let Some(is_active) = dto.is_active { webhook.is_active = Set(is_active); }
+        if let Some(events) = dto.events {
+            validate_events(&events)?;
+            webhook.events = Set(serde_json::to_value(events)?);
+        }
+        webhook.updated_at = Set(Utc::now());
+
+        let webhook = webhook.update(&self.app_state.db).await?;
+        Ok(WebhookResponse::from(webhook))
+    }
+
+    pub async fn delete_webhook(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        webhook_uuid: String,
+    ) -> Result<(), AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::DeleteWebhook,
+                ResourceType::Webhook(Some(webhook_uuid.clone())),
+            )
+            .await?;
+
+        let result = webhooks::Entity::delete_many()
+            .filter(webhooks::Column::WebhookUuid.eq(&webhook_uuid))
+            .filter(webhooks::Column::TenantId.eq(current_user.tenant_id))
+            .exec(&self.app_state.db)
+            .await?;
+        if result.rows_affected == 0 {
+            return Err(not_found!("Webhook not found"));
+        }
+        Ok(())
+    }
+
+    pub async fn list_deliveries(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        webhook_uuid: String,
+        params: DeliveryQueryParams,
+    ) -> Result<(Vec<WebhookDeliveryResponse>, u64), AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::ViewWebhook,
+                ResourceType::Webhook(Some(webhook_uuid.clone())),
+            )
+            .await?;
+
+        let webhook = self.find_webhook(&current_user, &webhook_uuid).await?;
+        let mut query = webhook_deliveries::Entity::find()
+            .filter(webhook_deliveries::Column::WebhookId.eq(webhook.webhook_id));
+        if let Some(status) = &params.status {
+            query = query.filter(webhook_deliveries::Column::Status.eq(status));
+        }
+
+        let paginator = query
+            .order_by_desc(webhook_deliveries::Column::Id)
+            .paginate(&self.app_state.db, params.page_size);
+        let total = paginator.num_items().await?;
+        let page_index = if params.page > 0 { params.page - 1 } else { 0 };
+        let results = paginator
+            .fetch_page(page_index)
+            .await?
+            .into_iter()
+            .map(WebhookDeliveryResponse::from)
+            .collect();
+
+        Ok((results, total))
+    }
+
+    /// 重新投递: 以原内容创建一条新的投递记录, 事件 ID 不变, 订阅方可据此去重
+    pub async fn redeliver(
+        &self,
+        current_user: CurrentUser,
+        context: CedarContext,
+        webhook_uuid: String,
+        delivery_uuid: String,
+    ) -> Result<WebhookDeliveryResponse, AppError> {
+        self.app_state
+            .auth_service
+            .check_permission(
+                &current_user.uuid,
+                context,
+                AuthAction::UpdateWebhook,
+                ResourceType::Webhook(Some(webhook_uuid.clone())),
+            )
+            .await?;
+
+        let webhook = self.find_webhook(&current_user, &webhook_uuid).await?;
+        let delivery = webhook_deliveries::Entity::find()
+            .filter(webhook_deliveries::Column::DeliveryUuid.eq(&delivery_uuid))
+            .filter(webhook_deliveries::Column::WebhookId.eq(webhook.webhook_id))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("Delivery not found"))?;
+
+        let delivery = redelivery_of(delivery, Utc::now())
+            .insert(&self.app_state.db)
+            .await?;
+
+        Ok(WebhookDeliveryResponse::from(delivery))
+    }
+
+    // 目标地址不能是内网、回环或链路本地地址
+    async fn validate_url(&self, url: &str) -> Result<(), AppError> {
+        if self.app_state.config.webhook.allow_private_targets {
+            return Ok(());
+        }
+        ensure_public_url(url)
+            .await
+            .map_err(|e| bad_request!("Webhook url is not allowed: {}", e))
+    }
+
+    async fn find_webhook(&self, current_user: &CurrentUser, webhook_uuid: &str) -> Result<webhooks::Model, AppError> {
+        webhooks::Entity::find()
+            .filter(webhooks::Column::WebhookUuid.eq(webhook_uuid))
+            .filter(webhooks::Column::TenantId.eq(current_user.tenant_id))
+            .one(&self.app_state.db)
+            .await?
+            .ok_or(not_found!("Webhook not found"))
+    }
+}
+
+// 以原投递的事件和内容创建新的待投递记录
+fn redelivery_of(delivery: webhook_deliveries::Model, now: DateTime<Utc>) -> webhook_deliveries::ActiveModel {
+    webhook_deliveries::ActiveModel {
+        delivery_uuid: Set(uuid::Uuid::new_v4().to_string()),
+        webhook_id: Set(delivery.webhook_id),
+        event_id: Set(delivery.event_id),
+        event_type: Set(delivery.event_type),
+        payload: Set(delivery.payload),
+        status: Set(DELIVERY_PENDING.to_string()),
+        attempts: Set(0),
+        next_attempt_at: Set(now),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+}
+
+fn validate_events(events: &[String]) -> Result<(), AppError> {
+    match events.iter().find(|e| !DOMAIN_EVENT_TYPES.contains(&e.as_str())) {
+        Some(event) => Err(bad_request!("Unknown event type: {}", event)),
+        None => Ok(()),
+    }
+}
+
+/// 为事件所属租户中订阅了该事件的 Webhook 创建投递记录
+pub async fn enqueue_webhook_deliveries(app_state: &AppState, envelope: &EventEnvelope) -> Result<(), AppError> {
+    let event_type = envelope.event.name();
+    let subscribers: Vec<webhooks::Model> = webhooks::Entity::find()
+        .filter(webhooks::Column::TenantId.eq(envelope.tenant_id))
+        .filter(webhooks::Column::IsActive.eq(true))
+        .all(&app_state.db)
+        .await?
+        .into_iter()
+        .filter(|webhook| {
+            let events: Vec<String> = serde_json::from_value(webhook.events.clone()).unwrap_or_default();
+            events.is_empty() || events.iter().any(|e| e == event_type)
+        })
+        .collect();
+    if subscribers.is_empty() {
+        return Ok(());
+    }
+
+    let payload = serde_json::to_string(&WebhookPayload::from(envelope))?;
+    let now = Utc::now();
+    let deliveries = subscribers.into_iter().map(|webhook| webhook_deliveries::ActiveModel {
+        delivery_uuid: Set(uuid::Uuid::new_v4().to_string()),
+        webhook_id: Set(webhook.webhook_id),
+        event_id: Set(envelope.id.clone()),
+        event_type: Set(event_type.to_string()),
+        payload: Set(payload.clone()),
+        status: Set(DELIVERY_PENDING.to_string()),
+        attempts: Set(0),
+        next_attempt_at: Set(now),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    });
+    webhook_deliveries::Entity::insert_many(deliveries)
+        .exec(&app_state.db)
+        .await?;
+    Ok(())
+}
+
+// 后台任务：投递到期的 Webhook
+pub async fn run_webhook_worker(state: AppState) {
+    let config = state.config.webhook.clone();
+    let mut builder = reqwest::Client::builder()
+        .timeout(StdDuration::from_secs(config.timeout_seconds))
+        .redirect(RedirectPolicy::none());
+    // 连接时只使用公网地址, 防止 DNS 重绑定
+    if !config.allow_private_targets {
+        builder = builder.dns_resolver(Arc::new(PublicOnlyResolver));
+    }
+    let client = match builder.build() {
+        Ok(client) => client,
+        Err(e) => {
+            error!("创建 Webhook HTTP 客户端失败: {}", e);
+            return;
+        }
+    };
+
+    let poll_interval = StdDuration::from_secs(config.poll_interval_seconds);
+    loop {
+        match process_deliveries(&state, &client, &config).await {
+            // 取满一批说明可能还有积压, 立即继续
+            Ok(count) if count as u64 >= config.batch_size => continue,
+            Ok(_) => {}
+            Err(e) => error!("处理 Webhook 投递失败: {:?}", e),
+        }
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+/// 取出一批到期的投递记录并发送, 返回取出的数量
+async fn process_deliveries(
+    state: &AppState,
+    client: &reqwest::Client,
+    config: &WebhookConfig,
+) -> Result<usize, AppError> {
+    reclaim_expired_leases(state, config).await?;
+    let due = webhook_deliveries::Entity::find()
+        .find_also_related(webhooks::Entity)
+        .filter(webhook_deliveries::Column::Status.eq(DELIVERY_PENDING))
+        .filter(webhook_deliveries::Column::NextAttemptAt.lte(Utc::now()))
+        .order_by_asc(webhook_deliveries::Column::Id)
+        .limit(config.batch_size)
+        .all(&state.db)
+        .await?;
+    let count = due.len();
+
+    for (delivery, webhook) in due {
+        let Some(webhook) = webhook else {
+            continue;
+        };
+        // 以状态作为条件抢占, 多实例部署时同一条记录只会被发送一次
+        let claimed = webhook_deliveries::Entity::update_many()
+            .col_expr(webhook_deliveries::Column::Status, Expr::value(DELIVERY_SENDING))
+            .col_expr(webhook_deliveries::Column::ClaimedAt, Expr::value(Utc::now()))
+            .filter(webhook_deliveries::Column::Id.eq(delivery.id))
+            .filter(webhook_deliveries::Column::Status.eq(DELIVERY_PENDING))
+            .exec(&state.db)
+            .await?;
+        if claimed.rows_affected == 0 {
+            continue;
+        }
+
+        // IP 字面量不经过 DNS 解析器, 发送前再检查一次目标地址
+        let target_check = match config.allow_private_targets {
+            true => Ok(()),
+            false => ensure_public_url(&webhook.url).await,
+        };
+        let (response_status, result) = match target_check {
+            Ok(()) => deliver(client, &webhook, &delivery).await,
+            Err(e) => (None, Err(e)),
+        };
+        let attempts = delivery.attempts + 1;
+        let delivery_uuid = delivery.delivery_uuid.clone();
+        let mut delivery: webhook_deliveries::ActiveModel = delivery.into();
+        delivery.attempts = Set(attempts);
+        delivery.response_status = Set(response_status);
+        delivery.claimed_at = Set(None);
+        delivery.updated_at = Set(Utc::now());
+        match result {
+            Ok(()) => {
+                delivery.status = Set(DELIVERY_SUCCEEDED.to_string());
+                delivery.delivered_at = Set(Some(Utc::now()));
+                delivery.last_error = Set(None);
+            }
+            Err(e) if attempts >= config.max_attempts => {
+                warn!(delivery_uuid = %delivery_uuid, "Webhook 重试 {} 次后仍投递失败, 不再重试: {}", attempts, e);
+                delivery.status = Set(DELIVERY_DEAD.to_string());
+                delivery.last_error = Set(Some(e));
+            }
+            Err(e) => {
+                warn!(delivery_uuid = %delivery_uuid, "Webhook 第 {} 次投递失败: {}", attempts, e);
+                delivery.status = Set(DELIVERY_PENDING.to_string());
+                delivery.last_error = Set(Some(e));
+                delivery.next_attempt_at = Set(Utc::now() + retry_delay(config, attempts));
+            }
+        }
+        delivery.update(&state.db).await?;
+    }
+    Ok(count)
+}
+
+// 发送中的记录只有租约过期(发送的实例已崩溃或卡住)后才重新排队, 不影响其他实例正在发送的记录
+async fn reclaim_expired_leases(state: &AppState, config: &WebhookConfig) -> Result<(), AppError> {
+    let expired_before = Utc::now() - Duration::seconds(config.lease_seconds);
+    let reclaimed = webhook_deliveries::Entity::update_many()
+        .col_expr(webhook_deliveries::Column::Status, Expr::value(DELIVERY_PENDING))
+        .col_expr(webhook_deliveries::Column::ClaimedAt, Expr::value(Option::<DateTime<Utc>>::None))
+        .filter(webhook_deliveries::Column::Status.eq(DELIVERY_SENDING))
+        .filter(
+            Condition::any()
+                .add(webhook_deliveries::Column::ClaimedAt.is_null())
+                .add(webhook_deliveries::Column::ClaimedAt.lte(expired_before)),
+        )
+        .exec(&state.db)
+        .await?;
+    if reclaimed.rows_affected > 0 {
+        warn!("{} 条 Webhook 投递的发送租约已过期, 重新排队", reclaimed.rows_affected);
+    }
+    Ok(())
+}
+
+/// 第 N 次失败后的重试间隔
+fn retry_delay(config: &WebhookConfig, attempts: i32) -> Duration {
+    let exponent = (attempts - 1).clamp(0, 30) as u32;
+    let seconds = config
+        .retry_base_seconds
+        .saturating_mul(2i64.saturating_pow(exponent))
+        .min(config.retry_max_seconds);
+    Duration::seconds(seconds)
+}
+
+/// 发送一次, 返回响应状态码和结果, 只有 2xx 视为成功
+async fn deliver(
+    client: &reqwest::Client,
+    webhook: &webhooks::Model,
+    delivery: &webhook_deliveries::Model,
+) -> (Option<i32>, Result<(), String>) {
+    let timestamp = Utc::now().timestamp().to_string();
+    let signature = match decrypt_data(WEBHOOK_SECRET_PURPOSE, &webhook.secret)
+        .and_then(|secret| sign_payload(&secret, &timestamp, &delivery.payload))
+    {
+        Ok(signature) => signature,
+        Err(e) => return (None, Err(e.to_string())),
+    };
+
+    let response = client
+        .post(&webhook.url)
+        .header(CONTENT_TYPE, "application/json")
+        .header(HEADER_EVENT, &delivery.event_type)
+        .header(HEADER_DELIVERY, &delivery.delivery_uuid)
+        .header(HEADER_TIMESTAMP, &timestamp)
+        .header(HEADER_SIGNATURE, signature)
+        .body(delivery.payload.clone())
+        .send()
+        .await;
+
+    match response {
+        Ok(response) => {
+            let status = response.status();
+            if status.is_success() {
+                info!(delivery_uuid = %delivery.delivery_uuid, "Webhook 投递成功");
+                return (Some(status.as_u16() as i32), Ok(()));
+            }
+            // 不读取和保存响应内容, 避免通过投递记录读取目标地址的响应
+            (Some(status.as_u16() as i32), Err(format!("HTTP {}", status)))
+        }
+        Err(e) => (None, Err(truncate(&e.to_string()))),
+    }
+}
+
+/// 签名请求头的值: `sha256=` + HMAC-SHA256(secret, "{timestamp}.{body}") 的十六进制
+fn sign_payload(secret: &str, timestamp: &str, payload: &str) -> Result<String, AppError> {
+    let signature = hmac_sha256_hex(secret, &format!("{}.{}", timestamp, payload))?;
+    Ok(format!("sha256={}", signature))
+}
+
+fn truncate(text: &str) -> String {
+    text.chars().take(MAX_ERROR_LENGTH).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+    use crate::utils::crypto::init_data_key;
+
+    const SECRET: &str = "whsec_test";
+
+    fn webhook(url: String) -> webhooks::Model {
+        init_data_key(&"11".repeat(32)).unwrap();
+        let now = Utc::now();
+        webhooks::Model {
+            webhook_id: 1,
+            webhook_uuid: "webhook-uuid".to_string(),
+            tenant_id: 1,
+            name: "test".to_string(),
+            url,
+            secret: encrypt_data(WEBHOOK_SECRET_PURPOSE, SECRET).unwrap(),
+            events: serde_json::json!(["user.created"]),
+            is_active: true,
+            created_by: 1,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn delivery() -> webhook_deliveries::Model {
+        let now = Utc::now();
+        webhook_deliveries::Model {
+            id: 7,
+            delivery_uuid: "delivery-uuid".to_string(),
+            webhook_id: 1,
+            event_id: "event-uuid".to_string(),
+            event_type: "user.created".to_string(),
+            payload: r#"{"id":1}"#.to_string(),
+            status: DELIVERY_DEAD.to_string(),
+            attempts: 8,
+            response_status: Some(500),
+            last_error: Some("HTTP 500 Internal Server Error".to_string()),
+            next_attempt_at: now,
+            claimed_at: None,
+            delivered_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    // 本地的订阅方替身: 校验签名后按路径返回不同状态码
+    async fn stand_in(status: StatusCode, headers: HeaderMap, body: String) -> (StatusCode, &'static str) {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).unwrap_or_default().to_string();
+        let expected = sign_payload(SECRET, &header(HEADER_TIMESTAMP), &body).unwrap();
+        if header(HEADER_SIGNATURE) != expected
+            || header(HEADER_EVENT) != "user.created"
+            || header(HEADER_DELIVERY) != "delivery-uuid"
+        {
+            return (StatusCode::UNAUTHORIZED, "bad signature");
+        }
+        (status, "receiver internal details")
+    }
+
+    async fn spawn_stand_in() -> String {
+        let app = Router::new()
+            .route("/ok", post(|h: HeaderMap, b: String| stand_in(StatusCode::OK, h, b)))
+            .route("/accepted", post(|h: HeaderMap, b: String| stand_in(StatusCode::NO_CONTENT, h, b)))
+            .route("/redirect", post(|h: HeaderMap, b: String| stand_in(StatusCode::FOUND, h, b)))
+            .route("/error", post(|h: HeaderMap, b: String| stand_in(StatusCode::INTERNAL_SERVER_ERROR, h, b)));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn signature_is_prefixed_hmac_of_timestamp_and_body() {
+        let signature = sign_payload(SECRET, "1700000000", r#"{"id":1}"#).unwrap();
+        assert_eq!(
+            signature,
+            "sha256=2f441ba4b3b2d50d28a9ab9d9fd8880376ecd1eb5d0435401553f5d8d0a5dcf8"
+        );
+    }
+
+    #[test]
+    fn retry_delay_doubles_up_to_max() {
+        let config = WebhookConfig {
+            retry_base_seconds: 30,
+            retry_max_seconds: 600,
+            ..Default::default()
+        };
+        assert_eq!(retry_delay(&config, 1), Duration::seconds(30));
+        assert_eq!(retry_delay(&config, 2), Duration::seconds(60));
+        assert_eq!(retry_delay(&config, 4), Duration::seconds(240));
+        assert_eq!(retry_delay(&config, 6), Duration::seconds(600));
+        assert_eq!(retry_delay(&config, 100), Duration::seconds(600));
+    }
+
+    #[tokio::test]
+    async fn only_2xx_counts_as_delivered() {
+        let base = spawn_stand_in().await;
+        let client = reqwest::Client::builder()
+            .redirect(RedirectPolicy::none())
+            .build()
+            .unwrap();
+        let delivery = delivery();
+
+        let (status, result) = deliver(&client, &webhook(format!("{}/ok", base)), &delivery).await;
+        assert_eq!(status, Some(200));
+        assert!(result.is_ok());
+
+        let (status, result) = deliver(&client, &webhook(format!("{}/accepted", base)), &delivery).await;
+        assert_eq!(status, Some(204));
+        assert!(result.is_ok());
+
+        for (path, code) in [("redirect", 302), ("error", 500)] {
+            let (status, result) = deliver(&client, &webhook(format!("{}/{}", base, path)), &delivery).await;
+            assert_eq!(status, Some(code));
+            let error = result.unwrap_err();
+            assert!(error.starts_with(&format!("HTTP {}", code)));
+            // 不保存订阅方的响应内容
+            assert!(!error.contains("receiver internal details"));
+        }
+    }
+
+    #[tokio::test]
+    async fn wrong_secret_is_rejected_by_receiver() {
+        let base = spawn_stand_in().await;
+        let client = reqwest::Client::new();
+        let mut webhook = webhook(format!("{}/ok", base));
+        webhook.secret = encrypt_data(WEBHOOK_SECRET_PURPOSE, "another_secret").unwrap();
+        let (status, result) = deliver(&client, &webhook, &delivery()).await;
+        assert_eq!(status, Some(401));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn redelivery_copies_event_as_new_pending_delivery() {
+        let original = delivery();
+        let now = Utc::now();
+        let redelivery = redelivery_of(original.clone(), now);
+        assert_ne!(redelivery.delivery_uuid.clone().unwrap(), original.delivery_uuid);
+        assert_eq!(redelivery.webhook_id.clone().unwrap(), original.webhook_id);
+        assert_eq!(redelivery.event_id.clone().unwrap(), original.event_id);
+        assert_eq!(redelivery.event_type.clone().unwrap(), original.event_type);
+        assert_eq!(redelivery.payload.clone().unwrap(), original.payload);
+        assert_eq!(redelivery.status.clone().unwrap(), DELIVERY_PENDING);
+        assert_eq!(redelivery.attempts.clone().unwrap(), 0);
+        assert_eq!(redelivery.next_attempt_at.clone().unwrap(), now);
+        assert!(redelivery.id.is_not_set());
+        assert!(redelivery.last_error.is_not_set());
+    }
+}
@@ -1,7 +1,7 @@
 use crate::errors::app_error::AppError;
 use crate::schemas::auth::CurrentUser;
 use crate::schemas::cedar_policy::CedarContext;
-use cedar_policy::{Context, Entities, EntityUid, Request};
+use cedar_policy::{Context, Entities, EntityTypeName, EntityUid, Request, Schema};
 use serde_json::{json};
 use std::str::FromStr;
 use tracing::log::debug;
@@ -25,11 +25,18 @@ pub const  ENTITY_TYPE_DEPARTMENT: &str = "Department";
 
 pub const  ENTITY_TYPE_POLICY: &str = "Policy";
 
+pub const  ENTITY_TYPE_OAUTH_CLIENT: &str = "OAuthClient";
+
 pub const  ENTITY_TYPE_ROBOT: &str = "Robot";
 pub const  ENTITY_TYPE_ROBOT_ACCOUNT: &str = "RobotAccount";
 
+pub const  ENTITY_TYPE_SYSTEM: &str = "System";
+
+pub const  ENTITY_TYPE_TENANT: &str = "Tenant";
+
 pub const  ENTITY_ATTR_NAME: &str = "name";
 pub const ENTITY_ATTR_OWNERS: &str = "owners";
+pub const ENTITY_ATTR_CODE: &str = "code";
 
 
 
@@ -58,11 +65,39 @@ pub enum AuthAction {
     DeleteRole,
     AssignRole,
     RevokeRole,
+    ApproveRoleRequest,
     ViewAuditLog,
     ViewPolicy,
     CreatePolicy,
     UpdatePolicy,
     DeletePolicy,
+    SendMessage,
+    ViewWebhook,
+    CreateWebhook,
+    UpdateWebhook,
+    DeleteWebhook,
+    ViewOAuthClient,
+    CreateOAuthClient,
+    UpdateOAuthClient,
+    DeleteOAuthClient,
+    OAuthLogin,
+    ViewRobotAccount,
+    CreateRobotAccount,
+    UpdateRobotAccount,
+    DeleteRobotAccount,
+    ViewSystem,
+    CreateSystem,
+    UpdateSystem,
+    DeleteSystem,
+    ViewSystemConfig,
+    UpdateSystemConfig,
+    ViewTenant,
+    CreateTenant,
+    UpdateTenant,
+    DeleteTenant,
+    ViewAccessReview,
+    CreateAccessReview,
+    UpdateAccessReview,
 }
 
 impl AuthAction {
@@ -90,11 +125,39 @@ impl AuthAction {
             AuthAction::DeleteRole => r#"Action::"DeleteRole""#,
             AuthAction::AssignRole => r#"Action::"AssignRole""#,
             AuthAction::RevokeRole => r#"Action::"RevokeRole""#,
+            AuthAction::ApproveRoleRequest => r#"Action::"ApproveRoleRequest""#,
             AuthAction::ViewAuditLog => r#"Action::"ViewAuditLog""#,
             AuthAction::ViewPolicy => r#"Action::"ViewPolicies""#,
             AuthAction::CreatePolicy => r#"Action::"CreatePolicies""#,
             AuthAction::UpdatePolicy => r#"Action::"UpdatePolicies""#,
             AuthAction::DeletePolicy => r#"Action::"DeletePolicies""#,
+            AuthAction::SendMessage => r#"Action::"SendMessage""#,
+            AuthAction::ViewWebhook => r#"Action::"ViewWebhook""#,
+            AuthAction::CreateWebhook => r#"Action::"CreateWebhook""#,
+            AuthAction::UpdateWebhook => r#"Action::"UpdateWebhook""#,
+            AuthAction::DeleteWebhook => r#"Action::"DeleteWebhook""#,
+            AuthAction::ViewOAuthClient => r#"Action::"ViewOAuthClient""#,
+            AuthAction::CreateOAuthClient => r#"Action::"CreateOAuthClient""#,
+            AuthAction::UpdateOAuthClient => r#"Action::"UpdateOAuthClient""#,
+            AuthAction::DeleteOAuthClient => r#"Action::"DeleteOAuthClient""#,
+            AuthAction::OAuthLogin => r#"Action::"OAuthLogin""#,
+            AuthAction::ViewRobotAccount => r#"Action::"ViewRobotAccount""#,
+            AuthAction::CreateRobotAccount => r#"Action::"CreateRobotAccount""#,
+            AuthAction::UpdateRobotAccount => r#"Action::"UpdateRobotAccount""#,
+            AuthAction::DeleteRobotAccount => r#"Action::"DeleteRobotAccount""#,
+            AuthAction::ViewSystem => r#"Action::"ViewSystem""#,
+            AuthAction::CreateSystem => r#"Action::"CreateSystem""#,
+            AuthAction::UpdateSystem => r#"Action::"UpdateSystem""#,
+            AuthAction::DeleteSystem => r#"Action::"DeleteSystem""#,
+            AuthAction::ViewSystemConfig => r#"Action::"ViewSystemConfig""#,
+            AuthAction::UpdateSystemConfig => r#"Action::"UpdateSystemConfig""#,
+            AuthAction::ViewTenant => r#"Action::"ViewTenant""#,
+            AuthAction::CreateTenant => r#"Action::"CreateTenant""#,
+            AuthAction::UpdateTenant => r#"Action::"UpdateTenant""#,
+            AuthAction::DeleteTenant => r#"Action::"DeleteTenant""#,
+            AuthAction::ViewAccessReview => r#"Action::"ViewAccessReview""#,
+            AuthAction::CreateAccessReview => r#"Action::"CreateAccessReview""#,
+            AuthAction::UpdateAccessReview => r#"Action::"UpdateAccessReview""#,
         }
     }
 }
@@ -107,14 +170,20 @@ pub enum ResourceType {
     Group(Option<String>),     // Group::* 或 Group::{id}
     Role(Option<String>),      // Role::* 或 Role::{id}
     Policy(Option<String>), // CedarPolicy::*
+    Webhook(Option<String>),
+    OAuthClient(Option<String>),
+    System(Option<String>),
     Robot(Option<String>),
     RobotAccount(Option<String>),
+    Tenant(Option<String>),
+    AccessReview(Option<String>),
     UI(Option<String>),
     AuditLog,               // AuditLog::*
 }
 
 impl ResourceType {
-    pub fn as_entity_uid(&self) -> Result<EntityUid, AppError> {
+    /// namespace 为租户的 Cedar 命名空间, 默认租户为 None
+    pub fn as_entity_uid(&self, namespace: Option<&str>) -> Result<EntityUid, AppError> {
         let uid_str = match self {
             ResourceType::User(Some(user_id)) => format!(r#"User::"{}""#, user_id),
             ResourceType::User(None) => r#"User::"*""#.to_string(),
@@ -126,6 +195,12 @@ impl ResourceType {
             ResourceType::Role(None) => r#"Role::"*""#.to_string(),
             ResourceType::Policy(Some(id)) => format!(r#"Policy::"{}""#, id),
             ResourceType::Policy(None) => r#"Policy::"*""#.to_string(),
+            ResourceType::Webhook(Some(id)) => format!(r#"Webhook::"{}""#, id),
+            ResourceType::Webhook(None) => r#"Webhook::"*""#.to_string(),
+            ResourceType::OAuthClient(Some(id)) => format!(r#"OAuthClient::"{}""#, id),
+            ResourceType::OAuthClient(None) => r#"OAuthClient::"*""#.to_string(),
+            ResourceType::System(Some(id)) => format!(r#"System::"{}""#, id),
+            ResourceType::System(None) => r#"System::"*""#.to_string(),
             ResourceType::AuditLog => r#"AuditLog::"*""#.to_string(),
             ResourceType::UI(Some(uid)) => format!(r#"UI::"{}""#, uid),
             &ResourceType::UI(None) => r#"UI::"*""#.to_string(),
@@ -133,17 +208,23 @@ impl ResourceType {
             //
             ResourceType::Robot(Some(id)) => format!(r#"Robot::"{}""#, id),
             ResourceType::Robot(None) => r#"Robot::"*""#.to_string(),
-            ResourceType::RobotAccount(Some(id)) => format!(r#"Robot::"{}""#, id),
-            ResourceType::RobotAccount(None) => r#"Robot::"*""#.to_string(),
+            ResourceType::RobotAccount(Some(id)) => format!(r#"RobotAccount::"{}""#, id),
+            ResourceType::RobotAccount(None) => r#"RobotAccount::"*""#.to_string(),
+            ResourceType::Tenant(Some(id)) => format!(r#"Tenant::"{}""#, id),
+            ResourceType::Tenant(None) => r#"Tenant::"*""#.to_string(),
+            ResourceType::AccessReview(Some(id)) => format!(r#"AccessReview::"{}""#, id),
+            ResourceType::AccessReview(None) => r#"AccessReview::"*""#.to_string(),
         };
 
-        EntityUid::from_str(&uid_str).map_err(|e| forbidden!(format!("Wrong entity UID: {}", e)))
+        EntityUid::from_str(&qualify(namespace, &uid_str)).map_err(|e| forbidden!(format!("Wrong entity UID: {}", e)))
     }
 }
 
 /// 授权检查构建器
 pub struct AuthorizationBuilder {
     user_id: UserUUID,
+    principal_type: &'static str,
+    namespace: Option<String>,
     context: CedarContext,
     action: AuthAction,
     resource: ResourceType,
@@ -154,6 +235,8 @@ impl AuthorizationBuilder {
     pub fn new(user_id: UserUUID, context: CedarContext) -> Self {
         Self {
             user_id,
+            principal_type: ENTITY_TYPE_USER,
+            namespace: None,
             context,
             action: AuthAction::ViewUser,
             resource: ResourceType::User(None),
@@ -161,6 +244,18 @@ impl AuthorizationBuilder {
         }
     }
 
+    /// 主体类型, 默认为 User
+    pub fn principal_type(mut self, principal_type: &'static str) -> Self {
+        self.principal_type = principal_type;
+        self
+    }
+
+    /// 主体所属租户的命名空间, 主体、操作和资源都在该命名空间下
+    pub fn namespace(mut self, namespace: Option<String>) -> Self {
+        self.namespace = namespace;
+        self
+    }
+
     pub fn action(mut self, action: AuthAction) -> Self {
         self.action = action;
         self
@@ -177,10 +272,11 @@ impl AuthorizationBuilder {
     }
 
     pub fn build(self) -> Result<(Request, Entities), AppError> {
-        let principal_str = format!(r#"User::"{}""#, self.user_id);
-        let principal = EntityUid::from_str(principal_str.as_str())?;
-        let action = EntityUid::from_str(self.action.as_str())?;
-        let resource = self.resource.as_entity_uid()?;
+        let namespace = self.namespace.as_deref();
+        let principal_str = format!(r#"{}::"{}""#, self.principal_type, self.user_id);
+        let principal = EntityUid::from_str(&qualify(namespace, &principal_str))?;
+        let action = EntityUid::from_str(&qualify(namespace, self.action.as_str()))?;
+        let resource = self.resource.as_entity_uid(namespace)?;
         let context = Context::from_json_value(json!(self.context), None)?;
 
         let request = Request::new(principal, action, resource, context, None)?;
@@ -192,6 +288,48 @@ impl AuthorizationBuilder {
 
 
 
+/// 根据缓存的主体实体判断主体是用户还是服务账号, 以及主体所属租户的命名空间
+pub fn principal_type_of(entities: &Entities, principal_id: &str) -> Result<(&'static str, Option<String>), AppError> {
+    entities
+        .iter()
+        .map(|entity| entity.uid())
+        .filter(|uid| uid.id().unescaped() == principal_id)
+        .find_map(|uid| {
+            let type_name = uid.type_name();
+            let principal_type = match type_name.basename() {
+                ENTITY_TYPE_ROBOT_ACCOUNT => ENTITY_TYPE_ROBOT_ACCOUNT,
+                ENTITY_TYPE_USER => ENTITY_TYPE_USER,
+                _ => return None,
+            };
+            let namespace = type_name.namespace();
+            Some((principal_type, (!namespace.is_empty()).then_some(namespace)))
+        })
+        .ok_or_else(|| forbidden!(format!("Principal {} not found in entities", principal_id)))
+}
+
+// 给实体 UID 或类型名加上命名空间前缀
+fn qualify(namespace: Option<&str>, name: &str) -> String {
+    match namespace {
+        Some(namespace) => format!("{}::{}", namespace, name),
+        None => name.to_string(),
+    }
+}
+
+/// Schema 所在的命名空间, 以 User 实体类型为准
+pub fn schema_namespace(schema: &Schema) -> Option<String> {
+    schema
+        .entity_types()
+        .find(|type_name| type_name.basename() == ENTITY_TYPE_USER)
+        .map(|type_name| type_name.namespace())
+        .filter(|namespace| !namespace.is_empty())
+}
+
+/// 按 Schema 的命名空间构造实体类型名, 如 `Acme::Role`
+pub fn entity_type_name(schema: &Schema, basename: &str) -> Result<EntityTypeName, AppError> {
+    let namespace = schema_namespace(schema);
+    Ok(EntityTypeName::from_str(&qualify(namespace.as_deref(), basename))?)
+}
+
 pub fn entities2json(entities: &Entities) -> Result<String, AppError> {
     let mut buffer = Vec::new();
     entities.write_to_json(&mut buffer)?;
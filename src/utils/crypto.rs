@@ -1,9 +1,16 @@
 use crate::errors::app_error::AppError;
 // 加密工具
 use argon2::{
-    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    password_hash::{rand_core::{OsRng, RngCore}, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use sha2::{Digest, Sha256};
+use std::sync::OnceLock;
+
+use crate::config::security::DATA_ENCRYPTION_KEY_ENV;
 
 
 // 系统用户
@@ -26,4 +33,105 @@ pub fn verify_password(
         .verify_password(password.as_bytes(), &parsed_hash)
         .is_ok();
     return Ok(result);
-}
\ No newline at end of file
+}
+
+// 用户不存在时也做一次哈希校验, 使响应耗时与密码错误时一致, 避免通过耗时枚举用户名
+pub fn dummy_password_hash() -> &'static str {
+    static DUMMY_HASH: OnceLock<String> = OnceLock::new();
+    DUMMY_HASH.get_or_init(|| hash_password("dummy-password").unwrap_or_default())
+}
+
+// 一次性令牌(邀请码、重置令牌等)只保存其 SHA-256 哈希
+pub fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+// 随机生成的密钥(如 Webhook 签名密钥), 32 字节的十六进制字符串
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+// HMAC-SHA256 签名, 返回十六进制字符串
+pub fn hmac_sha256_hex(key: &str, message: &str) -> Result<String, AppError> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes())
+        .map_err(|e| AppError::internal_server_error(anyhow::anyhow!(e)))?;
+    mac.update(message.as_bytes());
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+// PKCE 的 S256 code_challenge: BASE64URL(SHA256(code_verifier))
+pub fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+// 静态数据加密主密钥, 启动时从环境变量或配置加载
+static DATA_MASTER_KEY: OnceLock<[u8; 32]> = OnceLock::new();
+
+/// 加载静态数据加密主密钥, 环境变量优先; 未配置或格式不正确时返回错误, 服务拒绝启动
+pub fn init_data_key(configured: &str) -> Result<(), AppError> {
+    let hex_key = std::env::var(DATA_ENCRYPTION_KEY_ENV)
+        .ok()
+        .filter(|key| !key.trim().is_empty())
+        .unwrap_or_else(|| configured.to_string());
+    if hex_key.trim().is_empty() {
+        return Err(AppError::internal_server_error(anyhow::anyhow!(
+            "Data encryption key is not configured, set security.data_encryption_key or {}",
+            DATA_ENCRYPTION_KEY_ENV
+        )));
+    }
+    let key: [u8; 32] = hex::decode(hex_key.trim())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| {
+            AppError::internal_server_error(anyhow::anyhow!("Data encryption key must be 64 hex characters (32 bytes)"))
+        })?;
+    let _ = DATA_MASTER_KEY.set(key);
+    Ok(())
+}
+
+// 按用途从主密钥派生, 不同用途的密文互不通用
+fn data_key(purpose: &str) -> Result<LessSafeKey, AppError> {
+    let master = DATA_MASTER_KEY
+        .get()
+        .ok_or_else(|| AppError::internal_server_error(anyhow::anyhow!("Data encryption key is not initialized")))?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(master)
+        .map_err(|e| AppError::internal_server_error(anyhow::anyhow!(e)))?;
+    mac.update(purpose.as_bytes());
+    let key = mac.finalize().into_bytes();
+    let key = UnboundKey::new(&AES_256_GCM, &key)
+        .map_err(|_| AppError::internal_server_error(anyhow::anyhow!("invalid data key")))?;
+    Ok(LessSafeKey::new(key))
+}
+
+// AES-256-GCM 加密, 返回 BASE64URL(随机 nonce || 密文)
+pub fn encrypt_data(purpose: &str, plaintext: &str) -> Result<String, AppError> {
+    let key = data_key(purpose)?;
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    let mut buffer = plaintext.as_bytes().to_vec();
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut buffer)
+        .map_err(|_| AppError::internal_server_error(anyhow::anyhow!("failed to encrypt data")))?;
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&buffer);
+    Ok(URL_SAFE_NO_PAD.encode(sealed))
+}
+
+pub fn decrypt_data(purpose: &str, sealed: &str) -> Result<String, AppError> {
+    let invalid = || AppError::internal_server_error(anyhow::anyhow!("failed to decrypt data"));
+    let sealed = URL_SAFE_NO_PAD.decode(sealed).map_err(|_| invalid())?;
+    if sealed.len() < NONCE_LEN {
+        return Err(invalid());
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| invalid())?;
+    let mut buffer = ciphertext.to_vec();
+    let plaintext = data_key(purpose)?
+        .open_in_place(nonce, Aad::empty(), &mut buffer)
+        .map_err(|_| invalid())?;
+    String::from_utf8(plaintext.to_vec()).map_err(|_| invalid())
+}
@@ -1,19 +1,16 @@
-use crate::config::app::REDIS_PUB_SUB_CHANNEL;
 use crate::config::state::AppState;
-use crate::entity::{cedar_policy_set, cedar_schema, template_links};
+use crate::entity::{cedar_policy_set, cedar_schema, template_links, tenants};
 use crate::errors::app_error::AppError;
 use cedar_policy::{Policy, PolicyId, PolicySet, Schema, Template};
-use futures_util::StreamExt as _;
 use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect};
+use std::collections::HashMap;
 use std::str::FromStr;
 
-use tokio::time::{Duration, sleep};
-use tracing::{error, info, warn};
+use tracing::{info, warn};
 use crate::schemas::cedar_policy::TemplateLinkRecord;
+use crate::services::cedar_auth::{CedarAuthService, TenantAuthz};
+use crate::utils::cedar_utils::schema_namespace;
 
-const MAX_RETRY_ATTEMPTS: u32 = 3;
-const RETRY_DELAY: Duration = Duration::from_secs(5);
-const CONNECTION_RETRY_DELAY: Duration = Duration::from_secs(10);
 
 
 pub fn default_page() -> u64 {
@@ -38,100 +35,52 @@ pub fn default_number_1() -> i8 {
 
 
 
-// 后台任务：监听Redis Pub/Sub的策略更新通知
-pub async fn subscribe_to_policy_updates(state: AppState) {
-    let mut consecutive_failures = 0;
-
-    loop {
-        info!("尝试订阅Redis频道 '{}'...", REDIS_PUB_SUB_CHANNEL);
-
-        match establish_subscription(&state).await {
-            Ok(()) => {
-                consecutive_failures = 0;
-                info!("Redis订阅会话结束，准备重新连接...");
-            }
-            Err(e) => {
-                consecutive_failures += 1;
-                error!(
-                    "Redis订阅失败 (尝试 {}/{}): {}",
-                    consecutive_failures, MAX_RETRY_ATTEMPTS, e
-                );
-
-                if consecutive_failures >= MAX_RETRY_ATTEMPTS {
-                    error!("连续失败次数过多，延长重试间隔");
-                    sleep(CONNECTION_RETRY_DELAY * consecutive_failures).await;
-                } else {
-                    sleep(CONNECTION_RETRY_DELAY).await;
-                }
-            }
-        }
-    }
-}
-
-async fn establish_subscription(state: &AppState) -> Result<(), AppError> {
-    let pub_sub = state.redis.get_async_pubsub().await?;
-
-    let (mut sink, mut stream) = pub_sub.split();
+pub async fn reload_policies_and_schema(state: &AppState) -> Result<(), AppError> {
+    info!("从数据库重新加载所有租户的 Cedar 策略、模板、链接和模式...");
 
-    // 订阅频道
-    sink.subscribe(REDIS_PUB_SUB_CHANNEL).await?;
+    let tenants = load_tenants_authz(&state.db, &state.auth_service).await?;
+    state.auth_service.update_tenants(tenants).await;
 
-    info!("成功订阅Redis频道，等待通知...");
+    Ok(())
+}
 
-    // 处理消息循环
-    while let Some(msg) = stream.next().await {
-        let payload: String = match msg.get_payload() {
-            Ok(p) => p,
-            Err(e) => {
-                warn!("无法从Redis消息中获取payload: {}，跳过此次更新。", e);
-                continue; // 跳过当前消息，处理下一条
-            }
-        };
+/// 加载全部启用租户的 Schema, 并把各租户的策略和模板链接写入缓存
+pub async fn load_tenants_authz(
+    db: &DatabaseConnection,
+    auth_service: &CedarAuthService,
+) -> Result<HashMap<i32, TenantAuthz>, AppError> {
+    let active_tenants = tenants::Entity::find()
+        .filter(tenants::Column::IsActive.eq(true))
+        .all(db)
+        .await?;
 
-        if payload.is_empty() {
-            warn!("收到的payload为空，跳过此次更新。");
+    let mut result = HashMap::new();
+    for tenant in active_tenants {
+        let (policies_result, schema_result, links_result) = tokio::join!(
+            load_active_policies_and_templates(db, tenant.tenant_id),
+            load_active_schema(db, tenant.tenant_id),
+            load_all_template_links(db, tenant.tenant_id)
+        );
+        let schema = schema_result?;
+        if schema_namespace(&schema) != tenant.namespace {
+            warn!(
+                "租户[{}]的 Schema 命名空间与租户命名空间 {:?} 不一致, 跳过",
+                tenant.code, tenant.namespace
+            );
             continue;
         }
 
-        info!("收到策略更新通知: '{}'，开始重新加载...", payload);
-
-        if let Err(e) = reload_policies_and_schema(state).await {
-            error!("重新加载策略失败: {}", e);
-            // 继续监听，不因单次加载失败而中断
-        }
+        auth_service.update_policies_and_templates_in_cache(tenant.tenant_id, &policies_result?).await?;
+        auth_service.update_template_link_records_in_cache(tenant.tenant_id, &links_result?).await?;
+        result.insert(tenant.tenant_id, TenantAuthz { namespace: tenant.namespace, schema });
     }
-
-    warn!("Redis消息流结束");
-    Ok(())
-}
-
-pub async fn reload_policies_and_schema(state: &AppState) -> Result<(), AppError> {
-    info!("从数据库重新加载所有 Cedar 策略、模板、链接和模式...");
-
-    let (policies_result,
-        schema_result,
-        links_result
-    ) = tokio::join!(
-        load_active_policies_and_templates(&state.db),
-        load_active_schema(&state.db),
-        load_all_template_links(&state.db)
-    );
-
-    let new_policies = policies_result?;
-    let new_schema = schema_result?;
-    let new_links = links_result?;
-
-    state.auth_service.update_schema(new_schema).await;
-
-    state.auth_service.update_policies_and_templates_in_cache(&new_policies).await?;
-    state.auth_service.update_template_link_records_in_cache(&new_links).await?;
-
-    Ok(())
+    Ok(result)
 }
 
-pub async fn load_active_schema(db: &DatabaseConnection) -> Result<Schema, AppError> {
-    info!("从数据库加载启用的 Cedar schema.............");
+pub async fn load_active_schema(db: &DatabaseConnection, tenant_id: i32) -> Result<Schema, AppError> {
+    info!("从数据库加载租户[{}]的 Cedar schema.............", tenant_id);
     let active_schema_model = cedar_schema::Entity::find()
+        .filter(cedar_schema::Column::TenantId.eq(tenant_id))
         .one(db)
         .await?;
 
@@ -152,9 +101,10 @@ pub async fn load_active_schema(db: &DatabaseConnection) -> Result<Schema, AppEr
     }
 }
 
-pub async fn load_active_policies_and_templates(db: &DatabaseConnection) -> Result<PolicySet, AppError> {
-    info!("从数据库加载活动的 Cedar 策略和模板...");
+pub async fn load_active_policies_and_templates(db: &DatabaseConnection, tenant_id: i32) -> Result<PolicySet, AppError> {
+    info!("从数据库加载租户[{}]活动的 Cedar 策略和模板...", tenant_id);
     let active_policy_models = cedar_policy_set::Entity::find()
+        .filter(cedar_policy_set::Column::TenantId.eq(tenant_id))
         .filter(cedar_policy_set::Column::IsActive.eq(true))
         .all(db)
         .await?;
@@ -183,9 +133,12 @@ pub async fn load_active_policies_and_templates(db: &DatabaseConnection) -> Resu
 }
 
 
-pub async fn load_all_template_links(db: &DatabaseConnection) -> Result<Vec<TemplateLinkRecord>, AppError> {
-    info!("从数据库加载所有模板链接...");
-    let link_models = template_links::Entity::find().all(db).await?;
+pub async fn load_all_template_links(db: &DatabaseConnection, tenant_id: i32) -> Result<Vec<TemplateLinkRecord>, AppError> {
+    info!("从数据库加载租户[{}]的所有模板链接...", tenant_id);
+    let link_models = template_links::Entity::find()
+        .filter(template_links::Column::TenantId.eq(tenant_id))
+        .all(db)
+        .await?;
 
     let link_records = link_models
         .into_iter()
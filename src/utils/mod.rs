@@ -9,4 +9,7 @@ pub mod function;
 pub mod sse;
 pub mod cedar_utils;
 pub mod templates;
-pub mod logging;
\ No newline at end of file
+pub mod logging;
+pub mod password_policy;
+pub mod scim_filter;
+pub mod net;
\ No newline at end of file
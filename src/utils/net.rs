@@ -0,0 +1,238 @@
+// 出站请求的目标地址检查: 防止通过 Webhook 等用户配置的 URL 访问内网、回环或云元数据地址
+// 以及入站请求的客户端地址: 只信任来自受信任代理的 X-Forwarded-For
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use axum::http::HeaderMap;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use url::{Host, Url};
+
+/// 是否为公网地址, 回环、私有、链路本地(含 169.254.169.254 元数据服务)、保留地址均不是
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_ipv4(mapped),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        || a == 0
+        // 100.64.0.0/10 运营商级 NAT
+        || (a == 100 && (64..128).contains(&b))
+        // 192.0.0.0/24 协议分配
+        || (a == 192 && b == 0 && c == 0)
+        // 198.18.0.0/15 基准测试
+        || (a == 198 && (b == 18 || b == 19))
+        // 240.0.0.0/4 保留
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // fc00::/7 唯一本地地址
+        || (first & 0xfe00) == 0xfc00
+        // fe80::/10 链路本地地址
+        || (first & 0xffc0) == 0xfe80
+        // 2001:db8::/32 文档地址
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8)
+        // 64:ff9b::/96 NAT64, 可映射到任意 IPv4 地址
+        || (first == 0x0064 && ip.segments()[1] == 0xff9b))
+}
+
+/// 检查 URL 只能是 http(s), 且主机解析出的所有地址都是公网地址
+pub async fn ensure_public_url(raw_url: &str) -> Result<(), String> {
+    let url = Url::parse(raw_url).map_err(|e| format!("invalid url: {}", e))?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(format!("unsupported url scheme: {}", url.scheme()));
+    }
+    let port = url.port_or_known_default().unwrap_or(80);
+    let addrs: Vec<IpAddr> = match url.host() {
+        Some(Host::Ipv4(ip)) => vec![IpAddr::V4(ip)],
+        Some(Host::Ipv6(ip)) => vec![IpAddr::V6(ip)],
+        Some(Host::Domain(domain)) => tokio::net::lookup_host((domain, port))
+            .await
+            .map_err(|e| format!("failed to resolve {}: {}", domain, e))?
+            .map(|addr| addr.ip())
+            .collect(),
+        None => return Err("url has no host".to_string()),
+    };
+    if addrs.is_empty() {
+        return Err("url host did not resolve to any address".to_string());
+    }
+    match addrs.into_iter().find(|ip| !is_public_ip(*ip)) {
+        Some(ip) => Err(format!("url resolves to non-public address {}", ip)),
+        None => Ok(()),
+    }
+}
+
+/// 只返回公网地址的 DNS 解析器, 在连接时再检查一次, 防止 DNS 重绑定绕过创建时的检查
+#[derive(Debug, Default)]
+pub struct PublicOnlyResolver;
+
+impl Resolve for PublicOnlyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} does not resolve to a public address", host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+
+/// 解析受信任代理的配置项, 支持单个地址或 CIDR 网段, 返回网络地址和前缀长度
+pub fn parse_ip_range(range: &str) -> Option<(IpAddr, u8)> {
+    let (ip, prefix) = match range.trim().split_once('/') {
+        Some((ip, prefix)) => (ip.parse::<IpAddr>().ok()?, Some(prefix.parse::<u8>().ok()?)),
+        None => (range.trim().parse::<IpAddr>().ok()?, None),
+    };
+    let max = if ip.is_ipv4() { 32 } else { 128 };
+    let prefix = prefix.unwrap_or(max);
+    (prefix <= max).then_some((ip, prefix))
+}
+
+fn ip_in_range(ip: IpAddr, (network, prefix): (IpAddr, u8)) -> bool {
+    let ip = match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+        ip => ip,
+    };
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) => {
+            let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+            u32::from(ip) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(network)) => {
+            let mask = u128::MAX.checked_shl(128 - prefix as u32).unwrap_or(0);
+            u128::from(ip) & mask == u128::from(network) & mask
+        }
+        _ => false,
+    }
+}
+
+fn is_trusted_proxy(ip: IpAddr, trusted_proxies: &[String]) -> bool {
+    trusted_proxies
+        .iter()
+        .filter_map(|range| parse_ip_range(range))
+        .any(|range| ip_in_range(ip, range))
+}
+
+/// 客户端地址: 对端是受信任的代理时, 从 X-Forwarded-For 右侧起跳过受信任的代理, 取第一个不受信任的地址;
+/// 对端不是受信任的代理时直接使用对端地址, 客户端自己填写的 X-Forwarded-For 不起作用
+pub fn client_ip(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &[String]) -> IpAddr {
+    if !is_trusted_proxy(peer, trusted_proxies) {
+        return peer;
+    }
+    let forwarded: Vec<&str> = headers
+        .get_all(X_FORWARDED_FOR)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect();
+    // 无法解析的条目之前的内容不可信, 停在最后一个可解析的地址
+    let mut client = peer;
+    for ip in forwarded.into_iter().rev() {
+        let Ok(ip) = ip.parse::<IpAddr>() else {
+            break;
+        };
+        client = ip;
+        if !is_trusted_proxy(ip, trusted_proxies) {
+            break;
+        }
+    }
+    client
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_internal_ipv4() {
+        for ip in [
+            "127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254", "0.0.0.0",
+            "100.64.0.1", "255.255.255.255", "224.0.0.1",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{} should not be public", ip);
+        }
+        assert!(is_public_ip("93.184.216.34".parse().unwrap()));
+    }
+
+    #[test]
+    fn rejects_internal_ipv6() {
+        for ip in ["::1", "::", "fd00::1", "fe80::1", "::ffff:127.0.0.1", "::ffff:169.254.169.254", "64:ff9b::a00:1"] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{} should not be public", ip);
+        }
+        assert!(is_public_ip("2606:4700:4700::1111".parse().unwrap()));
+    }
+
+    fn forwarded(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(X_FORWARDED_FOR, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn parses_addresses_and_cidr_ranges() {
+        assert_eq!(parse_ip_range("10.0.0.1"), Some(("10.0.0.1".parse().unwrap(), 32)));
+        assert_eq!(parse_ip_range("10.0.0.0/8"), Some(("10.0.0.0".parse().unwrap(), 8)));
+        assert_eq!(parse_ip_range("fd00::/8"), Some(("fd00::".parse().unwrap(), 8)));
+        assert_eq!(parse_ip_range("10.0.0.0/33"), None);
+        assert_eq!(parse_ip_range("proxy.internal"), None);
+    }
+
+    #[test]
+    fn ignores_forwarded_header_from_untrusted_peer() {
+        let trusted = vec!["10.0.0.0/8".to_string()];
+        let peer: IpAddr = "203.0.113.7".parse().unwrap();
+        assert_eq!(client_ip(peer, &forwarded("198.51.100.1"), &trusted), peer);
+        assert_eq!(client_ip(peer, &forwarded("198.51.100.1"), &[]), peer);
+    }
+
+    #[test]
+    fn takes_first_untrusted_address_from_the_right() {
+        let trusted = vec!["10.0.0.0/8".to_string(), "192.168.1.1".to_string()];
+        let peer: IpAddr = "10.0.0.2".parse().unwrap();
+        // 客户端伪造的最左侧地址不会被采用
+        let headers = forwarded("1.2.3.4, 198.51.100.1, 192.168.1.1");
+        assert_eq!(client_ip(peer, &headers, &trusted), "198.51.100.1".parse::<IpAddr>().unwrap());
+        // 没有 X-Forwarded-For 时使用代理地址
+        assert_eq!(client_ip(peer, &HeaderMap::new(), &trusted), peer);
+        // 无法解析的条目之前的地址不可信
+        let headers = forwarded("1.2.3.4, garbage, 10.0.0.3");
+        assert_eq!(client_ip(peer, &headers, &trusted), "10.0.0.3".parse::<IpAddr>().unwrap());
+        // IPv4 映射的 IPv6 对端同样匹配 IPv4 网段
+        let mapped: IpAddr = "::ffff:10.0.0.2".parse().unwrap();
+        assert_eq!(client_ip(mapped, &forwarded("198.51.100.1"), &trusted), "198.51.100.1".parse::<IpAddr>().unwrap());
+    }
+
+    #[tokio::test]
+    async fn ensure_public_url_checks_literal_hosts_and_scheme() {
+        assert!(ensure_public_url("http://127.0.0.1:8080/hook").await.is_err());
+        assert!(ensure_public_url("http://[::1]/hook").await.is_err());
+        assert!(ensure_public_url("http://169.254.169.254/latest/meta-data").await.is_err());
+        assert!(ensure_public_url("http://localhost/hook").await.is_err());
+        assert!(ensure_public_url("ftp://93.184.216.34/hook").await.is_err());
+        assert!(ensure_public_url("https://93.184.216.34/hook").await.is_ok());
+    }
+}
@@ -0,0 +1,206 @@
+// 密码策略校验工具
+
+use crate::bad_request;
+use crate::config::security::PasswordPolicyConfig;
+use crate::errors::app_error::AppError;
+use crate::utils::crypto::verify_password;
+
+// 常见弱密码片段, 出现时强度评分最高为 1
+const WEAK_PATTERNS: &[&str] = &[
+    "password", "passw0rd", "qwerty", "123456", "admin", "letmein", "welcome", "iloveyou",
+    "abc123", "111111",
+];
+
+/// 按密码策略校验, 返回所有不满足的规则
+pub fn validate_password(
+    policy: &PasswordPolicyConfig,
+    password: &str,
+    username: &str,
+) -> Result<(), AppError> {
+    let mut violations = Vec::new();
+    let length = password.chars().count();
+
+    if length < policy.min_length {
+        violations.push(format!("at least {} characters", policy.min_length));
+    }
+    if policy.max_length > 0 && length > policy.max_length {
+        violations.push(format!("at most {} characters", policy.max_length));
+    }
+    if policy.require_uppercase && !password.chars().any(|c| c.is_uppercase()) {
+        violations.push("an uppercase letter".to_string());
+    }
+    if policy.require_lowercase && !password.chars().any(|c| c.is_lowercase()) {
+        violations.push("a lowercase letter".to_string());
+    }
+    if policy.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+        violations.push("a digit".to_string());
+    }
+    if policy.require_symbol && !password.chars().any(|c| !c.is_alphanumeric()) {
+        violations.push("a symbol".to_string());
+    }
+    if !violations.is_empty() {
+        return Err(bad_request!(
+            "Password must contain {}",
+            violations.join(", ")
+        ));
+    }
+
+    if strength_score(password, username) < policy.min_strength_score {
+        return Err(bad_request!("Password is too weak"));
+    }
+    Ok(())
+}
+
+/// 新密码不能与最近使用过的密码(哈希)相同
+pub fn ensure_not_reused(
+    policy: &PasswordPolicyConfig,
+    password: &str,
+    used_hashes: &[String],
+) -> Result<(), AppError> {
+    for used_hash in used_hashes {
+        if verify_password(password, used_hash)? {
+            return Err(bad_request!(
+                "Password must not match any of the last {} passwords",
+                policy.history_count
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 估算密码强度, 0(极弱) - 4(很强)
+pub fn strength_score(password: &str, username: &str) -> u8 {
+    let chars: Vec<char> = password.chars().collect();
+    if chars.is_empty() {
+        return 0;
+    }
+
+    let mut pool = 0u32;
+    if chars.iter().any(|c| c.is_ascii_lowercase()) {
+        pool += 26;
+    }
+    if chars.iter().any(|c| c.is_ascii_uppercase()) {
+        pool += 26;
+    }
+    if chars.iter().any(|c| c.is_ascii_digit()) {
+        pool += 10;
+    }
+    if chars.iter().any(|c| c.is_ascii() && !c.is_ascii_alphanumeric()) {
+        pool += 33;
+    }
+    if chars.iter().any(|c| !c.is_ascii()) {
+        pool += 100;
+    }
+
+    // 重复字符和连续字符(如 aaa, abc, 321)只计少量熵
+    let mut effective_length = 1.0;
+    for pair in chars.windows(2) {
+        let (prev, cur) = (pair[0] as i64, pair[1] as i64);
+        effective_length += if (cur - prev).abs() <= 1 { 0.25 } else { 1.0 };
+    }
+    let entropy = effective_length * f64::from(pool).log2();
+
+    let score = match entropy {
+        e if e < 28.0 => 0,
+        e if e < 36.0 => 1,
+        e if e < 60.0 => 2,
+        e if e < 80.0 => 3,
+        _ => 4,
+    };
+
+    let lowered = password.to_lowercase();
+    let username = username.to_lowercase();
+    let contains_weak = WEAK_PATTERNS.iter().any(|p| lowered.contains(p))
+        || (username.chars().count() >= 3 && lowered.contains(&username));
+    if contains_weak { score.min(1) } else { score }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::crypto::hash_password;
+
+    fn relaxed() -> PasswordPolicyConfig {
+        PasswordPolicyConfig {
+            min_length: 1,
+            max_length: 0,
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            require_symbol: false,
+            min_strength_score: 0,
+            ..PasswordPolicyConfig::default()
+        }
+    }
+
+    fn error(result: Result<(), AppError>) -> String {
+        result.unwrap_err().to_string()
+    }
+
+    #[test]
+    fn enforces_length() {
+        let policy = PasswordPolicyConfig { min_length: 8, max_length: 12, ..relaxed() };
+        assert!(error(validate_password(&policy, "short", "alice")).contains("at least 8 characters"));
+        assert!(error(validate_password(&policy, "much-too-long-pass", "alice")).contains("at most 12 characters"));
+        assert!(validate_password(&policy, "just-right", "alice").is_ok());
+        // 按字符数而不是字节数计算长度
+        assert!(validate_password(&policy, "密码密码密码密码", "alice").is_ok());
+    }
+
+    #[test]
+    fn enforces_each_character_class() {
+        let cases = [
+            (PasswordPolicyConfig { require_uppercase: true, ..relaxed() }, "lower1!", "Lower1!", "an uppercase letter"),
+            (PasswordPolicyConfig { require_lowercase: true, ..relaxed() }, "UPPER1!", "UPPEr1!", "a lowercase letter"),
+            (PasswordPolicyConfig { require_digit: true, ..relaxed() }, "NoDigit!", "Digit1!", "a digit"),
+            (PasswordPolicyConfig { require_symbol: true, ..relaxed() }, "NoSymbol1", "Symbol1!", "a symbol"),
+        ];
+        for (policy, rejected, accepted, rule) in cases {
+            assert!(error(validate_password(&policy, rejected, "alice")).contains(rule), "{}", rule);
+            assert!(validate_password(&policy, accepted, "alice").is_ok(), "{}", rule);
+        }
+    }
+
+    #[test]
+    fn reports_all_violations_at_once() {
+        let policy = PasswordPolicyConfig { min_length: 10, require_uppercase: true, require_digit: true, ..relaxed() };
+        let message = error(validate_password(&policy, "abc", "alice"));
+        assert!(message.contains("at least 10 characters"));
+        assert!(message.contains("an uppercase letter"));
+        assert!(message.contains("a digit"));
+    }
+
+    #[test]
+    fn scores_strength() {
+        assert_eq!(strength_score("", "alice"), 0);
+        assert_eq!(strength_score("abcdefgh", "alice"), 0);
+        assert!(strength_score("Tr0ub4dour&3x", "alice") >= 3);
+        assert_eq!(strength_score("correct-Horse-battery-staple-42", "alice"), 4);
+        // 含常见弱密码或用户名时最高为 1
+        assert_eq!(strength_score("Xk9#Password!7q", "alice"), 1);
+        assert_eq!(strength_score("Xk9#Alice!7qLm", "alice"), 1);
+    }
+
+    #[test]
+    fn enforces_minimum_strength_score() {
+        let policy = PasswordPolicyConfig { min_strength_score: 3, ..relaxed() };
+        assert_eq!(error(validate_password(&policy, "Password123!", "alice")), "Password is too weak");
+        assert!(validate_password(&policy, "Tr0ub4dour&3x", "alice").is_ok());
+
+        let policy = PasswordPolicyConfig { min_strength_score: 0, ..relaxed() };
+        assert!(validate_password(&policy, "Password123!", "alice").is_ok());
+    }
+
+    #[test]
+    fn rejects_reused_passwords() {
+        let policy = PasswordPolicyConfig { history_count: 2, ..relaxed() };
+        let used = vec![hash_password("Old-Pass-1").unwrap(), hash_password("Old-Pass-2").unwrap()];
+        assert_eq!(
+            error(ensure_not_reused(&policy, "Old-Pass-2", &used)),
+            "Password must not match any of the last 2 passwords"
+        );
+        assert!(ensure_not_reused(&policy, "Brand-New-3", &used).is_ok());
+        assert!(ensure_not_reused(&policy, "Old-Pass-1", &[]).is_ok());
+    }
+}
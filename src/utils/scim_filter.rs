@@ -0,0 +1,339 @@
+// SCIM 过滤表达式 (RFC 7644 3.4.2.2)
+// 支持 eq ne co sw ew gt ge lt le pr 以及 and、or、not 和括号, 不支持 emails[type eq "work"] 这类复杂属性过滤
+use sea_orm::{ColumnTrait, Condition, Value};
+
+use crate::bad_request;
+use crate::errors::app_error::AppError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterValue {
+    Str(String),
+    Bool(bool),
+    Num(i64),
+    Null,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScimFilter {
+    Compare { attr: String, op: String, value: FilterValue },
+    Present { attr: String },
+    And(Box<ScimFilter>, Box<ScimFilter>),
+    Or(Box<ScimFilter>, Box<ScimFilter>),
+    Not(Box<ScimFilter>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    Word(String),
+    Str(String),
+}
+
+const COMPARE_OPS: &[&str] = &["eq", "ne", "co", "sw", "ew", "gt", "ge", "lt", "le"];
+
+pub fn parse_filter(input: &str) -> Result<ScimFilter, AppError> {
+    let tokens = tokenize(input)?;
+    let mut pos = 0;
+    let filter = parse_or(&tokens, &mut pos)?;
+    if pos != tokens.len() {
+        return Err(bad_request!("Invalid filter: unexpected token near {:?}", tokens[pos]));
+    }
+    Ok(filter)
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, AppError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            '"' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(escaped) => value.push(escaped),
+                            None => return Err(bad_request!("Invalid filter: unterminated string")),
+                        },
+                        Some(ch) => value.push(ch),
+                        None => return Err(bad_request!("Invalid filter: unterminated string")),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            '[' | ']' => return Err(bad_request!("Invalid filter: complex attribute filters are not supported")),
+            _ => {
+                let mut word = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_whitespace() || matches!(ch, '(' | ')' | '"' | '[' | ']') {
+                        break;
+                    }
+                    word.push(ch);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+// and 的优先级高于 or
+fn parse_or(tokens: &[Token], pos: &mut usize) -> Result<ScimFilter, AppError> {
+    let mut left = parse_and(tokens, pos)?;
+    while is_keyword(tokens.get(*pos), "or") {
+        *pos += 1;
+        let right = parse_and(tokens, pos)?;
+        left = ScimFilter::Or(Box::new(left), Box::new(right));
+    }
+    Ok(left)
+}
+
+fn parse_and(tokens: &[Token], pos: &mut usize) -> Result<ScimFilter, AppError> {
+    let mut left = parse_term(tokens, pos)?;
+    while is_keyword(tokens.get(*pos), "and") {
+        *pos += 1;
+        let right = parse_term(tokens, pos)?;
+        left = ScimFilter::And(Box::new(left), Box::new(right));
+    }
+    Ok(left)
+}
+
+fn parse_term(tokens: &[Token], pos: &mut usize) -> Result<ScimFilter, AppError> {
+    match tokens.get(*pos) {
+        Some(Token::LParen) => {
+            *pos += 1;
+            let filter = parse_or(tokens, pos)?;
+            expect_rparen(tokens, pos)?;
+            Ok(filter)
+        }
+        Some(token) if is_keyword(Some(token), "not") => {
+            *pos += 1;
+            if tokens.get(*pos) != Some(&Token::LParen) {
+                return Err(bad_request!("Invalid filter: expected '(' after not"));
+            }
+            *pos += 1;
+            let filter = parse_or(tokens, pos)?;
+            expect_rparen(tokens, pos)?;
+            Ok(ScimFilter::Not(Box::new(filter)))
+        }
+        Some(Token::Word(attr)) => {
+            let attr = attr.clone();
+            *pos += 1;
+            let op = match tokens.get(*pos) {
+                Some(Token::Word(op)) => op.to_lowercase(),
+                _ => return Err(bad_request!("Invalid filter: expected operator after {}", attr)),
+            };
+            *pos += 1;
+            if op == "pr" {
+                return Ok(ScimFilter::Present { attr });
+            }
+            if !COMPARE_OPS.contains(&op.as_str()) {
+                return Err(bad_request!("Invalid filter: unsupported operator {}", op));
+            }
+            let value = match tokens.get(*pos) {
+                Some(Token::Str(s)) => FilterValue::Str(s.clone()),
+                Some(Token::Word(w)) => match w.to_lowercase().as_str() {
+                    "true" => FilterValue::Bool(true),
+                    "false" => FilterValue::Bool(false),
+                    "null" => FilterValue::Null,
+                    _ => FilterValue::Num(
+                        w.parse()
+                            .map_err(|_| bad_request!("Invalid filter: invalid value {}", w))?,
+                    ),
+                },
+                _ => return Err(bad_request!("Invalid filter: expected value after {}", op)),
+            };
+            *pos += 1;
+            Ok(ScimFilter::Compare { attr, op, value })
+        }
+        _ => Err(bad_request!("Invalid filter: expected attribute")),
+    }
+}
+
+fn is_keyword(token: Option<&Token>, keyword: &str) -> bool {
+    matches!(token, Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword))
+}
+
+fn expect_rparen(tokens: &[Token], pos: &mut usize) -> Result<(), AppError> {
+    if tokens.get(*pos) != Some(&Token::RParen) {
+        return Err(bad_request!("Invalid filter: expected ')'"));
+    }
+    *pos += 1;
+    Ok(())
+}
+
+/// 转换为数据库查询条件, resolve 将属性名(小写)映射为数据库列
+pub fn to_condition<C, F>(filter: &ScimFilter, resolve: &F) -> Result<Condition, AppError>
+where
+    C: ColumnTrait,
+    F: Fn(&str) -> Option<C>,
+{
+    let column = |attr: &str| {
+        resolve(&attr.to_lowercase()).ok_or(bad_request!("Invalid filter: unsupported attribute {}", attr))
+    };
+
+    let condition = match filter {
+        ScimFilter::And(left, right) => Condition::all()
+            .add(to_condition(left, resolve)?)
+            .add(to_condition(right, resolve)?),
+        ScimFilter::Or(left, right) => Condition::any()
+            .add(to_condition(left, resolve)?)
+            .add(to_condition(right, resolve)?),
+        ScimFilter::Not(inner) => to_condition(inner, resolve)?.not(),
+        ScimFilter::Present { attr } => Condition::all().add(column(attr)?.is_not_null()),
+        ScimFilter::Compare { attr, op, value } => {
+            let col = column(attr)?;
+            let expr = match (op.as_str(), value) {
+                ("eq", FilterValue::Null) => col.is_null(),
+                ("ne", FilterValue::Null) => col.is_not_null(),
+                ("co", FilterValue::Str(s)) => col.contains(s),
+                ("sw", FilterValue::Str(s)) => col.starts_with(s),
+                ("ew", FilterValue::Str(s)) => col.ends_with(s),
+                ("eq", v) => col.eq(to_value(v)),
+                ("ne", v) => col.ne(to_value(v)),
+                ("gt", v) => col.gt(to_value(v)),
+                ("ge", v) => col.gte(to_value(v)),
+                ("lt", v) => col.lt(to_value(v)),
+                ("le", v) => col.lte(to_value(v)),
+                _ => return Err(bad_request!("Invalid filter: operator {} is not valid for {:?}", op, value)),
+            };
+            Condition::all().add(expr)
+        }
+    };
+    Ok(condition)
+}
+
+fn to_value(value: &FilterValue) -> Value {
+    match value {
+        FilterValue::Str(s) => Value::from(s.clone()),
+        FilterValue::Bool(b) => Value::from(*b),
+        FilterValue::Num(n) => Value::from(*n),
+        FilterValue::Null => Value::String(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::users;
+    use sea_orm::{DbBackend, EntityTrait, QueryFilter, QueryTrait};
+
+    fn compare(attr: &str, op: &str, value: FilterValue) -> ScimFilter {
+        ScimFilter::Compare { attr: attr.to_string(), op: op.to_string(), value }
+    }
+
+    fn eq(attr: &str, value: &str) -> ScimFilter {
+        compare(attr, "eq", FilterValue::Str(value.to_string()))
+    }
+
+    fn user_column(attr: &str) -> Option<users::Column> {
+        match attr {
+            "username" => Some(users::Column::Username),
+            "active" => Some(users::Column::IsActive),
+            _ => None,
+        }
+    }
+
+    fn to_sql(filter: &str) -> Result<String, AppError> {
+        let condition = to_condition(&parse_filter(filter)?, &user_column)?;
+        Ok(users::Entity::find().filter(condition).build(DbBackend::MySql).to_string())
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let filter = parse_filter(r#"a eq "1" or b eq "2" and c eq "3""#).unwrap();
+        assert_eq!(
+            filter,
+            ScimFilter::Or(
+                Box::new(eq("a", "1")),
+                Box::new(ScimFilter::And(Box::new(eq("b", "2")), Box::new(eq("c", "3")))),
+            )
+        );
+    }
+
+    #[test]
+    fn parentheses_override_precedence() {
+        let filter = parse_filter(r#"(a eq "1" or b eq "2") and c eq "3""#).unwrap();
+        assert_eq!(
+            filter,
+            ScimFilter::And(
+                Box::new(ScimFilter::Or(Box::new(eq("a", "1")), Box::new(eq("b", "2")))),
+                Box::new(eq("c", "3")),
+            )
+        );
+        assert!(parse_filter(r#"(a eq "1""#).is_err());
+        assert!(parse_filter(r#"a eq "1")"#).is_err());
+    }
+
+    #[test]
+    fn not_requires_parentheses() {
+        assert_eq!(
+            parse_filter(r#"NOT (a eq "1")"#).unwrap(),
+            ScimFilter::Not(Box::new(eq("a", "1")))
+        );
+        assert!(parse_filter(r#"not a eq "1""#).is_err());
+    }
+
+    #[test]
+    fn parses_present_and_literal_values() {
+        assert_eq!(parse_filter("title pr").unwrap(), ScimFilter::Present { attr: "title".to_string() });
+        assert_eq!(parse_filter("active eq TRUE").unwrap(), compare("active", "eq", FilterValue::Bool(true)));
+        assert_eq!(parse_filter("manager eq null").unwrap(), compare("manager", "eq", FilterValue::Null));
+        assert_eq!(parse_filter("age GE 18").unwrap(), compare("age", "ge", FilterValue::Num(18)));
+    }
+
+    #[test]
+    fn unescapes_quoted_strings() {
+        assert_eq!(
+            parse_filter(r#"displayName eq "say \"hi\" \\ bye""#).unwrap(),
+            eq("displayName", r#"say "hi" \ bye"#)
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_filters() {
+        for filter in [
+            r#"userName eq "alice"#,
+            r#"userName eq "alice\"#,
+            r#"userName like "alice""#,
+            "userName eq",
+            "userName",
+            r#"userName eq alice"#,
+            r#"emails[type eq "work"]"#,
+            r#"userName eq "a" and"#,
+            "",
+        ] {
+            assert!(parse_filter(filter).is_err(), "{:?} should be rejected", filter);
+        }
+    }
+
+    #[test]
+    fn converts_to_sql_conditions() {
+        let sql = to_sql(r#"userName sw "al" and not (active eq false)"#).unwrap();
+        assert!(sql.contains("`users`.`username` LIKE 'al%'"), "{}", sql);
+        assert!(sql.contains("NOT"), "{}", sql);
+        let sql = to_sql("username pr or active eq true").unwrap();
+        assert!(sql.contains("`users`.`username` IS NOT NULL OR `users`.`is_active` = TRUE"), "{}", sql);
+    }
+
+    #[test]
+    fn to_condition_rejects_unknown_attributes_and_invalid_operators() {
+        assert!(to_sql(r#"password eq "secret""#).is_err());
+        assert!(to_sql(r#"userName eq "a" or password pr"#).is_err());
+        assert!(to_sql("active co true").is_err());
+    }
+}
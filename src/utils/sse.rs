@@ -1,56 +1,337 @@
 // SSE推送消息
+// 用户的 SSE 连接可能在集群中任意节点上: 每个节点订阅自己的 Redis 频道,
+// 并在 Redis 中登记在线用户, 推送时先查本地连接, 再转发给用户所在的节点
+// 每条消息按用户分配递增的 id 并记录在事件日志中, 客户端重连时按 Last-Event-ID 或离线标记从日志补发
+// 并发推送的消息可能乱序到达连接, 连接发现 id 不连续时从事件日志按顺序补齐
 
+use futures_util::StreamExt as _;
+use redis::{AsyncCommands, ExistenceCheck, SetOptions};
 use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::time::{sleep, Duration};
+use tracing::{error, info, warn};
+use crate::config::app::{
+    SSE_EVENT_LOG_PREFIX, SSE_EVENT_SEQ_PREFIX, SSE_NODE_CHANNEL_PREFIX, SSE_OFFLINE_SINCE_PREFIX, SSE_PRESENCE_PREFIX,
+};
 use crate::config::state::AppState;
 use crate::errors::app_error::AppError;
 
-#[derive(Deserialize, Serialize, Debug)]
-pub struct SSEPushPayload {
-    pub message_source: String, // 通知类型
-    pub message_level: String, // 消息等级
-    pub message: String, // 消息内容
+// SSE 事件类型
+pub const SSE_EVENT_NOTIFICATION: &str = "notification";
+pub const SSE_EVENT_PERMISSIONS_CHANGED: &str = "permissions_changed";
+
+// 用于重连补发的事件日志长度和有效期, 通知本身已持久化, 因此只保留最近的部分
+const EVENT_LOG_MAX: isize = 200;
+const EVENT_LOG_TTL: i64 = 7 * 24 * 3600;
+
+// 离线期间第一条消息的 id 在首次补发后保留的时间(秒), 同时打开的多个标签页都能收到离线消息
+const OFFLINE_REPLAY_GRACE_SECONDS: i64 = 60;
+
+// 在线状态心跳间隔和有效期(秒), 节点宕机后其登记的在线状态在有效期后自动失效
+const PRESENCE_HEARTBEAT_SECONDS: u64 = 30;
+const PRESENCE_TTL_SECONDS: i64 = 90;
+const SUBSCRIPTION_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// 推送给客户端的一条 SSE 消息, 对应 `id:`、`event:` 和 `data:` 字段
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SseMessage {
+    pub id: u64,
+    pub event: String,
+    pub data: String,
+}
+
+// 原子地分配递增 id 并写入事件日志, 返回 id 时比它小的消息都已在日志中
+// KEYS: 序号, 事件日志; ARGV: 事件类型, 数据, 日志长度, 日志有效期
+static APPEND_EVENT_SCRIPT: LazyLock<redis::Script> = LazyLock::new(|| {
+    redis::Script::new(
+        r#"
+local id = redis.call('INCR', KEYS[1])
+local message = cjson.encode({id = id, event = ARGV[1], data = ARGV[2]})
+redis.call('ZADD', KEYS[2], id, message)
+redis.call('ZREMRANGEBYRANK', KEYS[2], 0, -(tonumber(ARGV[3]) + 1))
+redis.call('EXPIRE', KEYS[2], ARGV[4])
+return id
+"#,
+    )
+});
+
+// 节点之间转发的消息
+#[derive(Serialize, Deserialize, Debug)]
+struct SseEnvelope {
+    user_uuid: String,
+    message: SseMessage,
 }
 
 pub async fn sse_push_message(
     state: &AppState,
     user_uuid: String,
-    payload: SSEPushPayload
+    event: &str,
+    payload: &impl Serialize,
 ) -> Result<(), AppError> {
-    let senders = state.sse_senders.lock().await;
-    let message = serde_json::to_value(&payload).unwrap().to_string();
-
-    if let Some(sender) = senders.get(&user_uuid) {
-        // 尝试发送消息
-        // 用户在线，尝试发送消息
-        if sender.send(message.clone()).await.is_ok() {
-            // 发送成功
-            tracing::debug!("消息[{:?}]已发送给在线用户 {}", &message, user_uuid);
-            return Ok(());
-        } else {
-            // 发送失败，说明用户刚刚断开连接，我们需要将此消息转为离线消息
-            tracing::info!("用户 {} 刚刚断开连接。正在存储消息。", user_uuid);
-            // 我们现在只需要存储消息即可。
+    let mut redis_conn = state.redis.get_multiplexed_async_connection().await?;
+
+    // 分配递增 id 并写入事件日志, 之后才投递, 保证重连补发和补齐乱序时不会遗漏
+    let data = serde_json::to_string(payload)?;
+    let id: u64 = APPEND_EVENT_SCRIPT
+        .key(redis_event_seq_key(&user_uuid))
+        .key(redis_event_log_key(&user_uuid))
+        .arg(event)
+        .arg(&data)
+        .arg(EVENT_LOG_MAX)
+        .arg(EVENT_LOG_TTL)
+        .invoke_async(&mut redis_conn)
+        .await?;
+    let message = SseMessage {
+        id,
+        event: event.to_string(),
+        data,
+    };
+
+    // 用户可能同时在多个节点上有连接, 本节点和其他节点都要投递
+    let delivered_local = deliver_local(state, &user_uuid, &message).await;
+
+    let envelope = serde_json::to_string(&SseEnvelope {
+        user_uuid: user_uuid.clone(),
+        message: message.clone(),
+    })?;
+    let mut receivers = 0;
+    for node_id in online_nodes(state, &user_uuid).await? {
+        if node_id == state.node_id {
+            continue;
         }
-    } else {
-        // 用户不在线
-        tracing::info!("用户 {} 处于离线状态。正在存储消息。", user_uuid);
+        let count: i64 = redis_conn
+            .publish(redis_node_channel(&node_id), &envelope)
+            .await?;
+        receivers += count;
+    }
+    if delivered_local || receivers > 0 {
+        return Ok(());
     }
 
-    drop(senders);
-    // 如果用户不在线，则存储消息
-    let cache_key = redis_offline_key(user_uuid);
+    // 用户不在线, 记录离线期间的第一条消息, 重连时从事件日志补发
+    tracing::info!("用户 {} 处于离线状态。正在存储消息。", user_uuid);
+    mark_offline_message(state, &user_uuid, &message).await
+}
 
-    let redis_conn = &mut state.redis.get_multiplexed_async_connection().await?;
-    let _: () = redis::cmd("LPUSH")
-        .arg(cache_key)
-        .arg(message)
-        .exec_async(redis_conn).await?;
+/// 发送给本节点上该用户的所有连接, 没有任何连接收到时返回 false
+/// 使用 try_send 不在持锁时等待, 缓冲区已满的慢连接直接断开, 客户端重连后按 Last-Event-ID 补发
+async fn deliver_local(state: &AppState, user_uuid: &str, message: &SseMessage) -> bool {
+    let mut senders = state.sse_senders.lock().await;
+    let Some(connections) = senders.get_mut(user_uuid) else {
+        return false;
+    };
 
+    let mut delivered = false;
+    connections.retain(|connection_id, sender| match sender.try_send(message.clone()) {
+        Ok(()) => {
+            delivered = true;
+            true
+        }
+        Err(TrySendError::Full(_)) => {
+            warn!("用户 {} 的 SSE 连接 {} 缓冲区已满, 断开连接", user_uuid, connection_id);
+            false
+        }
+        // 发送失败，说明该连接刚刚断开
+        Err(TrySendError::Closed(_)) => false,
+    });
+    if delivered {
+        tracing::debug!("消息[{}]已发送给在线用户 {}", message.id, user_uuid);
+    }
+    delivered
+}
+
+// 只记录离线期间第一条消息的 id, 消息本身已在事件日志中
+// 标记已存在(可能处于补发宽限期)时恢复有效期, 避免新的离线消息随宽限期过期而漏发
+async fn mark_offline_message(state: &AppState, user_uuid: &str, message: &SseMessage) -> Result<(), AppError> {
+    let mut redis_conn = state.redis.get_multiplexed_async_connection().await?;
+    let key = redis_offline_since_key(user_uuid);
+    let _: () = redis::pipe()
+        .atomic()
+        .set_options(
+            &key,
+            message.id,
+            SetOptions::default().conditional_set(ExistenceCheck::NX),
+        )
+        .ignore()
+        .expire(&key, EVENT_LOG_TTL)
+        .ignore()
+        .query_async(&mut redis_conn)
+        .await?;
     Ok(())
+}
+
+/// 没有 Last-Event-ID 时从事件日志补发离线期间的消息, 按时间顺序(旧 -> 新)返回
+/// 离线标记不立即删除, 在宽限期内同时建立的其他连接(如多个标签页)同样能收到
+pub async fn replay_offline_messages(state: &AppState, user_uuid: &str) -> Result<Vec<SseMessage>, AppError> {
+    let mut redis_conn = state.redis.get_multiplexed_async_connection().await?;
+    let key = redis_offline_since_key(user_uuid);
+    let (offline_since, _): (Option<u64>, i64) = redis::pipe()
+        .atomic()
+        .get(&key)
+        .expire(&key, OFFLINE_REPLAY_GRACE_SECONDS)
+        .query_async(&mut redis_conn)
+        .await?;
+    match offline_since {
+        Some(offline_since) => read_event_log(state, user_uuid, offline_since.saturating_sub(1)).await,
+        None => Ok(Vec::new()),
+    }
+}
+
+/// 读取 id 大于 last_event_id 的事件, 离线标记进入宽限期(事件日志已包含离线消息)
+pub async fn replay_messages(
+    state: &AppState,
+    user_uuid: &str,
+    last_event_id: u64,
+) -> Result<Vec<SseMessage>, AppError> {
+    let mut redis_conn = state.redis.get_multiplexed_async_connection().await?;
+    let _: () = redis_conn
+        .expire(redis_offline_since_key(user_uuid), OFFLINE_REPLAY_GRACE_SECONDS)
+        .await?;
+    read_event_log(state, user_uuid, last_event_id).await
+}
 
+/// 用户当前最新的消息 id, 建立连接时作为不带 Last-Event-ID 连接的起点
+pub async fn latest_event_id(state: &AppState, user_uuid: &str) -> Result<u64, AppError> {
+    let mut redis_conn = state.redis.get_multiplexed_async_connection().await?;
+    let id: Option<u64> = redis_conn.get(redis_event_seq_key(user_uuid)).await?;
+    Ok(id.unwrap_or(0))
 }
 
+/// 连接收到的消息 id 不连续时, 从事件日志按顺序读取 (after_id, up_to] 之间的消息
+pub async fn read_event_range(
+    state: &AppState,
+    user_uuid: &str,
+    after_id: u64,
+    up_to: u64,
+) -> Result<Vec<SseMessage>, AppError> {
+    let mut redis_conn = state.redis.get_multiplexed_async_connection().await?;
+    let messages: Vec<String> = redis_conn
+        .zrangebyscore(redis_event_log_key(user_uuid), format!("({}", after_id), up_to)
+        .await?;
+    Ok(messages
+        .iter()
+        .filter_map(|m| serde_json::from_str(m).ok())
+        .collect())
+}
+
+async fn read_event_log(state: &AppState, user_uuid: &str, after_id: u64) -> Result<Vec<SseMessage>, AppError> {
+    let mut redis_conn = state.redis.get_multiplexed_async_connection().await?;
+    let messages: Vec<String> = redis_conn
+        .zrangebyscore(redis_event_log_key(user_uuid), format!("({}", after_id), "+inf")
+        .await?;
+    Ok(messages
+        .iter()
+        .filter_map(|m| serde_json::from_str(m).ok())
+        .collect())
+}
 
-pub fn redis_offline_key(user_uuid: String) -> String {
-    format!("offline:messages:{}", user_uuid)
-}
\ No newline at end of file
+/// 用户当前有连接的节点
+async fn online_nodes(state: &AppState, user_uuid: &str) -> Result<Vec<String>, AppError> {
+    let mut redis_conn = state.redis.get_multiplexed_async_connection().await?;
+    let now = chrono::Utc::now().timestamp();
+    let nodes: Vec<String> = redis_conn
+        .zrangebyscore(redis_presence_key(user_uuid), now, "+inf")
+        .await?;
+    Ok(nodes)
+}
+
+/// 在 Redis 中登记用户在本节点在线
+pub async fn register_presence(state: &AppState, user_uuid: &str) -> Result<(), AppError> {
+    let mut redis_conn = state.redis.get_multiplexed_async_connection().await?;
+    let key = redis_presence_key(user_uuid);
+    let _: () = redis::pipe()
+        .zadd(&key, &state.node_id, chrono::Utc::now().timestamp() + PRESENCE_TTL_SECONDS)
+        .expire(&key, PRESENCE_TTL_SECONDS)
+        .query_async(&mut redis_conn)
+        .await?;
+    Ok(())
+}
+
+/// 用户在本节点的连接全部断开后移除在线登记
+pub async fn remove_presence(state: &AppState, user_uuid: &str) -> Result<(), AppError> {
+    let mut redis_conn = state.redis.get_multiplexed_async_connection().await?;
+    let _: () = redis_conn
+        .zrem(redis_presence_key(user_uuid), &state.node_id)
+        .await?;
+    Ok(())
+}
+
+// 后台任务：定期刷新本节点在线用户的登记
+pub async fn refresh_presence(state: AppState) {
+    loop {
+        sleep(Duration::from_secs(PRESENCE_HEARTBEAT_SECONDS)).await;
+
+        let user_uuids: Vec<String> = state.sse_senders.lock().await.keys().cloned().collect();
+        for user_uuid in user_uuids {
+            if let Err(e) = register_presence(&state, &user_uuid).await {
+                warn!("刷新用户 {} 的在线状态失败: {}", user_uuid, e);
+            }
+        }
+    }
+}
+
+// 后台任务：监听其他节点转发给本节点的消息
+pub async fn subscribe_to_sse_messages(state: AppState) {
+    loop {
+        let channel = redis_node_channel(&state.node_id);
+        info!("尝试订阅Redis频道 '{}'...", channel);
+
+        match establish_sse_subscription(&state, &channel).await {
+            Ok(()) => info!("SSE消息订阅会话结束，准备重新连接..."),
+            Err(e) => error!("SSE消息订阅失败: {}", e),
+        }
+        sleep(SUBSCRIPTION_RETRY_DELAY).await;
+    }
+}
+
+async fn establish_sse_subscription(state: &AppState, channel: &str) -> Result<(), AppError> {
+    let pub_sub = state.redis.get_async_pubsub().await?;
+    let (mut sink, mut stream) = pub_sub.split();
+    sink.subscribe(channel).await?;
+
+    while let Some(msg) = stream.next().await {
+        let envelope = match msg
+            .get_payload::<String>()
+            .map_err(AppError::from)
+            .and_then(|p| serde_json::from_str::<SseEnvelope>(&p).map_err(AppError::from))
+        {
+            Ok(envelope) => envelope,
+            Err(e) => {
+                warn!("无法解析转发的SSE消息: {}，跳过。", e);
+                continue;
+            }
+        };
+
+        // 转发途中用户已断开, 转为离线消息
+        if !deliver_local(state, &envelope.user_uuid, &envelope.message).await
+            && let Err(e) = mark_offline_message(state, &envelope.user_uuid, &envelope.message).await
+        {
+            error!("存储用户 {} 的离线消息失败: {}", envelope.user_uuid, e);
+        }
+    }
+
+    warn!("Redis消息流结束");
+    Ok(())
+}
+
+
+fn redis_offline_since_key(user_uuid: &str) -> String {
+    format!("{}:{}", SSE_OFFLINE_SINCE_PREFIX, user_uuid)
+}
+
+fn redis_event_seq_key(user_uuid: &str) -> String {
+    format!("{}:{}", SSE_EVENT_SEQ_PREFIX, user_uuid)
+}
+
+fn redis_event_log_key(user_uuid: &str) -> String {
+    format!("{}:{}", SSE_EVENT_LOG_PREFIX, user_uuid)
+}
+
+fn redis_presence_key(user_uuid: &str) -> String {
+    format!("{}:{}", SSE_PRESENCE_PREFIX, user_uuid)
+}
+
+fn redis_node_channel(node_id: &str) -> String {
+    format!("{}:{}", SSE_NODE_CHANNEL_PREFIX, node_id)
+}
@@ -11,4 +11,112 @@ pub struct CNPasswordResetTemplate<'a> {
 #[template(path = "en/reset_password.html")]
 pub struct ENPasswordResetTemplate<'a> {
     pub reset_url: &'a str,
-}
\ No newline at end of file
+}
+
+
+#[derive(Template)]
+#[template(path = "cn/confirm_email.html")]
+pub struct CNEmailChangeTemplate<'a> {
+    pub confirm_url: &'a str,
+}
+
+
+#[derive(Template)]
+#[template(path = "en/confirm_email.html")]
+pub struct ENEmailChangeTemplate<'a> {
+    pub confirm_url: &'a str,
+}
+
+
+#[derive(Template)]
+#[template(path = "cn/invitation.html")]
+pub struct CNInvitationTemplate<'a> {
+    pub inviter: &'a str,
+    pub accept_url: &'a str,
+    pub expires_at: &'a str,
+}
+
+
+#[derive(Template)]
+#[template(path = "en/invitation.html")]
+pub struct ENInvitationTemplate<'a> {
+    pub inviter: &'a str,
+    pub accept_url: &'a str,
+    pub expires_at: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "cn/access_review_reminder.html")]
+pub struct CNAccessReviewReminderTemplate<'a> {
+    pub review_name: &'a str,
+    pub pending: u64,
+    pub review_url: &'a str,
+    pub due_at: &'a str,
+}
+
+
+#[derive(Template)]
+#[template(path = "en/access_review_reminder.html")]
+pub struct ENAccessReviewReminderTemplate<'a> {
+    pub review_name: &'a str,
+    pub pending: u64,
+    pub review_url: &'a str,
+    pub due_at: &'a str,
+}
+
+/// 已注册的邮件模板, 每个模板在 templates/cn 和 templates/en 下各有一份
+pub enum EmailTemplate<'a> {
+    PasswordReset { reset_url: &'a str },
+    EmailChange { confirm_url: &'a str },
+    Invitation { inviter: &'a str, accept_url: &'a str, expires_at: &'a str },
+    AccessReviewReminder { review_name: &'a str, pending: u64, review_url: &'a str, due_at: &'a str },
+}
+
+impl EmailTemplate<'_> {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EmailTemplate::PasswordReset { .. } => "password_reset",
+            EmailTemplate::EmailChange { .. } => "email_change",
+            EmailTemplate::Invitation { .. } => "invitation",
+            EmailTemplate::AccessReviewReminder { .. } => "access_review_reminder",
+        }
+    }
+
+    /// 按语言渲染, 返回(主题, 正文), 未注册的语言使用英文模板
+    pub fn render(&self, language: &str) -> Result<(String, String), askama::Error> {
+        let cn = language.eq_ignore_ascii_case("CN");
+        let (subject, body) = match (self, cn) {
+            (EmailTemplate::PasswordReset { reset_url }, true) => {
+                ("[Axum Vue Admin] 密码重置请求", CNPasswordResetTemplate { reset_url }.render()?)
+            }
+            (EmailTemplate::PasswordReset { reset_url }, false) => {
+                ("[Axum Vue Admin] Password Reset Request", ENPasswordResetTemplate { reset_url }.render()?)
+            }
+            (EmailTemplate::EmailChange { confirm_url }, true) => {
+                ("[Axum Vue Admin] 确认新邮箱地址", CNEmailChangeTemplate { confirm_url }.render()?)
+            }
+            (EmailTemplate::EmailChange { confirm_url }, false) => {
+                ("[Axum Vue Admin] Confirm Your New Email Address", ENEmailChangeTemplate { confirm_url }.render()?)
+            }
+            (EmailTemplate::Invitation { inviter, accept_url, expires_at }, true) => {
+                ("[Axum Vue Admin] 邀请加入", CNInvitationTemplate { inviter, accept_url, expires_at }.render()?)
+            }
+            (EmailTemplate::Invitation { inviter, accept_url, expires_at }, false) => {
+                ("[Axum Vue Admin] You're Invited", ENInvitationTemplate { inviter, accept_url, expires_at }.render()?)
+            }
+            (EmailTemplate::AccessReviewReminder { review_name, pending, review_url, due_at }, true) => {
+                (
+                    "[Axum Vue Admin] 访问权限复核提醒",
+                    CNAccessReviewReminderTemplate { review_name, pending: *pending, review_url, due_at }.render()?,
+                )
+            }
+            (EmailTemplate::AccessReviewReminder { review_name, pending, review_url, due_at }, false) => {
+                (
+                    "[Axum Vue Admin] Access Review Reminder",
+                    ENAccessReviewReminderTemplate { review_name, pending: *pending, review_url, due_at }.render()?,
+                )
+            }
+        };
+        Ok((subject.to_string(), body))
+    }
+}